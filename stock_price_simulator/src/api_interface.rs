@@ -1,10 +1,26 @@
-use crate::random_process::TimeSeries;
+use crate::random_process::{
+    TimeSeries, StochasticProcess, GeometricBrownianMotion, OrnsteinUhlenbeck, CoxIngersollRoss, MertonJumpDiffusion,
+    Heston,
+};
 use crate::stock_simulation::StockSimulator;
-use crate::option_pricing::{EuropeanOption, OptionType, OptionPricer};
+use crate::bootstrap::HistoricalBootstrap;
+use crate::volatility_models::{Garch11, Egarch11};
+use crate::regime_switching::{MarkovRegimeSwitching, RegimePath};
+use crate::local_volatility::LocalVolatilityProcess;
+use crate::config::{AssetModelConfig, GlobalConfig, InnovationDistribution, ModelType};
+use crate::config::OptionType;
+use crate::option_pricing::{EuropeanOption, OptionPricer};
 use crate::futures_simulation::FuturesContract;
-use crate::etf_simulation::EtfDefinition;
+use crate::bond_simulation::{BondContract, BondPath};
+use crate::fx_simulation::{FxForwardContract, FxOption, FxPath};
+use crate::etf_simulation::{EtfDefinition, EtfNavSimulation, EtfTrackingReport};
+use crate::api_models::{ConstituentAttributionData, EtfData, EtfTrackingData};
+use crate::export::DEFAULT_TIMESTAMP_FORMAT;
+use crate::yield_curve::{DiscountCurve, RateSource};
+use crate::error::{invalid_config, invalid_parameter};
 use anyhow::Result;
 use serde::Deserialize; // Added for MonteCarloEuropeanOptionInput
+use schemars::JsonSchema;
 
 // --- Stock Simulation ---
 pub fn simulate_stock(
@@ -25,9 +41,10 @@ pub fn simulate_stock(
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn simulate_stock_with_config(
     asset_identifier: &str,
-    config: &crate::config::GlobalConfig,
+    config: &GlobalConfig,
     initial_price: f64,
     days: usize, // Number of simulation steps/days
     time_step_days: f64, // Duration of each step in days
@@ -35,34 +52,224 @@ pub fn simulate_stock_with_config(
     override_drift: Option<f64>,
     override_volatility: Option<f64>,
 ) -> Result<TimeSeries> {
-    // Find the model configuration for the given asset_identifier
-    let model_config = config.asset_models.as_ref() // Get Option<&Vec<AssetModelConfig>>
-        .ok_or_else(|| anyhow::anyhow!("No asset_models configured in GlobalConfig."))?
-        .iter()
-        .find(|m| m.asset_type == "stock" && m.asset_identifier_pattern == asset_identifier)
-        .ok_or_else(|| anyhow::anyhow!("No model config found for stock identifier: {}", asset_identifier))?;
+    let model_config = find_stock_model_config(config, asset_identifier)?;
+    simulate_stock_with_model(model_config, asset_identifier, initial_price, days, time_step_days, seed, override_drift, override_volatility)
+}
+
+// Like simulate_stock_with_config with the asset model already looked up (e.g. with an AssetMatcher).
+#[allow(clippy::too_many_arguments)]
+pub fn simulate_stock_with_model(
+    model_config: &AssetModelConfig,
+    asset_identifier: &str,
+    initial_price: f64,
+    days: usize,
+    time_step_days: f64,
+    seed: Option<u64>,
+    override_drift: Option<f64>,
+    override_volatility: Option<f64>,
+) -> Result<TimeSeries> {
+    let process = build_process(model_config, asset_identifier, override_drift, override_volatility)?;
+    StockSimulator::simulate_with_process(
+        process.as_ref(),
+        initial_price,
+        days,
+        time_step_days,
+        seed, // Use the per-simulation seed
+    )
+}
+
+// Like simulate_stock_with_config for an asset configured with ModelType::RegimeSwitching,
+// also returning the regime sequence of the path.
+pub fn simulate_stock_regimes_with_config(
+    asset_identifier: &str,
+    config: &GlobalConfig,
+    initial_price: f64,
+    days: usize,
+    time_step_days: f64,
+    seed: Option<u64>,
+) -> Result<RegimePath> {
+    let model_config = find_stock_model_config(config, asset_identifier)?;
+    if model_config.default_model != ModelType::RegimeSwitching {
+        return Err(invalid_parameter("asset_identifier", format!(
+            "Stock identifier {} is configured with {:?}, not RegimeSwitching.",
+            asset_identifier, model_config.default_model
+        )));
+    }
+    let process = build_regime_switching(model_config, asset_identifier)?;
+    StockSimulator::simulate_with_regimes(&process, initial_price, days, time_step_days, seed)
+}
+
+// Validates the config (see config_validation) and builds every stock model that passed, returning
+// one message per problem that would otherwise surface at request time. An empty list means the
+// config is usable.
+pub fn check_asset_models(config: &GlobalConfig) -> Vec<String> {
+    let diagnostics = crate::config_validation::validate_config(config);
+    let mut problems: Vec<String> = diagnostics.iter().map(|d| d.to_string()).collect();
+    let models = config.asset_models.as_deref().unwrap_or(&[]);
+    for (i, model) in models.iter().enumerate() {
+        if !model.asset_type.eq_ignore_ascii_case("stock") || diagnostics.iter().any(|d| d.index == Some(i)) {
+            continue;
+        }
+        if let Err(e) = build_process(model, &model.asset_identifier_pattern, None, None) {
+            problems.push(format!("asset_models[{}] ({} '{}'): {}", i, model.asset_type, model.asset_identifier_pattern, e));
+        }
+    }
+    problems
+}
+
+// Find the model configuration for the given asset_identifier (exact, glob, regex or DEFAULT_STOCK)
+fn find_stock_model_config<'a>(config: &'a GlobalConfig, asset_identifier: &str) -> Result<&'a AssetModelConfig> {
+    crate::asset_matching::find_asset_model(config, "stock", asset_identifier)
+}
+
+fn build_regime_switching(model_config: &AssetModelConfig, asset_identifier: &str) -> Result<MarkovRegimeSwitching> {
+    let params = &model_config.parameters;
+    let regime_switching = params.regime_switching.as_ref()
+        .ok_or_else(|| invalid_config(None, format!("Regime-switching parameters not configured for identifier: {}", asset_identifier)))?;
+    let innovations = params.innovations.clone().unwrap_or_default();
+    innovations.validate()?;
+    Ok(MarkovRegimeSwitching { innovations, ..MarkovRegimeSwitching::from_params(regime_switching)? })
+}
 
+// Builds the configured process for an asset, applying any per-request drift/volatility overrides.
+fn build_process(
+    model_config: &AssetModelConfig,
+    asset_identifier: &str,
+    override_drift: Option<f64>,
+    override_volatility: Option<f64>,
+) -> Result<Box<dyn StochasticProcess>> {
+    let params = &model_config.parameters;
+    let innovations = params.innovations.clone().unwrap_or_default();
+    innovations.validate()?;
     match model_config.default_model {
-        crate::config::ModelType::GeometricBrownianMotion => {
-            if let Some(gbm_params_from_config) = &model_config.parameters.gbm {
+        ModelType::GeometricBrownianMotion => {
+            if let Some(gbm_params_from_config) = &params.gbm {
                 let drift = override_drift.unwrap_or(gbm_params_from_config.drift);
-                let volatility = override_volatility.unwrap_or(gbm_params_from_config.volatility);
-
-                crate::stock_simulation::StockSimulator::simulate_stock_price(
-                    initial_price,
-                    drift,
-                    volatility,
-                    days,
-                    time_step_days,
-                    seed, // Use the per-simulation seed
-                )
+                let volatility = non_negative_volatility(override_volatility.unwrap_or(gbm_params_from_config.volatility))?;
+                Ok(Box::new(GeometricBrownianMotion { drift, volatility, innovations }))
             } else {
-                Err(anyhow::anyhow!("GBM parameters not configured for identifier: {}", asset_identifier))
+                Err(invalid_config(None, format!("GBM parameters not configured for identifier: {}", asset_identifier)))
             }
         }
-        // Add other model types here later, e.g.
-        // crate::config::ModelType::Heston => Err(anyhow::anyhow!("Heston model not yet implemented for stocks via config.")),
+        ModelType::OrnsteinUhlenbeck => {
+            let ou = params.ou.as_ref()
+                .ok_or_else(|| invalid_config(None, format!("OU parameters not configured for identifier: {}", asset_identifier)))?;
+            reject_override(override_drift, "Drift", "OrnsteinUhlenbeck")?;
+            require_normal_innovations(&innovations, "OrnsteinUhlenbeck")?;
+            Ok(Box::new(OrnsteinUhlenbeck {
+                mean_reversion_speed: ou.mean_reversion_speed,
+                long_term_mean: ou.long_term_mean,
+                volatility: non_negative_volatility(override_volatility.unwrap_or(ou.volatility))?,
+            }))
+        }
+        ModelType::CoxIngersollRoss => {
+            let cir = params.cir.as_ref()
+                .ok_or_else(|| invalid_config(None, format!("CIR parameters not configured for identifier: {}", asset_identifier)))?;
+            reject_override(override_drift, "Drift", "CoxIngersollRoss")?;
+            require_normal_innovations(&innovations, "CoxIngersollRoss")?;
+            Ok(Box::new(CoxIngersollRoss {
+                mean_reversion_speed: cir.mean_reversion_speed,
+                long_term_mean: cir.long_term_mean,
+                volatility: non_negative_volatility(override_volatility.unwrap_or(cir.volatility))?,
+            }))
+        }
+        ModelType::MertonJumpDiffusion => {
+            let jd = params.jump_diffusion.as_ref()
+                .ok_or_else(|| invalid_config(None, format!("Jump-diffusion parameters not configured for identifier: {}", asset_identifier)))?;
+            require_normal_innovations(&innovations, "MertonJumpDiffusion")?;
+            if jd.jump_intensity < 0.0 || jd.jump_volatility < 0.0 {
+                let field = if jd.jump_intensity < 0.0 { "jump_intensity" } else { "jump_volatility" };
+                return Err(invalid_config(Some(field), "Jump intensity and jump volatility cannot be negative."));
+            }
+            Ok(Box::new(MertonJumpDiffusion {
+                drift: override_drift.unwrap_or(jd.drift),
+                volatility: non_negative_volatility(override_volatility.unwrap_or(jd.volatility))?,
+                jump_intensity: jd.jump_intensity,
+                jump_mean: jd.jump_mean,
+                jump_volatility: jd.jump_volatility,
+            }))
+        }
+        ModelType::HistoricalBootstrap => {
+            let bootstrap = params.bootstrap.as_ref()
+                .ok_or_else(|| invalid_config(None, format!("Bootstrap parameters not configured for identifier: {}", asset_identifier)))?;
+            // Resampled paths carry the historical drift and volatility; there is nothing to override.
+            reject_override(override_drift, "Drift", "HistoricalBootstrap")?;
+            reject_override(override_volatility, "Volatility", "HistoricalBootstrap")?;
+            require_normal_innovations(&innovations, "HistoricalBootstrap")?;
+            Ok(Box::new(HistoricalBootstrap::from_params(bootstrap)?))
+        }
+        ModelType::Garch => {
+            let garch = params.garch.as_ref()
+                .ok_or_else(|| invalid_config(None, format!("GARCH parameters not configured for identifier: {}", asset_identifier)))?;
+            // Volatility is an output of the variance recursion rather than a single parameter.
+            reject_override(override_drift, "Drift", "Garch")?;
+            reject_override(override_volatility, "Volatility", "Garch")?;
+            Ok(Box::new(Garch11 { innovations, ..Garch11::from_params(garch)? }))
+        }
+        ModelType::Egarch => {
+            let egarch = params.egarch.as_ref()
+                .ok_or_else(|| invalid_config(None, format!("EGARCH parameters not configured for identifier: {}", asset_identifier)))?;
+            reject_override(override_drift, "Drift", "Egarch")?;
+            reject_override(override_volatility, "Volatility", "Egarch")?;
+            require_normal_innovations(&innovations, "Egarch")?;
+            Ok(Box::new(Egarch11::from_params(egarch)?))
+        }
+        ModelType::RegimeSwitching => {
+            // Drift and volatility are set per regime.
+            reject_override(override_drift, "Drift", "RegimeSwitching")?;
+            reject_override(override_volatility, "Volatility", "RegimeSwitching")?;
+            Ok(Box::new(build_regime_switching(model_config, asset_identifier)?))
+        }
+        ModelType::Heston => {
+            let heston = params.heston.as_ref()
+                .ok_or_else(|| invalid_config(None, format!("Heston parameters not configured for identifier: {}", asset_identifier)))?;
+            // Volatility is stochastic; there is no single value to override.
+            reject_override(override_volatility, "Volatility", "Heston")?;
+            require_normal_innovations(&innovations, "Heston")?;
+            heston.validate()?;
+            Ok(Box::new(Heston {
+                drift: override_drift.unwrap_or(heston.drift),
+                initial_variance: heston.initial_variance,
+                mean_reversion_speed: heston.mean_reversion_speed,
+                long_term_variance: heston.long_term_variance,
+                vol_of_vol: heston.vol_of_vol,
+                correlation: heston.correlation,
+            }))
+        }
+        ModelType::LocalVolatility => {
+            let local_vol = params.local_volatility.as_ref()
+                .ok_or_else(|| invalid_config(None, format!("Local volatility parameters not configured for identifier: {}", asset_identifier)))?;
+            // Volatility comes from the surface.
+            reject_override(override_volatility, "Volatility", "LocalVolatility")?;
+            require_normal_innovations(&innovations, "LocalVolatility")?;
+            let process = LocalVolatilityProcess::from_params(local_vol)?;
+            Ok(Box::new(LocalVolatilityProcess { drift: override_drift.unwrap_or(process.drift), ..process }))
+        }
+    }
+}
+
+// Models whose shocks are fixed by construction (Gaussian transition densities, resampled history,
+// the normal E|z| centring of EGARCH) cannot take another innovation distribution.
+fn require_normal_innovations(innovations: &InnovationDistribution, model_name: &str) -> Result<()> {
+    if *innovations != InnovationDistribution::StandardNormal {
+        return Err(invalid_config(Some("innovations"), format!("Innovation distribution {:?} is not supported for model {}.", innovations, model_name)));
     }
+    Ok(())
+}
+
+fn non_negative_volatility(volatility: f64) -> Result<f64> {
+    if volatility < 0.0 {
+        return Err(invalid_parameter("volatility", "Volatility cannot be negative."));
+    }
+    Ok(volatility)
+}
+
+// Some models have no drift (mean-reverting) or volatility (non-parametric) parameter to override.
+fn reject_override(override_value: Option<f64>, parameter: &str, model_name: &str) -> Result<()> {
+    if override_value.is_some() {
+        return Err(invalid_parameter(&parameter.to_lowercase(), format!("{} override is not supported for model {}.", parameter, model_name)));
+    }
+    Ok(())
 }
 
 // --- Option Pricing ---
@@ -80,38 +287,50 @@ pub fn price_european_option_black_scholes(
         underlying_price,
         strike_price,
         time_to_maturity_years,
-        risk_free_rate,
+        risk_free_rate: Some(risk_free_rate),
         volatility,
         option_type,
+        discount_curve: None,
     };
     // The black_scholes_price function needs to be modified to return Result
     // and perform input validation. This will be handled in a subsequent step.
     crate::option_pricing::black_scholes_price(&option)
 }
 
+// Black-Scholes for a fully specified option, including its discount curve if any.
+pub fn price_european_option(option: &EuropeanOption) -> Result<f64> {
+    crate::option_pricing::black_scholes_price(option)
+}
+
 // Monte Carlo
-#[derive(Debug, Clone, Deserialize)] // Added Deserialize
+#[derive(Debug, Clone, Deserialize, JsonSchema)] // Added Deserialize
 pub struct MonteCarloEuropeanOptionInput {
     pub underlying_initial_price: f64,
     pub strike_price: f64,
     pub time_to_maturity_years: f64,
-    pub risk_free_rate: f64,
+    #[serde(default)]
+    pub risk_free_rate: Option<f64>,
     pub underlying_volatility: f64,
     pub option_type: OptionType,
     pub num_paths: usize,
     pub num_steps_per_path: usize, // Corrected field name
     pub seed: Option<u64>,
+    #[serde(default)]
+    pub discount_curve: Option<DiscountCurve>, // Given instead of risk_free_rate
 }
 
 pub fn price_european_option_monte_carlo(
     input: &MonteCarloEuropeanOptionInput,
 ) -> Result<f64> {
+    let rates = RateSource::from_request(input.risk_free_rate, input.discount_curve.as_ref())?;
+    let rate_to_maturity = rates.zero_rate(input.time_to_maturity_years);
     let pricer = crate::option_pricing::MonteCarloOptionPricer {
         underlying_initial_price: input.underlying_initial_price,
         strike_price: input.strike_price,
         time_to_maturity_years: input.time_to_maturity_years,
         risk_free_rate: input.risk_free_rate,
-        underlying_drift: input.risk_free_rate, // Assuming risk-neutral drift for MC
+        discount_curve: input.discount_curve.clone(),
+        underlying_drift: rate_to_maturity, // Assuming risk-neutral drift for MC
         underlying_volatility: input.underlying_volatility,
         option_type: input.option_type, // OptionType is Copy
         num_paths: input.num_paths,
@@ -125,7 +344,68 @@ pub fn simulate_futures(contract_params: &FuturesContract) -> Result<TimeSeries>
     crate::futures_simulation::simulate_futures_price(contract_params)
 }
 
+// --- Bond Simulation ---
+pub fn simulate_bond(contract: &BondContract) -> Result<BondPath> {
+    crate::bond_simulation::simulate_bond(contract)
+}
+
+// --- FX Simulation ---
+pub fn simulate_fx(contract: &FxForwardContract) -> Result<FxPath> {
+    crate::fx_simulation::simulate_fx_forward(contract)
+}
+
+pub fn price_fx_option_garman_kohlhagen(option: &FxOption) -> Result<f64> {
+    crate::fx_simulation::garman_kohlhagen_price(option)
+}
+
 // --- ETF Simulation ---
 pub fn simulate_etf(etf_params: &EtfDefinition) -> Result<TimeSeries> {
     crate::etf_simulation::simulate_etf_nav(etf_params)
 }
+
+impl From<EtfTrackingReport> for EtfTrackingData {
+    fn from(report: EtfTrackingReport) -> Self {
+        EtfTrackingData {
+            benchmark_nav: report.benchmark_nav,
+            tracking_differences: report.tracking_differences,
+            tracking_difference: report.tracking_difference,
+            tracking_error: report.tracking_error,
+            attribution: report.attribution.into_iter().map(|a| ConstituentAttributionData {
+                symbol: a.symbol,
+                returns: a.returns,
+                contributions: a.contributions,
+                active_contributions: a.active_contributions,
+                total_contribution: a.total_contribution,
+            }).collect(),
+            expense_contributions: report.expense_contributions,
+        }
+    }
+}
+
+// The response body of a simulation, under the placeholder symbol; callers that know the basket's
+// symbol set etf_symbol.
+impl From<EtfNavSimulation> for EtfData {
+    fn from(simulation: EtfNavSimulation) -> Self {
+        let market = simulation.market;
+        EtfData {
+            etf_symbol: "SIMULATED_ETF".to_string(),
+            timestamps: simulation.nav.timestamps.iter().map(|t| t.format(DEFAULT_TIMESTAMP_FORMAT).to_string()).collect(),
+            nav_values: simulation.nav.prices,
+            market_prices: market.as_ref().map(|m| m.market_prices.clone()),
+            premiums: market.as_ref().map(|m| m.premiums.clone()),
+            shares_outstanding: market.map(|m| m.shares_outstanding),
+            tracking: simulation.tracking.map(EtfTrackingData::from),
+        }
+    }
+}
+
+// --- Calibration ---
+pub fn calibrate_asset_model(
+    asset_type: &str,
+    asset_identifier_pattern: &str,
+    history: &TimeSeries,
+    model: ModelType,
+    time_step_days: f64,
+) -> Result<AssetModelConfig> {
+    crate::calibration::calibrate_asset_model(asset_type, asset_identifier_pattern, history, model, time_step_days)
+}
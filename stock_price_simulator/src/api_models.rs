@@ -1,27 +1,85 @@
 use serde::{Serialize, Deserialize}; // Added Deserialize
+use schemars::JsonSchema;
+use crate::config::AssetModelConfig;
+use crate::error::ErrorCode;
 // use chrono::NaiveDateTime; // Not directly used in these structs, but for transformation logic later
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)] // Added Deserialize
+#[derive(Serialize, Deserialize, Debug, PartialEq, JsonSchema)] // Added Deserialize
 // Let derive macro add the Deserialize bound for T
 pub struct ApiResponse<T: Serialize + PartialEq> {
     pub status: String,
     pub data: T,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)] // Added Deserialize
+#[derive(Serialize, Deserialize, Debug, PartialEq, JsonSchema)] // Added Deserialize
 pub struct ApiErrorResponse {
     pub status: String,
-    pub error: String,
+    pub error: String, // Human-readable; branch on code instead
+    pub code: ErrorCode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>, // Path of the offending request or config field, e.g. constituents[1].weight
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)] // Added Deserialize
+#[derive(Serialize, Deserialize, Debug, PartialEq, JsonSchema)]
+pub struct ConfigStatusData {
+    pub version: u64,
+    pub hash: String,
+    pub loaded_at: String, // UTC
+    pub source: Option<String>, // Config file paths, base first
+    #[serde(default)]
+    pub files: Vec<String>, // Every file read, including overlays and includes
+    pub asset_model_count: usize,
+    pub last_reload_error: Option<String>,
+}
+
+// Answer of GET /healthz.
+#[derive(Serialize, Deserialize, Debug, PartialEq, JsonSchema)]
+pub struct HealthData {
+    pub config_version: u64, // Version of the config being served, as in GET /admin/config
+    pub config_reload_failing: bool, // The last reload failed, so an older config is still served
+}
+
+// Answer of GET /version.
+#[derive(Serialize, Deserialize, Debug, PartialEq, JsonSchema)]
+pub struct VersionData {
+    pub name: String,
+    pub version: String,     // Of the server build
+    pub api_version: String, // Prefix of the versioned routes, e.g. "v1"
+}
+
+// Answer of GET /admin/config/resolve: which asset model an identifier uses, and why.
+#[derive(Serialize, Deserialize, Debug, PartialEq, JsonSchema)]
+pub struct ModelResolutionData {
+    pub asset_type: String,
+    pub asset_identifier: String,
+    pub resolved: Option<ResolvedModelData>, // None when no pattern matches
+    pub candidates: Vec<PatternCandidateData>, // Every model of the asset type, in file order
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, JsonSchema)]
+pub struct ResolvedModelData {
+    pub index: usize, // Position in asset_models
+    pub pattern: String,
+    pub match_kind: String, // exact, glob, regex or default
+    pub model: AssetModelConfig,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, JsonSchema)]
+pub struct PatternCandidateData {
+    pub index: usize,
+    pub pattern: String,
+    pub match_kind: String,
+    pub matched: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, JsonSchema)] // Added Deserialize
 pub struct StockData {
     pub symbol: String,
     pub timestamps: Vec<String>, // ISO 8601 format
     pub prices: Vec<f64>,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Default)] // Added Deserialize
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default, JsonSchema)] // Added Deserialize
 pub struct OptionData {
     pub underlying_symbol: String,
     pub option_type: String, // "Call" or "Put"
@@ -33,7 +91,7 @@ pub struct OptionData {
     pub timestamps: Option<Vec<String>>,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)] // Added Deserialize
+#[derive(Serialize, Deserialize, Debug, PartialEq, JsonSchema)] // Added Deserialize
 pub struct FutureData {
     pub contract_symbol: String,
     pub timestamps: Vec<String>,
@@ -41,9 +99,61 @@ pub struct FutureData {
     pub spot_prices: Option<Vec<f64>>,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)] // Added Deserialize
+#[derive(Serialize, Deserialize, Debug, PartialEq, JsonSchema)]
+pub struct BondData {
+    pub bond_symbol: String,
+    pub timestamps: Vec<String>,
+    pub short_rates: Vec<f64>,
+    pub clean_prices: Vec<f64>,
+    pub dirty_prices: Vec<f64>,
+    pub accrued_interest: Vec<f64>,
+    pub yields: Vec<Option<f64>>, // None at maturity
+    pub macaulay_durations: Vec<f64>,
+    pub modified_durations: Vec<f64>,
+    pub convexities: Vec<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, JsonSchema)]
+pub struct FxData {
+    pub pair: String, // "BASE/QUOTE"
+    pub timestamps: Vec<String>,
+    pub spot_rates: Vec<f64>,
+    pub forward_rates: Vec<f64>,
+    pub forward_points: Vec<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, JsonSchema)] // Added Deserialize
 pub struct EtfData {
     pub etf_symbol: String,
     pub timestamps: Vec<String>,
     pub nav_values: Vec<f64>,
+    // Set when the request simulates the secondary market.
+    #[serde(default)]
+    pub market_prices: Option<Vec<f64>>,
+    #[serde(default)]
+    pub premiums: Option<Vec<f64>>,
+    #[serde(default)]
+    pub shares_outstanding: Option<Vec<u64>>,
+    // Set when the request names a benchmark.
+    #[serde(default)]
+    pub tracking: Option<EtfTrackingData>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, JsonSchema)]
+pub struct EtfTrackingData {
+    pub benchmark_nav: Vec<f64>,
+    pub tracking_differences: Vec<f64>, // Per period, 0 at the first timestamp
+    pub tracking_difference: f64,
+    pub tracking_error: f64, // Annualized
+    pub attribution: Vec<ConstituentAttributionData>,
+    pub expense_contributions: Vec<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, JsonSchema)]
+pub struct ConstituentAttributionData {
+    pub symbol: String,
+    pub returns: Vec<f64>,
+    pub contributions: Vec<f64>,
+    pub active_contributions: Vec<f64>,
+    pub total_contribution: f64,
 }
@@ -0,0 +1,216 @@
+use crate::config::{AssetModelConfig, GlobalConfig};
+use crate::error::{ErrorCode, SimulationError};
+use anyhow::Error;
+use regex::Regex;
+use std::cmp::Reverse;
+
+// Prefix marking an asset_identifier_pattern as a regular expression, e.g. "re:^[A-Z]{3}USD$".
+pub const REGEX_PREFIX: &str = "re:";
+
+// How an asset_identifier_pattern is interpreted, in precedence order: an exact key beats any glob,
+// a glob beats any regex, and the DEFAULT_<TYPE> entry (e.g. DEFAULT_STOCK) is the last resort.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PatternKind {
+    Exact,
+    Glob,  // `*` matches any run of characters, `?` exactly one
+    Regex, // Must match the whole identifier
+    Default,
+}
+
+impl PatternKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PatternKind::Exact => "exact",
+            PatternKind::Glob => "glob",
+            PatternKind::Regex => "regex",
+            PatternKind::Default => "default",
+        }
+    }
+}
+
+pub fn default_pattern(asset_type: &str) -> String {
+    format!("DEFAULT_{}", asset_type.to_ascii_uppercase())
+}
+
+pub fn pattern_kind(pattern: &str, asset_type: &str) -> PatternKind {
+    if pattern.starts_with(REGEX_PREFIX) {
+        PatternKind::Regex
+    } else if pattern.contains(['*', '?']) {
+        PatternKind::Glob
+    } else if pattern == default_pattern(asset_type) {
+        PatternKind::Default
+    } else {
+        PatternKind::Exact
+    }
+}
+
+fn compile_regex(pattern: &str) -> Result<Regex, Error> {
+    let expression = &pattern[REGEX_PREFIX.len()..];
+    Regex::new(&format!("^(?:{})$", expression))
+        .map_err(|e| anyhow::anyhow!("Invalid regex in asset_identifier_pattern '{}': {}", pattern, e))
+}
+
+// Greedy matcher: on a mismatch, the most recent `*` takes one more character and matching resumes
+// after it. Earlier stars never need to be revisited, so this is O(pattern * identifier).
+fn glob_matches(pattern: &[char], identifier: &[char]) -> bool {
+    let (mut p, mut i) = (0, 0);
+    let mut last_star: Option<(usize, usize)> = None; // (pattern index of the star, identifier index it resumes at)
+    while i < identifier.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                last_star = Some((p, i));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == identifier[i] => {
+                p += 1;
+                i += 1;
+            }
+            _ => match last_star {
+                Some((star, resume)) => {
+                    last_star = Some((star, resume + 1));
+                    p = star + 1;
+                    i = resume + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+// Literal characters in a glob; between two matching globs the one with more literals is more specific.
+fn glob_specificity(pattern: &str) -> usize {
+    pattern.chars().filter(|c| *c != '*' && *c != '?').count()
+}
+
+// An asset_identifier_pattern ready to be matched.
+#[derive(Debug, Clone)]
+enum CompiledPattern {
+    Exact(String),
+    Glob(Vec<char>),
+    Regex(Regex),
+    Default,
+}
+
+impl CompiledPattern {
+    fn new(pattern: &str, asset_type: &str) -> Result<Self, Error> {
+        Ok(match pattern_kind(pattern, asset_type) {
+            PatternKind::Exact => CompiledPattern::Exact(pattern.to_string()),
+            PatternKind::Glob => CompiledPattern::Glob(pattern.chars().collect()),
+            PatternKind::Regex => CompiledPattern::Regex(compile_regex(pattern)?),
+            PatternKind::Default => CompiledPattern::Default,
+        })
+    }
+
+    fn matches(&self, identifier: &str) -> bool {
+        match self {
+            CompiledPattern::Exact(pattern) => pattern == identifier,
+            CompiledPattern::Glob(pattern) => glob_matches(pattern, &identifier.chars().collect::<Vec<_>>()),
+            CompiledPattern::Regex(regex) => regex.is_match(identifier),
+            CompiledPattern::Default => true,
+        }
+    }
+}
+
+// Checks a single pattern against an identifier. A DEFAULT_<TYPE> entry also matches its own name.
+pub fn pattern_matches(pattern: &str, asset_type: &str, identifier: &str) -> Result<bool, Error> {
+    Ok(CompiledPattern::new(pattern, asset_type)?.matches(identifier))
+}
+
+// Rejects patterns that could never be evaluated (currently: regexes that do not compile).
+pub fn validate_pattern(pattern: &str) -> Result<(), Error> {
+    if pattern.starts_with(REGEX_PREFIX) {
+        compile_regex(pattern)?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub index: usize, // Position in asset_models
+    pub pattern: String,
+    pub kind: PatternKind,
+    pub matched: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolution {
+    pub asset_type: String,
+    pub asset_identifier: String,
+    // Index into asset_models of the winning entry, if any.
+    pub resolved: Option<usize>,
+    // Every entry of the asset type, in file order, with whether its pattern matched.
+    pub candidates: Vec<Candidate>,
+}
+
+// The asset_models patterns of one config, compiled once (the server keeps one per config snapshot).
+// Only valid for the config it was built from.
+#[derive(Debug, Clone)]
+pub struct AssetMatcher {
+    patterns: Vec<CompiledPattern>, // Parallel to asset_models
+}
+
+impl AssetMatcher {
+    pub fn new(config: &GlobalConfig) -> Result<Self, Error> {
+        let models = config.asset_models.as_deref().unwrap_or(&[]);
+        let patterns = models.iter()
+            .map(|model| CompiledPattern::new(&model.asset_identifier_pattern, &model.asset_type))
+            .collect::<Result<_, _>>()?;
+        Ok(AssetMatcher { patterns })
+    }
+
+    // Explains how `identifier` resolves among the models of `asset_type` (case-insensitive). The
+    // matching entry of the best kind wins (exact, then glob, then regex, then DEFAULT_<TYPE>); among
+    // globs the one with the most literal characters wins, and remaining ties go to the entry that
+    // comes first in the file.
+    pub fn resolve(&self, config: &GlobalConfig, asset_type: &str, identifier: &str) -> Resolution {
+        let mut candidates = Vec::new();
+        let mut best: Option<((PatternKind, Reverse<usize>), usize)> = None; // (precedence, index)
+        let models = config.asset_models.as_deref().unwrap_or(&[]);
+        for (index, (model, compiled)) in models.iter().zip(&self.patterns).enumerate() {
+            if !model.asset_type.eq_ignore_ascii_case(asset_type) {
+                continue;
+            }
+            let pattern = &model.asset_identifier_pattern;
+            let kind = pattern_kind(pattern, &model.asset_type);
+            let matched = compiled.matches(identifier);
+            if matched {
+                let specificity = if kind == PatternKind::Glob { glob_specificity(pattern) } else { 0 };
+                let precedence = (kind, Reverse(specificity));
+                // Strictly better only, so the first entry in the file wins ties.
+                if best.as_ref().is_none_or(|(current, _)| precedence < *current) {
+                    best = Some((precedence, index));
+                }
+            }
+            candidates.push(Candidate { index, pattern: pattern.clone(), kind, matched });
+        }
+        Resolution {
+            asset_type: asset_type.to_string(),
+            asset_identifier: identifier.to_string(),
+            resolved: best.map(|(_, index)| index),
+            candidates,
+        }
+    }
+
+    pub fn find<'a>(&self, config: &'a GlobalConfig, asset_type: &str, identifier: &str) -> Result<&'a AssetModelConfig, Error> {
+        if config.asset_models.is_none() {
+            return Err(SimulationError::new(ErrorCode::UnknownAsset, Some("asset_identifier"), "No asset_models configured in GlobalConfig.").into());
+        }
+        self.resolve(config, asset_type, identifier).resolved
+            .and_then(|index| config.asset_models.as_ref().and_then(|models| models.get(index)))
+            .ok_or_else(|| SimulationError::new(
+                ErrorCode::UnknownAsset,
+                Some("asset_identifier"),
+                format!("No model config found for {} identifier: {}", asset_type, identifier),
+            ).into())
+    }
+}
+
+// One-off lookups compile the config's patterns first; see AssetMatcher to reuse them.
+pub fn resolve(config: &GlobalConfig, asset_type: &str, identifier: &str) -> Result<Resolution, Error> {
+    Ok(AssetMatcher::new(config)?.resolve(config, asset_type, identifier))
+}
+
+pub fn find_asset_model<'a>(config: &'a GlobalConfig, asset_type: &str, identifier: &str) -> Result<&'a AssetModelConfig, Error> {
+    AssetMatcher::new(config)?.find(config, asset_type, identifier)
+}
@@ -0,0 +1,274 @@
+// Offline counterpart of stock_sim_server: runs one simulation through api_interface and writes the
+// result to stdout or a file. Request bodies use the same JSON as the HTTP endpoints, and JSON output
+// is the `data` part of the HTTP response.
+use anyhow::{Context, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::{Read, Write};
+
+use stock_price_simulator::api_interface::{self, MonteCarloEuropeanOptionInput};
+use stock_price_simulator::api_models::{EtfData, FutureData, OptionData, StockData};
+use stock_price_simulator::config::{self, ModelType};
+use stock_price_simulator::config_layers::load_layered_config;
+use stock_price_simulator::request_defaults::{with_defaults, RequestKind, StockSimulationRequest};
+use stock_price_simulator::etf_simulation::{self, EtfDefinition, EtfNavSimulation};
+use stock_price_simulator::export::{self, CsvExportOptions, DEFAULT_TIMESTAMP_FORMAT};
+use stock_price_simulator::futures_simulation::FuturesContract;
+use stock_price_simulator::historical_data::{load_price_history_csv, PriceCsvOptions};
+use stock_price_simulator::option_pricing::EuropeanOption;
+use stock_price_simulator::random_process::TimeSeries;
+
+#[derive(Parser, Debug)]
+#[command(name = "stock_sim_cli", version, about = "Run stock, option, futures and ETF simulations without the HTTP server")]
+struct Cli {
+    /// Output format
+    #[arg(long, value_enum, global = true, default_value = "json")]
+    format: OutputFormat,
+    /// Write the result to this file instead of stdout
+    #[arg(long, short, global = true)]
+    output: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum OutputFormat {
+    Json,
+    Csv,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Simulate a stock price path, like GET /simulate/stock
+    Stock(StockArgs),
+    /// Price a European option
+    #[command(subcommand)]
+    Option(OptionCommand),
+    /// Simulate a futures price path (body of POST /simulate/future)
+    Future(InputArgs),
+    /// Simulate an ETF NAV path (body of POST /simulate/etf)
+    Etf(InputArgs),
+    /// Fit a model to a price history CSV and print the resulting asset model config
+    Calibrate(CalibrateArgs),
+}
+
+#[derive(Subcommand, Debug)]
+enum OptionCommand {
+    /// Black-Scholes (body of POST /simulate/option/black_scholes)
+    Bs(InputArgs),
+    /// Monte Carlo (body of POST /simulate/option/monte_carlo)
+    Mc(InputArgs),
+}
+
+#[derive(Args, Debug)]
+struct InputArgs {
+    /// JSON request file, or - for stdin
+    #[arg(long, short, default_value = "-")]
+    input: String,
+}
+
+#[derive(Args, Debug)]
+struct StockArgs {
+    /// Asset identifier looked up in the config file
+    #[arg(long, default_value = "DEFAULT_STOCK")]
+    asset_identifier: String,
+    /// Config file with the asset models, repeat to layer overlays; without it --drift and --volatility
+    /// are required (plain GBM)
+    #[arg(long)]
+    config: Vec<String>,
+    #[arg(long)]
+    initial_price: f64,
+    /// Defaults to simulation_period_days of the config (required without --config)
+    #[arg(long)]
+    days: Option<usize>,
+    /// Defaults to time_step_minutes of the config, or 1 without --config
+    #[arg(long)]
+    time_step_days: Option<f64>,
+    /// Defaults to a seed derived from random_seed of the config, if it sets one
+    #[arg(long)]
+    seed: Option<u64>,
+    #[arg(long, allow_hyphen_values = true)]
+    drift: Option<f64>,
+    #[arg(long)]
+    volatility: Option<f64>,
+}
+
+#[derive(Args, Debug)]
+struct CalibrateArgs {
+    /// Price history CSV with a header row
+    #[arg(long)]
+    history: String,
+    /// Model to fit, e.g. GeometricBrownianMotion or OrnsteinUhlenbeck
+    #[arg(long)]
+    model: String,
+    #[arg(long, default_value = "stock")]
+    asset_type: String,
+    #[arg(long, default_value = "DEFAULT_STOCK")]
+    asset_identifier_pattern: String,
+    #[arg(long, default_value_t = 1.0)]
+    time_step_days: f64,
+    #[arg(long, default_value = "timestamp")]
+    timestamp_column: String,
+    #[arg(long, default_value = "price")]
+    price_column: String,
+    /// Also add (or replace) the fitted model in this config file
+    #[arg(long)]
+    update_config: Option<String>,
+}
+
+fn read_input<T: DeserializeOwned>(input: &str) -> Result<T> {
+    let mut body = String::new();
+    if input == "-" {
+        std::io::stdin().read_to_string(&mut body).context("Failed to read request from stdin")?;
+    } else {
+        body = std::fs::read_to_string(input).with_context(|| format!("Failed to read request file {}", input))?;
+    }
+    serde_json::from_str(&body).with_context(|| format!("Invalid request JSON in {}", input))
+}
+
+fn format_timestamps(series: &TimeSeries) -> Vec<String> {
+    series.timestamps.iter().map(|t| t.format(DEFAULT_TIMESTAMP_FORMAT).to_string()).collect()
+}
+
+fn to_json<T: Serialize>(data: &T) -> Result<String> {
+    Ok(serde_json::to_string_pretty(data)? + "\n")
+}
+
+// A series is written as JSON (`to_data`) or in the same CSV layout as the HTTP API.
+fn render_series<T: Serialize>(format: OutputFormat, series: TimeSeries, to_data: impl FnOnce(TimeSeries) -> T) -> Result<String> {
+    match format {
+        OutputFormat::Json => to_json(&to_data(series)),
+        OutputFormat::Csv => export::time_series_to_csv(&series, &CsvExportOptions::default()),
+    }
+}
+
+fn render_price(format: OutputFormat, data: OptionData) -> Result<String> {
+    match format {
+        OutputFormat::Json => to_json(&data),
+        OutputFormat::Csv => Ok(format!("option_type,strike_price,price\n{},{},{}\n", data.option_type, data.strike_price, data.price.unwrap_or(f64::NAN))),
+    }
+}
+
+fn option_data(option_type: String, strike_price: f64, price: f64) -> OptionData {
+    OptionData {
+        underlying_symbol: "N/A".to_string(),
+        option_type,
+        strike_price,
+        maturity_date: "N/A (calculated from TTM)".to_string(),
+        price: Some(price),
+        ..Default::default()
+    }
+}
+
+fn run_stock(args: &StockArgs, format: OutputFormat) -> Result<String> {
+    let series = if args.config.is_empty() {
+        let (drift, volatility) = match (args.drift, args.volatility) {
+            (Some(drift), Some(volatility)) => (drift, volatility),
+            _ => return Err(anyhow::anyhow!("--drift and --volatility are required without --config.")),
+        };
+        let days = args.days.ok_or_else(|| anyhow::anyhow!("--days is required without --config."))?;
+        api_interface::simulate_stock(args.initial_price, drift, volatility, days, args.time_step_days.unwrap_or(1.0), args.seed)?
+    } else {
+        let config = load_layered_config(&args.config, std::env::vars())?.config;
+        let query = serde_json::json!({
+            "asset_identifier": args.asset_identifier,
+            "initial_price": args.initial_price,
+            "days": args.days,
+            "time_step_days": args.time_step_days,
+            "seed": args.seed,
+            "drift": args.drift,
+            "volatility": args.volatility,
+        });
+        let request: StockSimulationRequest = with_defaults(&config, RequestKind::Stock, query)?;
+        api_interface::simulate_stock_with_config(
+            &request.asset_identifier,
+            &config,
+            request.initial_price,
+            request.days,
+            request.time_step_days,
+            request.seed,
+            request.drift,
+            request.volatility,
+        )?
+    };
+    render_series(format, series, |series| StockData {
+        symbol: args.asset_identifier.clone(),
+        timestamps: format_timestamps(&series),
+        prices: series.prices,
+    })
+}
+
+fn run_calibrate(args: &CalibrateArgs, format: OutputFormat) -> Result<String> {
+    // Checked first, so a rejected command never leaves the config file changed.
+    if format == OutputFormat::Csv {
+        return Err(anyhow::anyhow!("calibrate writes JSON only; use --format json."));
+    }
+    let model: ModelType = serde_json::from_value(serde_json::Value::String(args.model.clone()))
+        .map_err(|_| anyhow::anyhow!("Unknown model '{}'.", args.model))?;
+    let csv_options = PriceCsvOptions {
+        timestamp_column: args.timestamp_column.clone(),
+        price_column: args.price_column.clone(),
+        ..Default::default()
+    };
+    let history = load_price_history_csv(&args.history, &csv_options)?;
+    let asset_model = api_interface::calibrate_asset_model(
+        &args.asset_type,
+        &args.asset_identifier_pattern,
+        &history,
+        model,
+        args.time_step_days,
+    )?;
+
+    if let Some(config_path) = &args.update_config {
+        let mut config = config::load_config(config_path)?;
+        config::upsert_asset_model(&mut config, asset_model.clone());
+        config::save_config(&config, config_path)?;
+    }
+    to_json(&asset_model)
+}
+
+fn run(cli: &Cli) -> Result<String> {
+    let format = cli.format;
+    match &cli.command {
+        Command::Stock(args) => run_stock(args, format),
+        Command::Option(OptionCommand::Bs(args)) => {
+            let option: EuropeanOption = read_input(&args.input)?;
+            let price = api_interface::price_european_option(&option)?;
+            render_price(format, option_data(format!("{:?}", option.option_type), option.strike_price, price))
+        }
+        Command::Option(OptionCommand::Mc(args)) => {
+            let input: MonteCarloEuropeanOptionInput = read_input(&args.input)?;
+            let price = api_interface::price_european_option_monte_carlo(&input)?;
+            render_price(format, option_data(format!("{:?}", input.option_type), input.strike_price, price))
+        }
+        Command::Future(args) => {
+            let contract: FuturesContract = read_input(&args.input)?;
+            let series = api_interface::simulate_futures(&contract)?;
+            render_series(format, series, |series| FutureData {
+                contract_symbol: contract.underlying_symbol.clone(),
+                timestamps: format_timestamps(&series),
+                prices: series.prices,
+                spot_prices: None,
+            })
+        }
+        Command::Etf(args) => {
+            let etf_def: EtfDefinition = read_input(&args.input)?;
+            let simulation = etf_simulation::simulate_etf_with_market(&etf_def)?;
+            render_series(format, simulation.nav, |nav| EtfData::from(EtfNavSimulation { nav, ..simulation }))
+        }
+        Command::Calibrate(args) => run_calibrate(args, format),
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let result = run(&cli).and_then(|rendered| match &cli.output {
+        Some(path) => std::fs::write(path, rendered).with_context(|| format!("Failed to write {}", path)),
+        None => std::io::stdout().write_all(rendered.as_bytes()).context("Failed to write to stdout"),
+    });
+    if let Err(e) = result {
+        eprintln!("Error: {:#}", e);
+        std::process::exit(1);
+    }
+}
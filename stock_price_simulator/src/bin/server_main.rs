@@ -1,89 +1,127 @@
-use stock_price_simulator::http_server::run_server;
-use stock_price_simulator::config::GlobalConfig; // For type annotation
-use actix_web::web; // For web::Data
+use stock_price_simulator::http_server::{run_server_with_options, ServerOptions, API_PREFIX};
+use stock_price_simulator::config_layers::{load_layered_config, LayeredConfig};
+use stock_price_simulator::config_store::ConfigStore;
+use stock_price_simulator::openapi::openapi_document;
+use clap::Parser;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::Duration;
+
+const LOG_LEVELS: [&str; 6] = ["off", "error", "warn", "info", "debug", "trace"];
+
+// Every flag can also be set through its environment variable, so containers can be configured
+// without changing the command line. Flags win over the environment.
+#[derive(Parser, Debug)]
+#[command(name = "stock_sim_server", version, about = "Stock price simulator HTTP server")]
+struct ServerArgs {
+    /// Address to bind
+    #[arg(long, env = "STOCK_SIM_HOST", default_value = "127.0.0.1")]
+    host: String,
+    #[arg(long, env = "STOCK_SIM_PORT", default_value_t = 8080)]
+    port: u16,
+    /// Config file (TOML, YAML or JSON). Repeat, or separate with commas, to layer overlays on a base file
+    #[arg(long, env = "STOCK_SIM_CONFIG", default_value = "config.toml", value_delimiter = ',')]
+    config: Vec<String>,
+    /// Number of worker threads (default: one per physical core)
+    #[arg(long, env = "STOCK_SIM_WORKERS")]
+    workers: Option<NonZeroUsize>,
+    #[arg(long, env = "STOCK_SIM_LOG_LEVEL", default_value = "info", value_parser = LOG_LEVELS)]
+    log_level: String,
+    /// Seconds between checks of the config file for changes; 0 reloads only on SIGHUP or POST /admin/config/reload
+    #[arg(long, env = "STOCK_SIM_CONFIG_POLL_SECS", default_value_t = 5)]
+    config_poll_secs: u64,
+    /// Token the admin routes require as 'Authorization: Bearer <token>'; without one they only answer loopback clients
+    #[arg(long, env = "STOCK_SIM_ADMIN_TOKEN", hide_env_values = true)]
+    admin_token: Option<String>,
+    /// Validate the config file, print a report and exit (non-zero if it is not usable)
+    #[arg(long)]
+    check_config: bool,
+}
+
+// Loads the config layers and builds every asset model once, so a bad file is reported before serving.
+fn load_checked_config(config_paths: &[String]) -> Result<LayeredConfig, Vec<String>> {
+    if let Some(missing) = config_paths.iter().find(|path| std::fs::metadata(path).is_err()) {
+        return Err(vec![format!(
+            "Config file '{}' not found. Pass --config or set STOCK_SIM_CONFIG (config.example.toml is a starting point).",
+            missing
+        )]);
+    }
+    let layered = load_layered_config(config_paths, std::env::vars())
+        .map_err(|e| vec![format!("Failed to parse {}: {:#}", config_paths.join(", "), e)])?;
+    let problems = stock_price_simulator::api_interface::check_asset_models(&layered.config);
+    if problems.is_empty() {
+        Ok(layered)
+    } else {
+        Err(problems)
+    }
+}
+
+fn print_problems(config_path: &str, problems: &[String]) {
+    eprintln!("Config {} is invalid ({} problem(s)):", config_path, problems.len());
+    for problem in problems {
+        eprintln!("  - {}", problem);
+    }
+}
 
 #[tokio::main]
 async fn main() -> std::io::Result<()> {
-    let address = "127.0.0.1:8080";
-    let base_url = format!("http://{}", address);
+    let args = ServerArgs::parse();
 
-    // Load configuration
-    let config_path = "config.toml";
-    if std::fs::metadata(config_path).is_err() {
-        match std::fs::copy("config.example.toml", config_path) {
-            Ok(_) => println!("Copied config.example.toml to {}", config_path),
-            Err(e) => {
-                eprintln!("Error copying config.example.toml to {}: {}. Please ensure config.example.toml exists or create a valid {}.", e, config_path, config_path);
-                // Depending on desired behavior, might exit or use hardcoded defaults.
-                // For this example, we'll proceed, and load_config will likely fail if file is still missing.
-            }
+    let config_source = args.config.join(", ");
+    let layered = match load_checked_config(&args.config) {
+        Ok(layered) => layered,
+        Err(problems) => {
+            print_problems(&config_source, &problems);
+            std::process::exit(1);
+        }
+    };
+    if args.check_config {
+        println!(
+            "Config {} is valid: {} asset model(s).",
+            config_source,
+            layered.config.asset_models.as_ref().map_or(0, Vec::len)
+        );
+        if layered.files.len() > 1 {
+            println!("  Files read: {}", layered.files.join(", "));
         }
+        if !layered.env_overrides.is_empty() {
+            println!("  Overridden from the environment: {}", layered.env_overrides.join(", "));
+        }
+        return Ok(());
     }
 
-    let config: GlobalConfig = match stock_price_simulator::config::load_config(config_path) {
-        Ok(cfg) => cfg,
+    let options = ServerOptions {
+        address: format!("{}:{}", args.host, args.port),
+        workers: args.workers.map(NonZeroUsize::get),
+        log_level: args.log_level.clone(),
+        config_poll_interval: (args.config_poll_secs > 0).then(|| Duration::from_secs(args.config_poll_secs)),
+        admin_token: args.admin_token.clone(),
+    };
+    let base_url = format!("http://{}", options.address);
+    let config_store = match ConfigStore::from_layers(layered, args.config.clone()) {
+        Ok(store) => Arc::new(store),
         Err(e) => {
-            eprintln!("Failed to load configuration from {}: {}", config_path, e);
-            eprintln!("Please ensure '{}' is correctly formatted or remove it to use defaults from a copied example.", config_path);
-            // Fallback or panic. For this example, we'll panic as config is essential.
-            // In a real app, you might use default values or provide a clearer recovery path.
-            panic!("Configuration error: {}", e);
+            print_problems(&config_source, &[e.to_string()]);
+            std::process::exit(1);
         }
     };
-    let app_config_data = web::Data::new(config.clone()); // Clone for Actix app data
 
     println!("Starting server on {} ...", base_url);
     println!("-----------------------------------------------------------------------");
-    println!("API Endpoints - Try these in your browser or API client (e.g., Postman, curl):");
-    println!("-----------------------------------------------------------------------");
-
-    // Stock Simulation (GET)
-    println!("\n[GET] Stock Simulation (using config):");
-    println!("  Calculates a simulated stock price path. Uses parameters from config if not overridden.");
-    println!("  Example (using 'DEFAULT_STOCK' from config): {}/simulate/stock?asset_identifier=DEFAULT_STOCK&initial_price=150&days=20&time_step_days=1&seed=123", base_url);
-    println!("  Example (overriding drift & vol): {}/simulate/stock?asset_identifier=DEFAULT_STOCK&initial_price=150&days=20&time_step_days=1&drift=0.07&volatility=0.25&seed=123", base_url);
-
-
-    // Option Pricing - Black-Scholes (POST)
-    println!("\n[POST] Option Pricing (Black-Scholes):");
-    println!("  Calculates the price of a European option using the Black-Scholes model.");
-    println!("  Endpoint: {}/simulate/option/black_scholes", base_url);
-    println!("  Method: POST");
-    println!("  Body (JSON): {{ \"underlying_price\": 100.0, \"strike_price\": 105.0, \"time_to_maturity_years\": 0.5, \"risk_free_rate\": 0.02, \"volatility\": 0.22, \"option_type\": \"Call\" }}");
-    println!("  (Note: OptionType can be \"Call\" or \"Put\")");
-
-    // Option Pricing - Monte Carlo (POST)
-    println!("\n[POST] Option Pricing (Monte Carlo):");
-    println!("  Calculates the price of a European option using Monte Carlo simulation.");
-    println!("  Endpoint: {}/simulate/option/monte_carlo", base_url);
-    println!("  Method: POST");
-    println!("  Body (JSON): {{ \"underlying_initial_price\": 100.0, \"strike_price\": 102.0, \"time_to_maturity_years\": 0.75, \"risk_free_rate\": 0.025, \"underlying_volatility\": 0.20, \"option_type\": \"Put\", \"num_paths\": 10000, \"num_steps_per_path\": 100, \"seed\": 456 }}");
-    // Corrected field name to num_steps_per_path in the example
-
-    // Futures Simulation (POST)
-    println!("\n[POST] Futures Simulation:");
-    println!("  Simulates a futures contract price path.");
-    println!("  Endpoint: {}/simulate/future", base_url);
-    println!("  Method: POST");
-    println!("  Body (JSON): {{ \"underlying_symbol\": \"CRUDE_OIL\", \"initial_spot_price\": 70.0, \"risk_free_rate\": 0.03, \"volatility\": 0.25, \"time_to_maturity_days\": 90, \"time_step_days\": 1, \"seed\": 789 }}");
-
-    // ETF Simulation (POST)
-    println!("\n[POST] ETF Simulation:");
-    println!("  Simulates the Net Asset Value (NAV) of an ETF based on its constituents.");
-    println!("  Endpoint: {}/simulate/etf", base_url);
-    println!("  Method: POST");
-    println!("  Body (JSON):");
-    println!("  {{");
-    println!("    \"constituents\": [");
-    println!("      {{ \"symbol\": \"STOCK_A\", \"initial_price\": 50.0, \"drift\": 0.1, \"volatility\": 0.3, \"weight\": 0.6 }},");
-    println!("      {{ \"symbol\": \"STOCK_B\", \"initial_price\": 80.0, \"drift\": 0.05, \"volatility\": 0.2, \"weight\": 0.4 }}");
-    println!("    ],");
-    println!("    \"simulation_days\": 15,");
-    println!("    \"time_step_days\": 1,");
-    println!("    \"seed\": 101");
-    println!("  }}");
+    // Listed from the OpenAPI document, so this stays in step with the routes.
+    let document = openapi_document();
+    for (path, item) in document["paths"].as_object().into_iter().flatten() {
+        for (method, operation) in item.as_object().into_iter().flatten() {
+            let summary = operation["summary"].as_str().unwrap_or_default();
+            println!("[{:<4}] {}{}  {}", method.to_uppercase(), base_url, path, summary);
+        }
+    }
+    println!("Request and response schemas: {}/openapi.json", base_url);
+    println!("Config {} reloads on SIGHUP, when a config file changes and on POST {}{}/admin/config/reload", config_source, base_url, API_PREFIX);
+    if options.admin_token.is_none() {
+        println!("Admin routes answer loopback clients only; set --admin-token to call them remotely");
+    }
     println!("-----------------------------------------------------------------------");
 
-    // Pass app_config_data to run_server
-    run_server(address, app_config_data).await
+    run_server_with_options(&options, config_store).await
 }
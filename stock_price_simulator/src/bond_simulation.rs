@@ -0,0 +1,376 @@
+use crate::random_process::{
+    generate_markov_path, CoxIngersollRoss, OrnsteinUhlenbeck, StochasticProcess, TimeSeries, TRADING_DAYS_PER_YEAR,
+};
+use crate::yield_curve::DiscountCurve;
+use crate::error::{invalid_parameter, within};
+use anyhow::Error;
+use chrono::NaiveDateTime;
+use rand_distr::{Distribution, Normal};
+use serde::Deserialize;
+use schemars::JsonSchema;
+
+// Cash flows and coupon dates closer than this (in years) are treated as simultaneous.
+const TIME_EPSILON: f64 = 1e-9;
+const YIELD_BISECTION_ITERATIONS: usize = 200;
+const MAX_YIELD: f64 = 10.0;
+// The whole rate path and every coupon date are held in memory, so a request may need at most this
+// many of either.
+pub const MAX_BOND_STEPS: usize = 1_000_000;
+// Every step prices the coupon dates still to come, so steps times coupon dates bounds the work.
+pub const MAX_BOND_STEP_COUPONS: f64 = 5_000_000.0;
+
+// One-factor short rate models with closed-form zero-coupon bond prices. Rates are annualized and
+// continuously compounded; time is in years of TRADING_DAYS_PER_YEAR days, as for the other processes.
+// In JSON: {"model": "Vasicek", "mean_reversion_speed": 0.5, ...}.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(tag = "model")]
+pub enum ShortRateModel {
+    // dr = mean_reversion_speed * (long_term_rate - r) dt + volatility dW, simulated exactly.
+    Vasicek { initial_rate: f64, mean_reversion_speed: f64, long_term_rate: f64, volatility: f64 },
+    // dr = mean_reversion_speed * (long_term_rate - r) dt + volatility sqrt(r) dW, full truncation Euler.
+    CoxIngersollRoss { initial_rate: f64, mean_reversion_speed: f64, long_term_rate: f64, volatility: f64 },
+    // dr = (theta(t) - mean_reversion_speed * r) dt + volatility dW with theta(t) chosen so that the
+    // model reprices discount_curve today; the initial rate is the curve's instantaneous forward at 0.
+    HullWhite { mean_reversion_speed: f64, volatility: f64, discount_curve: DiscountCurve },
+}
+
+impl ShortRateModel {
+    pub fn validate(&self) -> Result<(), Error> {
+        let (speed, volatility) = match self {
+            ShortRateModel::Vasicek { mean_reversion_speed, volatility, .. }
+            | ShortRateModel::HullWhite { mean_reversion_speed, volatility, .. } => (*mean_reversion_speed, *volatility),
+            ShortRateModel::CoxIngersollRoss { initial_rate, mean_reversion_speed, long_term_rate, volatility } => {
+                if *initial_rate < 0.0 || *long_term_rate < 0.0 {
+                    let field = if *initial_rate < 0.0 { "initial_rate" } else { "long_term_rate" };
+                    return Err(invalid_parameter(field, "CIR rates cannot be negative."));
+                }
+                if *volatility <= 0.0 {
+                    return Err(invalid_parameter("volatility", format!("CIR volatility must be positive. Got {}", volatility)));
+                }
+                (*mean_reversion_speed, *volatility)
+            }
+        };
+        if speed <= 0.0 {
+            return Err(invalid_parameter("mean_reversion_speed", format!("Mean reversion speed must be positive. Got {}", speed)));
+        }
+        if volatility < 0.0 {
+            return Err(invalid_parameter("volatility", "Volatility cannot be negative."));
+        }
+        Ok(())
+    }
+
+    pub fn initial_rate(&self) -> f64 {
+        match self {
+            ShortRateModel::Vasicek { initial_rate, .. } | ShortRateModel::CoxIngersollRoss { initial_rate, .. } => *initial_rate,
+            ShortRateModel::HullWhite { discount_curve, .. } => discount_curve.instantaneous_forward(0.0),
+        }
+    }
+
+    // Simulates the short rate over `steps` points spaced `dt` days apart.
+    pub fn simulate(&self, dt: f64, steps: usize, seed: Option<u64>) -> TimeSeries {
+        match self {
+            ShortRateModel::Vasicek { initial_rate, mean_reversion_speed, long_term_rate, volatility } => OrnsteinUhlenbeck {
+                mean_reversion_speed: *mean_reversion_speed,
+                long_term_mean: *long_term_rate,
+                volatility: *volatility,
+            }.generate_path(*initial_rate, dt, steps, seed),
+            ShortRateModel::CoxIngersollRoss { initial_rate, mean_reversion_speed, long_term_rate, volatility } => CoxIngersollRoss {
+                mean_reversion_speed: *mean_reversion_speed,
+                long_term_mean: *long_term_rate,
+                volatility: *volatility,
+            }.generate_path(*initial_rate, dt, steps, seed),
+            ShortRateModel::HullWhite { mean_reversion_speed, volatility, discount_curve } => {
+                // r(t) = x(t) + alpha(t) with x a zero-mean OU process, so each step is exact.
+                let (a, sigma) = (*mean_reversion_speed, *volatility);
+                let alpha = |t: f64| discount_curve.instantaneous_forward(t) + sigma * sigma / (2.0 * a * a) * (1.0 - (-a * t).exp()).powi(2);
+                let normal_dist = Normal::new(0.0, 1.0).unwrap();
+                let mut t = 0.0;
+                generate_markov_path(alpha(0.0), dt, steps, seed, |r, dt_years, rng| {
+                    let decay = (-a * dt_years).exp();
+                    let std_dev = sigma * ((1.0 - decay * decay) / (2.0 * a)).sqrt();
+                    let x = (r - alpha(t)) * decay + std_dev * normal_dist.sample(rng);
+                    t += dt_years;
+                    x + alpha(t)
+                })
+            }
+        }
+    }
+
+    // Price at time t of a unit zero-coupon bond maturing at `maturity` (both in years) given the
+    // short rate r at t.
+    pub fn zero_coupon_price(&self, t: f64, maturity: f64, r: f64) -> f64 {
+        let tau = maturity - t;
+        if tau <= 0.0 {
+            return 1.0;
+        }
+        match self {
+            ShortRateModel::Vasicek { mean_reversion_speed: a, long_term_rate: b, volatility: sigma, .. } => {
+                let big_b = (1.0 - (-a * tau).exp()) / a;
+                let log_a = (b - sigma * sigma / (2.0 * a * a)) * (big_b - tau) - sigma * sigma * big_b * big_b / (4.0 * a);
+                (log_a - big_b * r).exp()
+            }
+            ShortRateModel::CoxIngersollRoss { mean_reversion_speed: a, long_term_rate: b, volatility: sigma, .. } => {
+                let h = (a * a + 2.0 * sigma * sigma).sqrt();
+                let growth = (h * tau).exp() - 1.0;
+                let denominator = 2.0 * h + (a + h) * growth;
+                let big_a = (2.0 * h * ((a + h) * tau / 2.0).exp() / denominator).powf(2.0 * a * b / (sigma * sigma));
+                let big_b = 2.0 * growth / denominator;
+                big_a * (-big_b * r).exp()
+            }
+            ShortRateModel::HullWhite { mean_reversion_speed: a, volatility: sigma, discount_curve } => {
+                let big_b = (1.0 - (-a * tau).exp()) / a;
+                let forward = discount_curve.instantaneous_forward(t);
+                let variance_term = sigma * sigma / (4.0 * a) * (1.0 - (-2.0 * a * t).exp()) * big_b * big_b;
+                discount_curve.discount_factor(maturity) / discount_curve.discount_factor(t)
+                    * (big_b * forward - variance_term - big_b * r).exp()
+            }
+        }
+    }
+}
+
+fn default_coupons_per_year() -> u32 {
+    2
+}
+
+// Fixed-coupon bullet bond; a coupon_rate of 0 makes it a zero-coupon bond. Coupons of
+// face_value * coupon_rate / coupons_per_year fall every 1 / coupons_per_year years counting back
+// from maturity. Yields are quoted with the same compounding frequency.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct BondContract {
+    pub bond_symbol: String,
+    pub face_value: f64,
+    #[serde(default)]
+    pub coupon_rate: f64, // Annual
+    #[serde(default = "default_coupons_per_year")]
+    pub coupons_per_year: u32,
+    pub time_to_maturity_years: f64,
+    pub short_rate_model: ShortRateModel,
+    pub time_step_days: f64,
+    pub seed: Option<u64>,
+}
+
+// Bond analytics at every point of a simulated short rate path, up to and including maturity.
+// At maturity the final coupon and face value are about to be paid: the dirty price is their sum,
+// durations and convexity are 0 and the yield is undefined (None).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BondPath {
+    pub timestamps: Vec<NaiveDateTime>,
+    pub short_rates: Vec<f64>,
+    pub dirty_prices: Vec<f64>,
+    pub clean_prices: Vec<f64>,
+    pub accrued_interest: Vec<f64>,
+    pub yields: Vec<Option<f64>>,
+    pub macaulay_durations: Vec<f64>, // Years
+    pub modified_durations: Vec<f64>,
+    pub convexities: Vec<f64>,
+}
+
+impl BondPath {
+    pub fn clean_price_series(&self) -> TimeSeries {
+        TimeSeries { timestamps: self.timestamps.clone(), prices: self.clean_prices.clone() }
+    }
+}
+
+// Yield and risk measures of a bond's remaining cash flows at a given dirty price.
+pub struct YieldAnalytics {
+    pub yield_to_maturity: f64,
+    pub macaulay_duration: f64,
+    pub modified_duration: f64,
+    pub convexity: f64,
+}
+
+impl BondContract {
+    fn validate(&self) -> Result<(), Error> {
+        if self.face_value <= 0.0 {
+            return Err(invalid_parameter("face_value", "Face value must be positive."));
+        }
+        if self.coupon_rate < 0.0 {
+            return Err(invalid_parameter("coupon_rate", "Coupon rate cannot be negative."));
+        }
+        if self.coupons_per_year == 0 {
+            return Err(invalid_parameter("coupons_per_year", "Coupons per year must be at least 1."));
+        }
+        if self.time_to_maturity_years <= 0.0 {
+            return Err(invalid_parameter("time_to_maturity_years", "Time to maturity must be positive."));
+        }
+        if self.time_step_days <= 0.0 {
+            return Err(invalid_parameter("time_step_days", "Time step in days must be positive."));
+        }
+        let steps = self.time_to_maturity_years * TRADING_DAYS_PER_YEAR / self.time_step_days;
+        if !steps.is_finite() || steps > MAX_BOND_STEPS as f64 {
+            return Err(invalid_parameter("time_step_days", format!(
+                "A maturity of {} years in steps of {} days needs {} steps; at most {} are allowed.",
+                self.time_to_maturity_years, self.time_step_days, steps.ceil(), MAX_BOND_STEPS,
+            )));
+        }
+        let coupons = self.time_to_maturity_years * self.coupons_per_year as f64;
+        if coupons > MAX_BOND_STEPS as f64 {
+            return Err(invalid_parameter("coupons_per_year", format!(
+                "A maturity of {} years with {} coupons per year has {} coupon dates; at most {} are allowed.",
+                self.time_to_maturity_years, self.coupons_per_year, coupons.ceil(), MAX_BOND_STEPS,
+            )));
+        }
+        if steps.ceil() * coupons.ceil() > MAX_BOND_STEP_COUPONS {
+            return Err(invalid_parameter("time_step_days", format!(
+                "{} steps over {} coupon dates is too much work; steps times coupon dates may be at most {}.",
+                steps.ceil(), coupons.ceil(), MAX_BOND_STEP_COUPONS,
+            )));
+        }
+        self.short_rate_model.validate().map_err(|e| within("short_rate_model", e))
+    }
+
+    fn coupon_period(&self) -> f64 {
+        1.0 / self.coupons_per_year as f64
+    }
+
+    // Coupon dates in years from today, ascending, ending at maturity.
+    fn coupon_dates(&self) -> Vec<f64> {
+        let period = self.coupon_period();
+        let mut dates: Vec<f64> = (0..)
+            .map(|k| self.time_to_maturity_years - k as f64 * period)
+            .take_while(|t| *t > TIME_EPSILON)
+            .collect();
+        dates.reverse();
+        dates
+    }
+
+    // Index of the first of `dates` (from coupon_dates) still to be paid at t: the first after t, or
+    // maturity itself. The dates ascend, so this is a binary search.
+    fn next_coupon_index(&self, dates: &[f64], t: f64) -> usize {
+        let maturity = self.time_to_maturity_years;
+        dates.partition_point(|date| *date <= t + TIME_EPSILON && (*date - maturity).abs() >= TIME_EPSILON)
+    }
+
+    // Payments on the coupon dates still to come, as (payment time, amount).
+    fn cash_flows_on(&self, dates: &[f64]) -> Vec<(f64, f64)> {
+        let coupon = self.face_value * self.coupon_rate * self.coupon_period();
+        let maturity = self.time_to_maturity_years;
+        dates.iter()
+            .map(|&date| {
+                let redemption = if (date - maturity).abs() < TIME_EPSILON { self.face_value } else { 0.0 };
+                (date, coupon + redemption)
+            })
+            .filter(|(_, amount)| *amount > 0.0)
+            .collect()
+    }
+
+    // Coupon accrued at t since the coupon date before `next_coupon`.
+    fn accrued_before(&self, next_coupon: f64, t: f64) -> f64 {
+        let period = self.coupon_period();
+        let fraction = ((t - (next_coupon - period)) / period).clamp(0.0, 1.0);
+        self.face_value * self.coupon_rate * period * fraction
+    }
+
+    // Cash flows still to be received at time t (years), as (payment time, amount). A coupon paid
+    // exactly at t is excluded, except at maturity.
+    pub fn remaining_cash_flows(&self, t: f64) -> Vec<(f64, f64)> {
+        let dates = self.coupon_dates();
+        self.cash_flows_on(&dates[self.next_coupon_index(&dates, t)..])
+    }
+
+    // Coupon accrued since the last coupon date at time t (years).
+    pub fn accrued_interest(&self, t: f64) -> f64 {
+        let dates = self.coupon_dates();
+        let next_coupon = dates.get(self.next_coupon_index(&dates, t)).copied().unwrap_or(self.time_to_maturity_years);
+        self.accrued_before(next_coupon, t)
+    }
+
+    // Yield to maturity (compounded coupons_per_year times a year) and risk measures of the
+    // remaining cash flows at time t for the given dirty price. None once nothing is left to discount.
+    pub fn yield_analytics(&self, t: f64, dirty_price: f64) -> Option<YieldAnalytics> {
+        self.yield_analytics_of(&self.remaining_cash_flows(t), t, dirty_price)
+    }
+
+    fn yield_analytics_of(&self, remaining: &[(f64, f64)], t: f64, dirty_price: f64) -> Option<YieldAnalytics> {
+        let m = self.coupons_per_year as f64;
+        let flows: Vec<(f64, f64)> = remaining
+            .iter()
+            .map(|&(date, amount)| (date - t, amount))
+            .filter(|(time, _)| *time > TIME_EPSILON)
+            .collect();
+        if flows.is_empty() || dirty_price <= 0.0 {
+            return None;
+        }
+        let present_value = |y: f64| -> f64 { flows.iter().map(|(s, c)| c * (1.0 + y / m).powf(-m * s)).sum() };
+
+        // The present value falls as the yield rises.
+        let (mut lo, mut hi) = (-0.99 * m, MAX_YIELD);
+        if present_value(lo) < dirty_price || present_value(hi) > dirty_price {
+            return None;
+        }
+        for _ in 0..YIELD_BISECTION_ITERATIONS {
+            let mid = 0.5 * (lo + hi);
+            if present_value(mid) > dirty_price {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        let y = 0.5 * (lo + hi);
+
+        let growth = 1.0 + y / m;
+        let price = present_value(y);
+        let macaulay_duration = flows.iter().map(|(s, c)| s * c * growth.powf(-m * s)).sum::<f64>() / price;
+        let convexity = flows.iter().map(|(s, c)| c * s * (s + 1.0 / m) * growth.powf(-m * s)).sum::<f64>()
+            / (price * growth * growth);
+        Some(YieldAnalytics {
+            yield_to_maturity: y,
+            macaulay_duration,
+            modified_duration: macaulay_duration / growth,
+            convexity,
+        })
+    }
+
+    // Dirty price at time t (years) when the short rate is r, from the model's zero-coupon prices.
+    pub fn dirty_price(&self, t: f64, r: f64) -> f64 {
+        self.dirty_price_of(&self.remaining_cash_flows(t), t, r)
+    }
+
+    fn dirty_price_of(&self, remaining: &[(f64, f64)], t: f64, r: f64) -> f64 {
+        remaining.iter().map(|(date, amount)| amount * self.short_rate_model.zero_coupon_price(t, *date, r)).sum()
+    }
+}
+
+// Simulates the short rate from today to maturity in steps of time_step_days and prices the bond
+// off it at every step.
+pub fn simulate_bond(contract: &BondContract) -> Result<BondPath, Error> {
+    contract.validate()?;
+    let maturity_days = contract.time_to_maturity_years * TRADING_DAYS_PER_YEAR;
+    let num_steps = (maturity_days / contract.time_step_days - TIME_EPSILON).ceil() as usize;
+    let rate_path = contract.short_rate_model.simulate(contract.time_step_days, num_steps + 1, contract.seed);
+
+    let points = rate_path.prices.len();
+    let mut path = BondPath {
+        timestamps: rate_path.timestamps,
+        short_rates: Vec::with_capacity(points),
+        dirty_prices: Vec::with_capacity(points),
+        clean_prices: Vec::with_capacity(points),
+        accrued_interest: Vec::with_capacity(points),
+        yields: Vec::with_capacity(points),
+        macaulay_durations: Vec::with_capacity(points),
+        modified_durations: Vec::with_capacity(points),
+        convexities: Vec::with_capacity(points),
+    };
+
+    // The schedule is built once; each step only looks up where it is in it.
+    let dates = contract.coupon_dates();
+    for (i, &r) in rate_path.prices.iter().enumerate() {
+        // When the step does not divide the maturity, the last point is taken as the maturity date.
+        let t = (i as f64 * contract.time_step_days / TRADING_DAYS_PER_YEAR).min(contract.time_to_maturity_years);
+        let next = contract.next_coupon_index(&dates, t);
+        let flows = contract.cash_flows_on(&dates[next..]);
+        let dirty = contract.dirty_price_of(&flows, t, r);
+        let accrued = contract.accrued_before(dates.get(next).copied().unwrap_or(contract.time_to_maturity_years), t);
+        let analytics = contract.yield_analytics_of(&flows, t, dirty);
+
+        path.short_rates.push(r);
+        path.dirty_prices.push(dirty);
+        path.clean_prices.push(dirty - accrued);
+        path.accrued_interest.push(accrued);
+        path.yields.push(analytics.as_ref().map(|a| a.yield_to_maturity));
+        path.macaulay_durations.push(analytics.as_ref().map_or(0.0, |a| a.macaulay_duration));
+        path.modified_durations.push(analytics.as_ref().map_or(0.0, |a| a.modified_duration));
+        path.convexities.push(analytics.as_ref().map_or(0.0, |a| a.convexity));
+    }
+    Ok(path)
+}
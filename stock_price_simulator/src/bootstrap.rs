@@ -0,0 +1,165 @@
+use crate::config::{BootstrapMethod, BootstrapParams, GarchParams};
+use crate::error::{invalid_config, within};
+use crate::historical_data::{load_price_history_csv, log_returns, PriceCsvOptions};
+use crate::random_process::{check_whole_model_steps, generate_markov_path, model_steps_per_step, StochasticProcess, TimeSeries};
+use crate::volatility_models::{fit_garch, garch_conditional_variances, Garch11};
+use anyhow::{Context, Error};
+use rand::Rng;
+use std::path::Path;
+
+#[derive(Debug, Clone)]
+pub enum ResamplingScheme {
+    Iid,
+    StationaryBlock { mean_block_length: f64 },
+    FilteredHistorical { garch: GarchParams }, // Filters with mean_return, omega, alpha and beta
+}
+
+// Non-parametric path generator: each simulated step applies resampled historical log returns.
+// `history_time_step_days` is the spacing of the historical data; a simulated step of dt days, which
+// must be a whole multiple of it, sums dt / history_time_step_days consecutive draws.
+#[derive(Debug, Clone)]
+pub struct HistoricalBootstrap {
+    pub log_returns: Vec<f64>,
+    pub scheme: ResamplingScheme,
+    pub history_time_step_days: f64,
+}
+
+fn history_csv_returns(path: &str, params: &BootstrapParams) -> Result<Vec<f64>, Error> {
+    let defaults = PriceCsvOptions::default();
+    let options = PriceCsvOptions {
+        timestamp_column: params.timestamp_column.clone().unwrap_or(defaults.timestamp_column),
+        price_column: params.price_column.clone().unwrap_or(defaults.price_column),
+        ..defaults
+    };
+    let history = load_price_history_csv(path, &options).with_context(|| format!("Failed to load history_csv {}", path))?;
+    Ok(log_returns(&history.prices))
+}
+
+// Prepares a bootstrap entry at config load so that requests never do the work: reads its
+// history_csv, relative to `base_dir` (the directory of the config file that set it), into
+// history_returns, and for FilteredHistorical without garch fits one into fitted_garch. Returns the
+// resolved history_csv path, if it read one.
+pub fn load_bootstrap_inputs(params: &mut BootstrapParams, base_dir: &Path) -> Result<Option<String>, Error> {
+    let mut read = None;
+    if let Some(path) = &params.history_csv {
+        let resolved = base_dir.join(path).to_string_lossy().into_owned();
+        params.history_returns = Some(history_csv_returns(&resolved, params)?);
+        read = Some(resolved);
+    }
+    if params.method == BootstrapMethod::FilteredHistorical && params.garch.is_none() {
+        if let Some(returns) = params.log_returns.as_ref().or(params.history_returns.as_ref()) {
+            params.fitted_garch = Some(fit_garch(returns, params.history_time_step_days).context("Failed to fit GARCH to the history")?);
+        }
+    }
+    Ok(read)
+}
+
+impl HistoricalBootstrap {
+    pub fn new(log_returns: Vec<f64>, scheme: ResamplingScheme, history_time_step_days: f64) -> Result<Self, Error> {
+        if log_returns.len() < 2 {
+            return Err(invalid_config(Some("log_returns"), "At least two historical returns are required for bootstrapping."));
+        }
+        if log_returns.iter().any(|r| !r.is_finite()) {
+            return Err(invalid_config(Some("log_returns"), "Historical returns must be finite numbers."));
+        }
+        if history_time_step_days <= 0.0 {
+            return Err(invalid_config(Some("history_time_step_days"), "History time step must be positive."));
+        }
+        match &scheme {
+            ResamplingScheme::Iid => {}
+            ResamplingScheme::StationaryBlock { mean_block_length } => {
+                if *mean_block_length < 1.0 {
+                    return Err(invalid_config(Some("mean_block_length"), format!("Mean block length must be at least 1. Got {}", mean_block_length)));
+                }
+            }
+            ResamplingScheme::FilteredHistorical { garch } => {
+                Garch11::from_params(garch).map_err(|e| within("garch", e))?;
+            }
+        }
+        Ok(HistoricalBootstrap { log_returns, scheme, history_time_step_days })
+    }
+
+    // Builds the generator described by a `[asset_models.parameters.bootstrap]` config entry.
+    pub fn from_params(params: &BootstrapParams) -> Result<Self, Error> {
+        let returns = match (&params.log_returns, &params.history_csv) {
+            (Some(returns), None) => returns.clone(),
+            (None, Some(path)) => match &params.history_returns {
+                Some(returns) => returns.clone(),
+                None => history_csv_returns(path, params)?,
+            },
+            (Some(_), Some(_)) => {
+                return Err(invalid_config(Some("history_csv"), "Bootstrap parameters must set only one of log_returns or history_csv."))
+            }
+            (None, None) => {
+                return Err(invalid_config(Some("log_returns"), "Bootstrap parameters must set either log_returns or history_csv."))
+            }
+        };
+        let scheme = match params.method {
+            BootstrapMethod::Iid => ResamplingScheme::Iid,
+            BootstrapMethod::StationaryBlock => ResamplingScheme::StationaryBlock {
+                mean_block_length: params.mean_block_length
+                    .ok_or_else(|| invalid_config(Some("mean_block_length"), "StationaryBlock bootstrap requires mean_block_length."))?,
+            },
+            BootstrapMethod::FilteredHistorical => {
+                let garch = match params.garch.as_ref().or(params.fitted_garch.as_ref()) {
+                    Some(garch) => garch.clone(),
+                    None => fit_garch(&returns, params.history_time_step_days)?,
+                };
+                ResamplingScheme::FilteredHistorical { garch }
+            }
+        };
+        Self::new(returns, scheme, params.history_time_step_days)
+    }
+
+}
+
+impl StochasticProcess for HistoricalBootstrap {
+    fn generate_path(&self, initial_value: f64, dt: f64, steps: usize, seed: Option<u64>) -> TimeSeries {
+        let n = self.log_returns.len();
+        let draws = model_steps_per_step(dt, self.history_time_step_days);
+
+        match &self.scheme {
+            ResamplingScheme::Iid => generate_markov_path(initial_value, dt, steps, seed, |s, _, rng| {
+                let log_return: f64 = (0..draws).map(|_| self.log_returns[rng.gen_range(0..n)]).sum();
+                s * log_return.exp()
+            }),
+            ResamplingScheme::StationaryBlock { mean_block_length } => {
+                let restart_probability = 1.0 / mean_block_length;
+                let mut index: Option<usize> = None;
+                generate_markov_path(initial_value, dt, steps, seed, |s, _, rng| {
+                    let mut log_return = 0.0;
+                    for _ in 0..draws {
+                        let next = match index {
+                            Some(i) if rng.gen::<f64>() >= restart_probability => (i + 1) % n, // Continue the block, wrapping circularly
+                            _ => rng.gen_range(0..n),
+                        };
+                        index = Some(next);
+                        log_return += self.log_returns[next];
+                    }
+                    s * log_return.exp()
+                })
+            }
+            ResamplingScheme::FilteredHistorical { garch } => {
+                let mean = garch.mean_return;
+                let residuals: Vec<f64> = self.log_returns.iter().map(|r| r - mean).collect();
+                let (variances, forecast) = garch_conditional_variances(&residuals, garch.omega, garch.alpha, garch.beta);
+                let standardized: Vec<f64> = residuals.iter().zip(&variances).map(|(e, v)| e / v.sqrt()).collect();
+
+                let mut variance = forecast;
+                generate_markov_path(initial_value, dt, steps, seed, |s, _, rng| {
+                    let mut log_return = 0.0;
+                    for _ in 0..draws {
+                        let shock = variance.sqrt() * standardized[rng.gen_range(0..n)];
+                        log_return += mean + shock;
+                        variance = garch.omega + garch.alpha * shock * shock + garch.beta * variance;
+                    }
+                    s * log_return.exp()
+                })
+            }
+        }
+    }
+
+    fn check_time_step(&self, dt: f64) -> Result<(), Error> {
+        check_whole_model_steps(dt, self.history_time_step_days, "history time step")
+    }
+}
@@ -0,0 +1,358 @@
+use crate::config::{
+    AssetModelConfig, ModelType, ModelParameters, GeometricBrownianMotionParams, OrnsteinUhlenbeckParams,
+    CoxIngersollRossParams, JumpDiffusionParams, HestonParams,
+};
+use crate::fourier_pricing::{CharacteristicModel, FourierOptionPricer};
+use crate::config::OptionType;
+use crate::error::{invalid_parameter, within, ErrorCode, SimulationError};
+use crate::historical_data::log_returns;
+use crate::optimization::{nelder_mead, NelderMeadOptions};
+use crate::volatility_models::{fit_garch, fit_egarch};
+use crate::random_process::{TimeSeries, TRADING_DAYS_PER_YEAR};
+use anyhow::Error;
+
+// Calibrators estimate annualized parameters in the same convention the simulators use:
+// each observation is one step of `time_step_days` trading days, i.e. dt = time_step_days / 252 years.
+
+const MIN_OBSERVATIONS: usize = 3;
+const MIN_JUMP_OBSERVATIONS: usize = 30;
+const MAX_JUMPS_PER_STEP: usize = 10; // Truncation of the Poisson mixture in the Merton likelihood
+const JUMP_THRESHOLD_STD_DEVS: f64 = 3.0;
+const HESTON_FIT_RESTARTS: usize = 3; // Nelder-Mead restarts from the best point found so far
+
+fn step_in_years(time_step_days: f64) -> Result<f64, Error> {
+    if time_step_days <= 0.0 {
+        return Err(invalid_parameter("time_step_days", "Time step must be positive."));
+    }
+    Ok(time_step_days / TRADING_DAYS_PER_YEAR)
+}
+
+fn check_length(values: &[f64], min_len: usize) -> Result<(), Error> {
+    if values.len() < min_len {
+        return Err(invalid_parameter("prices", format!(
+            "At least {} observations are required for calibration, got {}.",
+            min_len,
+            values.len()
+        )));
+    }
+    if values.iter().any(|v| !v.is_finite()) {
+        return Err(invalid_parameter("prices", "Observations must be finite numbers."));
+    }
+    Ok(())
+}
+
+fn check_positive(values: &[f64]) -> Result<(), Error> {
+    if values.iter().any(|&v| v <= 0.0) {
+        return Err(invalid_parameter("prices", "All observations must be positive for this model."));
+    }
+    Ok(())
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+// Maximum-likelihood (1/n) variance.
+fn mle_variance(values: &[f64], mean: f64) -> f64 {
+    values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64
+}
+
+// GBM drift and volatility from the MLE of i.i.d. normal log returns.
+pub fn calibrate_gbm(prices: &[f64], time_step_days: f64) -> Result<GeometricBrownianMotionParams, Error> {
+    let dt = step_in_years(time_step_days)?;
+    check_length(prices, MIN_OBSERVATIONS)?;
+    check_positive(prices)?;
+
+    let returns = log_returns(prices);
+    let m = mean(&returns);
+    let variance = mle_variance(&returns, m);
+
+    let volatility = (variance / dt).sqrt();
+    let drift = m / dt + 0.5 * volatility.powi(2);
+    Ok(GeometricBrownianMotionParams { drift, volatility })
+}
+
+// OU parameters from the exact AR(1) discretisation x_{t+1} = a + b x_t + e, whose
+// conditional MLE coincides with ordinary least squares.
+pub fn calibrate_ou(values: &[f64], time_step_days: f64) -> Result<OrnsteinUhlenbeckParams, Error> {
+    let dt = step_in_years(time_step_days)?;
+    check_length(values, MIN_OBSERVATIONS)?;
+
+    let x = &values[..values.len() - 1];
+    let y = &values[1..];
+    let (x_mean, y_mean) = (mean(x), mean(y));
+    let sxx: f64 = x.iter().map(|xi| (xi - x_mean).powi(2)).sum();
+    let sxy: f64 = x.iter().zip(y).map(|(xi, yi)| (xi - x_mean) * (yi - y_mean)).sum();
+    if sxx <= 0.0 {
+        return Err(invalid_parameter("prices", "Series is constant; OU parameters are not identifiable."));
+    }
+
+    let b = sxy / sxx;
+    let a = y_mean - b * x_mean;
+    if b <= 0.0 || b >= 1.0 {
+        return Err(invalid_parameter("prices", format!(
+            "Series shows no mean reversion (AR(1) coefficient {:.6} is outside (0, 1)).",
+            b
+        )));
+    }
+    let residual_variance = x.iter().zip(y).map(|(xi, yi)| (yi - a - b * xi).powi(2)).sum::<f64>() / x.len() as f64;
+
+    let mean_reversion_speed = -b.ln() / dt;
+    let long_term_mean = a / (1.0 - b);
+    let volatility = (residual_variance * 2.0 * mean_reversion_speed / (1.0 - b * b)).sqrt();
+    Ok(OrnsteinUhlenbeckParams { mean_reversion_speed, long_term_mean, volatility })
+}
+
+// CIR parameters by least squares on the Euler discretisation scaled by sqrt(x_t):
+// (x_{t+1} - x_t) / sqrt(x_t) = kappa*theta*dt / sqrt(x_t) - kappa*dt*sqrt(x_t) + sigma*sqrt(dt)*e.
+pub fn calibrate_cir(values: &[f64], time_step_days: f64) -> Result<CoxIngersollRossParams, Error> {
+    let dt = step_in_years(time_step_days)?;
+    check_length(values, MIN_OBSERVATIONS)?;
+    check_positive(values)?;
+
+    let (mut s11, mut s12, mut s22, mut s1y, mut s2y) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    let mut samples = Vec::with_capacity(values.len() - 1);
+    for w in values.windows(2) {
+        let sqrt_x = w[0].sqrt();
+        let z1 = dt / sqrt_x;
+        let z2 = dt * sqrt_x;
+        let y = (w[1] - w[0]) / sqrt_x;
+        s11 += z1 * z1;
+        s12 += z1 * z2;
+        s22 += z2 * z2;
+        s1y += z1 * y;
+        s2y += z2 * y;
+        samples.push((z1, z2, y));
+    }
+    let det = s11 * s22 - s12 * s12;
+    if det.abs() < f64::EPSILON * s11 * s22 {
+        return Err(invalid_parameter("prices", "Series is constant; CIR parameters are not identifiable."));
+    }
+    let beta1 = (s22 * s1y - s12 * s2y) / det;
+    let beta2 = (s11 * s2y - s12 * s1y) / det;
+
+    let mean_reversion_speed = -beta2;
+    if mean_reversion_speed <= 0.0 || beta1 <= 0.0 {
+        return Err(invalid_parameter("prices", "Series shows no mean reversion to a positive level; CIR fit failed."));
+    }
+    let long_term_mean = beta1 / mean_reversion_speed;
+    let residual_variance = samples.iter()
+        .map(|(z1, z2, y)| (y - beta1 * z1 - beta2 * z2).powi(2))
+        .sum::<f64>() / samples.len() as f64;
+    let volatility = (residual_variance / dt).sqrt();
+    Ok(CoxIngersollRossParams { mean_reversion_speed, long_term_mean, volatility })
+}
+
+// Per-step log-return density of the Merton model: a Poisson mixture of normals.
+fn merton_step_log_likelihood(returns: &[f64], diffusion_mean: f64, diffusion_std: f64, jumps_per_step: f64, jump_mean: f64, jump_std: f64) -> f64 {
+    let mut poisson_weights = Vec::with_capacity(MAX_JUMPS_PER_STEP + 1);
+    let mut weight = (-jumps_per_step).exp();
+    for k in 0..=MAX_JUMPS_PER_STEP {
+        if k > 0 {
+            weight *= jumps_per_step / k as f64;
+        }
+        poisson_weights.push(weight);
+    }
+
+    returns.iter().map(|&r| {
+        let density: f64 = poisson_weights.iter().enumerate().map(|(k, w)| {
+            let variance = diffusion_std.powi(2) + k as f64 * jump_std.powi(2);
+            let mu = diffusion_mean + k as f64 * jump_mean;
+            w * (-(r - mu).powi(2) / (2.0 * variance)).exp() / (2.0 * std::f64::consts::PI * variance).sqrt()
+        }).sum();
+        density.max(f64::MIN_POSITIVE).ln()
+    }).sum()
+}
+
+// Merton jump-diffusion parameters by maximum likelihood. The optimiser is seeded from a
+// threshold split of the returns into diffusive moves and jumps.
+pub fn calibrate_jump_diffusion(prices: &[f64], time_step_days: f64) -> Result<JumpDiffusionParams, Error> {
+    let dt = step_in_years(time_step_days)?;
+    check_length(prices, MIN_JUMP_OBSERVATIONS)?;
+    check_positive(prices)?;
+    let returns = log_returns(prices);
+
+    // Initial guess: iteratively strip returns beyond the threshold and treat them as jumps.
+    let mut diffusive: Vec<f64> = returns.clone();
+    for _ in 0..5 {
+        let m = mean(&diffusive);
+        let s = mle_variance(&diffusive, m).sqrt();
+        let kept: Vec<f64> = returns.iter().copied().filter(|r| (r - m).abs() <= JUMP_THRESHOLD_STD_DEVS * s).collect();
+        if kept.len() == diffusive.len() || kept.len() < MIN_OBSERVATIONS {
+            break;
+        }
+        diffusive = kept;
+    }
+    let diffusion_mean0 = mean(&diffusive);
+    let diffusion_std0 = mle_variance(&diffusive, diffusion_mean0).sqrt().max(1e-8);
+    let jumps: Vec<f64> = returns.iter().map(|r| r - diffusion_mean0)
+        .filter(|r| r.abs() > JUMP_THRESHOLD_STD_DEVS * diffusion_std0)
+        .collect();
+    let (jumps_per_step0, jump_mean0, jump_std0) = if jumps.is_empty() {
+        (0.5 / returns.len() as f64, 0.0, JUMP_THRESHOLD_STD_DEVS * diffusion_std0)
+    } else {
+        let jm = mean(&jumps);
+        (jumps.len() as f64 / returns.len() as f64, jm, mle_variance(&jumps, jm).sqrt().max(diffusion_std0))
+    };
+
+    // Optimise over unconstrained coordinates: [mean, ln std, ln intensity, jump mean, ln jump std].
+    let initial = [diffusion_mean0, diffusion_std0.ln(), jumps_per_step0.ln(), jump_mean0, jump_std0.ln()];
+    let options = NelderMeadOptions {
+        max_iterations: 5000,
+        tolerance: 1e-9,
+        initial_step: vec![diffusion_std0 * 0.1, 0.1, 0.5, diffusion_std0, 0.2],
+    };
+    let result = nelder_mead(
+        |p| {
+            let jumps_per_step = p[2].exp();
+            if jumps_per_step > 1.0 {
+                return f64::INFINITY; // More than one expected jump per step is outside the truncation's accuracy
+            }
+            -merton_step_log_likelihood(&returns, p[0], p[1].exp(), jumps_per_step, p[3], p[4].exp())
+        },
+        &initial,
+        &options,
+    );
+    if !result.value.is_finite() {
+        return Err(SimulationError::new(ErrorCode::SimulationFailed, None, "Jump-diffusion likelihood optimisation failed.").into());
+    }
+
+    let p = &result.point;
+    let volatility = p[1].exp() / dt.sqrt();
+    let jump_intensity = p[2].exp() / dt;
+    let jump_mean = p[3];
+    let jump_volatility = p[4].exp();
+    let compensator = (jump_mean + 0.5 * jump_volatility.powi(2)).exp() - 1.0;
+    let drift = p[0] / dt + 0.5 * volatility.powi(2) + jump_intensity * compensator;
+
+    Ok(JumpDiffusionParams { drift, volatility, jump_intensity, jump_mean, jump_volatility })
+}
+
+// A quoted European option price used as a calibration target.
+#[derive(Debug, Clone)]
+pub struct OptionQuote {
+    pub strike_price: f64,
+    pub time_to_maturity_years: f64,
+    pub option_type: OptionType,
+    pub price: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct HestonCalibration {
+    pub params: HestonParams, // drift is set to the risk-free rate
+    pub rmse: f64,            // Root mean squared price error over the quotes
+}
+
+// Least-squares fit of Heston parameters to quoted option prices, each priced with
+// FourierOptionPricer. Parameters are optimised in log space (correlation through tanh) so every
+// trial point is admissible; `initial_guess` seeds the search.
+pub fn calibrate_heston(
+    underlying_price: f64,
+    risk_free_rate: f64,
+    quotes: &[OptionQuote],
+    initial_guess: &HestonParams,
+) -> Result<HestonCalibration, Error> {
+    if quotes.is_empty() {
+        return Err(invalid_parameter("quotes", "At least one option quote is required for Heston calibration."));
+    }
+    if let Some(i) = quotes.iter().position(|q| !q.price.is_finite() || q.price < 0.0 || q.time_to_maturity_years <= 0.0) {
+        return Err(invalid_parameter(&format!("quotes[{}]", i), "Option quotes need non-negative prices and positive maturities."));
+    }
+    initial_guess.validate().map_err(|e| within("initial_guess", e))?;
+    if initial_guess.initial_variance <= 0.0 || initial_guess.correlation.abs() >= 1.0 {
+        let field = if initial_guess.initial_variance <= 0.0 { "initial_guess.initial_variance" } else { "initial_guess.correlation" };
+        return Err(invalid_parameter(field, "Initial guess needs a positive initial variance and |correlation| < 1."));
+    }
+
+    let unpack = |p: &[f64]| HestonParams {
+        drift: risk_free_rate,
+        initial_variance: p[0].exp(),
+        mean_reversion_speed: p[1].exp(),
+        long_term_variance: p[2].exp(),
+        vol_of_vol: p[3].exp(),
+        correlation: p[4].tanh(),
+    };
+    let sum_squared_errors = |p: &[f64]| {
+        let model = CharacteristicModel::Heston(unpack(p));
+        let mut total = 0.0;
+        for quote in quotes {
+            let pricer = FourierOptionPricer {
+                underlying_price,
+                strike_price: quote.strike_price,
+                time_to_maturity_years: quote.time_to_maturity_years,
+                risk_free_rate,
+                option_type: quote.option_type,
+                model: model.clone(),
+            };
+            match pricer.fourier_price() {
+                Ok(price) => total += (price - quote.price).powi(2),
+                Err(_) => return f64::INFINITY,
+            }
+        }
+        total
+    };
+
+    let mut point = vec![
+        initial_guess.initial_variance.ln(),
+        initial_guess.mean_reversion_speed.ln(),
+        initial_guess.long_term_variance.ln(),
+        initial_guess.vol_of_vol.ln(),
+        initial_guess.correlation.atanh(),
+    ];
+    let options = NelderMeadOptions { max_iterations: 2000, tolerance: 1e-14, initial_step: vec![0.3; 5] };
+    let mut value = f64::INFINITY;
+    for _ in 0..HESTON_FIT_RESTARTS {
+        let result = nelder_mead(sum_squared_errors, &point, &options);
+        let improved = result.value < value * (1.0 - 1e-6);
+        point = result.point;
+        value = result.value;
+        if !improved {
+            break;
+        }
+    }
+    if !value.is_finite() {
+        return Err(SimulationError::new(ErrorCode::SimulationFailed, None, "Heston calibration failed to price the quotes.").into());
+    }
+
+    Ok(HestonCalibration { params: unpack(&point), rmse: (value / quotes.len() as f64).sqrt() })
+}
+
+// Fits `model` to a historical series and wraps the result in an AssetModelConfig that can be
+// merged into a GlobalConfig (see config::upsert_asset_model) and saved back to TOML.
+pub fn calibrate_asset_model(
+    asset_type: &str,
+    asset_identifier_pattern: &str,
+    history: &TimeSeries,
+    model: ModelType,
+    time_step_days: f64,
+) -> Result<AssetModelConfig, Error> {
+    let mut parameters = ModelParameters::default();
+    match model {
+        ModelType::GeometricBrownianMotion => parameters.gbm = Some(calibrate_gbm(&history.prices, time_step_days)?),
+        ModelType::OrnsteinUhlenbeck => parameters.ou = Some(calibrate_ou(&history.prices, time_step_days)?),
+        ModelType::CoxIngersollRoss => parameters.cir = Some(calibrate_cir(&history.prices, time_step_days)?),
+        ModelType::MertonJumpDiffusion => {
+            parameters.jump_diffusion = Some(calibrate_jump_diffusion(&history.prices, time_step_days)?)
+        }
+        ModelType::Garch => parameters.garch = Some(fit_garch(&log_returns(&history.prices), time_step_days)?),
+        ModelType::Egarch => parameters.egarch = Some(fit_egarch(&log_returns(&history.prices), time_step_days)?),
+        ModelType::HistoricalBootstrap => {
+            return Err(invalid_parameter("model", "HistoricalBootstrap is non-parametric and has nothing to calibrate."))
+        }
+        ModelType::RegimeSwitching => {
+            return Err(invalid_parameter("model", "Calibration of RegimeSwitching models is not supported; configure the regimes directly."))
+        }
+        ModelType::Heston => {
+            return Err(invalid_parameter("model", "Heston is calibrated to option quotes with calibrate_heston, not to a price history."))
+        }
+        ModelType::LocalVolatility => {
+            return Err(invalid_parameter("model", "LocalVolatility is built from an implied volatility surface, not from a price history."))
+        }
+    }
+    Ok(AssetModelConfig {
+        asset_type: asset_type.to_string(),
+        asset_identifier_pattern: asset_identifier_pattern.to_string(),
+        default_model: model,
+        parameters,
+    })
+}
@@ -1,27 +1,311 @@
 use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use std::fs;
+use std::collections::BTreeMap;
+use std::path::Path;
 use anyhow::Error;
+use crate::error::invalid_config;
+use crate::yield_curve::DiscountCurve;
+pub use crate::yield_curve::{CurveInterpolation, CurveQuote, DiscountCurveParams}; // Defined with the curve they build
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 pub enum ModelType {
     GeometricBrownianMotion,
-    // Future models: Heston, JumpDiffusion, etc.
+    OrnsteinUhlenbeck,
+    CoxIngersollRoss,
+    MertonJumpDiffusion,
+    HistoricalBootstrap,
+    Garch,
+    Egarch,
+    RegimeSwitching,
+    LocalVolatility,
+    Heston,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+// Distribution of the standardised shocks (mean 0, variance 1) driving a model.
+// In TOML: [asset_models.parameters.innovations] with distribution = "StudentT" etc.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[serde(tag = "distribution", deny_unknown_fields)]
+pub enum InnovationDistribution {
+    #[default]
+    StandardNormal,
+    // Student-t rescaled to unit variance; requires degrees_of_freedom > 2.
+    StudentT { degrees_of_freedom: f64 },
+    // Hansen (1994) skewed Student-t; skewness in (-1, 1), negative means a heavier left tail.
+    SkewedStudentT { degrees_of_freedom: f64, skewness: f64 },
+    // Normal inverse Gaussian with tail heaviness alpha > 0 and asymmetry |beta| < alpha,
+    // location and scale chosen so the shocks have mean 0 and variance 1.
+    NormalInverseGaussian { alpha: f64, beta: f64 },
+}
+
+impl InnovationDistribution {
+    pub fn validate(&self) -> Result<(), Error> {
+        match *self {
+            InnovationDistribution::StandardNormal => Ok(()),
+            InnovationDistribution::StudentT { degrees_of_freedom } => check_degrees_of_freedom(degrees_of_freedom),
+            InnovationDistribution::SkewedStudentT { degrees_of_freedom, skewness } => {
+                check_degrees_of_freedom(degrees_of_freedom)?;
+                if !(skewness > -1.0 && skewness < 1.0) {
+                    return Err(invalid_config(Some("skewness"), format!("Skewed Student-t skewness must be in (-1, 1). Got {}", skewness)));
+                }
+                Ok(())
+            }
+            InnovationDistribution::NormalInverseGaussian { alpha, beta } => {
+                if !(alpha > 0.0 && beta.abs() < alpha) {
+                    let field = if alpha > 0.0 { "beta" } else { "alpha" };
+                    return Err(invalid_config(Some(field), format!(
+                        "NIG parameters require alpha > 0 and |beta| < alpha. Got alpha = {}, beta = {}",
+                        alpha, beta
+                    )));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// The variance of a Student-t is finite only for more than two degrees of freedom.
+fn check_degrees_of_freedom(degrees_of_freedom: f64) -> Result<(), Error> {
+    if degrees_of_freedom.is_nan() || degrees_of_freedom <= 2.0 {
+        return Err(invalid_config(Some("degrees_of_freedom"), format!("Degrees of freedom must be greater than 2. Got {}", degrees_of_freedom)));
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct GeometricBrownianMotionParams {
     pub drift: f64,
     pub volatility: f64,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+// dX = mean_reversion_speed * (long_term_mean - X) dt + volatility dW
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct OrnsteinUhlenbeckParams {
+    pub mean_reversion_speed: f64, // Annualized
+    pub long_term_mean: f64,
+    pub volatility: f64,           // Annualized, absolute units of X
+}
+
+// dX = mean_reversion_speed * (long_term_mean - X) dt + volatility * sqrt(X) dW
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CoxIngersollRossParams {
+    pub mean_reversion_speed: f64,
+    pub long_term_mean: f64,
+    pub volatility: f64,
+}
+
+// GBM with compound Poisson log-normal jumps (Merton 1976). `drift` is the total expected return,
+// the jump compensator is applied inside the process.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JumpDiffusionParams {
+    pub drift: f64,
+    pub volatility: f64,
+    pub jump_intensity: f64,  // Expected jumps per year
+    pub jump_mean: f64,       // Mean of log jump size
+    pub jump_volatility: f64, // Std dev of log jump size
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, JsonSchema)]
+pub enum BootstrapMethod {
+    Iid,                // Draw single returns independently
+    StationaryBlock,    // Politis-Romano: blocks with geometrically distributed length
+    FilteredHistorical, // Resample GARCH-standardised residuals and rescale by simulated volatility
+}
+
+// Historical returns come from either an inline list of log returns or a price CSV
+// read with historical_data::load_price_history_csv. A relative history_csv is relative to the
+// config file that sets it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BootstrapParams {
+    pub method: BootstrapMethod,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_csv: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_column: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price_column: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_returns: Option<Vec<f64>>,
+    #[serde(default = "default_history_time_step_days")]
+    pub history_time_step_days: f64, // Spacing of the historical observations in days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mean_block_length: Option<f64>, // StationaryBlock only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub garch: Option<GarchParams>,     // FilteredHistorical only; fitted by MLE when omitted
+    // Log returns of history_csv, read once by load_layered_config (see bootstrap::load_bootstrap_inputs).
+    // Unset for a config parsed from a string, whose history_csv is then read on use.
+    #[serde(skip)]
+    pub history_returns: Option<Vec<f64>>,
+    // FilteredHistorical GARCH fitted once at load when garch is omitted; unset for a config parsed
+    // from a string, which then fits on use.
+    #[serde(skip)]
+    pub fitted_garch: Option<GarchParams>,
+}
+
+fn default_history_time_step_days() -> f64 {
+    1.0
+}
+
+// GARCH(1,1) path generator, sigma^2_t = omega + alpha * eps^2_{t-1} + beta * sigma^2_{t-1}, also
+// the filter of a FilteredHistorical bootstrap. Parameters are per observation of the return series
+// they were fitted on (spaced history_time_step_days apart); mean_return is the per-observation mean
+// log return.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GarchParams {
+    pub mean_return: f64,
+    pub omega: f64,
+    pub alpha: f64,
+    pub beta: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_variance: Option<f64>, // Defaults to the unconditional variance
+    #[serde(default = "default_history_time_step_days")]
+    pub history_time_step_days: f64,
+}
+
+// EGARCH(1,1): ln sigma^2_t = omega + alpha * (|z| - E|z|) + gamma * z + beta * ln sigma^2_{t-1}
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EgarchParams {
+    pub mean_return: f64,
+    pub omega: f64,
+    pub alpha: f64, // Size effect
+    pub gamma: f64, // Sign (leverage) effect, typically negative for equities
+    pub beta: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_variance: Option<f64>,
+    #[serde(default = "default_history_time_step_days")]
+    pub history_time_step_days: f64,
+}
+
+// Heston stochastic volatility:
+// dS = drift * S dt + sqrt(v) S dW1, dv = mean_reversion_speed * (long_term_variance - v) dt + vol_of_vol * sqrt(v) dW2,
+// with corr(dW1, dW2) = correlation. Variances are annualized.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct HestonParams {
+    pub drift: f64,
+    pub initial_variance: f64,
+    pub mean_reversion_speed: f64,
+    pub long_term_variance: f64,
+    pub vol_of_vol: f64,
+    pub correlation: f64,
+}
+
+impl HestonParams {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.initial_variance < 0.0 || self.long_term_variance <= 0.0 {
+            let field = if self.initial_variance < 0.0 { "initial_variance" } else { "long_term_variance" };
+            return Err(invalid_config(Some(field), "Heston variances must be positive (initial variance may be zero)."));
+        }
+        if self.mean_reversion_speed <= 0.0 || self.vol_of_vol <= 0.0 {
+            let field = if self.mean_reversion_speed <= 0.0 { "mean_reversion_speed" } else { "vol_of_vol" };
+            return Err(invalid_config(Some(field), "Heston mean reversion speed and vol of vol must be positive."));
+        }
+        if !(-1.0..=1.0).contains(&self.correlation) {
+            return Err(invalid_config(Some("correlation"), format!("Heston correlation must be in [-1, 1]. Got {}", self.correlation)));
+        }
+        Ok(())
+    }
+}
+
+// One state of a regime-switching model (e.g. bull, bear, crisis). Annualized, as for GBM.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RegimeParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub drift: f64,
+    pub volatility: f64,
+}
+
+// Markov chain over `regimes`: transition_matrix[i][j] is the probability of moving from regime i
+// to regime j over one transition_time_step_days. Rows must sum to 1; a large diagonal entry makes
+// the regime persistent (expected stay of 1 / (1 - p_ii) transition steps).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RegimeSwitchingParams {
+    pub regimes: Vec<RegimeParams>,
+    pub transition_matrix: Vec<Vec<f64>>,
+    #[serde(default)]
+    pub initial_regime: usize, // Index into regimes
+    #[serde(default = "default_transition_time_step_days")]
+    pub transition_time_step_days: f64,
+}
+
+fn default_transition_time_step_days() -> f64 {
+    1.0
+}
+
+// Local volatility sigma(S, t) given directly on a grid: volatilities[i][j] applies at times_years[i]
+// and spot level spots[j]. Both axes ascending; interpolated bilinearly, flat outside the grid.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LocalVolGridParams {
+    pub spots: Vec<f64>,
+    pub times_years: Vec<f64>,
+    pub volatilities: Vec<Vec<f64>>,
+}
+
+// Implied (Black-Scholes) volatility quotes: volatilities[i][j] for maturities_years[i] and strikes[j],
+// observed with the underlying at `spot`. Converted to a local volatility grid with the Dupire formula.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ImpliedVolSurfaceParams {
+    pub spot: f64,
+    pub risk_free_rate: f64,
+    pub strikes: Vec<f64>,
+    pub maturities_years: Vec<f64>,
+    pub volatilities: Vec<Vec<f64>>,
+}
+
+// Exactly one of `grid` and `implied_surface` must be set.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LocalVolatilityParams {
+    pub drift: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grid: Option<LocalVolGridParams>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub implied_surface: Option<ImpliedVolSurfaceParams>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ModelParameters {
     // Optional fields for each model type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub gbm: Option<GeometricBrownianMotionParams>,
-    // heston: Option<HestonParams>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ou: Option<OrnsteinUhlenbeckParams>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cir: Option<CoxIngersollRossParams>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jump_diffusion: Option<JumpDiffusionParams>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bootstrap: Option<BootstrapParams>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub garch: Option<GarchParams>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub egarch: Option<EgarchParams>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regime_switching: Option<RegimeSwitchingParams>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_volatility: Option<LocalVolatilityParams>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heston: Option<HestonParams>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub innovations: Option<InnovationDistribution>, // Standard normal when omitted
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct AssetModelConfig {
     pub asset_type: String, // e.g., "stock", "option", "future"
     pub asset_identifier_pattern: String, // e.g., "AAPL", "DEFAULT_STOCK", "ESZ24"
@@ -29,16 +313,292 @@ pub struct AssetModelConfig {
     pub parameters: ModelParameters,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+// A named option contract. Fields use the Black-Scholes request names; for Monte Carlo requests
+// underlying_price and volatility become underlying_initial_price and underlying_volatility.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct OptionInstrumentConfig {
+    pub identifier: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub underlying_symbol: Option<String>, // Reported in the response
+    pub option_type: OptionType,
+    pub strike_price: f64,
+    pub time_to_maturity_years: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub underlying_price: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volatility: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk_free_rate: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discount_curve: Option<DiscountCurve>,
+}
+
+// A named futures contract; the fields are those of futures_simulation::FuturesContract.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FuturesInstrumentConfig {
+    pub identifier: String,
+    pub underlying_symbol: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_spot_price: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk_free_rate: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volatility: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_to_maturity_days: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discount_curve: Option<DiscountCurve>,
+}
+
+// Instrument definitions shared by requests and [instruments]; the simulators price them.
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum OptionType {
+    Call,
+    Put,
+}
+
+fn default_pip_size() -> f64 {
+    0.0001
+}
+
+// Currency pair BASE/QUOTE, e.g. EUR/USD: spot_rate is the price of one unit of the base (foreign)
+// currency in the quote (domestic) currency. Each currency takes either a flat continuously
+// compounded rate or a curve, not both.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FxPair {
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub spot_rate: f64,
+    #[serde(default)]
+    pub domestic_rate: Option<f64>, // Quote currency
+    #[serde(default)]
+    pub foreign_rate: Option<f64>, // Base currency
+    pub volatility: f64,
+    #[serde(default)]
+    pub domestic_curve: Option<DiscountCurve>,
+    #[serde(default)]
+    pub foreign_curve: Option<DiscountCurve>,
+    #[serde(default = "default_pip_size")]
+    pub pip_size: f64, // 0.0001 for most pairs, 0.01 for JPY crosses
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EtfConstituent {
+    pub symbol: String,
+    pub initial_price: f64,
+    pub drift: f64,
+    pub volatility: f64,
+    pub weight: f64, // Proportion in the ETF
+    // When set, the constituent follows this regime-switching model and drift/volatility are unused.
+    #[serde(default)]
+    pub regime_switching: Option<RegimeSwitchingParams>,
+    // Currency the constituent's prices are quoted in; None means the fund's base currency.
+    #[serde(default)]
+    pub currency: Option<String>,
+}
+
+// Multi-currency funds: NAV is computed in base_currency, converting each foreign constituent at a
+// simulated spot rate of the pair whose base_currency is the constituent's currency and whose
+// quote_currency is the fund's base currency. A currency-hedged share class replaces spot with the
+// FX forward locked in at inception, so it keeps the rate differential (carry) but not FX moves.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EtfFxSettings {
+    pub base_currency: String,
+    #[serde(default)]
+    pub fx_pairs: Vec<FxPair>,
+    #[serde(default)]
+    pub currency_hedged: bool,
+}
+
+// Benchmark index over the fund's constituents. Weights are by symbol; constituents left out have
+// weight 0 in the index, and no weights at all means the fund's own weights (so only fees differ).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EtfBenchmark {
+    #[serde(default)]
+    pub weights: BTreeMap<String, f64>,
+    // Annual cost deducted from the index, 0 for a pure price index.
+    #[serde(default)]
+    pub expense_ratio: f64,
+}
+
+fn default_premium_impact_bps_per_unit() -> f64 {
+    1.0
+}
+
+// Secondary market for the ETF's shares. The premium (market price / NAV - 1) follows an OU process
+// around zero; once it leaves the band set by the authorized participants' (APs) costs, APs create
+// units (premium) or redeem them (discount), each unit moving the premium back by
+// premium_impact_bps_per_unit until it is inside the band again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EtfMarketParams {
+    #[serde(default)]
+    pub initial_premium: f64,
+    pub premium_mean_reversion: f64, // Per year, without arbitrage
+    pub premium_volatility: f64,     // Annualized
+    pub creation_unit_size: u64,     // Shares per creation unit
+    pub creation_cost_bps: f64,      // AP cost of creating one unit, in bps of its value
+    pub redemption_cost_bps: f64,
+    pub initial_shares_outstanding: u64,
+    #[serde(default = "default_premium_impact_bps_per_unit")]
+    pub premium_impact_bps_per_unit: f64,
+}
+
+// A named ETF basket; the fields are those of etf_simulation::EtfDefinition that describe the fund.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EtfInstrumentConfig {
+    pub identifier: String,
+    pub constituents: Vec<EtfConstituent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expense_ratio: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fx: Option<EtfFxSettings>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub market: Option<EtfMarketParams>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub benchmark: Option<EtfBenchmark>,
+}
+
+// Instruments the POST endpoints can reference by `asset_identifier` instead of describing them in
+// full (see instruments::resolve_instrument). In TOML: [[instruments.options]], [[instruments.futures]]
+// and [[instruments.etfs]].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct InstrumentsConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<OptionInstrumentConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub futures: Vec<FuturesInstrumentConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub etfs: Vec<EtfInstrumentConfig>,
+}
+
+impl InstrumentsConfig {
+    pub fn is_empty(&self) -> bool {
+        self.options.is_empty() && self.futures.is_empty() && self.etfs.is_empty()
+    }
+
+    // Adds the instruments of `other`, replacing those with the same identifier.
+    pub fn merge(&mut self, other: InstrumentsConfig) {
+        upsert_by_identifier(&mut self.options, other.options, |o| &o.identifier);
+        upsert_by_identifier(&mut self.futures, other.futures, |f| &f.identifier);
+        upsert_by_identifier(&mut self.etfs, other.etfs, |e| &e.identifier);
+    }
+}
+
+fn upsert_by_identifier<T>(entries: &mut Vec<T>, new_entries: Vec<T>, identifier: impl Fn(&T) -> &String) {
+    for entry in new_entries {
+        match entries.iter().position(|e| identifier(e) == identifier(&entry)) {
+            Some(i) => entries[i] = entry,
+            None => entries.push(entry),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct GlobalConfig {
+    // Files with more asset models, relative to this one. Resolved by config_layers::load_layered_config;
+    // load_config keeps them as written.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
     pub random_seed: Option<u64>,
-    pub simulation_period_days: u32, // Retained for now
-    pub time_step_minutes: u64,      // Retained for now
+    // Default horizon and step for requests that omit them (see request_defaults).
+    pub simulation_period_days: u32,
+    pub time_step_minutes: u64,
     pub asset_models: Option<Vec<AssetModelConfig>>, // Changed to Option for backward compatibility if file missing this
+    #[serde(default, skip_serializing_if = "InstrumentsConfig::is_empty")]
+    pub instruments: InstrumentsConfig,
+}
+
+// Config file format, chosen by extension: .json, .yaml/.yml, and TOML for anything else.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigFormat {
+    Toml,
+    Yaml,
+    Json,
+}
+
+impl ConfigFormat {
+    pub fn from_path(file_path: &str) -> Self {
+        let extension = Path::new(file_path).extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("json") => ConfigFormat::Json,
+            Some("yaml") | Some("yml") => ConfigFormat::Yaml,
+            _ => ConfigFormat::Toml,
+        }
+    }
 }
 
 pub fn load_config(file_path: &str) -> Result<GlobalConfig, Error> {
     let contents = fs::read_to_string(file_path)?;
-    let config: GlobalConfig = toml::from_str(&contents)?;
-    Ok(config)
+    parse_config_as(&contents, ConfigFormat::from_path(file_path))
+}
+
+// Parses TOML into a GlobalConfig. Unknown keys are rejected; when the error is inside an
+// [[asset_models]] entry, the message names that entry by index.
+pub fn parse_config(contents: &str) -> Result<GlobalConfig, Error> {
+    parse_config_as(contents, ConfigFormat::Toml)
+}
+
+pub fn parse_config_as<T: DeserializeOwned>(contents: &str, format: ConfigFormat) -> Result<T, Error> {
+    match format {
+        ConfigFormat::Toml => toml::from_str(contents).map_err(|e| {
+            let entry = e.span().and_then(|span| {
+                let before = &contents[..span.start.min(contents.len())];
+                let headers: Vec<&str> = before.lines().map(str::trim_start).filter(|line| line.starts_with('[')).collect();
+                // Only when the error is under an [[asset_models]] entry, not in a later section.
+                let in_asset_models = headers.last()
+                    .is_some_and(|h| h.starts_with("[[asset_models]]") || h.starts_with("[asset_models."));
+                if !in_asset_models {
+                    return None;
+                }
+                headers.iter().filter(|h| h.starts_with("[[asset_models]]")).count().checked_sub(1)
+            });
+            match entry {
+                Some(index) => invalid_config(Some(&format!("asset_models[{}]", index)), format!("In asset_models[{}]: {}", index, e)),
+                None => invalid_config(None, e.to_string()),
+            }
+        }),
+        ConfigFormat::Yaml => serde_yaml::from_str(contents).map_err(|e| invalid_config(None, e.to_string())),
+        ConfigFormat::Json => serde_json::from_str(contents).map_err(|e| invalid_config(None, e.to_string())),
+    }
+}
+
+pub fn config_to_toml_string(config: &GlobalConfig) -> Result<String, Error> {
+    Ok(toml::to_string_pretty(config)?)
+}
+
+// Writes in the format of the file's extension, like load_config reads.
+pub fn save_config(config: &GlobalConfig, file_path: &str) -> Result<(), Error> {
+    let contents = match ConfigFormat::from_path(file_path) {
+        ConfigFormat::Toml => config_to_toml_string(config)?,
+        ConfigFormat::Yaml => serde_yaml::to_string(config)?,
+        ConfigFormat::Json => serde_json::to_string_pretty(config)? + "\n",
+    };
+    fs::write(file_path, contents)?;
+    Ok(())
+}
+
+// Replaces the entry with the same asset_type (in any case, as lookups and validation compare it)
+// and asset_identifier_pattern, or appends a new one.
+pub fn upsert_asset_model(config: &mut GlobalConfig, model: AssetModelConfig) {
+    upsert_model(config.asset_models.get_or_insert_with(Vec::new), model);
+}
+
+pub(crate) fn upsert_model(models: &mut Vec<AssetModelConfig>, model: AssetModelConfig) {
+    match models.iter_mut().find(|m| {
+        m.asset_type.eq_ignore_ascii_case(&model.asset_type) && m.asset_identifier_pattern == model.asset_identifier_pattern
+    }) {
+        Some(existing) => *existing = model,
+        None => models.push(model),
+    }
 }
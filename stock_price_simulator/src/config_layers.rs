@@ -0,0 +1,142 @@
+use crate::bootstrap::load_bootstrap_inputs;
+use crate::config::{parse_config_as, upsert_model, AssetModelConfig, ConfigFormat, GlobalConfig, InstrumentsConfig};
+use anyhow::{Context, Error};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+// Scalar GlobalConfig fields that can be set from the environment, applied after every file
+// (variable, field).
+pub const ENV_OVERRIDES: [(&str, &str); 3] = [
+    ("STOCK_SIM_RANDOM_SEED", "random_seed"),
+    ("STOCK_SIM_SIMULATION_PERIOD_DAYS", "simulation_period_days"),
+    ("STOCK_SIM_TIME_STEP_MINUTES", "time_step_minutes"),
+];
+
+// One file of a layered config. Everything is optional, so an overlay only states what it changes.
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+struct ConfigLayer {
+    #[serde(default)]
+    include: Vec<String>,
+    random_seed: Option<u64>,
+    simulation_period_days: Option<u32>,
+    time_step_minutes: Option<u64>,
+    asset_models: Option<Vec<AssetModelConfig>>,
+    instruments: Option<InstrumentsConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayeredConfig {
+    pub config: GlobalConfig,
+    pub files: Vec<String>,         // Every file read, includes first, each followed by its history_csv files
+    pub env_overrides: Vec<String>, // Environment variables that changed a field
+}
+
+#[derive(Default)]
+struct Merged {
+    random_seed: Option<u64>,
+    simulation_period_days: Option<u32>,
+    time_step_minutes: Option<u64>,
+    asset_models: Vec<AssetModelConfig>,
+    instruments: InstrumentsConfig,
+    files: Vec<String>,
+}
+
+impl Merged {
+    // Applies `path` and, before its own settings, the files it includes. `stack` holds the files
+    // currently being applied, to reject include cycles.
+    fn apply_file(&mut self, path: &Path, stack: &mut Vec<PathBuf>) -> Result<(), Error> {
+        let display = path.display().to_string();
+        let canonical = path.canonicalize().with_context(|| format!("Config file {} not found", display))?;
+        if stack.contains(&canonical) {
+            let chain: Vec<String> = stack.iter().chain([&canonical]).map(|p| p.display().to_string()).collect();
+            return Err(anyhow::anyhow!("Include cycle: {}", chain.join(" -> ")));
+        }
+        let contents = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", display))?;
+        let mut layer: ConfigLayer = parse_config_as(&contents, ConfigFormat::from_path(&display))
+            .with_context(|| format!("Failed to parse {}", display))?;
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        let mut data_files = Vec::new();
+        for (i, model) in layer.asset_models.iter_mut().flatten().enumerate() {
+            if let Some(bootstrap) = model.parameters.bootstrap.as_mut() {
+                let csv = load_bootstrap_inputs(bootstrap, base_dir).with_context(|| format!("In asset_models[{}] of {}", i, display))?;
+                data_files.extend(csv);
+            }
+        }
+
+        stack.push(canonical);
+        for include in &layer.include {
+            self.apply_file(&base_dir.join(include), stack)
+                .with_context(|| format!("Included from {}", display))?;
+        }
+        stack.pop();
+
+        if layer.random_seed.is_some() {
+            self.random_seed = layer.random_seed;
+        }
+        if layer.simulation_period_days.is_some() {
+            self.simulation_period_days = layer.simulation_period_days;
+        }
+        if layer.time_step_minutes.is_some() {
+            self.time_step_minutes = layer.time_step_minutes;
+        }
+        // Keyed like lookups and validation: asset_type in any case, then the exact pattern.
+        for model in layer.asset_models.unwrap_or_default() {
+            upsert_model(&mut self.asset_models, model);
+        }
+        self.instruments.merge(layer.instruments.unwrap_or_default());
+        self.files.push(display);
+        for file in data_files {
+            if !self.files.contains(&file) {
+                self.files.push(file);
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_env(&mut self, env: impl IntoIterator<Item = (String, String)>) -> Result<Vec<String>, Error> {
+        let mut applied = Vec::new();
+        for (name, value) in env {
+            let invalid = || anyhow::anyhow!("{} must be a non-negative integer. Got '{}'", name, value);
+            match name.as_str() {
+                "STOCK_SIM_RANDOM_SEED" => self.random_seed = Some(value.trim().parse().map_err(|_| invalid())?),
+                "STOCK_SIM_SIMULATION_PERIOD_DAYS" => self.simulation_period_days = Some(value.trim().parse().map_err(|_| invalid())?),
+                "STOCK_SIM_TIME_STEP_MINUTES" => self.time_step_minutes = Some(value.trim().parse().map_err(|_| invalid())?),
+                _ => continue,
+            }
+            applied.push(name);
+        }
+        applied.sort();
+        Ok(applied)
+    }
+}
+
+// Builds one GlobalConfig from `paths`, base first: each later file overrides the scalar fields it
+// sets and replaces asset models with the same asset_type and asset_identifier_pattern, and
+// instruments with the same identifier (others are appended). A file's `include` list is applied
+// before the file itself, relative to its directory. Files may be TOML, YAML or JSON (by
+// extension). `env` supplies the ENV_OVERRIDES, applied last; pass `std::env::vars()` outside tests.
+pub fn load_layered_config<S: AsRef<str>>(
+    paths: &[S],
+    env: impl IntoIterator<Item = (String, String)>,
+) -> Result<LayeredConfig, Error> {
+    if paths.is_empty() {
+        return Err(anyhow::anyhow!("No config file given."));
+    }
+    let mut merged = Merged::default();
+    for path in paths {
+        merged.apply_file(Path::new(path.as_ref()), &mut Vec::new())?;
+    }
+    let env_overrides = merged.apply_env(env)?;
+
+    let missing = |field: &str| anyhow::anyhow!("{} is not set by any of: {}", field, merged.files.join(", "));
+    let config = GlobalConfig {
+        include: Vec::new(), // Resolved
+        random_seed: merged.random_seed,
+        simulation_period_days: merged.simulation_period_days.ok_or_else(|| missing("simulation_period_days"))?,
+        time_step_minutes: merged.time_step_minutes.ok_or_else(|| missing("time_step_minutes"))?,
+        asset_models: (!merged.asset_models.is_empty()).then_some(merged.asset_models),
+        instruments: merged.instruments,
+    };
+    Ok(LayeredConfig { config, files: merged.files, env_overrides })
+}
@@ -0,0 +1,233 @@
+use crate::config::{config_to_toml_string, GlobalConfig};
+use crate::asset_matching::AssetMatcher;
+use crate::config_layers::{load_layered_config, LayeredConfig};
+use anyhow::Error;
+use chrono::{NaiveDateTime, Utc};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime};
+
+// One loaded version of the config. Requests clone the Arc, so a reload never changes the config
+// under a request that is already running.
+#[derive(Debug, Clone)]
+pub struct ConfigSnapshot {
+    pub config: Arc<GlobalConfig>,
+    pub matcher: AssetMatcher, // The asset_models patterns of `config`, compiled
+    pub version: u64, // 1 for the config the server started with, +1 per successful reload
+    pub hash: String, // Of the parsed config, so comment or whitespace edits do not count as a change
+    pub loaded_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReloadOutcome {
+    Reloaded { version: u64 },
+    Unchanged { version: u64 },
+}
+
+// The live GlobalConfig of the server, reloadable from the layered files in `paths`.
+#[derive(Debug)]
+pub struct ConfigStore {
+    pub paths: Vec<String>, // Base first; empty for a config built in code, which then cannot be reloaded
+    current: RwLock<Arc<ConfigSnapshot>>,
+    last_error: Mutex<Option<String>>,
+    // Every file read by the last load (layers, includes and history_csv files) with its modification
+    // time then.
+    watched: Mutex<Vec<(String, Option<SystemTime>)>>,
+}
+
+// FNV-1a, 64 bit: stable across builds, unlike std's DefaultHasher.
+pub(crate) fn fnv1a64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+fn fnv1a_hex(bytes: &[u8]) -> String {
+    format!("{:016x}", fnv1a64(bytes))
+}
+
+// Also covers the history_csv returns read at load, which the serialized config leaves out, so an
+// edit to a CSV alone counts as a change.
+pub fn config_hash(config: &GlobalConfig) -> Result<String, Error> {
+    let mut bytes = config_to_toml_string(config)?.into_bytes();
+    for model in config.asset_models.iter().flatten() {
+        if let Some(returns) = model.parameters.bootstrap.as_ref().and_then(|b| b.history_returns.as_ref()) {
+            bytes.extend(returns.iter().flat_map(|r| r.to_le_bytes()));
+        }
+    }
+    Ok(fnv1a_hex(&bytes))
+}
+
+// Same check as at startup: every asset model must build.
+fn validate(config: &GlobalConfig) -> Result<(), Error> {
+    let problems = crate::api_interface::check_asset_models(config);
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("{}", problems.join("; ")))
+    }
+}
+
+fn modified_time(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn with_modified_times(files: Vec<String>) -> Vec<(String, Option<SystemTime>)> {
+    files.into_iter().map(|file| {
+        let modified = modified_time(&file);
+        (file, modified)
+    }).collect()
+}
+
+impl ConfigStore {
+    pub fn new(config: GlobalConfig, path: Option<String>) -> Result<Self, Error> {
+        let files = path.iter().cloned().collect();
+        ConfigStore::from_layers(LayeredConfig { config, files, env_overrides: Vec::new() }, path.into_iter().collect())
+    }
+
+    // Takes a config already loaded from `paths` with load_layered_config.
+    pub fn from_layers(layered: LayeredConfig, paths: Vec<String>) -> Result<Self, Error> {
+        let snapshot = ConfigSnapshot {
+            hash: config_hash(&layered.config)?,
+            matcher: AssetMatcher::new(&layered.config)?,
+            config: Arc::new(layered.config),
+            version: 1,
+            loaded_at: Utc::now().naive_utc(),
+        };
+        Ok(ConfigStore {
+            paths,
+            current: RwLock::new(Arc::new(snapshot)),
+            last_error: Mutex::new(None),
+            watched: Mutex::new(with_modified_times(layered.files)),
+        })
+    }
+
+    pub fn from_files(paths: &[String]) -> Result<Self, Error> {
+        let layered = load_layered_config(paths, std::env::vars())?;
+        validate(&layered.config)?;
+        ConfigStore::from_layers(layered, paths.to_vec())
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, Error> {
+        ConfigStore::from_files(&[path.to_string()])
+    }
+
+    // Every file the current config was read from: layers, includes and history_csv files.
+    pub fn files(&self) -> Vec<String> {
+        self.watched.lock().unwrap().iter().map(|(file, _)| file.clone()).collect()
+    }
+
+    pub fn current(&self) -> Arc<ConfigSnapshot> {
+        self.current.read().unwrap().clone()
+    }
+
+    pub fn config(&self) -> Arc<GlobalConfig> {
+        self.current().config.clone()
+    }
+
+    // Error of the most recent failed reload, cleared by the next successful one.
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().unwrap().clone()
+    }
+
+    // Re-reads the config files and swaps the result in if it parses, validates and differs from the
+    // current one. On failure the current config stays in place and the error is kept for
+    // `last_error`.
+    pub fn reload(&self) -> Result<ReloadOutcome, Error> {
+        let result = self.try_reload();
+        *self.last_error.lock().unwrap() = result.as_ref().err().map(|e| e.to_string());
+        result
+    }
+
+    fn try_reload(&self) -> Result<ReloadOutcome, Error> {
+        if self.paths.is_empty() {
+            return Err(anyhow::anyhow!("The config was not loaded from a file and cannot be reloaded."));
+        }
+        let source = self.paths.join(", ");
+        // Taken before reading, so an edit made during the load triggers another one.
+        let watched = {
+            let mut watched = self.watched.lock().unwrap();
+            for (file, modified) in watched.iter_mut() {
+                *modified = modified_time(file);
+            }
+            watched.clone()
+        };
+        let layered = load_layered_config(&self.paths, std::env::vars())
+            .map_err(|e| anyhow::anyhow!("Failed to load {}: {:#}", source, e))?;
+        let files = layered.files.into_iter().map(|file| {
+            let known = watched.iter().find(|(watched_file, _)| *watched_file == file).and_then(|(_, modified)| *modified);
+            let modified = known.or_else(|| modified_time(&file));
+            (file, modified)
+        }).collect();
+        *self.watched.lock().unwrap() = files;
+        let config = layered.config;
+        validate(&config).map_err(|e| anyhow::anyhow!("Invalid config in {}: {}", source, e))?;
+        let hash = config_hash(&config)?;
+
+        let mut current = self.current.write().unwrap();
+        if current.hash == hash {
+            return Ok(ReloadOutcome::Unchanged { version: current.version });
+        }
+        let version = current.version + 1;
+        *current = Arc::new(ConfigSnapshot {
+            matcher: AssetMatcher::new(&config)?,
+            config: Arc::new(config),
+            version,
+            hash,
+            loaded_at: Utc::now().naive_utc(),
+        });
+        Ok(ReloadOutcome::Reloaded { version })
+    }
+
+    // Reloads only when the modification time of one of the files moved since the last load attempt.
+    pub fn reload_if_modified(&self) -> Option<Result<ReloadOutcome, Error>> {
+        let changed = self.watched.lock().unwrap().iter().any(|(file, last)| {
+            let modified = modified_time(file);
+            modified.is_some() && modified != *last
+        });
+        changed.then(|| self.reload())
+    }
+}
+
+fn log_reload(trigger: &str, result: Result<ReloadOutcome, Error>) {
+    match result {
+        Ok(ReloadOutcome::Reloaded { version }) => log::info!("Config reloaded on {} (version {})", trigger, version),
+        Ok(ReloadOutcome::Unchanged { .. }) => log::info!("Config unchanged on {}", trigger),
+        Err(e) => log::warn!("Config reload on {} failed, keeping the current config: {}", trigger, e),
+    }
+}
+
+// Starts the background reload triggers on the current Tokio runtime: SIGHUP (Unix only) and,
+// when `poll_interval` is set, a check of the file's modification time at that interval.
+pub fn spawn_reload_triggers(store: Arc<ConfigStore>, poll_interval: Option<Duration>) {
+    if store.paths.is_empty() {
+        return;
+    }
+    #[cfg(unix)]
+    {
+        let store = store.clone();
+        tokio::spawn(async move {
+            use tokio::signal::unix::{signal, SignalKind};
+            let mut hangups = match signal(SignalKind::hangup()) {
+                Ok(hangups) => hangups,
+                Err(e) => {
+                    log::warn!("Cannot listen for SIGHUP, config reload on signal disabled: {}", e);
+                    return;
+                }
+            };
+            while hangups.recv().await.is_some() {
+                log_reload("SIGHUP", store.reload());
+            }
+        });
+    }
+    if let Some(interval) = poll_interval {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Some(result) = store.reload_if_modified() {
+                    log_reload("file change", result);
+                }
+            }
+        });
+    }
+}
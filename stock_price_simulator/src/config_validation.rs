@@ -0,0 +1,267 @@
+use crate::asset_matching::validate_pattern;
+use crate::config::{AssetModelConfig, GlobalConfig, ModelParameters, ModelType};
+use std::fmt;
+
+// One problem found in a GlobalConfig. `index` is the position of the offending [[asset_models]]
+// entry, None for top-level settings; `field` is the dotted path of the key inside that entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigDiagnostic {
+    pub index: Option<usize>,
+    pub asset_type: Option<String>,
+    pub asset_identifier_pattern: Option<String>,
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(index) = self.index {
+            write!(
+                f,
+                "asset_models[{}] ({} '{}'): ",
+                index,
+                self.asset_type.as_deref().unwrap_or(""),
+                self.asset_identifier_pattern.as_deref().unwrap_or("")
+            )?;
+        }
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+// Collects the diagnostics of one [[asset_models]] entry.
+struct EntryChecker<'a> {
+    index: usize,
+    model: &'a AssetModelConfig,
+    diagnostics: Vec<ConfigDiagnostic>,
+}
+
+impl EntryChecker<'_> {
+    fn report(&mut self, field: &str, message: String) {
+        self.diagnostics.push(ConfigDiagnostic {
+            index: Some(self.index),
+            asset_type: Some(self.model.asset_type.clone()),
+            asset_identifier_pattern: Some(self.model.asset_identifier_pattern.clone()),
+            field: field.to_string(),
+            message,
+        });
+    }
+
+    fn check_volatility(&mut self, field: &str, value: f64) {
+        if !(value.is_finite() && value >= 0.0) {
+            self.report(field, format!("must be a finite, non-negative number. Got {}", value));
+        }
+    }
+
+    fn check_finite(&mut self, field: &str, value: f64) {
+        if !value.is_finite() {
+            self.report(field, format!("must be a finite number. Got {}", value));
+        }
+    }
+
+    fn check_result(&mut self, field: &str, result: anyhow::Result<()>) {
+        if let Err(e) = result {
+            self.report(field, e.to_string());
+        }
+    }
+}
+
+// Name of the [asset_models.parameters] table each model reads, and whether it is present.
+fn required_parameters(model: &ModelType, parameters: &ModelParameters) -> (&'static str, bool) {
+    match model {
+        ModelType::GeometricBrownianMotion => ("gbm", parameters.gbm.is_some()),
+        ModelType::OrnsteinUhlenbeck => ("ou", parameters.ou.is_some()),
+        ModelType::CoxIngersollRoss => ("cir", parameters.cir.is_some()),
+        ModelType::MertonJumpDiffusion => ("jump_diffusion", parameters.jump_diffusion.is_some()),
+        ModelType::HistoricalBootstrap => ("bootstrap", parameters.bootstrap.is_some()),
+        ModelType::Garch => ("garch", parameters.garch.is_some()),
+        ModelType::Egarch => ("egarch", parameters.egarch.is_some()),
+        ModelType::RegimeSwitching => ("regime_switching", parameters.regime_switching.is_some()),
+        ModelType::LocalVolatility => ("local_volatility", parameters.local_volatility.is_some()),
+        ModelType::Heston => ("heston", parameters.heston.is_some()),
+    }
+}
+
+fn check_entry(checker: &mut EntryChecker) {
+    let model = checker.model;
+    if model.asset_type.trim().is_empty() {
+        checker.report("asset_type", "must not be empty".to_string());
+    }
+    if model.asset_identifier_pattern.trim().is_empty() {
+        checker.report("asset_identifier_pattern", "must not be empty".to_string());
+    }
+    checker.check_result("asset_identifier_pattern", validate_pattern(&model.asset_identifier_pattern));
+
+    let params = &model.parameters;
+    let (required, present) = required_parameters(&model.default_model, params);
+    if !present {
+        checker.report(
+            &format!("parameters.{}", required),
+            format!("missing, but default_model {:?} needs it", model.default_model),
+        );
+    }
+
+    if let Some(gbm) = &params.gbm {
+        checker.check_finite("parameters.gbm.drift", gbm.drift);
+        checker.check_volatility("parameters.gbm.volatility", gbm.volatility);
+    }
+    if let Some(ou) = &params.ou {
+        checker.check_volatility("parameters.ou.volatility", ou.volatility);
+        checker.check_volatility("parameters.ou.mean_reversion_speed", ou.mean_reversion_speed);
+    }
+    if let Some(cir) = &params.cir {
+        checker.check_volatility("parameters.cir.volatility", cir.volatility);
+        checker.check_volatility("parameters.cir.mean_reversion_speed", cir.mean_reversion_speed);
+    }
+    if let Some(jumps) = &params.jump_diffusion {
+        checker.check_finite("parameters.jump_diffusion.drift", jumps.drift);
+        checker.check_volatility("parameters.jump_diffusion.volatility", jumps.volatility);
+        checker.check_volatility("parameters.jump_diffusion.jump_intensity", jumps.jump_intensity);
+        checker.check_volatility("parameters.jump_diffusion.jump_volatility", jumps.jump_volatility);
+    }
+    if let Some(regimes) = &params.regime_switching {
+        for (i, regime) in regimes.regimes.iter().enumerate() {
+            checker.check_volatility(&format!("parameters.regime_switching.regimes[{}].volatility", i), regime.volatility);
+        }
+    }
+    if let Some(local_vol) = &params.local_volatility {
+        if local_vol.grid.is_some() == local_vol.implied_surface.is_some() {
+            checker.report("parameters.local_volatility", "set exactly one of grid and implied_surface".to_string());
+        }
+        let grids = [
+            ("grid", local_vol.grid.as_ref().map(|g| &g.volatilities)),
+            ("implied_surface", local_vol.implied_surface.as_ref().map(|s| &s.volatilities)),
+        ];
+        for (name, volatilities) in grids {
+            let negative = volatilities.into_iter().flatten().flatten().find(|v| !(v.is_finite() && **v >= 0.0));
+            if let Some(v) = negative {
+                checker.report(
+                    &format!("parameters.local_volatility.{}.volatilities", name),
+                    format!("must all be finite and non-negative. Got {}", v),
+                );
+            }
+        }
+    }
+    if let Some(heston) = &params.heston {
+        checker.check_result("parameters.heston", heston.validate());
+    }
+    if let Some(innovations) = &params.innovations {
+        checker.check_result("parameters.innovations", innovations.validate());
+    }
+}
+
+fn top_level(field: &str, message: &str) -> ConfigDiagnostic {
+    ConfigDiagnostic {
+        index: None,
+        asset_type: None,
+        asset_identifier_pattern: None,
+        field: field.to_string(),
+        message: message.to_string(),
+    }
+}
+
+fn finite_non_negative(value: f64) -> bool {
+    value.is_finite() && value >= 0.0
+}
+
+// Identifiers of one [[instruments.<section>]] list: non-empty and unique, as lookups take the first.
+fn check_identifiers<'a>(section: &str, identifiers: impl Iterator<Item = &'a String>, diagnostics: &mut Vec<ConfigDiagnostic>) {
+    let identifiers: Vec<&String> = identifiers.collect();
+    for (i, identifier) in identifiers.iter().enumerate() {
+        let field = format!("instruments.{}[{}].identifier", section, i);
+        if identifier.trim().is_empty() {
+            diagnostics.push(top_level(&field, "must not be empty"));
+        } else if let Some(first) = identifiers[..i].iter().position(|other| other == identifier) {
+            diagnostics.push(top_level(&field, &format!(
+                "duplicates instruments.{}[{}], so this entry is never used", section, first
+            )));
+        }
+    }
+}
+
+fn check_instruments(config: &GlobalConfig, diagnostics: &mut Vec<ConfigDiagnostic>) {
+    let instruments = &config.instruments;
+    check_identifiers("options", instruments.options.iter().map(|o| &o.identifier), diagnostics);
+    for (i, option) in instruments.options.iter().enumerate() {
+        let field = |name: &str| format!("instruments.options[{}].{}", i, name);
+        if !(option.strike_price.is_finite() && option.strike_price > 0.0) {
+            diagnostics.push(top_level(&field("strike_price"), &format!("must be positive. Got {}", option.strike_price)));
+        }
+        if !finite_non_negative(option.time_to_maturity_years) {
+            diagnostics.push(top_level(&field("time_to_maturity_years"), &format!(
+                "must be a finite, non-negative number. Got {}", option.time_to_maturity_years
+            )));
+        }
+        if let Some(volatility) = option.volatility.filter(|v| !finite_non_negative(*v)) {
+            diagnostics.push(top_level(&field("volatility"), &format!("must be a finite, non-negative number. Got {}", volatility)));
+        }
+    }
+
+    check_identifiers("futures", instruments.futures.iter().map(|f| &f.identifier), diagnostics);
+    for (i, future) in instruments.futures.iter().enumerate() {
+        if let Some(volatility) = future.volatility.filter(|v| !finite_non_negative(*v)) {
+            diagnostics.push(top_level(
+                &format!("instruments.futures[{}].volatility", i),
+                &format!("must be a finite, non-negative number. Got {}", volatility),
+            ));
+        }
+    }
+
+    check_identifiers("etfs", instruments.etfs.iter().map(|e| &e.identifier), diagnostics);
+    for (i, etf) in instruments.etfs.iter().enumerate() {
+        if etf.constituents.is_empty() {
+            diagnostics.push(top_level(&format!("instruments.etfs[{}].constituents", i), "must not be empty"));
+        }
+        for (j, constituent) in etf.constituents.iter().enumerate() {
+            let field = |name: &str| format!("instruments.etfs[{}].constituents[{}].{}", i, j, name);
+            if !finite_non_negative(constituent.volatility) {
+                diagnostics.push(top_level(&field("volatility"), &format!(
+                    "must be a finite, non-negative number. Got {}", constituent.volatility
+                )));
+            }
+            if !finite_non_negative(constituent.weight) {
+                diagnostics.push(top_level(&field("weight"), &format!(
+                    "must be a finite, non-negative number. Got {}", constituent.weight
+                )));
+            }
+        }
+        for (j, pair) in etf.fx.iter().flat_map(|fx| fx.fx_pairs.iter()).enumerate() {
+            if let Err(e) = pair.validate() {
+                diagnostics.push(top_level(&format!("instruments.etfs[{}].fx.fx_pairs[{}]", i, j), &e.to_string()));
+            }
+        }
+    }
+}
+
+// Checks a parsed config for problems TOML parsing cannot catch: missing parameter tables for the
+// selected model, negative or non-finite volatilities, invalid patterns and duplicate entries, in
+// [[asset_models]] and in the [instruments] sections.
+// Returns every problem found, in file order; an empty list means the config passed.
+pub fn validate_config(config: &GlobalConfig) -> Vec<ConfigDiagnostic> {
+    let mut diagnostics = Vec::new();
+    if config.simulation_period_days == 0 {
+        diagnostics.push(top_level("simulation_period_days", "must be positive"));
+    }
+    if config.time_step_minutes == 0 {
+        diagnostics.push(top_level("time_step_minutes", "must be positive"));
+    }
+
+    let models = config.asset_models.as_deref().unwrap_or(&[]);
+    for (index, model) in models.iter().enumerate() {
+        let mut checker = EntryChecker { index, model, diagnostics: Vec::new() };
+        check_entry(&mut checker);
+        // Lookups take the first of two identical entries, so a later duplicate is never used.
+        let duplicate_of = models[..index].iter().position(|other| {
+            other.asset_type.eq_ignore_ascii_case(&model.asset_type)
+                && other.asset_identifier_pattern == model.asset_identifier_pattern
+        });
+        if let Some(first) = duplicate_of {
+            checker.report(
+                "asset_identifier_pattern",
+                format!("duplicates asset_models[{}], so this entry is never used", first),
+            );
+        }
+        diagnostics.extend(checker.diagnostics);
+    }
+    check_instruments(config, &mut diagnostics);
+    diagnostics
+}
@@ -0,0 +1,107 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+
+// Machine-readable class of a failure, sent as `code` in ApiErrorResponse.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InvalidParameter,     // A value is outside its allowed range
+    MissingParameter,     // A required field is absent and no default supplies it
+    InvalidRequest,       // Malformed JSON or query string, or a field of the wrong type
+    UnsupportedMediaType, // Body not sent as application/json
+    PayloadTooLarge,
+    UnknownAsset,         // No asset model or instrument for the identifier
+    InvalidConfig,        // The server's config is wrong, not the request
+    NotFound,             // No such route
+    Forbidden,            // Admin route without the admin token, or from a remote client when none is set
+    SimulationFailed,     // Anything else that went wrong while simulating or pricing
+}
+
+// Error with a code clients can branch on and, when one value is at fault, the request or config
+// field it came from as a dotted path (`constituents[2].weight`). The simulation modules return it
+// inside anyhow::Error, so their signatures are unchanged; see classify for getting it back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationError {
+    pub code: ErrorCode,
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for SimulationError {}
+
+impl SimulationError {
+    pub fn new(code: ErrorCode, field: Option<&str>, message: impl Into<String>) -> Self {
+        SimulationError { code, field: field.map(str::to_string), message: message.into() }
+    }
+}
+
+pub fn invalid_parameter(field: &str, message: impl Into<String>) -> anyhow::Error {
+    SimulationError::new(ErrorCode::InvalidParameter, Some(field), message).into()
+}
+
+pub fn invalid_config(field: Option<&str>, message: impl Into<String>) -> anyhow::Error {
+    SimulationError::new(ErrorCode::InvalidConfig, field, message).into()
+}
+
+// Puts a field reported by a nested validation under the path of the value it checked
+// (`volatility` becomes `fx.fx_pairs[0].volatility`). Errors without a SimulationError pass through.
+pub fn within(prefix: &str, error: anyhow::Error) -> anyhow::Error {
+    let inner = match error.chain().find_map(|e| e.downcast_ref::<SimulationError>()) {
+        Some(inner) => inner,
+        None => return error,
+    };
+    let field = match &inner.field {
+        Some(field) => format!("{}.{}", prefix, field),
+        None => prefix.to_string(),
+    };
+    SimulationError { code: inner.code, field: Some(field), message: error.to_string() }.into()
+}
+
+// The SimulationError inside `error`, looking through any context added on the way up, with the
+// message of the whole error. Errors that carry none are SimulationFailed.
+pub fn classify(error: &anyhow::Error) -> SimulationError {
+    let inner = error.chain().find_map(|e| e.downcast_ref::<SimulationError>());
+    SimulationError {
+        code: inner.map_or(ErrorCode::SimulationFailed, |e| e.code),
+        field: inner.and_then(|e| e.field.clone()),
+        message: error.to_string(),
+    }
+}
+
+// Deserializes a request, reporting a missing field as MissingParameter and any other problem as
+// InvalidRequest, both with the path of the offending field. `what` starts the message
+// ("Invalid future request").
+pub fn deserialize_request<'de, T, D>(deserializer: D, what: &str) -> Result<T, anyhow::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        let path = e.path().to_string();
+        let parent = (path != ".").then_some(path);
+        let detail = e.inner().to_string();
+        rejected_request(format!("{}: {}", what, detail), &detail, parent).into()
+    })
+}
+
+// A request serde could not read: MissingParameter when `detail` (serde's own message) names a
+// missing field, otherwise InvalidRequest. `parent` is the path of the value being read, if known.
+pub fn rejected_request(message: String, detail: &str, parent: Option<String>) -> SimulationError {
+    // serde reports a missing field on the object that lacks it, so the name is only in the message.
+    let missing = detail.strip_prefix("missing field `").and_then(|rest| rest.split('`').next());
+    match missing {
+        Some(name) => SimulationError {
+            code: ErrorCode::MissingParameter,
+            field: Some(parent.map_or(name.to_string(), |parent| format!("{}.{}", parent, name))),
+            message,
+        },
+        None => SimulationError { code: ErrorCode::InvalidRequest, field: parent, message },
+    }
+}
@@ -1,87 +1,453 @@
-use crate::random_process::TimeSeries;
+pub use crate::config::{EtfBenchmark, EtfConstituent, EtfFxSettings, EtfMarketParams}; // Defined with the instrument configs
+use crate::error::{invalid_parameter, within, ErrorCode, SimulationError};
+use crate::random_process::{rng_from_seed, GeometricBrownianMotion, StochasticProcess, TimeSeries, TRADING_DAYS_PER_YEAR};
+use crate::regime_switching::MarkovRegimeSwitching;
 use anyhow::Error;
+use rand_distr::{Distribution, Normal};
 use serde::Deserialize; // Added for derive
+use schemars::JsonSchema;
+use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, Deserialize)] // Added Deserialize
-pub struct EtfConstituent {
-    pub symbol: String,
-    pub initial_price: f64,
-    pub drift: f64,
-    pub volatility: f64,
-    pub weight: f64, // Proportion in the ETF
-}
-
-#[derive(Debug, Clone, Deserialize)] // Added Deserialize
+#[derive(Debug, Clone, Deserialize, JsonSchema)] // Added Deserialize
 pub struct EtfDefinition {
     pub constituents: Vec<EtfConstituent>,
     pub simulation_days: usize, // Number of simulation steps/days
     pub time_step_days: f64,    // Granularity of each step
     pub seed: Option<u64>,
+    // Required when any constituent is quoted in a currency other than the fund's.
+    #[serde(default)]
+    pub fx: Option<EtfFxSettings>,
+    // Annual expense ratio (0.002 for 20 bps), accrued at every step and taken out of NAV.
+    #[serde(default)]
+    pub expense_ratio: f64,
+    // When set, a traded market price is simulated around NAV.
+    #[serde(default)]
+    pub market: Option<EtfMarketParams>,
+    // When set, the fund is compared against this index built from the same constituent paths.
+    #[serde(default)]
+    pub benchmark: Option<EtfBenchmark>,
+}
+
+// Per-period (step i-1 to i) figures are indexed by the end of the period; index 0 is always 0 so
+// every series lines up with the NAV timestamps.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstituentAttribution {
+    pub symbol: String,
+    pub returns: Vec<f64>,       // In the fund's currency
+    pub contributions: Vec<f64>, // Fund weight at the start of the period times the return
+    // Contribution to the fund's return in excess of the benchmark: (fund weight - index weight) * return.
+    pub active_contributions: Vec<f64>,
+    pub total_contribution: f64, // Sum of contributions over all periods
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EtfTrackingReport {
+    pub benchmark_nav: Vec<f64>,
+    // Fund return minus benchmark return, per period.
+    pub tracking_differences: Vec<f64>,
+    // Cumulative fund return minus cumulative benchmark return over the whole simulation.
+    pub tracking_difference: f64,
+    // Annualized standard deviation of the per-period tracking differences.
+    pub tracking_error: f64,
+    pub attribution: Vec<ConstituentAttribution>,
+    // Return lost to the fund's expenses per period; with the constituent contributions it sums to the NAV return.
+    pub expense_contributions: Vec<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EtfMarketSimulation {
+    pub market_prices: Vec<f64>,
+    pub premiums: Vec<f64>, // Market price / NAV - 1
+    pub shares_outstanding: Vec<u64>,
+    pub net_creation_units: Vec<i64>, // Units created (positive) or redeemed (negative) at each step
 }
 
 use crate::stock_simulation::StockSimulator;
 
 const WEIGHT_SUM_ACCURACY: f64 = 1e-6;
+// Offsets the ETF seed for the regime chains so they do not replay a constituent's shock stream.
+const REGIME_SEED_OFFSET: u64 = 0x9E37_79B9_7F4A_7C15;
+// Same for the FX paths, one seed per currency pair after this offset.
+const FX_SEED_OFFSET: u64 = 0xC2B2_AE3D_27D4_EB4F;
+// And for the premium/discount process.
+const PREMIUM_SEED_OFFSET: u64 = 0x1656_67B1_9E37_79F9;
+const BPS: f64 = 1e-4;
+// Most creation units APs can create or redeem in one step; a tiny premium impact would otherwise
+// ask for more units than a share count can hold.
+const MAX_UNITS_PER_STEP: u64 = 1_000_000;
+
+#[derive(Debug, Clone)]
+pub struct EtfNavSimulation {
+    pub nav: TimeSeries,
+    // Regime sequence per constituent, in constituent order; None for constituents without regime switching.
+    pub constituent_regimes: Vec<Option<Vec<usize>>>,
+    // Simulated spot rate (fund currency per unit) for each foreign currency held, by currency code.
+    pub fx_rates: BTreeMap<String, Vec<f64>>,
+    // NAV before expenses; equal to nav when expense_ratio is 0.
+    pub gross_nav: Vec<f64>,
+    pub market: Option<EtfMarketSimulation>,
+    pub tracking: Option<EtfTrackingReport>,
+}
+
+impl EtfBenchmark {
+    // Index weight of each constituent, in constituent order.
+    fn constituent_weights(&self, constituents: &[EtfConstituent]) -> Result<Vec<f64>, Error> {
+        if !(0.0..1.0).contains(&self.expense_ratio) {
+            return Err(invalid_parameter("benchmark.expense_ratio", format!("Benchmark expense ratio must be in [0, 1). Got {}", self.expense_ratio)));
+        }
+        if self.weights.is_empty() {
+            return Ok(constituents.iter().map(|c| c.weight).collect());
+        }
+        if let Some(symbol) = self.weights.keys().find(|symbol| !constituents.iter().any(|c| c.symbol == **symbol)) {
+            return Err(invalid_parameter(&format!("benchmark.weights.{}", symbol), format!("Benchmark weight for '{}', which is not an ETF constituent.", symbol)));
+        }
+        if self.weights.values().any(|w| *w < 0.0) {
+            return Err(invalid_parameter("benchmark.weights", "Benchmark weights cannot be negative."));
+        }
+        let total_weight: f64 = self.weights.values().sum();
+        if (total_weight - 1.0).abs() > WEIGHT_SUM_ACCURACY {
+            return Err(invalid_parameter("benchmark.weights", format!("Sum of benchmark weights ({}) must be close to 1.0.", total_weight)));
+        }
+        Ok(constituents.iter().map(|c| self.weights.get(&c.symbol).copied().unwrap_or(0.0)).collect())
+    }
+}
+
+// Buy-and-hold portfolio worth 1 at t=0: value of each holding at every point, from prices already
+// converted into the fund's currency.
+fn holding_values(prices: &[Vec<f64>], weights: &[f64]) -> Vec<Vec<f64>> {
+    prices.iter().zip(weights)
+        .map(|(path, weight)| path.iter().map(|p| weight / path[0] * p).collect())
+        .collect()
+}
+
+fn period_returns(path: &[f64]) -> Vec<f64> {
+    std::iter::once(0.0).chain(path.windows(2).map(|w| w[1] / w[0] - 1.0)).collect()
+}
+
+fn apply_expenses(gross: &[f64], expense_ratio: f64, time_step_days: f64) -> Vec<f64> {
+    // Expenses accrue continuously on the net assets, so the fee never exceeds the assets however
+    // long the step.
+    gross.iter().enumerate()
+        .map(|(i, value)| value * (-expense_ratio * i as f64 * time_step_days / TRADING_DAYS_PER_YEAR).exp())
+        .collect()
+}
+
+fn tracking_report(
+    etf_def: &EtfDefinition,
+    benchmark: &EtfBenchmark,
+    prices: &[Vec<f64>],
+    fund_values: &[Vec<f64>],
+    net_nav: &[f64],
+) -> Result<EtfTrackingReport, Error> {
+    let benchmark_weights = benchmark.constituent_weights(&etf_def.constituents)?;
+    let benchmark_values = holding_values(prices, &benchmark_weights);
+    let points = net_nav.len();
+    let gross_index: Vec<f64> = (0..points).map(|t| benchmark_values.iter().map(|v| v[t]).sum()).collect();
+    let gross_fund: Vec<f64> = (0..points).map(|t| fund_values.iter().map(|v| v[t]).sum()).collect();
+    let benchmark_nav = apply_expenses(&gross_index, benchmark.expense_ratio, etf_def.time_step_days);
+
+    let fund_returns = period_returns(net_nav);
+    let gross_returns = period_returns(&gross_fund);
+    let tracking_differences: Vec<f64> = fund_returns.iter().zip(period_returns(&benchmark_nav))
+        .map(|(fund, index)| fund - index)
+        .collect();
+    let tracking_difference = (net_nav[points - 1] - net_nav[0]) - (benchmark_nav[points - 1] - benchmark_nav[0]);
+
+    let periods = (points - 1) as f64;
+    let tracking_error = if points > 2 {
+        let mean = tracking_differences[1..].iter().sum::<f64>() / periods;
+        let variance = tracking_differences[1..].iter().map(|d| (d - mean).powi(2)).sum::<f64>() / (periods - 1.0);
+        (variance * TRADING_DAYS_PER_YEAR / etf_def.time_step_days).sqrt()
+    } else {
+        0.0
+    };
+
+    let attribution = etf_def.constituents.iter().enumerate().map(|(j, constituent)| {
+        let returns = period_returns(&prices[j]);
+        let mut contributions = vec![0.0; points];
+        let mut active_contributions = vec![0.0; points];
+        for t in 1..points {
+            let fund_weight = fund_values[j][t - 1] / gross_fund[t - 1];
+            let index_weight = benchmark_values[j][t - 1] / gross_index[t - 1];
+            contributions[t] = fund_weight * returns[t];
+            active_contributions[t] = (fund_weight - index_weight) * returns[t];
+        }
+        ConstituentAttribution {
+            symbol: constituent.symbol.clone(),
+            total_contribution: contributions.iter().sum(),
+            returns,
+            contributions,
+            active_contributions,
+        }
+    }).collect();
+    let expense_contributions = fund_returns.iter().zip(&gross_returns).map(|(net, gross)| net - gross).collect();
+
+    Ok(EtfTrackingReport {
+        benchmark_nav,
+        tracking_differences,
+        tracking_difference,
+        tracking_error,
+        attribution,
+        expense_contributions,
+    })
+}
+
+impl EtfMarketParams {
+    fn validate(&self) -> Result<(), Error> {
+        let finite = [
+            ("market.initial_premium", self.initial_premium),
+            ("market.premium_mean_reversion", self.premium_mean_reversion),
+            ("market.premium_volatility", self.premium_volatility),
+            ("market.creation_cost_bps", self.creation_cost_bps),
+            ("market.redemption_cost_bps", self.redemption_cost_bps),
+        ];
+        if let Some((field, value)) = finite.iter().find(|(_, value)| !value.is_finite()) {
+            return Err(invalid_parameter(field, format!("Market parameters must be finite. Got {}", value)));
+        }
+        if self.premium_mean_reversion < 0.0 || self.premium_volatility < 0.0 {
+            let field = if self.premium_mean_reversion < 0.0 { "market.premium_mean_reversion" } else { "market.premium_volatility" };
+            return Err(invalid_parameter(field, "Premium mean reversion and volatility cannot be negative."));
+        }
+        if self.creation_unit_size == 0 {
+            return Err(invalid_parameter("market.creation_unit_size", "Creation unit size must be positive."));
+        }
+        if self.creation_cost_bps < 0.0 || self.redemption_cost_bps < 0.0 {
+            let field = if self.creation_cost_bps < 0.0 { "market.creation_cost_bps" } else { "market.redemption_cost_bps" };
+            return Err(invalid_parameter(field, "Creation and redemption costs cannot be negative."));
+        }
+        // Subnormal impacts are rejected too: dividing a premium by one overflows.
+        if !(self.premium_impact_bps_per_unit.is_normal() && self.premium_impact_bps_per_unit > 0.0) {
+            return Err(invalid_parameter("market.premium_impact_bps_per_unit", "Premium impact per creation unit must be positive."));
+        }
+        if self.initial_premium <= -1.0 {
+            return Err(invalid_parameter("market.initial_premium", "Initial premium must be above -100%."));
+        }
+        Ok(())
+    }
+}
+
+// Premium/discount path with AP arbitrage on top of a simulated NAV path.
+fn simulate_market(params: &EtfMarketParams, nav: &[f64], time_step_days: f64, seed: Option<u64>) -> Result<EtfMarketSimulation, Error> {
+    let mut rng = rng_from_seed(seed);
+    let normal_dist = Normal::new(0.0, 1.0).unwrap();
+    let dt_years = time_step_days / TRADING_DAYS_PER_YEAR;
+    let decay = (-params.premium_mean_reversion * dt_years).exp();
+    let shock_std = if params.premium_mean_reversion > 0.0 {
+        params.premium_volatility * ((1.0 - decay * decay) / (2.0 * params.premium_mean_reversion)).sqrt()
+    } else {
+        params.premium_volatility * dt_years.sqrt()
+    };
+    let (upper_band, lower_band) = (params.creation_cost_bps * BPS, -params.redemption_cost_bps * BPS);
+    let impact = params.premium_impact_bps_per_unit * BPS;
+
+    let mut premium = params.initial_premium;
+    let mut shares = params.initial_shares_outstanding;
+    let mut simulation = EtfMarketSimulation {
+        market_prices: Vec::with_capacity(nav.len()),
+        premiums: Vec::with_capacity(nav.len()),
+        shares_outstanding: Vec::with_capacity(nav.len()),
+        net_creation_units: Vec::with_capacity(nav.len()),
+    };
+    for (i, nav_t) in nav.iter().enumerate() {
+        let mut units: i64 = 0;
+        if i > 0 {
+            premium = premium * decay + shock_std * normal_dist.sample(&mut rng);
+            if premium > upper_band {
+                // Creating a unit is profitable: buy the basket, deliver it, sell the new shares.
+                let created = units_for(premium - upper_band, impact);
+                units = created as i64;
+                premium -= created as f64 * impact;
+                shares = created.checked_mul(params.creation_unit_size)
+                    .and_then(|new_shares| shares.checked_add(new_shares))
+                    .ok_or_else(share_count_overflow)?;
+            } else if premium < lower_band {
+                // Redemptions are limited by the shares in issue.
+                let redeemed = units_for(lower_band - premium, impact).min(shares / params.creation_unit_size);
+                units = -(redeemed as i64);
+                premium += redeemed as f64 * impact;
+                // Cannot overflow: at most shares / creation_unit_size units are redeemed.
+                shares -= redeemed * params.creation_unit_size;
+            }
+            premium = premium.max(-1.0 + f64::EPSILON);
+        }
+        simulation.market_prices.push(nav_t * (1.0 + premium));
+        simulation.premiums.push(premium);
+        simulation.shares_outstanding.push(shares);
+        simulation.net_creation_units.push(units);
+    }
+    Ok(simulation)
+}
+
+// Creation units that close a premium gap at `impact` per unit, at most MAX_UNITS_PER_STEP.
+fn units_for(gap: f64, impact: f64) -> u64 {
+    (gap / impact).ceil().min(MAX_UNITS_PER_STEP as f64) as u64
+}
+
+fn share_count_overflow() -> Error {
+    SimulationError::new(
+        ErrorCode::SimulationFailed,
+        Some("market.creation_unit_size"),
+        "Shares outstanding overflowed; use a smaller creation_unit_size or initial_shares_outstanding.",
+    ).into()
+}
+
+// (conversion factors used for NAV, simulated spot paths), by currency code.
+type FxConversion = (BTreeMap<String, Vec<f64>>, BTreeMap<String, Vec<f64>>);
+
+// Conversion factor into the fund's currency at each point of the simulation for every foreign
+// currency held: the simulated spot path, or the inception forward for a hedged share class.
+fn simulate_fx_conversion(etf_def: &EtfDefinition) -> Result<FxConversion, Error> {
+    let mut factors = BTreeMap::new();
+    let mut spot_paths = BTreeMap::new();
+    let settings = match &etf_def.fx {
+        Some(settings) => settings,
+        None => {
+            if let Some(i) = etf_def.constituents.iter().position(|c| c.currency.is_some()) {
+                return Err(invalid_parameter(
+                    &format!("constituents[{}].currency", i),
+                    format!("Constituent '{}' has a currency but the ETF has no fx settings.", etf_def.constituents[i].symbol),
+                ));
+            }
+            return Ok((factors, spot_paths));
+        }
+    };
+
+    for (k, pair) in settings.fx_pairs.iter().enumerate() {
+        let field = format!("fx.fx_pairs[{}]", k);
+        pair.validate().map_err(|e| within(&field, e))?;
+        if pair.quote_currency != settings.base_currency {
+            return Err(invalid_parameter(
+                &format!("{}.quote_currency", field),
+                format!(
+                    "FX pair {}/{} must be quoted in the fund's base currency {}.",
+                    pair.base_currency, pair.quote_currency, settings.base_currency
+                ),
+            ));
+        }
+        if settings.fx_pairs[..k].iter().any(|other| other.base_currency == pair.base_currency) {
+            return Err(invalid_parameter(
+                &format!("{}.base_currency", field),
+                format!("More than one FX pair for currency {}.", pair.base_currency),
+            ));
+        }
+    }
+
+    for (i, constituent) in etf_def.constituents.iter().enumerate() {
+        let currency = match &constituent.currency {
+            Some(currency) if *currency != settings.base_currency => currency,
+            _ => continue,
+        };
+        if spot_paths.contains_key(currency) {
+            continue;
+        }
+        let (k, pair) = settings.fx_pairs.iter().enumerate()
+            .find(|(_, pair)| pair.base_currency == *currency)
+            .ok_or_else(|| invalid_parameter(
+                &format!("constituents[{}].currency", i),
+                format!("No FX pair {}/{} for constituent '{}'.", currency, settings.base_currency, constituent.symbol),
+            ))?;
+
+        let horizon = etf_def.simulation_days as f64 * etf_def.time_step_days / TRADING_DAYS_PER_YEAR;
+        let gbm = GeometricBrownianMotion::new(pair.rate_differential(horizon), pair.volatility);
+        let fx_seed = etf_def.seed.map(|s| s.wrapping_add(FX_SEED_OFFSET).wrapping_add(k as u64));
+        let spot_path = gbm.generate_path(pair.spot_rate, etf_def.time_step_days, etf_def.simulation_days, fx_seed).prices;
+
+        let factor_path = if settings.currency_hedged {
+            (0..etf_def.simulation_days)
+                .map(|i| pair.forward_rate(i as f64 * etf_def.time_step_days / TRADING_DAYS_PER_YEAR))
+                .collect()
+        } else {
+            spot_path.clone()
+        };
+        factors.insert(currency.clone(), factor_path);
+        spot_paths.insert(currency.clone(), spot_path);
+    }
+    Ok((factors, spot_paths))
+}
 
 pub fn simulate_etf_nav(etf_def: &EtfDefinition) -> Result<TimeSeries, Error> {
+    simulate_etf_with_market(etf_def).map(|simulation| simulation.nav)
+}
+
+// NAV together with FX paths and, when configured, the market price and benchmark tracking.
+// All regime-switching constituents draw their regime chain from one shared seed, so constituents
+// with the same transition matrix enter and leave regimes (e.g. a crisis) together.
+pub fn simulate_etf_with_market(etf_def: &EtfDefinition) -> Result<EtfNavSimulation, Error> {
     if etf_def.constituents.is_empty() {
-        return Err(anyhow::anyhow!("ETF constituents list cannot be empty."));
+        return Err(invalid_parameter("constituents", "ETF constituents list cannot be empty."));
     }
 
     let total_weight: f64 = etf_def.constituents.iter().map(|c| c.weight).sum();
     if (total_weight - 1.0).abs() > WEIGHT_SUM_ACCURACY {
-        return Err(anyhow::anyhow!(
-            "Sum of constituent weights ({}) must be close to 1.0.",
-            total_weight
+        return Err(invalid_parameter(
+            "constituents",
+            format!("Sum of constituent weights ({}) must be close to 1.0.", total_weight),
         ));
     }
     if etf_def.simulation_days == 0 {
-        return Err(anyhow::anyhow!("Simulation days must be greater than 0."));
+        return Err(invalid_parameter("simulation_days", "Simulation days must be greater than 0."));
     }
      if etf_def.time_step_days <= 0.0 {
-        return Err(anyhow::anyhow!("Time step in days must be positive."));
+        return Err(invalid_parameter("time_step_days", "Time step in days must be positive."));
+    }
+    if !(0.0..1.0).contains(&etf_def.expense_ratio) {
+        return Err(invalid_parameter("expense_ratio", format!("Expense ratio must be in [0, 1). Got {}", etf_def.expense_ratio)));
+    }
+    if let Some(market) = &etf_def.market {
+        market.validate()?;
     }
 
 
+    let (fx_factors, fx_rates) = simulate_fx_conversion(etf_def)?;
+
     let mut constituent_price_paths: Vec<Vec<f64>> = Vec::with_capacity(etf_def.constituents.len());
+    let mut constituent_regimes: Vec<Option<Vec<usize>>> = Vec::with_capacity(etf_def.constituents.len());
     let mut timestamps: Option<Vec<chrono::NaiveDateTime>> = None;
+    let regime_seed = etf_def.seed.map_or_else(rand::random, |s| s.wrapping_add(REGIME_SEED_OFFSET));
 
     for (i, constituent) in etf_def.constituents.iter().enumerate() {
         if constituent.initial_price <= 0.0 {
-            return Err(anyhow::anyhow!("Constituent '{}' initial price must be positive.", constituent.symbol));
+            return Err(invalid_parameter(&format!("constituents[{}].initial_price", i), format!("Constituent '{}' initial price must be positive.", constituent.symbol)));
         }
         if constituent.volatility < 0.0 {
-             return Err(anyhow::anyhow!("Constituent '{}' volatility cannot be negative.", constituent.symbol));
+             return Err(invalid_parameter(&format!("constituents[{}].volatility", i), format!("Constituent '{}' volatility cannot be negative.", constituent.symbol)));
         }
         if constituent.weight < 0.0 { // Weight can be 0, but not negative
-             return Err(anyhow::anyhow!("Constituent '{}' weight cannot be negative.", constituent.symbol));
+             return Err(invalid_parameter(&format!("constituents[{}].weight", i), format!("Constituent '{}' weight cannot be negative.", constituent.symbol)));
         }
 
 
-        let constituent_seed = etf_def.seed.map(|s| s + i as u64);
-        let stock_path_result = StockSimulator::simulate_stock_price(
-            constituent.initial_price,
-            constituent.drift,
-            constituent.volatility,
-            etf_def.simulation_days, // This is 'steps' for simulate_stock_price
-            etf_def.time_step_days,
-            constituent_seed,
-        );
+        let constituent_seed = etf_def.seed.map(|s| s.wrapping_add(i as u64));
+        let stock_path_result = match &constituent.regime_switching {
+            Some(params) => MarkovRegimeSwitching::from_params(params).map_err(|e| within("regime_switching", e)).and_then(|process| {
+                let process = MarkovRegimeSwitching { regime_seed: Some(regime_seed), ..process };
+                StockSimulator::simulate_with_regimes(
+                    &process,
+                    constituent.initial_price,
+                    etf_def.simulation_days,
+                    etf_def.time_step_days,
+                    constituent_seed,
+                )
+            }).map(|regime_path| (regime_path.path, Some(regime_path.regimes))),
+            None => StockSimulator::simulate_stock_price(
+                constituent.initial_price,
+                constituent.drift,
+                constituent.volatility,
+                etf_def.simulation_days, // This is 'steps' for simulate_stock_price
+                etf_def.time_step_days,
+                constituent_seed,
+            ).map(|stock_path| (stock_path, None)),
+        };
 
         match stock_path_result {
-            Ok(stock_path) => {
+            Ok((stock_path, regimes)) => {
                 if i == 0 {
                     timestamps = Some(stock_path.timestamps);
                 }
                 constituent_price_paths.push(stock_path.prices);
+                constituent_regimes.push(regimes);
             }
-            Err(e) => {
-                return Err(anyhow::anyhow!(
-                    "Failed to simulate stock price for constituent {}: {}",
-                    constituent.symbol,
-                    e
-                ));
-            }
+            Err(e) => return Err(within(&format!("constituents[{}]", i), e)),
         }
     }
 
@@ -101,26 +467,41 @@ pub fn simulate_etf_nav(etf_def: &EtfDefinition) -> Result<TimeSeries, Error> {
     // The number of points in constituent_price_paths[j] is etf_def.simulation_days as per StockSimulator.
     // So, the NAV path will also have etf_def.simulation_days points.
 
-    let mut etf_nav_path = Vec::with_capacity(num_nav_points);
-
-    for t_idx in 0..num_nav_points {
-        let mut nav_at_t = 0.0;
-        for (j, constituent) in etf_def.constituents.iter().enumerate() {
-            let price_j_t = constituent_price_paths[j][t_idx];
-            // Initial number of shares of constituent j for a $1 initial ETF investment
-            let num_shares_j = constituent.weight / constituent.initial_price;
-            nav_at_t += num_shares_j * price_j_t;
-        }
-        etf_nav_path.push(nav_at_t);
-    }
+    // Foreign prices are converted into the fund's currency; 1.0 for the fund's own currency.
+    let converted_prices: Vec<Vec<f64>> = etf_def.constituents.iter().zip(&constituent_price_paths)
+        .map(|(constituent, price_path)| {
+            let fx_path = constituent.currency.as_ref().and_then(|currency| fx_factors.get(currency));
+            (0..num_nav_points).map(|t_idx| price_path[t_idx] * fx_path.map_or(1.0, |path| path[t_idx])).collect()
+        })
+        .collect();
+    // Initial number of shares of each constituent is set for a 1-unit initial ETF investment.
+    let weights: Vec<f64> = etf_def.constituents.iter().map(|c| c.weight).collect();
+    let fund_values = holding_values(&converted_prices, &weights);
+    let etf_nav_path: Vec<f64> = (0..num_nav_points).map(|t_idx| fund_values.iter().map(|v| v[t_idx]).sum()).collect();
 
     // If the NAV path has fewer points than timestamps due to how steps vs points are handled:
     // The timestamps from simulate_stock_price will match the number of price points it generates.
     // If simulation_days is 1 for stock_simulator, it produces 1 price point.
     // So final_timestamps should have the same length as etf_nav_path.
 
-    Ok(TimeSeries {
-        timestamps: final_timestamps,
-        prices: etf_nav_path,
+    let net_nav_path = apply_expenses(&etf_nav_path, etf_def.expense_ratio, etf_def.time_step_days);
+    let tracking = etf_def.benchmark.as_ref()
+        .map(|benchmark| tracking_report(etf_def, benchmark, &converted_prices, &fund_values, &net_nav_path))
+        .transpose()?;
+    let market = etf_def.market.as_ref().map(|params| {
+        let premium_seed = etf_def.seed.map(|s| s.wrapping_add(PREMIUM_SEED_OFFSET));
+        simulate_market(params, &net_nav_path, etf_def.time_step_days, premium_seed)
+    }).transpose()?;
+
+    Ok(EtfNavSimulation {
+        nav: TimeSeries {
+            timestamps: final_timestamps,
+            prices: net_nav_path,
+        },
+        constituent_regimes,
+        fx_rates,
+        gross_nav: etf_nav_path,
+        market,
+        tracking,
     })
 }
@@ -0,0 +1,190 @@
+use crate::random_process::TimeSeries;
+use anyhow::Error;
+use std::io::Write;
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, Float64Array, RecordBatch, TimestampMillisecondArray, UInt32Array};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+
+// Same layout as the JSON responses, so CSV and JSON timestamps line up.
+pub const DEFAULT_TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+// --- CSV ---
+
+#[derive(Debug, Clone)]
+pub struct CsvExportOptions {
+    pub delimiter: u8,
+    pub timestamp_format: String, // chrono strftime format
+    pub include_header: bool,
+}
+
+impl Default for CsvExportOptions {
+    fn default() -> Self {
+        CsvExportOptions {
+            delimiter: b',',
+            timestamp_format: DEFAULT_TIMESTAMP_FORMAT.to_string(),
+            include_header: true,
+        }
+    }
+}
+
+fn validate_series(series: &TimeSeries) -> Result<(), Error> {
+    if series.timestamps.len() != series.prices.len() {
+        return Err(anyhow::anyhow!(
+            "Time series has {} timestamps but {} prices.",
+            series.timestamps.len(),
+            series.prices.len()
+        ));
+    }
+    Ok(())
+}
+
+fn csv_writer<W: Write>(writer: W, options: &CsvExportOptions) -> csv::Writer<W> {
+    csv::WriterBuilder::new()
+        .delimiter(options.delimiter)
+        .has_headers(false) // Headers are written explicitly so include_header is honoured for empty series too
+        .from_writer(writer)
+}
+
+// Writes a single series as `timestamp,price` rows.
+pub fn write_time_series_csv<W: Write>(writer: W, series: &TimeSeries, options: &CsvExportOptions) -> Result<(), Error> {
+    validate_series(series)?;
+    let mut wtr = csv_writer(writer, options);
+    if options.include_header {
+        wtr.write_record(["timestamp", "price"])?;
+    }
+    for (timestamp, price) in series.timestamps.iter().zip(&series.prices) {
+        wtr.write_record([timestamp.format(&options.timestamp_format).to_string(), price.to_string()])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+// Writes several paths in long format (`path,timestamp,price`), one row per path and step.
+pub fn write_paths_csv<W: Write>(writer: W, paths: &[TimeSeries], options: &CsvExportOptions) -> Result<(), Error> {
+    let mut wtr = csv_writer(writer, options);
+    if options.include_header {
+        wtr.write_record(["path", "timestamp", "price"])?;
+    }
+    for (path_idx, series) in paths.iter().enumerate() {
+        validate_series(series)?;
+        for (timestamp, price) in series.timestamps.iter().zip(&series.prices) {
+            wtr.write_record([
+                path_idx.to_string(),
+                timestamp.format(&options.timestamp_format).to_string(),
+                price.to_string(),
+            ])?;
+        }
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn time_series_to_csv(series: &TimeSeries, options: &CsvExportOptions) -> Result<String, Error> {
+    let mut buffer = Vec::new();
+    write_time_series_csv(&mut buffer, series, options)?;
+    Ok(String::from_utf8(buffer)?)
+}
+
+pub fn paths_to_csv(paths: &[TimeSeries], options: &CsvExportOptions) -> Result<String, Error> {
+    let mut buffer = Vec::new();
+    write_paths_csv(&mut buffer, paths, options)?;
+    Ok(String::from_utf8(buffer)?)
+}
+
+// --- Arrow / Parquet ---
+// Columns: optional `path` (UInt32), `timestamp` (millisecond timestamp, no timezone), `price` (Float64).
+
+fn series_record_batch(paths: &[TimeSeries], with_path_column: bool) -> Result<RecordBatch, Error> {
+    let mut path_ids = Vec::new();
+    let mut timestamps = Vec::new();
+    let mut prices = Vec::new();
+
+    for (path_idx, series) in paths.iter().enumerate() {
+        validate_series(series)?;
+        let path_id = u32::try_from(path_idx).map_err(|_| anyhow::anyhow!("Too many paths to export."))?;
+        for (timestamp, price) in series.timestamps.iter().zip(&series.prices) {
+            path_ids.push(path_id);
+            timestamps.push(timestamp.and_utc().timestamp_millis());
+            prices.push(*price);
+        }
+    }
+
+    let mut fields = Vec::with_capacity(3);
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(3);
+    if with_path_column {
+        fields.push(Field::new("path", DataType::UInt32, false));
+        columns.push(Arc::new(UInt32Array::from(path_ids)));
+    }
+    fields.push(Field::new("timestamp", DataType::Timestamp(TimeUnit::Millisecond, None), false));
+    columns.push(Arc::new(TimestampMillisecondArray::from(timestamps)));
+    fields.push(Field::new("price", DataType::Float64, false));
+    columns.push(Arc::new(Float64Array::from(prices)));
+
+    Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)?)
+}
+
+fn write_arrow_ipc<W: Write>(writer: W, batch: &RecordBatch) -> Result<(), Error> {
+    let mut ipc_writer = arrow_ipc::writer::StreamWriter::try_new(writer, &batch.schema())?;
+    ipc_writer.write(batch)?;
+    ipc_writer.finish()?;
+    Ok(())
+}
+
+fn write_parquet<W: Write + Send>(writer: W, batch: &RecordBatch) -> Result<(), Error> {
+    let props = parquet::file::properties::WriterProperties::builder()
+        .set_compression(parquet::basic::Compression::SNAPPY)
+        .build();
+    let mut parquet_writer = parquet::arrow::ArrowWriter::try_new(writer, batch.schema(), Some(props))?;
+    parquet_writer.write(batch)?;
+    parquet_writer.close()?;
+    Ok(())
+}
+
+// Writes a single series as an Arrow IPC stream with `timestamp` and `price` columns.
+pub fn write_time_series_arrow_ipc<W: Write>(writer: W, series: &TimeSeries) -> Result<(), Error> {
+    let batch = series_record_batch(std::slice::from_ref(series), false)?;
+    write_arrow_ipc(writer, &batch)
+}
+
+// Writes several paths as an Arrow IPC stream with `path`, `timestamp` and `price` columns.
+pub fn write_paths_arrow_ipc<W: Write>(writer: W, paths: &[TimeSeries]) -> Result<(), Error> {
+    let batch = series_record_batch(paths, true)?;
+    write_arrow_ipc(writer, &batch)
+}
+
+// Writes a single series as a Snappy-compressed Parquet file with `timestamp` and `price` columns.
+pub fn write_time_series_parquet<W: Write + Send>(writer: W, series: &TimeSeries) -> Result<(), Error> {
+    let batch = series_record_batch(std::slice::from_ref(series), false)?;
+    write_parquet(writer, &batch)
+}
+
+// Writes several paths as a Snappy-compressed Parquet file with `path`, `timestamp` and `price` columns.
+pub fn write_paths_parquet<W: Write + Send>(writer: W, paths: &[TimeSeries]) -> Result<(), Error> {
+    let batch = series_record_batch(paths, true)?;
+    write_parquet(writer, &batch)
+}
+
+pub fn time_series_to_arrow_ipc(series: &TimeSeries) -> Result<Vec<u8>, Error> {
+    let mut buffer = Vec::new();
+    write_time_series_arrow_ipc(&mut buffer, series)?;
+    Ok(buffer)
+}
+
+pub fn paths_to_arrow_ipc(paths: &[TimeSeries]) -> Result<Vec<u8>, Error> {
+    let mut buffer = Vec::new();
+    write_paths_arrow_ipc(&mut buffer, paths)?;
+    Ok(buffer)
+}
+
+pub fn time_series_to_parquet(series: &TimeSeries) -> Result<Vec<u8>, Error> {
+    let mut buffer = Vec::new();
+    write_time_series_parquet(&mut buffer, series)?;
+    Ok(buffer)
+}
+
+pub fn paths_to_parquet(paths: &[TimeSeries]) -> Result<Vec<u8>, Error> {
+    let mut buffer = Vec::new();
+    write_paths_parquet(&mut buffer, paths)?;
+    Ok(buffer)
+}
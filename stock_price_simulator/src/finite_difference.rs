@@ -0,0 +1,340 @@
+use crate::config::OptionType;
+use crate::option_pricing::{EuropeanOption, OptionPricer};
+use crate::error::invalid_parameter;
+use anyhow::Error;
+
+// Number of initial time steps taken fully implicit (Rannacher start-up) to damp the
+// Crank-Nicolson oscillations caused by the payoff kink.
+const RANNACHER_STEPS: usize = 2;
+// Width of the spot grid above max(S, K) in standard deviations of ln S over the option's life.
+const GRID_STD_DEVS: f64 = 6.0;
+const PSOR_RELAXATION: f64 = 1.2;
+const PSOR_TOLERANCE: f64 = 1e-10;
+const PSOR_MAX_ITERATIONS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExerciseStyle {
+    European,
+    American,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BarrierType {
+    UpAndOut,
+    DownAndOut,
+    UpAndIn,
+    DownAndIn,
+}
+
+// Continuously monitored barrier without rebate.
+#[derive(Debug, Clone, Copy)]
+pub struct Barrier {
+    pub barrier_type: BarrierType,
+    pub level: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FiniteDifferenceResult {
+    pub price: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub theta: f64, // Per year of calendar time
+}
+
+// Crank-Nicolson solver for the Black-Scholes PDE
+// V_t + 1/2 sigma^2 S^2 V_SS + r(t) S V_S - r(t) V = 0
+// on a uniform spot grid, stepping backwards from maturity. Knock-out barriers are grid boundaries;
+// knock-in options are priced by in-out parity (European only).
+#[derive(Debug, Clone)]
+pub struct FiniteDifferenceOptionPricer {
+    pub underlying_price: f64,
+    pub strike_price: f64,
+    pub time_to_maturity_years: f64,
+    pub risk_free_rate: f64,
+    pub volatility: f64,
+    pub option_type: OptionType,
+    pub exercise: ExerciseStyle,
+    pub barrier: Option<Barrier>,
+    // (time in years, annualized short rate) knots, linearly interpolated and flat outside.
+    // Replaces risk_free_rate when set.
+    pub short_rates: Option<Vec<(f64, f64)>>,
+    pub spot_steps: usize,
+    pub time_steps: usize,
+}
+
+struct Grid {
+    s_min: f64,
+    ds: f64,
+    spots: Vec<f64>,
+}
+
+fn solve_tridiagonal(lower: &[f64], diag: &[f64], upper: &[f64], rhs: &[f64]) -> Vec<f64> {
+    let n = diag.len();
+    let mut c_prime = vec![0.0; n];
+    let mut d_prime = vec![0.0; n];
+    c_prime[0] = upper[0] / diag[0];
+    d_prime[0] = rhs[0] / diag[0];
+    for i in 1..n {
+        let m = diag[i] - lower[i] * c_prime[i - 1];
+        c_prime[i] = upper[i] / m;
+        d_prime[i] = (rhs[i] - lower[i] * d_prime[i - 1]) / m;
+    }
+    let mut x = vec![0.0; n];
+    x[n - 1] = d_prime[n - 1];
+    for i in (0..n - 1).rev() {
+        x[i] = d_prime[i] - c_prime[i] * x[i + 1];
+    }
+    x
+}
+
+// Projected SOR for the linear complementarity problem A x >= rhs, x >= floor, with equality in one
+// of the two at every node. Starts from `x`.
+fn solve_projected_sor(lower: &[f64], diag: &[f64], upper: &[f64], rhs: &[f64], floor: &[f64], mut x: Vec<f64>) -> Vec<f64> {
+    let n = diag.len();
+    for _ in 0..PSOR_MAX_ITERATIONS {
+        let mut change: f64 = 0.0;
+        for i in 0..n {
+            let mut residual = rhs[i] - diag[i] * x[i];
+            if i > 0 {
+                residual -= lower[i] * x[i - 1];
+            }
+            if i + 1 < n {
+                residual -= upper[i] * x[i + 1];
+            }
+            let updated = (x[i] + PSOR_RELAXATION * residual / diag[i]).max(floor[i]);
+            change = change.max((updated - x[i]).abs());
+            x[i] = updated;
+        }
+        if change < PSOR_TOLERANCE {
+            break;
+        }
+    }
+    x
+}
+
+impl FiniteDifferenceOptionPricer {
+    // Vanilla European pricer on a grid of the given size, using the option's volatility and flat rate.
+    // An option's discount curve is sampled into short rate knots at the grid's time levels.
+    pub fn for_european_option(option: &EuropeanOption, spot_steps: usize, time_steps: usize) -> Result<Self, Error> {
+        let risk_free_rate = option.rate_to_maturity()?;
+        let short_rates = option.discount_curve.as_ref().map(|curve| {
+            let dt = option.time_to_maturity_years / time_steps.max(1) as f64;
+            (0..=time_steps).map(|n| {
+                let t = n as f64 * dt;
+                (t, curve.instantaneous_forward(t))
+            }).collect()
+        });
+        Ok(FiniteDifferenceOptionPricer {
+            underlying_price: option.underlying_price,
+            strike_price: option.strike_price,
+            time_to_maturity_years: option.time_to_maturity_years,
+            risk_free_rate,
+            volatility: option.volatility,
+            option_type: option.option_type,
+            exercise: ExerciseStyle::European,
+            barrier: None,
+            short_rates,
+            spot_steps,
+            time_steps,
+        })
+    }
+
+    fn short_rate(&self, t: f64) -> f64 {
+        let knots = match &self.short_rates {
+            Some(knots) => knots,
+            None => return self.risk_free_rate,
+        };
+        let last = knots.len() - 1;
+        if t <= knots[0].0 {
+            return knots[0].1;
+        }
+        if t >= knots[last].0 {
+            return knots[last].1;
+        }
+        let hi = knots.partition_point(|(time, _)| *time <= t);
+        let ((t0, r0), (t1, r1)) = (knots[hi - 1], knots[hi]);
+        r0 + (t - t0) / (t1 - t0) * (r1 - r0)
+    }
+
+    fn payoff(&self, s: f64) -> f64 {
+        match self.option_type {
+            OptionType::Call => (s - self.strike_price).max(0.0),
+            OptionType::Put => (self.strike_price - s).max(0.0),
+        }
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        let (s, k, t, sigma) = (self.underlying_price, self.strike_price, self.time_to_maturity_years, self.volatility);
+        if s <= 0.0 { return Err(invalid_parameter("underlying_price", format!("Underlying price (S) must be positive. Got {}", s))); }
+        if k <= 0.0 { return Err(invalid_parameter("strike_price", format!("Strike price (K) must be positive. Got {}", k))); }
+        if t <= 0.0 { return Err(invalid_parameter("time_to_maturity_years", format!("Time to maturity (T) must be positive. Got {}", t))); }
+        if sigma <= 0.0 { return Err(invalid_parameter("volatility", format!("Volatility (sigma) must be positive. Got {}", sigma))); }
+        if self.spot_steps < 3 || self.time_steps == 0 {
+            let field = if self.spot_steps < 3 { "spot_steps" } else { "time_steps" };
+            return Err(invalid_parameter(field, "Finite-difference grid needs at least 3 spot steps and 1 time step."));
+        }
+        if let Some(knots) = &self.short_rates {
+            if knots.is_empty() || knots.windows(2).any(|w| w[1].0 <= w[0].0) {
+                return Err(invalid_parameter("short_rates", "Short rate knots must be non-empty with strictly increasing times."));
+            }
+        }
+        if let Some(barrier) = &self.barrier {
+            if barrier.level <= 0.0 {
+                return Err(invalid_parameter("barrier.level", format!("Barrier level must be positive. Got {}", barrier.level)));
+            }
+            let knock_in = matches!(barrier.barrier_type, BarrierType::UpAndIn | BarrierType::DownAndIn);
+            if knock_in && self.exercise == ExerciseStyle::American {
+                return Err(invalid_parameter("exercise", "Knock-in barriers are only supported with European exercise."));
+            }
+        }
+        Ok(())
+    }
+
+    fn build_grid(&self, lower_barrier: Option<f64>, upper_barrier: Option<f64>) -> Grid {
+        let spread = (GRID_STD_DEVS * self.volatility * self.time_to_maturity_years.sqrt()).exp().max(2.0);
+        let s_min = lower_barrier.unwrap_or(0.0);
+        let s_max = upper_barrier.unwrap_or(self.underlying_price.max(self.strike_price) * spread);
+        let ds = (s_max - s_min) / self.spot_steps as f64;
+        let spots = (0..=self.spot_steps).map(|i| s_min + i as f64 * ds).collect();
+        Grid { s_min, ds, spots }
+    }
+
+    // Values at the boundaries given the discount factor from the current time to maturity.
+    fn boundary_values(&self, grid: &Grid, knock_out_low: bool, knock_out_high: bool, discount: f64) -> (f64, f64) {
+        let (s_low, s_high) = (grid.spots[0], grid.spots[grid.spots.len() - 1]);
+        let american = self.exercise == ExerciseStyle::American;
+        let low = if knock_out_low {
+            0.0
+        } else {
+            match self.option_type {
+                OptionType::Call => 0.0,
+                OptionType::Put if american => self.strike_price - s_low,
+                OptionType::Put => self.strike_price * discount - s_low,
+            }
+        };
+        let high = if knock_out_high {
+            0.0
+        } else {
+            match self.option_type {
+                OptionType::Call => s_high - self.strike_price * discount, // Never exercised early without dividends
+                OptionType::Put => 0.0,
+            }
+        };
+        (low, high)
+    }
+
+    // Solves for a vanilla (barrier = None) or knock-out option and returns the values on the grid at
+    // t = 0 and one time step later.
+    fn solve_grid(&self, knock_out: Option<Barrier>) -> (Grid, Vec<f64>, Vec<f64>) {
+        let lower_barrier = knock_out.filter(|b| b.barrier_type == BarrierType::DownAndOut).map(|b| b.level);
+        let upper_barrier = knock_out.filter(|b| b.barrier_type == BarrierType::UpAndOut).map(|b| b.level);
+        let grid = self.build_grid(lower_barrier, upper_barrier);
+        let n = grid.spots.len();
+        let dt = self.time_to_maturity_years / self.time_steps as f64;
+        let sigma2 = self.volatility * self.volatility;
+
+        let payoff: Vec<f64> = grid.spots.iter().map(|s| self.payoff(*s)).collect();
+        let mut values = payoff.clone();
+        if lower_barrier.is_some() { values[0] = 0.0; }
+        if upper_barrier.is_some() { values[n - 1] = 0.0; }
+        let mut previous = values.clone();
+        let mut discount = 1.0; // From the current time level to maturity
+
+        // Interior unknowns are nodes 1..n-1; the boundaries enter through the right-hand side.
+        let m = n - 2;
+        for step in 0..self.time_steps {
+            let t_mid = self.time_to_maturity_years - (step as f64 + 0.5) * dt;
+            let r = self.short_rate(t_mid);
+            discount *= (-r * dt).exp();
+            let theta = if step < RANNACHER_STEPS { 1.0 } else { 0.5 };
+
+            let (mut lower, mut diag, mut upper, mut rhs) = (vec![0.0; m], vec![0.0; m], vec![0.0; m], vec![0.0; m]);
+            let (low_value, high_value) = self.boundary_values(&grid, lower_barrier.is_some(), upper_barrier.is_some(), discount);
+            for j in 0..m {
+                let i = j + 1;
+                let x = (grid.s_min + i as f64 * grid.ds) / grid.ds; // S_i / dS
+                let a = 0.5 * sigma2 * x * x - 0.5 * r * x;
+                let b = -sigma2 * x * x - r;
+                let c = 0.5 * sigma2 * x * x + 0.5 * r * x;
+
+                lower[j] = -theta * dt * a;
+                diag[j] = 1.0 - theta * dt * b;
+                upper[j] = -theta * dt * c;
+                let explicit = (1.0 - theta) * dt;
+                rhs[j] = values[i] + explicit * (a * values[i - 1] + b * values[i] + c * values[i + 1]);
+                if j == 0 { rhs[j] += theta * dt * a * low_value; }
+                if j == m - 1 { rhs[j] += theta * dt * c * high_value; }
+            }
+
+            let interior = match self.exercise {
+                ExerciseStyle::European => solve_tridiagonal(&lower, &diag, &upper, &rhs),
+                ExerciseStyle::American => {
+                    solve_projected_sor(&lower, &diag, &upper, &rhs, &payoff[1..n - 1], values[1..n - 1].to_vec())
+                }
+            };
+            previous = values.clone();
+            values[0] = low_value;
+            values[n - 1] = high_value;
+            values[1..n - 1].copy_from_slice(&interior);
+        }
+        (grid, values, previous)
+    }
+
+    // Quadratic interpolation through the three nodes nearest the spot: value, first and second derivative.
+    fn interpolate(grid: &Grid, values: &[f64], spot: f64) -> (f64, f64, f64) {
+        let n = grid.spots.len();
+        let nearest = ((spot - grid.s_min) / grid.ds).round() as usize;
+        let c = nearest.clamp(1, n - 2);
+        let h = grid.ds;
+        let x = spot - grid.spots[c];
+        let (v0, v1, v2) = (values[c - 1], values[c], values[c + 1]);
+        let first = (v2 - v0) / (2.0 * h);
+        let second = (v2 - 2.0 * v1 + v0) / (h * h);
+        (v1 + first * x + 0.5 * second * x * x, first + second * x, second)
+    }
+
+    fn solve_knock_out_or_vanilla(&self, knock_out: Option<Barrier>) -> FiniteDifferenceResult {
+        let (grid, values, next_step) = self.solve_grid(knock_out);
+        let (price, delta, gamma) = Self::interpolate(&grid, &values, self.underlying_price);
+        let (later, _, _) = Self::interpolate(&grid, &next_step, self.underlying_price);
+        let dt = self.time_to_maturity_years / self.time_steps as f64;
+        FiniteDifferenceResult { price, delta, gamma, theta: (later - price) / dt }
+    }
+
+    pub fn solve(&self) -> Result<FiniteDifferenceResult, Error> {
+        self.validate()?;
+        let zero = FiniteDifferenceResult { price: 0.0, delta: 0.0, gamma: 0.0, theta: 0.0 };
+        let barrier = match self.barrier {
+            None => return Ok(self.solve_knock_out_or_vanilla(None)),
+            Some(barrier) => barrier,
+        };
+        let breached = match barrier.barrier_type {
+            BarrierType::UpAndOut | BarrierType::UpAndIn => self.underlying_price >= barrier.level,
+            BarrierType::DownAndOut | BarrierType::DownAndIn => self.underlying_price <= barrier.level,
+        };
+
+        match barrier.barrier_type {
+            BarrierType::UpAndOut | BarrierType::DownAndOut if breached => Ok(zero),
+            BarrierType::UpAndOut | BarrierType::DownAndOut => Ok(self.solve_knock_out_or_vanilla(Some(barrier))),
+            BarrierType::UpAndIn | BarrierType::DownAndIn if breached => Ok(self.solve_knock_out_or_vanilla(None)),
+            BarrierType::UpAndIn | BarrierType::DownAndIn => {
+                let knock_out_type = if barrier.barrier_type == BarrierType::UpAndIn { BarrierType::UpAndOut } else { BarrierType::DownAndOut };
+                let vanilla = self.solve_knock_out_or_vanilla(None);
+                let knock_out = self.solve_knock_out_or_vanilla(Some(Barrier { barrier_type: knock_out_type, level: barrier.level }));
+                Ok(FiniteDifferenceResult {
+                    price: vanilla.price - knock_out.price,
+                    delta: vanilla.delta - knock_out.delta,
+                    gamma: vanilla.gamma - knock_out.gamma,
+                    theta: vanilla.theta - knock_out.theta,
+                })
+            }
+        }
+    }
+}
+
+impl OptionPricer for FiniteDifferenceOptionPricer {
+    // Deterministic; the seed is ignored.
+    fn price(&self, _seed: Option<u64>) -> Result<f64, Error> {
+        Ok(self.solve()?.price)
+    }
+}
@@ -0,0 +1,164 @@
+use crate::config::{HestonParams, JumpDiffusionParams};
+use crate::config::OptionType;
+use crate::option_pricing::OptionPricer;
+use crate::error::{invalid_config, invalid_parameter, ErrorCode, SimulationError};
+use anyhow::Error;
+use num_complex::Complex64;
+
+// The Lewis integral is evaluated with Gauss-Legendre rules on consecutive chunks of the
+// frequency axis until two chunks in a row contribute less than INTEGRATION_TOLERANCE.
+const CHUNK_WIDTH: f64 = 4.0;
+const GAUSS_LEGENDRE_NODES: usize = 20;
+const MAX_CHUNKS: usize = 1000;
+const INTEGRATION_TOLERANCE: f64 = 1e-10;
+
+// Models with a closed-form characteristic function of the log return. Only the risk-neutral part
+// of the parameters is used: the drift is replaced by the risk-free rate.
+#[derive(Debug, Clone)]
+pub enum CharacteristicModel {
+    Heston(HestonParams),
+    MertonJumpDiffusion(JumpDiffusionParams),
+}
+
+impl CharacteristicModel {
+    fn validate(&self) -> Result<(), Error> {
+        match self {
+            CharacteristicModel::Heston(params) => params.validate(),
+            CharacteristicModel::MertonJumpDiffusion(params) => {
+                if params.volatility < 0.0 || params.jump_intensity < 0.0 || params.jump_volatility < 0.0 {
+                    let field = if params.volatility < 0.0 {
+                        "volatility"
+                    } else if params.jump_intensity < 0.0 {
+                        "jump_intensity"
+                    } else {
+                        "jump_volatility"
+                    };
+                    return Err(invalid_config(Some(field), "Volatility, jump intensity and jump volatility cannot be negative."));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    // E[exp(i u X_T)] for X_T = ln(S_T / S_0) - r T under the risk-neutral measure, i.e. the
+    // characteristic function of the discounted log return. `u` may be complex.
+    pub fn log_return_cf(&self, u: Complex64, t: f64) -> Complex64 {
+        let i = Complex64::i();
+        match self {
+            CharacteristicModel::Heston(p) => {
+                // Albrecher et al. ("little Heston trap") form, continuous in u for long maturities.
+                let (kappa, sigma, rho) = (p.mean_reversion_speed, p.vol_of_vol, p.correlation);
+                let beta = kappa - rho * sigma * i * u;
+                let d = (beta * beta + sigma * sigma * (i * u + u * u)).sqrt();
+                let g = (beta - d) / (beta + d);
+                let exp_dt = (-d * t).exp();
+                let c = kappa * p.long_term_variance / (sigma * sigma)
+                    * ((beta - d) * t - 2.0 * ((1.0 - g * exp_dt) / (1.0 - g)).ln());
+                let big_d = (beta - d) / (sigma * sigma) * (1.0 - exp_dt) / (1.0 - g * exp_dt);
+                (c + big_d * p.initial_variance).exp()
+            }
+            CharacteristicModel::MertonJumpDiffusion(p) => {
+                let compensator = (p.jump_mean + 0.5 * p.jump_volatility.powi(2)).exp() - 1.0;
+                let diffusion = -i * u * (0.5 * p.volatility.powi(2) + p.jump_intensity * compensator)
+                    - 0.5 * p.volatility.powi(2) * u * u;
+                let jumps = p.jump_intensity * ((i * u * p.jump_mean - 0.5 * p.jump_volatility.powi(2) * u * u).exp() - 1.0);
+                ((diffusion + jumps) * t).exp()
+            }
+        }
+    }
+}
+
+// European option priced from the model's characteristic function with the Lewis (2001) formula:
+// C = S - sqrt(S K) e^{-rT/2} / pi * Int_0^inf Re[e^{i u k} phi(u - i/2)] / (u^2 + 1/4) du, k = ln(S/K) + rT,
+// with phi the characteristic function of the discounted log return.
+// Puts follow from put-call parity.
+#[derive(Debug, Clone)]
+pub struct FourierOptionPricer {
+    pub underlying_price: f64,
+    pub strike_price: f64,
+    pub time_to_maturity_years: f64,
+    pub risk_free_rate: f64,
+    pub option_type: OptionType,
+    pub model: CharacteristicModel,
+}
+
+// Nodes and weights of the n-point Gauss-Legendre rule on [-1, 1], from Newton iteration on the
+// Legendre polynomial P_n.
+fn gauss_legendre(n: usize) -> Vec<(f64, f64)> {
+    (1..=n).map(|i| {
+        let mut x = (std::f64::consts::PI * (i as f64 - 0.25) / (n as f64 + 0.5)).cos();
+        let mut derivative = 1.0;
+        for _ in 0..100 {
+            let (mut p_prev, mut p) = (1.0, x);
+            for k in 2..=n {
+                let p_next = ((2 * k - 1) as f64 * x * p - (k - 1) as f64 * p_prev) / k as f64;
+                p_prev = p;
+                p = p_next;
+            }
+            derivative = n as f64 * (x * p - p_prev) / (x * x - 1.0);
+            let step = p / derivative;
+            x -= step;
+            if step.abs() < 1e-15 {
+                break;
+            }
+        }
+        (x, 2.0 / ((1.0 - x * x) * derivative * derivative))
+    }).collect()
+}
+
+fn integrate_to_infinity<F: Fn(f64) -> f64>(f: F) -> f64 {
+    let rule = gauss_legendre(GAUSS_LEGENDRE_NODES);
+    let half_width = 0.5 * CHUNK_WIDTH;
+    let mut total = 0.0;
+    let mut small_chunks = 0;
+    for chunk in 0..MAX_CHUNKS {
+        let centre = (chunk as f64 + 0.5) * CHUNK_WIDTH;
+        let contribution: f64 = rule.iter().map(|(x, w)| w * f(centre + half_width * x)).sum::<f64>() * half_width;
+        total += contribution;
+        small_chunks = if contribution.abs() < INTEGRATION_TOLERANCE { small_chunks + 1 } else { 0 };
+        if small_chunks == 2 {
+            break;
+        }
+    }
+    total
+}
+
+impl FourierOptionPricer {
+    pub fn fourier_price(&self) -> Result<f64, Error> {
+        let (s, k, t, r) = (self.underlying_price, self.strike_price, self.time_to_maturity_years, self.risk_free_rate);
+        if s <= 0.0 { return Err(invalid_parameter("underlying_price", format!("Underlying price (S) must be positive. Got {}", s))); }
+        if k <= 0.0 { return Err(invalid_parameter("strike_price", format!("Strike price (K) must be positive. Got {}", k))); }
+        if t < 0.0 { return Err(invalid_parameter("time_to_maturity_years", format!("Time to maturity (T) must be positive if not zero. Got {}", t))); }
+        self.model.validate()?;
+        if t == 0.0 {
+            return Ok(match self.option_type {
+                OptionType::Call => (s - k).max(0.0),
+                OptionType::Put => (k - s).max(0.0),
+            });
+        }
+
+        let log_moneyness = (s / k).ln() + r * t;
+        let half_i = Complex64::new(0.0, 0.5);
+        let integral = integrate_to_infinity(|u| {
+            let phase = Complex64::new(0.0, u * log_moneyness).exp();
+            (phase * self.model.log_return_cf(Complex64::new(u, 0.0) - half_i, t)).re / (u * u + 0.25)
+        });
+        let discount = (-r * t).exp();
+        let call = s - (s * k).sqrt() * (-0.5 * r * t).exp() / std::f64::consts::PI * integral;
+        if !call.is_finite() {
+            return Err(SimulationError::new(ErrorCode::SimulationFailed, None, "Fourier pricing did not produce a finite price.").into());
+        }
+
+        Ok(match self.option_type {
+            OptionType::Call => call,
+            OptionType::Put => call - s + k * discount,
+        })
+    }
+}
+
+impl OptionPricer for FourierOptionPricer {
+    // Deterministic; the seed is ignored.
+    fn price(&self, _seed: Option<u64>) -> Result<f64, Error> {
+        self.fourier_price()
+    }
+}
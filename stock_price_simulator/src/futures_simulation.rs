@@ -1,16 +1,24 @@
 use crate::random_process::TimeSeries;
 use anyhow::Error;
 use serde::Deserialize; // Added for derive
+use schemars::JsonSchema;
+use crate::yield_curve::{DiscountCurve, RateSource};
+use crate::error::invalid_parameter;
 
-#[derive(Debug, Clone, Deserialize)] // Added Deserialize
+#[derive(Debug, Clone, Deserialize, JsonSchema)] // Added Deserialize
 pub struct FuturesContract {
     pub underlying_symbol: String,
     pub initial_spot_price: f64,
-    pub risk_free_rate: f64,
+    #[serde(default)]
+    pub risk_free_rate: Option<f64>,
     pub volatility: f64, // Volatility of the underlying spot price
     pub time_to_maturity_days: u32, // Initial time to maturity in days
     pub time_step_days: f64,        // Granularity of simulation steps in days
     pub seed: Option<u64>,
+    // Term structure of rates, given instead of risk_free_rate, so the cost of carry over each
+    // remaining period comes from the curve's forward rate.
+    #[serde(default)]
+    pub discount_curve: Option<DiscountCurve>,
 }
 
 use crate::random_process::{GeometricBrownianMotion, StochasticProcess};
@@ -19,14 +27,15 @@ const DAYS_IN_YEAR: f64 = 365.0;
 
 pub fn simulate_futures_price(contract: &FuturesContract) -> Result<TimeSeries, Error> {
     if contract.initial_spot_price <= 0.0 {
-        return Err(anyhow::anyhow!("Initial spot price must be positive."));
+        return Err(invalid_parameter("initial_spot_price", "Initial spot price must be positive."));
     }
     if contract.volatility < 0.0 {
-        return Err(anyhow::anyhow!("Volatility cannot be negative."));
+        return Err(invalid_parameter("volatility", "Volatility cannot be negative."));
     }
     if contract.time_step_days <= 0.0 {
-        return Err(anyhow::anyhow!("Time step in days must be positive."));
+        return Err(invalid_parameter("time_step_days", "Time step in days must be positive."));
     }
+    let rates = RateSource::from_request(contract.risk_free_rate, contract.discount_curve.as_ref())?;
     if contract.time_to_maturity_days == 0 && contract.time_step_days > 0.0 {
          // Allow simulation if maturity is 0, but it will be a single point.
          // If time_step_days is also 0, it's an error, caught by previous check.
@@ -43,10 +52,12 @@ pub fn simulate_futures_price(contract: &FuturesContract) -> Result<TimeSeries,
     let gbm_steps = if contract.time_to_maturity_days == 0 { 1 } else { num_steps +1 };
 
 
-    let gbm = GeometricBrownianMotion {
-        drift: contract.risk_free_rate, // Assuming risk-neutral drift for spot
-        volatility: contract.volatility,
-    };
+    let maturity_years = contract.time_to_maturity_days as f64 / DAYS_IN_YEAR;
+    let spot_drift = rates.zero_rate(maturity_years);
+    let gbm = GeometricBrownianMotion::new(
+        spot_drift, // Assuming risk-neutral drift for spot
+        contract.volatility,
+    );
 
     // Generate spot price path
     // dt for generate_path is in days, which contract.time_step_days is.
@@ -70,7 +81,8 @@ pub fn simulate_futures_price(contract: &FuturesContract) -> Result<TimeSeries,
         // Max(0.0, ...) handles cases where days_elapsed might slightly exceed TTM due to ceiling or floating point.
         let remaining_time_years = (remaining_days.max(0.0)) / DAYS_IN_YEAR;
 
-        let futures_price_t = spot_price_t * (contract.risk_free_rate * remaining_time_years).exp();
+        let carry_rate = rates.forward_rate(maturity_years - remaining_time_years, maturity_years);
+        let futures_price_t = spot_price_t * (carry_rate * remaining_time_years).exp();
         futures_prices.push(futures_price_t);
     }
 
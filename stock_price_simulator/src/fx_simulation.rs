@@ -0,0 +1,155 @@
+pub use crate::config::FxPair; // Defined with the instrument configs
+use crate::config::OptionType;
+use crate::option_pricing::{black_scholes_price, EuropeanOption};
+use crate::random_process::{GeometricBrownianMotion, StochasticProcess, TRADING_DAYS_PER_YEAR};
+use crate::error::{invalid_parameter, within};
+use crate::yield_curve::RateSource;
+use anyhow::Error;
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+use schemars::JsonSchema;
+
+// The spot and forward paths are held in memory, so a forward may need at most this many steps to
+// delivery, as bonds may (MAX_BOND_STEPS).
+pub const MAX_FX_STEPS: usize = 1_000_000;
+
+impl FxPair {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.spot_rate <= 0.0 {
+            return Err(invalid_parameter("spot_rate", format!("FX spot rate must be positive. Got {}", self.spot_rate)));
+        }
+        if self.volatility < 0.0 {
+            return Err(invalid_parameter("volatility", "Volatility cannot be negative."));
+        }
+        if self.pip_size <= 0.0 {
+            return Err(invalid_parameter("pip_size", "Pip size must be positive."));
+        }
+        RateSource::from_fields("domestic_rate", self.domestic_rate, "domestic_curve", self.domestic_curve.as_ref())?;
+        RateSource::from_fields("foreign_rate", self.foreign_rate, "foreign_curve", self.foreign_curve.as_ref())?;
+        Ok(())
+    }
+
+    // The rates below are those of a validated pair, which has exactly one of rate and curve per
+    // currency; a currency with neither carries a NaN rate.
+    fn domestic_rates(&self) -> RateSource<'_> {
+        self.domestic_curve.as_ref().map_or(RateSource::Flat(self.domestic_rate.unwrap_or(f64::NAN)), RateSource::Curve)
+    }
+
+    fn foreign_rates(&self) -> RateSource<'_> {
+        self.foreign_curve.as_ref().map_or(RateSource::Flat(self.foreign_rate.unwrap_or(f64::NAN)), RateSource::Curve)
+    }
+
+    pub fn domestic_discount_factor(&self, t: f64) -> f64 {
+        self.domestic_rates().discount_factor(t)
+    }
+
+    pub fn foreign_discount_factor(&self, t: f64) -> f64 {
+        self.foreign_rates().discount_factor(t)
+    }
+
+    // Covered interest parity: the rate agreed at time t for delivery at `delivery` (both in years
+    // from today) when spot is `spot`, F = S * DF_foreign(t, delivery) / DF_domestic(t, delivery).
+    pub fn forward_rate_at(&self, spot: f64, t: f64, delivery: f64) -> f64 {
+        let foreign = self.foreign_discount_factor(delivery) / self.foreign_discount_factor(t);
+        let domestic = self.domestic_discount_factor(delivery) / self.domestic_discount_factor(t);
+        spot * foreign / domestic
+    }
+
+    pub fn forward_rate(&self, delivery: f64) -> f64 {
+        self.forward_rate_at(self.spot_rate, 0.0, delivery)
+    }
+
+    // Forward minus spot, in pips.
+    pub fn forward_points(&self, delivery: f64) -> f64 {
+        (self.forward_rate(delivery) - self.spot_rate) / self.pip_size
+    }
+
+    // Risk-neutral drift of spot over [0, t]: domestic minus foreign zero rate.
+    pub fn rate_differential(&self, t: f64) -> f64 {
+        self.domestic_rates().zero_rate(t) - self.foreign_rates().zero_rate(t)
+    }
+}
+
+// European option on an FX pair: the right to buy (Call) or sell (Put) one unit of the base currency
+// for strike_price units of the quote currency. Prices are in the quote currency.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct FxOption {
+    pub pair: FxPair,
+    pub strike_price: f64,
+    pub time_to_maturity_years: f64,
+    pub option_type: OptionType,
+}
+
+// Garman-Kohlhagen price: Black-Scholes with the foreign rate as a continuous dividend yield,
+// evaluated as black_scholes_price on the spot discounted at the foreign rate.
+pub fn garman_kohlhagen_price(option: &FxOption) -> Result<f64, Error> {
+    option.pair.validate().map_err(|e| within("pair", e))?;
+    let t = option.time_to_maturity_years;
+    let foreign_discount = if t > 0.0 { option.pair.foreign_discount_factor(t) } else { 1.0 };
+    black_scholes_price(&EuropeanOption {
+        underlying_price: option.pair.spot_rate * foreign_discount,
+        strike_price: option.strike_price,
+        time_to_maturity_years: t,
+        risk_free_rate: option.pair.domestic_rate,
+        volatility: option.pair.volatility,
+        option_type: option.option_type,
+        discount_curve: option.pair.domestic_curve.clone(),
+    })
+}
+
+// Spot path of a pair together with an outright forward for a fixed delivery date.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct FxForwardContract {
+    pub pair: FxPair,
+    pub time_to_maturity_days: u32, // Delivery, in days of the spot path from today
+    pub time_step_days: f64,
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FxPath {
+    pub timestamps: Vec<NaiveDateTime>,
+    pub spot_rates: Vec<f64>,
+    pub forward_rates: Vec<f64>,
+    pub forward_points: Vec<f64>, // Pips
+}
+
+// Simulates spot under Garman-Kohlhagen dynamics (GBM drifting at the domestic minus the foreign
+// rate to delivery) and marks the forward for the contract's delivery date at every step. Days are
+// those of the spot path, TRADING_DAYS_PER_YEAR to the year, so that spot diffusion and forward
+// accrual use the same year fraction (as the FX legs of an ETF do).
+pub fn simulate_fx_forward(contract: &FxForwardContract) -> Result<FxPath, Error> {
+    let pair = &contract.pair;
+    pair.validate().map_err(|e| within("pair", e))?;
+    if !contract.time_step_days.is_finite() || contract.time_step_days <= 0.0 {
+        return Err(invalid_parameter("time_step_days", "Time step in days must be positive."));
+    }
+    let steps = (contract.time_to_maturity_days as f64 / contract.time_step_days).ceil();
+    if steps > MAX_FX_STEPS as f64 {
+        return Err(invalid_parameter("time_step_days", format!(
+            "A delivery in {} days in steps of {} days needs {} steps; at most {} are allowed.",
+            contract.time_to_maturity_days, contract.time_step_days, steps, MAX_FX_STEPS,
+        )));
+    }
+
+    let delivery = contract.time_to_maturity_days as f64 / TRADING_DAYS_PER_YEAR;
+    let num_steps = steps as usize;
+    let gbm = GeometricBrownianMotion::new(pair.rate_differential(delivery), pair.volatility);
+    let spot_path = gbm.generate_path(pair.spot_rate, contract.time_step_days, num_steps + 1, contract.seed);
+
+    let mut forward_rates = Vec::with_capacity(spot_path.prices.len());
+    let mut forward_points = Vec::with_capacity(spot_path.prices.len());
+    for (i, spot) in spot_path.prices.iter().enumerate() {
+        let t = (i as f64 * contract.time_step_days / TRADING_DAYS_PER_YEAR).min(delivery);
+        let forward = pair.forward_rate_at(*spot, t, delivery);
+        forward_rates.push(forward);
+        forward_points.push((forward - spot) / pair.pip_size);
+    }
+
+    Ok(FxPath {
+        timestamps: spot_path.timestamps,
+        spot_rates: spot_path.prices,
+        forward_rates,
+        forward_points,
+    })
+}
@@ -0,0 +1,100 @@
+use crate::random_process::TimeSeries;
+use anyhow::Error;
+use chrono::{NaiveDate, NaiveDateTime};
+use std::fs::File;
+use std::io::Read;
+
+// Formats tried, in order, when no explicit timestamp_format is given.
+const FALLBACK_TIMESTAMP_FORMATS: [&str; 3] = ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%d"];
+
+#[derive(Debug, Clone)]
+pub struct PriceCsvOptions {
+    pub delimiter: u8,
+    pub timestamp_column: String, // Matched case-insensitively against the header row
+    pub price_column: String,
+    pub timestamp_format: Option<String>, // chrono format; date-only formats map to midnight
+}
+
+impl Default for PriceCsvOptions {
+    // Matches the layout written by export::write_time_series_csv.
+    fn default() -> Self {
+        PriceCsvOptions {
+            delimiter: b',',
+            timestamp_column: "timestamp".to_string(),
+            price_column: "price".to_string(),
+            timestamp_format: None,
+        }
+    }
+}
+
+fn parse_timestamp(raw: &str, format: Option<&str>) -> Option<NaiveDateTime> {
+    let formats: Vec<&str> = match format {
+        Some(f) => vec![f],
+        None => FALLBACK_TIMESTAMP_FORMATS.to_vec(),
+    };
+    formats.iter().find_map(|f| {
+        NaiveDateTime::parse_from_str(raw, f)
+            .ok()
+            .or_else(|| NaiveDate::parse_from_str(raw, f).ok().and_then(|d| d.and_hms_opt(0, 0, 0)))
+    })
+}
+
+fn find_column(headers: &csv::StringRecord, name: &str) -> Result<usize, Error> {
+    headers
+        .iter()
+        .position(|h| h.trim().eq_ignore_ascii_case(name))
+        .ok_or_else(|| anyhow::anyhow!("Column '{}' not found in CSV header.", name))
+}
+
+// Reads a price history with a header row. Rows are returned sorted by timestamp; duplicate
+// timestamps and non-positive or non-numeric prices are rejected with the offending line number.
+pub fn read_price_history_csv<R: Read>(reader: R, options: &PriceCsvOptions) -> Result<TimeSeries, Error> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+
+    let headers = rdr.headers()?.clone();
+    let timestamp_idx = find_column(&headers, &options.timestamp_column)?;
+    let price_idx = find_column(&headers, &options.price_column)?;
+
+    let mut rows: Vec<(NaiveDateTime, f64)> = Vec::new();
+    for (i, record) in rdr.records().enumerate() {
+        let record = record?;
+        let line = i + 2; // 1-based, after the header
+        let raw_timestamp = record.get(timestamp_idx).unwrap_or("");
+        let raw_price = record.get(price_idx).unwrap_or("");
+
+        let timestamp = parse_timestamp(raw_timestamp, options.timestamp_format.as_deref())
+            .ok_or_else(|| anyhow::anyhow!("Line {}: cannot parse timestamp '{}'.", line, raw_timestamp))?;
+        let price: f64 = raw_price
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Line {}: cannot parse price '{}'.", line, raw_price))?;
+        if !price.is_finite() || price <= 0.0 {
+            return Err(anyhow::anyhow!("Line {}: price must be positive. Got {}", line, price));
+        }
+        rows.push((timestamp, price));
+    }
+
+    if rows.is_empty() {
+        return Err(anyhow::anyhow!("Price history contains no rows."));
+    }
+    rows.sort_by_key(|(t, _)| *t);
+    if let Some(pair) = rows.windows(2).find(|w| w[0].0 == w[1].0) {
+        return Err(anyhow::anyhow!("Duplicate timestamp {} in price history.", pair[0].0));
+    }
+
+    let (timestamps, prices) = rows.into_iter().unzip();
+    Ok(TimeSeries { timestamps, prices })
+}
+
+pub fn load_price_history_csv(file_path: &str, options: &PriceCsvOptions) -> Result<TimeSeries, Error> {
+    let file = File::open(file_path)
+        .map_err(|e| anyhow::anyhow!("Failed to open price history '{}': {}", file_path, e))?;
+    read_price_history_csv(file, options)
+}
+
+// Log returns ln(P_t / P_{t-1}) of consecutive prices.
+pub fn log_returns(prices: &[f64]) -> Vec<f64> {
+    prices.windows(2).map(|w| (w[1] / w[0]).ln()).collect()
+}
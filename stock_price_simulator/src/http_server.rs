@@ -1,28 +1,56 @@
-use serde::Deserialize;
-use actix_web::{web, HttpResponse, http::StatusCode}; // Removed Responder
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use actix_web::{web, HttpRequest, HttpResponse, http::StatusCode}; // Removed Responder
+use actix_web::http::header::{self, Accept, Header};
+use actix_web::error::{InternalError, JsonPayloadError};
 use chrono::NaiveDateTime;
 
-// TimeSeries unused, removed.
-use crate::api_models::{ApiResponse, StockData, ApiErrorResponse};
+use crate::random_process::TimeSeries;
+use crate::api_models::{ApiResponse, StockData, ApiErrorResponse, ConfigStatusData, ModelResolutionData, ResolvedModelData, PatternCandidateData, HealthData, VersionData};
 use crate::api_interface;
+use crate::error::{classify, deserialize_request, ErrorCode, SimulationError};
+use crate::config_store::{ConfigStore, ReloadOutcome};
+use crate::request_defaults::{with_defaults, RequestKind, StockSimulationRequest};
+use crate::instruments::{resolve_instrument, InstrumentKind, IDENTIFIER_FIELD};
+use crate::export;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+// Simulation and admin routes are served under this prefix. The same routes without it are kept
+// for clients written before the API was versioned, but are not part of the OpenAPI document.
+pub const API_PREFIX: &str = "/v1";
+pub const API_VERSION: &str = "v1";
 
 // --- Request Structs ---
 
-#[derive(Deserialize, Debug)]
+// days, time_step_days and seed default to the GlobalConfig settings (see request_defaults).
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct StockSimulationQueryParams {
     pub asset_identifier: String, // To look up in config
     pub initial_price: f64,
-    pub days: usize,
-    pub time_step_days: f64,
+    pub days: Option<usize>,
+    pub time_step_days: Option<f64>,
     pub seed: Option<u64>,
     pub drift: Option<f64>, // Optional override
     pub volatility: Option<f64>, // Optional override
 }
 
+
+#[derive(Deserialize, Debug, JsonSchema)]
+pub struct ModelResolutionQueryParams {
+    #[serde(default = "default_resolution_asset_type")]
+    pub asset_type: String,
+    pub asset_identifier: String,
+}
+
+fn default_resolution_asset_type() -> String {
+    "stock".to_string()
+}
+
 // --- Helper Functions ---
 
 fn format_timestamps(timestamps: &[NaiveDateTime]) -> Vec<String> {
-    timestamps.iter().map(|t| t.format("%Y-%m-%dT%H:%M:%S").to_string()).collect()
+    timestamps.iter().map(|t| t.format(export::DEFAULT_TIMESTAMP_FORMAT).to_string()).collect()
 }
 
 // Return HttpResponse directly to unify types in match arms
@@ -33,24 +61,174 @@ fn success_response<T: serde::Serialize + PartialEq>(data: T) -> HttpResponse {
     })
 }
 
+// Requests the server rejects, and simulations that fail on the inputs given, are client errors.
+fn status_for(code: ErrorCode) -> StatusCode {
+    match code {
+        ErrorCode::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        ErrorCode::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+        ErrorCode::InvalidConfig => StatusCode::UNPROCESSABLE_ENTITY,
+        ErrorCode::NotFound => StatusCode::NOT_FOUND,
+        ErrorCode::Forbidden => StatusCode::FORBIDDEN,
+        ErrorCode::InvalidParameter
+        | ErrorCode::MissingParameter
+        | ErrorCode::InvalidRequest
+        | ErrorCode::UnknownAsset
+        | ErrorCode::SimulationFailed => StatusCode::BAD_REQUEST,
+    }
+}
+
 // Return HttpResponse directly
-fn error_response(err_msg: String, status_code: StatusCode) -> HttpResponse {
+fn error_response(error: SimulationError, status_code: StatusCode) -> HttpResponse {
     HttpResponse::build(status_code).json(ApiErrorResponse {
         status: "error".to_string(),
-        error: err_msg,
+        error: error.message,
+        code: error.code,
+        field: error.field,
     })
 }
 
+// The error envelope for a failed request, with the status its code calls for.
+fn api_error(e: &anyhow::Error) -> HttpResponse {
+    let error = classify(e);
+    let status = status_for(error.code);
+    error_response(error, status)
+}
+
+// Malformed or oversized bodies and bodies of another content type, which actix rejects before
+// the handler runs, get the same envelope as every other error.
+fn json_error_handler(err: JsonPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    let error = match &err {
+        JsonPayloadError::ContentType => SimulationError::new(
+            ErrorCode::UnsupportedMediaType, None, "The request body must be sent as application/json.",
+        ),
+        JsonPayloadError::Overflow { .. } | JsonPayloadError::OverflowKnownLength { .. } => {
+            SimulationError::new(ErrorCode::PayloadTooLarge, None, err.to_string())
+        }
+        _ => SimulationError::new(ErrorCode::InvalidRequest, None, err.to_string()),
+    };
+    let status = status_for(error.code);
+    InternalError::from_response(err, error_response(error, status)).into()
+}
+
+// Reads the query string like a request body, so a missing or unreadable parameter is reported by
+// name (web::Query's errors do not say which parameter was wrong).
+fn query_params<T: serde::de::DeserializeOwned>(req: &HttpRequest) -> anyhow::Result<T> {
+    let parser = form_urlencoded::parse(req.query_string().as_bytes());
+    deserialize_request(serde_urlencoded::Deserializer::new(parser), "Query deserialize error")
+}
+
+async fn not_found_handler(req: HttpRequest) -> HttpResponse {
+    let message = format!("No route for {} {}.", req.method(), req.path());
+    error_response(SimulationError::new(ErrorCode::NotFound, None, message), StatusCode::NOT_FOUND)
+}
+
+// Expands a body that names a configured instrument, then fills what is still missing from the
+// GlobalConfig defaults (for request kinds that have them). Also returns the expanded body.
+fn instrument_request<T: serde::de::DeserializeOwned>(
+    config: &crate::config::GlobalConfig,
+    instrument: InstrumentKind,
+    defaults: Option<RequestKind>,
+    body: serde_json::Value,
+) -> anyhow::Result<(T, serde_json::Value)> {
+    let request = resolve_instrument(config, instrument, body)?;
+    let params = match defaults {
+        Some(kind) => with_defaults(config, kind, request.clone())?,
+        None => deserialize_request(request.clone(), "Invalid request")?,
+    };
+    Ok((params, request))
+}
+
+fn string_field(request: &serde_json::Value, field: &str) -> Option<String> {
+    request.get(field).and_then(serde_json::Value::as_str).map(str::to_string)
+}
+
+// --- Response Format Negotiation ---
+
+pub const CSV_CONTENT_TYPE: &str = "text/csv; charset=utf-8";
+pub const ARROW_STREAM_CONTENT_TYPE: &str = "application/vnd.apache.arrow.stream";
+pub const PARQUET_CONTENT_TYPE: &str = "application/vnd.apache.parquet";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ResponseFormat {
+    Json,
+    Csv,
+    ArrowIpc,
+    Parquet,
+}
+
+// Picks the highest-ranked media type from the Accept header that we can produce.
+// Missing, unparsable or unsupported Accept values fall back to JSON.
+fn negotiate_format(req: &HttpRequest) -> ResponseFormat {
+    let accept = match Accept::parse(req) {
+        Ok(accept) => accept,
+        Err(_) => return ResponseFormat::Json,
+    };
+    for mime in accept.ranked() {
+        match mime.essence_str() {
+            "application/json" | "*/*" | "application/*" => return ResponseFormat::Json,
+            "text/csv" => return ResponseFormat::Csv,
+            "application/vnd.apache.arrow.stream" => return ResponseFormat::ArrowIpc,
+            "application/vnd.apache.parquet" => return ResponseFormat::Parquet,
+            _ => {}
+        }
+    }
+    ResponseFormat::Json
+}
+
+// Renders a simulated series either as the usual JSON envelope or, if the client asked for it,
+// as CSV / Arrow IPC / Parquet. Errors are always reported through the JSON error envelope.
+fn time_series_response<T, F>(req: &HttpRequest, time_series: TimeSeries, to_json: F) -> HttpResponse
+where
+    T: serde::Serialize + PartialEq,
+    F: FnOnce(TimeSeries) -> T,
+{
+    let exported = match negotiate_format(req) {
+        ResponseFormat::Json => return success_response(to_json(time_series)),
+        ResponseFormat::Csv => export::time_series_to_csv(&time_series, &export::CsvExportOptions::default())
+            .map(|csv| (CSV_CONTENT_TYPE, csv.into_bytes())),
+        ResponseFormat::ArrowIpc => export::time_series_to_arrow_ipc(&time_series)
+            .map(|bytes| (ARROW_STREAM_CONTENT_TYPE, bytes)),
+        ResponseFormat::Parquet => export::time_series_to_parquet(&time_series)
+            .map(|bytes| (PARQUET_CONTENT_TYPE, bytes)),
+    };
+    match exported {
+        Ok((content_type, body)) => HttpResponse::Ok()
+            .insert_header((header::CONTENT_TYPE, content_type))
+            .body(body),
+        Err(e) => error_response(
+            SimulationError::new(ErrorCode::SimulationFailed, None, e.to_string()),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ),
+    }
+}
+
 // --- API Handlers ---
 
 // GET /simulate/stock
 pub async fn simulate_stock_handler( // Made pub
-    params: web::Query<StockSimulationQueryParams>,
-    config: web::Data<crate::config::GlobalConfig>, // Access loaded config
+    req: HttpRequest,
+    config_store: web::Data<ConfigStore>, // Live config, swapped on reload
 ) -> HttpResponse { // Return HttpResponse
-    match api_interface::simulate_stock_with_config(
+    let params: StockSimulationQueryParams = match query_params(&req) {
+        Ok(params) => params,
+        Err(e) => return api_error(&e),
+    };
+    let snapshot = config_store.current(); // This request keeps its snapshot even if a reload happens meanwhile
+    let config = &snapshot.config;
+    let params: StockSimulationRequest = match serde_json::to_value(&params)
+        .map_err(anyhow::Error::from)
+        .and_then(|query| with_defaults(config, RequestKind::Stock, query))
+    {
+        Ok(params) => params,
+        Err(e) => return api_error(&e),
+    };
+    let model_config = match snapshot.matcher.find(config, "stock", &params.asset_identifier) {
+        Ok(model_config) => model_config,
+        Err(e) => return api_error(&e),
+    };
+    match api_interface::simulate_stock_with_model(
+        model_config,
         &params.asset_identifier,
-        &config.into_inner(), // Get reference to GlobalConfig
         params.initial_price,
         params.days,
         params.time_step_days,
@@ -58,132 +236,432 @@ pub async fn simulate_stock_handler( // Made pub
         params.drift,
         params.volatility,
     ) {
-        Ok(time_series) => {
-            let response_data = StockData {
-                symbol: params.asset_identifier.clone(), // Use asset_identifier as symbol
-                timestamps: format_timestamps(&time_series.timestamps),
-                prices: time_series.prices,
-            };
-            success_response(response_data)
-        }
-        Err(e) => error_response(e.to_string(), StatusCode::BAD_REQUEST),
+        Ok(time_series) => time_series_response(&req, time_series, |time_series| StockData {
+            symbol: params.asset_identifier.clone(), // Use asset_identifier as symbol
+            timestamps: format_timestamps(&time_series.timestamps),
+            prices: time_series.prices,
+        }),
+        Err(e) => api_error(&e),
     }
 }
 
 use actix_web::{App, HttpServer, middleware::Logger}; // Added middleware::Logger back
-use crate::api_models::{OptionData, FutureData, EtfData}; // Added FutureData, EtfData
+use crate::api_models::{OptionData, FutureData, EtfData, BondData, FxData}; // Added FutureData, EtfData
 use crate::option_pricing::EuropeanOption;
 use crate::api_interface::MonteCarloEuropeanOptionInput;
 use crate::futures_simulation::FuturesContract;
-use crate::etf_simulation::EtfDefinition;
+use crate::etf_simulation::{self, EtfDefinition, EtfNavSimulation};
+use crate::bond_simulation::BondContract;
+use crate::fx_simulation::{FxForwardContract, FxOption};
 
 
 // POST /simulate/option/black_scholes
 pub async fn simulate_option_bs_handler( // Made pub
-    option_params: web::Json<EuropeanOption>, // EuropeanOption needs Deserialize
+    body: web::Json<serde_json::Value>,
+    config_store: web::Data<ConfigStore>,
 ) -> HttpResponse { // Return HttpResponse
-    // Access inner data using .0 to avoid consuming web::Json if fields are needed later,
-    // though for this specific handler, all fields are passed to the api_interface function.
-    // If option_params itself was needed after the call, .into_inner() would consume it.
-    // Here, option_params.0 provides a reference to EuropeanOption.
-    match api_interface::price_european_option_black_scholes(
-        option_params.0.underlying_price,
-        option_params.0.strike_price,
-        option_params.0.time_to_maturity_years,
-        option_params.0.risk_free_rate,
-        option_params.0.volatility,
-        option_params.0.option_type,
-    ) {
+    // A body with an asset_identifier starts from that [[instruments.options]] entry.
+    let (option, request): (EuropeanOption, _) = match instrument_request(&config_store.config(), InstrumentKind::OptionBlackScholes, None, body.into_inner()) {
+        Ok(resolved) => resolved,
+        Err(e) => return api_error(&e),
+    };
+    // The whole option is passed so that an optional discount curve is honoured.
+    match api_interface::price_european_option(&option) {
         Ok(price) => {
             let response_data = OptionData {
-                underlying_symbol: "N/A".to_string(),
-                option_type: format!("{:?}", option_params.0.option_type),
-                strike_price: option_params.0.strike_price,
+                underlying_symbol: string_field(&request, "underlying_symbol").unwrap_or_else(|| "N/A".to_string()),
+                option_type: format!("{:?}", option.option_type),
+                strike_price: option.strike_price,
                 maturity_date: "N/A (calculated from TTM)".to_string(),
                 price: Some(price),
                 ..Default::default()
             };
             success_response(response_data)
         }
-        Err(e) => error_response(e.to_string(), StatusCode::BAD_REQUEST),
+        Err(e) => api_error(&e),
     }
 }
 
 // POST /simulate/option/monte_carlo
 pub async fn simulate_option_mc_handler( // Made pub
-    params: web::Json<MonteCarloEuropeanOptionInput>,
+    body: web::Json<serde_json::Value>,
+    config_store: web::Data<ConfigStore>,
 ) -> HttpResponse {
-    // Use params.0 to access the inner MonteCarloEuropeanOptionInput data
-    // The api_interface function takes a reference, so no ownership issues here.
-    match api_interface::price_european_option_monte_carlo(&params.0) {
+    // A body with an asset_identifier starts from that [[instruments.options]] entry; fields still
+    // missing come from the live GlobalConfig.
+    let (params, request): (MonteCarloEuropeanOptionInput, _) = match instrument_request(
+        &config_store.config(), InstrumentKind::OptionMonteCarlo, Some(RequestKind::OptionMonteCarlo), body.into_inner(),
+    ) {
+        Ok(resolved) => resolved,
+        Err(e) => return api_error(&e),
+    };
+    match api_interface::price_european_option_monte_carlo(&params) {
         Ok(price) => {
             let response_data = OptionData {
-                underlying_symbol: "N/A".to_string(), // MC input doesn't have a separate symbol field
-                option_type: format!("{:?}", params.0.option_type),
-                strike_price: params.0.strike_price,
+                underlying_symbol: string_field(&request, "underlying_symbol").unwrap_or_else(|| "N/A".to_string()),
+                option_type: format!("{:?}", params.option_type),
+                strike_price: params.strike_price,
                 maturity_date: "N/A (calculated from TTM)".to_string(),
                 price: Some(price),
                 ..Default::default()
             };
             success_response(response_data)
         }
-        Err(e) => error_response(e.to_string(), StatusCode::BAD_REQUEST),
+        Err(e) => api_error(&e),
     }
 }
 
 // POST /simulate/future
 pub async fn simulate_future_handler( // Made pub
-    params: web::Json<FuturesContract>,
+    req: HttpRequest,
+    body: web::Json<serde_json::Value>,
+    config_store: web::Data<ConfigStore>,
 ) -> HttpResponse {
+    // A body with an asset_identifier starts from that [[instruments.futures]] entry; fields still
+    // missing come from the live GlobalConfig.
+    let (params, request): (FuturesContract, _) = match instrument_request(
+        &config_store.config(), InstrumentKind::Future, Some(RequestKind::Future), body.into_inner(),
+    ) {
+        Ok(resolved) => resolved,
+        Err(e) => return api_error(&e),
+    };
+    let contract_symbol = string_field(&request, IDENTIFIER_FIELD).unwrap_or_else(|| params.underlying_symbol.clone());
     // api_interface::simulate_futures expects a reference
-    match api_interface::simulate_futures(&params.0) {
-        Ok(time_series) => {
-            let response_data = FutureData {
-                contract_symbol: params.0.underlying_symbol.clone(), // Assuming FuturesContract has this
-                timestamps: format_timestamps(&time_series.timestamps),
-                prices: time_series.prices,
-                spot_prices: None, // Current simulate_futures doesn't return spot path
+    match api_interface::simulate_futures(&params) {
+        Ok(time_series) => time_series_response(&req, time_series, |time_series| FutureData {
+            contract_symbol: contract_symbol.clone(),
+            timestamps: format_timestamps(&time_series.timestamps),
+            prices: time_series.prices,
+            spot_prices: None, // Current simulate_futures doesn't return spot path
+        }),
+        Err(e) => api_error(&e),
+    }
+}
+
+// POST /simulate/bond
+pub async fn simulate_bond_handler(
+    req: HttpRequest,
+    body: web::Json<serde_json::Value>,
+) -> HttpResponse {
+    let contract: BondContract = match deserialize_request(body.into_inner(), "Invalid bond request") {
+        Ok(contract) => contract,
+        Err(e) => return api_error(&e),
+    };
+    match api_interface::simulate_bond(&contract) {
+        // CSV / Arrow / Parquet carry the clean price series; JSON carries every analytic.
+        Ok(bond_path) => time_series_response(&req, bond_path.clean_price_series(), |_| BondData {
+            bond_symbol: contract.bond_symbol.clone(),
+            timestamps: format_timestamps(&bond_path.timestamps),
+            short_rates: bond_path.short_rates,
+            clean_prices: bond_path.clean_prices,
+            dirty_prices: bond_path.dirty_prices,
+            accrued_interest: bond_path.accrued_interest,
+            yields: bond_path.yields,
+            macaulay_durations: bond_path.macaulay_durations,
+            modified_durations: bond_path.modified_durations,
+            convexities: bond_path.convexities,
+        }),
+        Err(e) => api_error(&e),
+    }
+}
+
+// POST /simulate/option/garman_kohlhagen
+pub async fn simulate_fx_option_handler(
+    body: web::Json<serde_json::Value>,
+) -> HttpResponse {
+    let option: FxOption = match deserialize_request(body.into_inner(), "Invalid FX option request") {
+        Ok(option) => option,
+        Err(e) => return api_error(&e),
+    };
+    match api_interface::price_fx_option_garman_kohlhagen(&option) {
+        Ok(price) => {
+            let response_data = OptionData {
+                underlying_symbol: format!("{}/{}", option.pair.base_currency, option.pair.quote_currency),
+                option_type: format!("{:?}", option.option_type),
+                strike_price: option.strike_price,
+                maturity_date: "N/A (calculated from TTM)".to_string(),
+                price: Some(price),
+                ..Default::default()
             };
             success_response(response_data)
         }
-        Err(e) => error_response(e.to_string(), StatusCode::BAD_REQUEST),
+        Err(e) => api_error(&e),
+    }
+}
+
+// POST /simulate/fx
+pub async fn simulate_fx_handler(
+    req: HttpRequest,
+    body: web::Json<serde_json::Value>,
+) -> HttpResponse {
+    let contract: FxForwardContract = match deserialize_request(body.into_inner(), "Invalid FX request") {
+        Ok(contract) => contract,
+        Err(e) => return api_error(&e),
+    };
+    match api_interface::simulate_fx(&contract) {
+        // CSV / Arrow / Parquet carry the spot series; JSON also has the forward.
+        Ok(fx_path) => {
+            let spot_series = TimeSeries { timestamps: fx_path.timestamps.clone(), prices: fx_path.spot_rates.clone() };
+            time_series_response(&req, spot_series, |_| FxData {
+                pair: format!("{}/{}", contract.pair.base_currency, contract.pair.quote_currency),
+                timestamps: format_timestamps(&fx_path.timestamps),
+                spot_rates: fx_path.spot_rates,
+                forward_rates: fx_path.forward_rates,
+                forward_points: fx_path.forward_points,
+            })
+        }
+        Err(e) => api_error(&e),
     }
 }
 
 // POST /simulate/etf
 pub async fn simulate_etf_handler( // Made pub
-    params: web::Json<EtfDefinition>,
+    req: HttpRequest,
+    body: web::Json<serde_json::Value>,
+    config_store: web::Data<ConfigStore>,
 ) -> HttpResponse {
-    // api_interface::simulate_etf expects a reference
-    match api_interface::simulate_etf(&params.0) {
-        Ok(time_series) => {
-            let response_data = EtfData {
-                etf_symbol: "SIMULATED_ETF".to_string(), // EtfDefinition has no single symbol
-                timestamps: format_timestamps(&time_series.timestamps),
-                nav_values: time_series.prices,
-            };
-            success_response(response_data)
+    // A body with an asset_identifier starts from that [[instruments.etfs]] basket; fields still
+    // missing come from the live GlobalConfig.
+    let (params, request): (EtfDefinition, _) = match instrument_request(
+        &config_store.config(), InstrumentKind::Etf, Some(RequestKind::Etf), body.into_inner(),
+    ) {
+        Ok(resolved) => resolved,
+        Err(e) => return api_error(&e),
+    };
+    let etf_symbol = string_field(&request, IDENTIFIER_FIELD).unwrap_or_else(|| "SIMULATED_ETF".to_string());
+    match etf_simulation::simulate_etf_with_market(&params) {
+        Ok(simulation) => time_series_response(&req, simulation.nav, |nav| EtfData {
+            etf_symbol, // The configured basket, if the request named one
+            ..EtfData::from(EtfNavSimulation { nav, ..simulation })
+        }),
+        Err(e) => api_error(&e),
+    }
+}
+
+// --- Admin ---
+
+// Who may call the admin routes. With a token, requests must send `Authorization: Bearer <token>`.
+// Without one, only clients connecting over loopback may, so a server bound to a public --host
+// cannot have its config reloaded or inspected from outside. Put it in the app data; an app without
+// it behaves as if no token were set.
+#[derive(Debug, Clone, Default)]
+pub struct AdminAccess {
+    pub token: Option<String>,
+}
+
+// Compares max(len) bytes, padding the shorter side with zeros and counting a length difference as
+// a mismatch, with no early exit: the time depends only on the longer length, not on where or
+// whether the two differ.
+fn token_matches(given: &str, token: &str) -> bool {
+    let (given, token) = (given.as_bytes(), token.as_bytes());
+    let byte = |bytes: &[u8], i: usize| bytes.get(i).copied().unwrap_or(0) as usize;
+    let diff = (0..given.len().max(token.len()))
+        .fold(given.len() ^ token.len(), |diff, i| diff | (byte(given, i) ^ byte(token, i)));
+    diff == 0
+}
+
+fn check_admin_access(req: &HttpRequest) -> Result<(), HttpResponse> {
+    let token = req.app_data::<web::Data<AdminAccess>>().and_then(|access| access.token.as_deref());
+    let allowed = match token {
+        Some(token) => req.headers().get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|given| token_matches(given, token)),
+        None => req.peer_addr().is_some_and(|peer| peer.ip().is_loopback()),
+    };
+    if allowed {
+        return Ok(());
+    }
+    let message = match token {
+        Some(_) => "Admin routes require the admin token as 'Authorization: Bearer <token>'.",
+        None => "Admin routes only answer loopback clients when the server has no admin token.",
+    };
+    Err(error_response(SimulationError::new(ErrorCode::Forbidden, None, message), StatusCode::FORBIDDEN))
+}
+
+fn config_status(store: &ConfigStore) -> ConfigStatusData {
+    let snapshot = store.current();
+    ConfigStatusData {
+        version: snapshot.version,
+        hash: snapshot.hash.clone(),
+        loaded_at: snapshot.loaded_at.format(export::DEFAULT_TIMESTAMP_FORMAT).to_string(),
+        source: (!store.paths.is_empty()).then(|| store.paths.join(", ")),
+        files: store.files(),
+        asset_model_count: snapshot.config.asset_models.as_ref().map_or(0, Vec::len),
+        last_reload_error: store.last_error(),
+    }
+}
+
+// Problems reading or checking the config files are the config's fault whatever their cause.
+fn config_error(e: &anyhow::Error) -> SimulationError {
+    SimulationError { code: ErrorCode::InvalidConfig, ..classify(e) }
+}
+
+// GET /admin/config
+pub async fn config_status_handler(req: HttpRequest, config_store: web::Data<ConfigStore>) -> HttpResponse {
+    if let Err(response) = check_admin_access(&req) {
+        return response;
+    }
+    success_response(config_status(&config_store))
+}
+
+// POST /admin/config/reload
+pub async fn reload_config_handler(req: HttpRequest, config_store: web::Data<ConfigStore>) -> HttpResponse {
+    if let Err(response) = check_admin_access(&req) {
+        return response;
+    }
+    match config_store.reload() {
+        Ok(ReloadOutcome::Reloaded { .. }) | Ok(ReloadOutcome::Unchanged { .. }) => {
+            success_response(config_status(&config_store))
         }
-        Err(e) => error_response(e.to_string(), StatusCode::BAD_REQUEST),
+        // The previous config is still being served.
+        Err(e) => error_response(config_error(&e), StatusCode::UNPROCESSABLE_ENTITY),
+    }
+}
+
+// GET /admin/config/resolve
+pub async fn resolve_model_handler(
+    req: HttpRequest,
+    config_store: web::Data<ConfigStore>,
+) -> HttpResponse {
+    if let Err(response) = check_admin_access(&req) {
+        return response;
     }
+    let params: ModelResolutionQueryParams = match query_params(&req) {
+        Ok(params) => params,
+        Err(e) => return api_error(&e),
+    };
+    let snapshot = config_store.current();
+    let config = &snapshot.config;
+    let resolution = snapshot.matcher.resolve(config, &params.asset_type, &params.asset_identifier);
+    let models = config.asset_models.as_deref().unwrap_or(&[]);
+    let resolved = resolution.resolved.and_then(|index| {
+        let candidate = resolution.candidates.iter().find(|c| c.index == index)?;
+        Some(ResolvedModelData {
+            index,
+            pattern: candidate.pattern.clone(),
+            match_kind: candidate.kind.name().to_string(),
+            model: models.get(index)?.clone(),
+        })
+    });
+    success_response(ModelResolutionData {
+        asset_type: resolution.asset_type,
+        asset_identifier: resolution.asset_identifier,
+        resolved,
+        candidates: resolution.candidates.into_iter().map(|c| PatternCandidateData {
+            index: c.index,
+            pattern: c.pattern,
+            match_kind: c.kind.name().to_string(),
+            matched: c.matched,
+        }).collect(),
+    })
+}
+
+// --- Service ---
+
+// GET /healthz
+pub async fn health_handler(config_store: web::Data<ConfigStore>) -> HttpResponse {
+    success_response(HealthData {
+        config_version: config_store.current().version,
+        config_reload_failing: config_store.last_error().is_some(),
+    })
+}
+
+// GET /version
+pub async fn version_handler() -> HttpResponse {
+    success_response(VersionData {
+        name: env!("CARGO_PKG_NAME").to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        api_version: API_VERSION.to_string(),
+    })
+}
+
+// GET /openapi.json. The document only depends on the build, so it is generated once.
+pub async fn openapi_handler() -> HttpResponse {
+    static DOCUMENT: OnceLock<serde_json::Value> = OnceLock::new();
+    HttpResponse::Ok().json(DOCUMENT.get_or_init(crate::openapi::openapi_document))
+}
+
+// Simulation and admin routes, relative to API_PREFIX.
+fn api_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/simulate/stock", web::get().to(simulate_stock_handler))
+        .route("/simulate/option/black_scholes", web::post().to(simulate_option_bs_handler))
+        .route("/simulate/option/monte_carlo", web::post().to(simulate_option_mc_handler))
+        .route("/simulate/future", web::post().to(simulate_future_handler))
+        .route("/simulate/bond", web::post().to(simulate_bond_handler))
+        .route("/simulate/fx", web::post().to(simulate_fx_handler))
+        .route("/simulate/option/garman_kohlhagen", web::post().to(simulate_fx_option_handler))
+        .route("/simulate/etf", web::post().to(simulate_etf_handler))
+        .route("/admin/config", web::get().to(config_status_handler))
+        .route("/admin/config/reload", web::post().to(reload_config_handler))
+        .route("/admin/config/resolve", web::get().to(resolve_model_handler));
+}
+
+// Registers every route of the server: the API under API_PREFIX and again unprefixed (deprecated),
+// plus the unversioned /healthz, /version and /openapi.json. Handlers need the ConfigStore as app data.
+// Every error, including unknown routes and bodies or queries actix cannot read, is an ApiErrorResponse.
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.app_data(web::JsonConfig::default().error_handler(json_error_handler))
+        .default_service(web::to(not_found_handler))
+        .route("/healthz", web::get().to(health_handler))
+        .route("/version", web::get().to(version_handler))
+        .route("/openapi.json", web::get().to(openapi_handler))
+        .service(web::scope(API_PREFIX).configure(api_routes))
+        .configure(api_routes);
 }
 
 // --- Server Setup ---
+
+#[derive(Debug, Clone)]
+pub struct ServerOptions {
+    pub address: String,        // host:port to bind
+    pub workers: Option<usize>, // None uses actix's default, one per physical core
+    pub log_level: String,      // error, warn, info, debug, trace or off
+    // How often to check the config file for changes; None reloads only on SIGHUP or the admin endpoint.
+    pub config_poll_interval: Option<Duration>,
+    pub admin_token: Option<String>, // See AdminAccess
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        ServerOptions {
+            address: "127.0.0.1:8080".to_string(),
+            workers: None,
+            log_level: "info".to_string(),
+            config_poll_interval: None,
+            admin_token: None,
+        }
+    }
+}
+
+// Serves a fixed config; use run_server_with_options with a file-backed ConfigStore to allow reloads.
 pub async fn run_server(address: &str, config_data: web::Data<crate::config::GlobalConfig>) -> std::io::Result<()> {
-    std::env::set_var("RUST_LOG", "actix_web=info,stock_price_simulator=info");
-    env_logger::init();
+    let options = ServerOptions { address: address.to_string(), ..ServerOptions::default() };
+    let store = ConfigStore::new(config_data.as_ref().clone(), None)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+    run_server_with_options(&options, Arc::new(store)).await
+}
 
-    HttpServer::new(move || {
+pub async fn run_server_with_options(options: &ServerOptions, config_store: Arc<ConfigStore>) -> std::io::Result<()> {
+    // try_init: a second server in the same process keeps the logger of the first.
+    let _ = env_logger::Builder::new()
+        .parse_filters(&format!("actix_web={0},stock_price_simulator={0}", options.log_level))
+        .try_init();
+
+    crate::config_store::spawn_reload_triggers(config_store.clone(), options.config_poll_interval);
+    let config_data = web::Data::from(config_store);
+    let admin_access = web::Data::new(AdminAccess { token: options.admin_token.clone() });
+
+    let server = HttpServer::new(move || {
         App::new()
             .app_data(config_data.clone()) // Share config with handlers
+            .app_data(admin_access.clone())
             .wrap(Logger::default()) // Re-add Logger
-            .route("/simulate/stock", web::get().to(simulate_stock_handler))
-            .route("/simulate/option/black_scholes", web::post().to(simulate_option_bs_handler))
-            .route("/simulate/option/monte_carlo", web::post().to(simulate_option_mc_handler))
-            .route("/simulate/future", web::post().to(simulate_future_handler))
-            .route("/simulate/etf", web::post().to(simulate_etf_handler))
-    })
-    .bind(address)?
-    .run()
-    .await
+            .configure(configure_routes)
+    });
+    let server = match options.workers {
+        Some(workers) => server.workers(workers),
+        None => server,
+    };
+    server.bind(options.address.as_str())?.run().await
 }
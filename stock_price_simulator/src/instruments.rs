@@ -0,0 +1,100 @@
+use crate::config::GlobalConfig;
+use crate::error::{ErrorCode, SimulationError};
+use anyhow::Error;
+use serde_json::{Map, Value};
+
+// Request field naming the configured instrument, as for GET /simulate/stock.
+pub const IDENTIFIER_FIELD: &str = "asset_identifier";
+
+// POST endpoints whose body can reference an instrument of GlobalConfig.instruments.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InstrumentKind {
+    OptionBlackScholes,
+    OptionMonteCarlo,
+    Future,
+    Etf,
+}
+
+impl InstrumentKind {
+    // Section of [instruments] the identifier is looked up in.
+    pub fn section(&self) -> &'static str {
+        match self {
+            InstrumentKind::OptionBlackScholes | InstrumentKind::OptionMonteCarlo => "options",
+            InstrumentKind::Future => "futures",
+            InstrumentKind::Etf => "etfs",
+        }
+    }
+}
+
+// The configured instrument as a request body, or None when there is no such identifier.
+fn configured_request(config: &GlobalConfig, kind: InstrumentKind, identifier: &str) -> Result<Option<Value>, Error> {
+    let instruments = &config.instruments;
+    let entry = match kind {
+        InstrumentKind::OptionBlackScholes | InstrumentKind::OptionMonteCarlo => instruments.options.iter()
+            .find(|o| o.identifier == identifier).map(serde_json::to_value),
+        InstrumentKind::Future => instruments.futures.iter()
+            .find(|f| f.identifier == identifier).map(serde_json::to_value),
+        InstrumentKind::Etf => instruments.etfs.iter()
+            .find(|e| e.identifier == identifier).map(serde_json::to_value),
+    };
+    let mut request = match entry.transpose()? {
+        Some(Value::Object(map)) => map,
+        _ => return Ok(None),
+    };
+    request.remove("identifier");
+    if kind == InstrumentKind::OptionMonteCarlo {
+        for (config_name, request_name) in [("underlying_price", "underlying_initial_price"), ("volatility", "underlying_volatility")] {
+            if let Some(value) = request.remove(config_name) {
+                request.insert(request_name.to_string(), value);
+            }
+        }
+    }
+    Ok(Some(Value::Object(request)))
+}
+
+// JSON Merge Patch (RFC 7386): objects are merged key by key, a null removes the key and anything
+// else (arrays included) replaces the target.
+fn merge_patch(target: &mut Value, patch: Value) {
+    match patch {
+        Value::Object(patch) => {
+            if !target.is_object() {
+                *target = Value::Object(Map::new());
+            }
+            let target = target.as_object_mut().expect("target was just made an object");
+            for (key, value) in patch {
+                if value.is_null() {
+                    target.remove(&key);
+                } else {
+                    merge_patch(target.entry(key).or_insert(Value::Null), value);
+                }
+            }
+        }
+        other => *target = other,
+    }
+}
+
+// Expands a request body that names a configured instrument in `asset_identifier`: the body is
+// applied to the instrument's definition as a JSON merge patch, so the fields it sets override the
+// config (nested objects such as `market` key by key, arrays such as `constituents` as a whole) and
+// a null removes a configured field. `asset_identifier` is kept in the result. Bodies without an
+// identifier are returned unchanged; an identifier missing from the config is an error.
+pub fn resolve_instrument(config: &GlobalConfig, kind: InstrumentKind, body: Value) -> Result<Value, Error> {
+    let identifier = match body.get(IDENTIFIER_FIELD) {
+        None | Some(Value::Null) => return Ok(body),
+        Some(Value::String(identifier)) => identifier.clone(),
+        Some(other) => return Err(SimulationError::new(
+            ErrorCode::InvalidRequest,
+            Some(IDENTIFIER_FIELD),
+            format!("{} must be a string. Got {}", IDENTIFIER_FIELD, other),
+        ).into()),
+    };
+    let mut request = configured_request(config, kind, &identifier)?.ok_or_else(|| {
+        SimulationError::new(
+            ErrorCode::UnknownAsset,
+            Some(IDENTIFIER_FIELD),
+            format!("No instrument '{}' in the [[instruments.{}]] config section.", identifier, kind.section()),
+        )
+    })?;
+    merge_patch(&mut request, body);
+    Ok(request)
+}
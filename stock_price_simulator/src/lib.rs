@@ -1,4 +1,11 @@
+pub mod error;
 pub mod config;
+pub mod config_layers;
+pub mod config_store;
+pub mod request_defaults;
+pub mod instruments;
+pub mod config_validation;
+pub mod asset_matching;
 pub mod random_process;
 pub mod stock_simulation;
 pub mod option_pricing;
@@ -7,6 +14,20 @@ pub mod etf_simulation;
 pub mod api_models;
 pub mod api_interface;
 pub mod http_server; // Added http_server module
+pub mod openapi;
+pub mod export;
+pub mod historical_data;
+pub mod optimization;
+pub mod calibration;
+pub mod bootstrap;
+pub mod volatility_models;
+pub mod regime_switching;
+pub mod local_volatility;
+pub mod fourier_pricing;
+pub mod finite_difference;
+pub mod yield_curve;
+pub mod bond_simulation;
+pub mod fx_simulation;
 
 // Re-exports for convenience
 pub use random_process::TimeSeries;
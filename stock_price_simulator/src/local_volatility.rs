@@ -0,0 +1,235 @@
+use crate::config::{ImpliedVolSurfaceParams, LocalVolGridParams, LocalVolatilityParams};
+use crate::error::{invalid_config, within};
+use crate::random_process::{generate_markov_path, StochasticProcess, TimeSeries};
+use anyhow::Error;
+use rand_distr::{Distribution, Normal};
+
+// Position of x on an ascending grid: the two bracketing indices and the weight of the upper one.
+// Outside the grid the nearest end point is used (flat extrapolation).
+fn bracket(grid: &[f64], x: f64) -> (usize, usize, f64) {
+    let last = grid.len() - 1;
+    if x <= grid[0] {
+        return (0, 0, 0.0);
+    }
+    if x >= grid[last] {
+        return (last, last, 0.0);
+    }
+    let hi = grid.partition_point(|g| *g <= x);
+    let lo = hi - 1;
+    (lo, hi, (x - grid[lo]) / (grid[hi] - grid[lo]))
+}
+
+fn interpolate(grid: &[f64], values: &[f64], x: f64) -> f64 {
+    let (lo, hi, w) = bracket(grid, x);
+    values[lo] + w * (values[hi] - values[lo])
+}
+
+// `field` is the config field the axis comes from, `name` how messages call it.
+fn validate_axis(values: &[f64], field: &str, name: &str) -> Result<(), Error> {
+    if values.is_empty() {
+        return Err(invalid_config(Some(field), format!("{} must not be empty.", name)));
+    }
+    if values.iter().any(|v| !v.is_finite()) || values.windows(2).any(|w| w[1] <= w[0]) {
+        return Err(invalid_config(Some(field), format!("{} must be finite and strictly increasing.", name)));
+    }
+    Ok(())
+}
+
+fn validate_volatilities(volatilities: &[Vec<f64>], rows: usize, columns: usize) -> Result<(), Error> {
+    if volatilities.len() != rows || volatilities.iter().any(|row| row.len() != columns) {
+        return Err(invalid_config(Some("volatilities"), format!("Volatility grid must have {} rows of {} values.", rows, columns)));
+    }
+    if volatilities.iter().flatten().any(|v| !v.is_finite() || *v <= 0.0) {
+        return Err(invalid_config(Some("volatilities"), "Volatilities must be positive."));
+    }
+    Ok(())
+}
+
+// sigma(S, t) on a spot x time grid, bilinear inside and flat outside.
+#[derive(Debug, Clone)]
+pub struct LocalVolSurface {
+    pub spots: Vec<f64>,
+    pub times: Vec<f64>,             // Years
+    pub volatilities: Vec<Vec<f64>>, // volatilities[time][spot]
+}
+
+impl LocalVolSurface {
+    pub fn new(spots: Vec<f64>, times: Vec<f64>, volatilities: Vec<Vec<f64>>) -> Result<Self, Error> {
+        validate_axis(&spots, "spots", "Spot levels")?;
+        validate_axis(&times, "times_years", "Times")?;
+        validate_volatilities(&volatilities, times.len(), spots.len())?;
+        Ok(LocalVolSurface { spots, times, volatilities })
+    }
+
+    pub fn from_params(params: &LocalVolGridParams) -> Result<Self, Error> {
+        Self::new(params.spots.clone(), params.times_years.clone(), params.volatilities.clone())
+    }
+
+    pub fn local_vol(&self, spot: f64, t: f64) -> f64 {
+        let (lo, hi, w) = bracket(&self.times, t);
+        let at_lo = interpolate(&self.spots, &self.volatilities[lo], spot);
+        let at_hi = interpolate(&self.spots, &self.volatilities[hi], spot);
+        at_lo + w * (at_hi - at_lo)
+    }
+}
+
+// Black-Scholes implied volatilities by maturity and strike, quoted with the underlying at `spot`
+// and a flat risk_free_rate (no dividends). Interpolated linearly in strike and linearly in total
+// implied variance between maturities.
+#[derive(Debug, Clone)]
+pub struct ImpliedVolSurface {
+    pub spot: f64,
+    pub risk_free_rate: f64,
+    pub strikes: Vec<f64>,
+    pub maturities: Vec<f64>,        // Years
+    pub volatilities: Vec<Vec<f64>>, // volatilities[maturity][strike]
+}
+
+impl ImpliedVolSurface {
+    pub fn new(spot: f64, risk_free_rate: f64, strikes: Vec<f64>, maturities: Vec<f64>, volatilities: Vec<Vec<f64>>) -> Result<Self, Error> {
+        if spot <= 0.0 {
+            return Err(invalid_config(Some("spot"), format!("Spot must be positive. Got {}", spot)));
+        }
+        validate_axis(&strikes, "strikes", "Strikes")?;
+        validate_axis(&maturities, "maturities_years", "Maturities")?;
+        if strikes[0] <= 0.0 || maturities[0] <= 0.0 {
+            let field = if strikes[0] <= 0.0 { "strikes" } else { "maturities_years" };
+            return Err(invalid_config(Some(field), "Strikes and maturities must be positive."));
+        }
+        validate_volatilities(&volatilities, maturities.len(), strikes.len())?;
+        Ok(ImpliedVolSurface { spot, risk_free_rate, strikes, maturities, volatilities })
+    }
+
+    pub fn from_params(params: &ImpliedVolSurfaceParams) -> Result<Self, Error> {
+        Self::new(
+            params.spot,
+            params.risk_free_rate,
+            params.strikes.clone(),
+            params.maturities_years.clone(),
+            params.volatilities.clone(),
+        )
+    }
+
+    fn forward(&self, maturity: f64) -> f64 {
+        self.spot * (self.risk_free_rate * maturity).exp()
+    }
+
+    // Total implied variance w = sigma^2 T at quoted maturity i and log-moneyness y = ln(K / F(T_i)).
+    fn total_variance_at_maturity(&self, i: usize, y: f64) -> f64 {
+        let t = self.maturities[i];
+        let vol = interpolate(&self.strikes, &self.volatilities[i], self.forward(t) * y.exp());
+        vol * vol * t
+    }
+
+    pub fn implied_vol(&self, strike: f64, maturity: f64) -> f64 {
+        if maturity <= self.maturities[0] {
+            return interpolate(&self.strikes, &self.volatilities[0], strike);
+        }
+        let y = (strike / self.forward(maturity)).ln();
+        let (lo, hi, w) = bracket(&self.maturities, maturity);
+        let (w_lo, w_hi) = (self.total_variance_at_maturity(lo, y), self.total_variance_at_maturity(hi, y));
+        if lo == hi {
+            // Beyond the last maturity, keep its implied volatility.
+            return (w_lo / self.maturities[lo]).sqrt();
+        }
+        ((w_lo + w * (w_hi - w_lo)) / maturity).sqrt()
+    }
+
+    // Local volatility at every (strike, maturity) node via Dupire's formula in total implied variance
+    // (Gatheral, "The Volatility Surface", eq. 1.10). Strike derivatives are three-point differences
+    // on the quoted strikes; the maturity derivative is taken at fixed log-moneyness, with w = 0 at T = 0.
+    // Fails on calendar (dw/dT < 0) or butterfly (non-positive denominator) arbitrage in the quotes.
+    pub fn to_local_vol(&self) -> Result<LocalVolSurface, Error> {
+        let n_strikes = self.strikes.len();
+        let mut volatilities = Vec::with_capacity(self.maturities.len());
+
+        for (i, &t) in self.maturities.iter().enumerate() {
+            let forward = self.forward(t);
+            let ys: Vec<f64> = self.strikes.iter().map(|k| (k / forward).ln()).collect();
+            let ws: Vec<f64> = self.volatilities[i].iter().map(|v| v * v * t).collect();
+
+            let mut row = Vec::with_capacity(n_strikes);
+            for j in 0..n_strikes {
+                let (y, w) = (ys[j], ws[j]);
+
+                let (dw_dy, d2w_dy2) = if n_strikes < 3 {
+                    (0.0, 0.0)
+                } else {
+                    let c = j.clamp(1, n_strikes - 2); // Centre of the stencil; one-sided at the ends
+                    let (h1, h2) = (ys[c] - ys[c - 1], ys[c + 1] - ys[c]);
+                    let first = -h2 / (h1 * (h1 + h2)) * ws[c - 1] + (h2 - h1) / (h1 * h2) * ws[c] + h1 / (h2 * (h1 + h2)) * ws[c + 1];
+                    let second = 2.0 * (ws[c - 1] / (h1 * (h1 + h2)) - ws[c] / (h1 * h2) + ws[c + 1] / (h2 * (h1 + h2)));
+                    // Carry the slope from the stencil centre to node j.
+                    (first + second * (y - ys[c]), second)
+                };
+
+                let dw_dt = if i + 1 < self.maturities.len() {
+                    let (t_prev, w_prev) = if i == 0 { (0.0, 0.0) } else { (self.maturities[i - 1], self.total_variance_at_maturity(i - 1, y)) };
+                    (self.total_variance_at_maturity(i + 1, y) - w_prev) / (self.maturities[i + 1] - t_prev)
+                } else if i > 0 {
+                    (w - self.total_variance_at_maturity(i - 1, y)) / (t - self.maturities[i - 1])
+                } else {
+                    w / t
+                };
+                if dw_dt < 0.0 {
+                    return Err(invalid_config(Some("volatilities"), format!(
+                        "Calendar arbitrage in implied volatility surface at strike {} and maturity {}.",
+                        self.strikes[j], t
+                    )));
+                }
+
+                let denominator = 1.0 - y / w * dw_dy
+                    + 0.25 * (-0.25 - 1.0 / w + y * y / (w * w)) * dw_dy * dw_dy
+                    + 0.5 * d2w_dy2;
+                if denominator <= 0.0 {
+                    return Err(invalid_config(Some("volatilities"), format!(
+                        "Butterfly arbitrage in implied volatility surface at strike {} and maturity {}.",
+                        self.strikes[j], t
+                    )));
+                }
+                row.push((dw_dt / denominator).sqrt().max(f64::MIN_POSITIVE));
+            }
+            volatilities.push(row);
+        }
+
+        LocalVolSurface::new(self.strikes.clone(), self.maturities.clone(), volatilities)
+    }
+}
+
+// dS = drift * S dt + sigma(S, t) * S dW, simulated with a log-Euler step that freezes sigma at the
+// start of each step. Time t is measured in years from the start of the path.
+#[derive(Debug, Clone)]
+pub struct LocalVolatilityProcess {
+    pub drift: f64,
+    pub surface: LocalVolSurface,
+}
+
+impl LocalVolatilityProcess {
+    pub fn from_params(params: &LocalVolatilityParams) -> Result<Self, Error> {
+        let surface = match (&params.grid, &params.implied_surface) {
+            (Some(grid), None) => LocalVolSurface::from_params(grid).map_err(|e| within("grid", e))?,
+            (None, Some(implied)) => ImpliedVolSurface::from_params(implied)
+                .and_then(|surface| surface.to_local_vol())
+                .map_err(|e| within("implied_surface", e))?,
+            (Some(_), Some(_)) => {
+                return Err(invalid_config(Some("implied_surface"), "Local volatility parameters must set only one of grid or implied_surface."))
+            }
+            (None, None) => {
+                return Err(invalid_config(Some("grid"), "Local volatility parameters must set either grid or implied_surface."))
+            }
+        };
+        Ok(LocalVolatilityProcess { drift: params.drift, surface })
+    }
+}
+
+impl StochasticProcess for LocalVolatilityProcess {
+    fn generate_path(&self, initial_value: f64, dt: f64, steps: usize, seed: Option<u64>) -> TimeSeries {
+        let normal_dist = Normal::new(0.0, 1.0).unwrap();
+        let mut t = 0.0;
+        generate_markov_path(initial_value, dt, steps, seed, |s, dt_years, rng| {
+            let sigma = self.surface.local_vol(s, t);
+            t += dt_years;
+            s * ((self.drift - 0.5 * sigma * sigma) * dt_years + sigma * dt_years.sqrt() * normal_dist.sample(rng)).exp()
+        })
+    }
+}
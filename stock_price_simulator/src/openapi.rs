@@ -0,0 +1,245 @@
+use crate::api_interface::MonteCarloEuropeanOptionInput;
+use crate::api_models::{
+    ApiErrorResponse, ApiResponse, BondData, ConfigStatusData, EtfData, FutureData, FxData, HealthData, ModelResolutionData,
+    OptionData, StockData, VersionData,
+};
+use crate::bond_simulation::BondContract;
+use crate::etf_simulation::EtfDefinition;
+use crate::futures_simulation::FuturesContract;
+use crate::fx_simulation::{FxForwardContract, FxOption};
+use crate::http_server::{ModelResolutionQueryParams, StockSimulationQueryParams, API_PREFIX, ARROW_STREAM_CONTENT_TYPE, CSV_CONTENT_TYPE, PARQUET_CONTENT_TYPE};
+use crate::instruments::IDENTIFIER_FIELD;
+use crate::option_pricing::EuropeanOption;
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+const ADMIN_FORBIDDEN: &str = "No valid admin token, or a remote client when the server has none";
+
+// What a request body can leave out, beyond the fields its type marks optional.
+#[derive(Clone, Copy, PartialEq)]
+enum BodyDefaults {
+    None,
+    Instrument,          // Fields may come from the instrument named in asset_identifier
+    InstrumentAndConfig, // ... and then from the GlobalConfig defaults (see request_defaults)
+}
+
+// Builds the document: operations are added one by one, and every type they mention ends up in
+// components/schemas.
+struct SpecBuilder {
+    generator: SchemaGenerator,
+    paths: Map<String, Value>,
+}
+
+fn to_json(schema: impl Serialize) -> Value {
+    serde_json::to_value(schema).expect("JSON schemas always serialize")
+}
+
+impl SpecBuilder {
+    fn new() -> Self {
+        SpecBuilder { generator: SchemaSettings::openapi3().into_generator(), paths: Map::new() }
+    }
+
+    fn reference<T: JsonSchema>(&mut self) -> Value {
+        to_json(self.generator.subschema_for::<T>())
+    }
+
+    // The fields of T as query parameters.
+    fn query_parameters<T: JsonSchema>(&mut self) -> Value {
+        let schema = to_json(T::json_schema(&mut self.generator));
+        let required: Vec<&str> = schema["required"].as_array().into_iter().flatten().filter_map(Value::as_str).collect();
+        let properties = schema["properties"].as_object().cloned().unwrap_or_default();
+        properties.into_iter().map(|(name, property)| json!({
+            "name": name,
+            "in": "query",
+            "required": required.contains(&name.as_str()),
+            "schema": property,
+        })).collect()
+    }
+
+    fn request_body<T: JsonSchema>(&mut self, defaults: BodyDefaults) -> Value {
+        let schema = match defaults {
+            BodyDefaults::None => self.reference::<T>(),
+            BodyDefaults::Instrument | BodyDefaults::InstrumentAndConfig => {
+                // Every field can come from a configured instrument, so none is required by the
+                // schema; the handler reports what is still missing.
+                let description = match defaults {
+                    BodyDefaults::InstrumentAndConfig => {
+                        "Fields left out come from the instrument named in asset_identifier, then from the GlobalConfig defaults."
+                    }
+                    _ => "Fields left out come from the instrument named in asset_identifier.",
+                };
+                let mut schema = to_json(T::json_schema(&mut self.generator));
+                if let Value::Object(object) = &mut schema {
+                    object.remove("required");
+                    object.insert("description".to_string(), json!(description));
+                    if let Some(Value::Object(properties)) = object.get_mut("properties") {
+                        properties.insert(IDENTIFIER_FIELD.to_string(), json!({
+                            "type": "string",
+                            "description": "Identifier of an instrument in the config's [instruments] sections.",
+                        }));
+                    }
+                }
+                schema
+            }
+        };
+        json!({ "required": true, "content": { "application/json": { "schema": schema } } })
+    }
+
+    // 200 with ApiResponse<T>, plus the CSV / Arrow / Parquet renderings for simulated series.
+    fn success<T: JsonSchema + Serialize + PartialEq>(&mut self, time_series: bool) -> Value {
+        let mut content = json!({ "application/json": { "schema": self.reference::<ApiResponse<T>>() } });
+        if time_series {
+            content[CSV_CONTENT_TYPE] = json!({ "schema": { "type": "string" } });
+            for binary in [ARROW_STREAM_CONTENT_TYPE, PARQUET_CONTENT_TYPE] {
+                content[binary] = json!({ "schema": { "type": "string", "format": "binary" } });
+            }
+        }
+        json!({ "description": "Success", "content": content })
+    }
+
+    fn error(&mut self, description: &str) -> Value {
+        json!({
+            "description": description,
+            "content": { "application/json": { "schema": self.reference::<ApiErrorResponse>() } },
+        })
+    }
+
+    fn add(&mut self, method: &str, path: &str, operation: Value) {
+        let item = self.paths.entry(path.to_string()).or_insert_with(|| json!({}));
+        item[method] = operation;
+    }
+
+    fn finish(mut self) -> Value {
+        let schemas: Map<String, Value> = self.generator.take_definitions().into_iter()
+            .map(|(name, schema)| (name, to_json(schema)))
+            .collect();
+        json!({
+            "openapi": "3.0.3",
+            "info": {
+                "title": "Stock Price Simulator API",
+                "version": env!("CARGO_PKG_VERSION"),
+                "description": "Simulated price paths and option prices. Simulation and admin routes are versioned under /v1.",
+            },
+            "paths": self.paths,
+            "components": { "schemas": schemas },
+        })
+    }
+}
+
+// POST operation that simulates or prices the body.
+fn post_operation<Body: JsonSchema, Data: JsonSchema + Serialize + PartialEq>(
+    spec: &mut SpecBuilder,
+    operation_id: &str,
+    summary: &str,
+    defaults: BodyDefaults,
+    time_series: bool,
+) -> Value {
+    json!({
+        "operationId": operation_id,
+        "summary": summary,
+        "requestBody": spec.request_body::<Body>(defaults),
+        "responses": {
+            "200": spec.success::<Data>(time_series),
+            "400": spec.error("Invalid request, or the simulation failed"),
+            "413": spec.error("Body larger than the server accepts"),
+            "415": spec.error("Body not sent as application/json"),
+        },
+    })
+}
+
+// OpenAPI 3 description of every route of http_server::configure_routes, with the request and
+// response schemas derived from the types the handlers read and write. Served at GET /openapi.json.
+pub fn openapi_document() -> Value {
+    let mut spec = SpecBuilder::new();
+    let versioned = |path: &str| format!("{}{}", API_PREFIX, path);
+
+    let operation = json!({
+        "operationId": "simulateStock",
+        "summary": "Simulate a stock price path with the asset model configured for asset_identifier",
+        "parameters": spec.query_parameters::<StockSimulationQueryParams>(),
+        "responses": {
+            "200": spec.success::<StockData>(true),
+            "400": spec.error("Invalid query, or no asset model matches asset_identifier"),
+            "422": spec.error("The matching asset model is missing parameters its model type needs"),
+        },
+    });
+    spec.add("get", &versioned("/simulate/stock"), operation);
+
+    let posts = [
+        ("/simulate/option/black_scholes", post_operation::<EuropeanOption, OptionData>(
+            &mut spec, "priceOptionBlackScholes", "Price a European option with Black-Scholes", BodyDefaults::Instrument, false,
+        )),
+        ("/simulate/option/monte_carlo", post_operation::<MonteCarloEuropeanOptionInput, OptionData>(
+            &mut spec, "priceOptionMonteCarlo", "Price a European option by Monte Carlo simulation", BodyDefaults::InstrumentAndConfig, false,
+        )),
+        ("/simulate/future", post_operation::<FuturesContract, FutureData>(
+            &mut spec, "simulateFuture", "Simulate a futures price path", BodyDefaults::InstrumentAndConfig, true,
+        )),
+        ("/simulate/bond", post_operation::<BondContract, BondData>(
+            &mut spec, "simulateBond", "Simulate a bond's prices and analytics under a short-rate model", BodyDefaults::None, true,
+        )),
+        ("/simulate/fx", post_operation::<FxForwardContract, FxData>(
+            &mut spec, "simulateFx", "Simulate an FX spot path with its forward", BodyDefaults::None, true,
+        )),
+        ("/simulate/option/garman_kohlhagen", post_operation::<FxOption, OptionData>(
+            &mut spec, "priceFxOption", "Price a European FX option with Garman-Kohlhagen", BodyDefaults::None, false,
+        )),
+        ("/simulate/etf", post_operation::<EtfDefinition, EtfData>(
+            &mut spec, "simulateEtf", "Simulate an ETF's NAV, and optionally its market price and tracking", BodyDefaults::InstrumentAndConfig, true,
+        )),
+    ];
+    for (path, operation) in posts {
+        spec.add("post", &versioned(path), operation);
+    }
+
+    let operation = json!({
+        "operationId": "getConfigStatus",
+        "summary": "Version, hash and source files of the config being served",
+        "responses": {
+            "200": spec.success::<ConfigStatusData>(false),
+            "403": spec.error(ADMIN_FORBIDDEN),
+        },
+    });
+    spec.add("get", &versioned("/admin/config"), operation);
+    let operation = json!({
+        "operationId": "reloadConfig",
+        "summary": "Reload the config files now",
+        "responses": {
+            "200": spec.success::<ConfigStatusData>(false),
+            "403": spec.error(ADMIN_FORBIDDEN),
+            "422": spec.error("The new config is invalid; the previous one is still served"),
+        },
+    });
+    spec.add("post", &versioned("/admin/config/reload"), operation);
+    let operation = json!({
+        "operationId": "resolveAssetModel",
+        "summary": "Explain which asset model an identifier resolves to",
+        "parameters": spec.query_parameters::<ModelResolutionQueryParams>(),
+        "responses": {
+            "200": spec.success::<ModelResolutionData>(false),
+            "403": spec.error(ADMIN_FORBIDDEN),
+        },
+    });
+    spec.add("get", &versioned("/admin/config/resolve"), operation);
+
+    let operation = json!({
+        "operationId": "health",
+        "summary": "Liveness check",
+        "responses": { "200": spec.success::<HealthData>(false) },
+    });
+    spec.add("get", "/healthz", operation);
+    let operation = json!({
+        "operationId": "version",
+        "summary": "Server and API version",
+        "responses": { "200": spec.success::<VersionData>(false) },
+    });
+    spec.add("get", "/version", operation);
+    spec.add("get", "/openapi.json", json!({
+        "operationId": "openapi",
+        "summary": "This document",
+        "responses": { "200": { "description": "OpenAPI 3 document", "content": { "application/json": {} } } },
+    }));
+    spec.finish()
+}
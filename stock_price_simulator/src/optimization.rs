@@ -0,0 +1,115 @@
+// Derivative-free minimisation used by the likelihood-based calibrators.
+
+#[derive(Debug, Clone)]
+pub struct NelderMeadOptions {
+    pub max_iterations: usize,
+    pub tolerance: f64,          // Stop once the spread of objective values in the simplex is below this
+    pub initial_step: Vec<f64>,  // Per-coordinate offset used to build the initial simplex
+}
+
+#[derive(Debug, Clone)]
+pub struct OptimizationResult {
+    pub point: Vec<f64>,
+    pub value: f64,
+    pub iterations: usize,
+    pub converged: bool,
+}
+
+// Minimises `objective` starting from `initial_point` with the Nelder-Mead simplex method.
+// Non-finite objective values are treated as +infinity, so callers can reject infeasible
+// parameters by returning `f64::INFINITY` or NaN.
+pub fn nelder_mead<F>(mut objective: F, initial_point: &[f64], options: &NelderMeadOptions) -> OptimizationResult
+where
+    F: FnMut(&[f64]) -> f64,
+{
+    let n = initial_point.len();
+    let mut eval = |x: &[f64]| {
+        let v = objective(x);
+        if v.is_finite() { v } else { f64::INFINITY }
+    };
+    if n == 0 {
+        let value = eval(initial_point);
+        return OptimizationResult { point: Vec::new(), value, iterations: 0, converged: true };
+    }
+
+    let mut simplex: Vec<Vec<f64>> = Vec::with_capacity(n + 1);
+    simplex.push(initial_point.to_vec());
+    for i in 0..n {
+        let mut vertex = initial_point.to_vec();
+        let step = options.initial_step.get(i).copied().unwrap_or(0.1);
+        vertex[i] += if step != 0.0 { step } else { 0.1 };
+        simplex.push(vertex);
+    }
+    let mut values: Vec<f64> = simplex.iter().map(|v| eval(v)).collect();
+
+    let (alpha, gamma, rho, sigma) = (1.0, 2.0, 0.5, 0.5);
+    let mut iterations = 0;
+    let mut converged = false;
+
+    while iterations < options.max_iterations {
+        iterations += 1;
+
+        let mut order: Vec<usize> = (0..=n).collect();
+        order.sort_by(|&a, &b| values[a].partial_cmp(&values[b]).unwrap_or(std::cmp::Ordering::Equal));
+        simplex = order.iter().map(|&i| simplex[i].clone()).collect();
+        values = order.iter().map(|&i| values[i]).collect();
+
+        if values[n].is_finite() && (values[n] - values[0]).abs() <= options.tolerance {
+            converged = true;
+            break;
+        }
+
+        let centroid: Vec<f64> = (0..n)
+            .map(|j| simplex[..n].iter().map(|v| v[j]).sum::<f64>() / n as f64)
+            .collect();
+        let towards = |coef: f64| -> Vec<f64> {
+            centroid.iter().zip(&simplex[n]).map(|(c, w)| c + coef * (c - w)).collect()
+        };
+
+        let reflected = towards(alpha);
+        let reflected_value = eval(&reflected);
+
+        if reflected_value < values[0] {
+            let expanded = towards(gamma);
+            let expanded_value = eval(&expanded);
+            if expanded_value < reflected_value {
+                simplex[n] = expanded;
+                values[n] = expanded_value;
+            } else {
+                simplex[n] = reflected;
+                values[n] = reflected_value;
+            }
+            continue;
+        }
+        if reflected_value < values[n - 1] {
+            simplex[n] = reflected;
+            values[n] = reflected_value;
+            continue;
+        }
+
+        let contracted = if reflected_value < values[n] { towards(rho) } else { towards(-rho) };
+        let contracted_value = eval(&contracted);
+        if contracted_value < values[n].min(reflected_value) {
+            simplex[n] = contracted;
+            values[n] = contracted_value;
+            continue;
+        }
+
+        // Shrink towards the best vertex.
+        let best = simplex[0].clone();
+        for i in 1..=n {
+            simplex[i] = best.iter().zip(&simplex[i]).map(|(b, x)| b + sigma * (x - b)).collect();
+            values[i] = eval(&simplex[i]);
+        }
+    }
+
+    let best_idx = (0..=n)
+        .min_by(|&a, &b| values[a].partial_cmp(&values[b]).unwrap_or(std::cmp::Ordering::Equal))
+        .unwrap_or(0);
+    OptimizationResult {
+        point: simplex[best_idx].clone(),
+        value: values[best_idx],
+        iterations,
+        converged,
+    }
+}
@@ -1,19 +1,30 @@
 use serde::Deserialize; // Added for derive
+use schemars::JsonSchema;
+pub use crate::config::OptionType; // Defined with the instrument configs; re-exported at its original path
+use crate::yield_curve::{DiscountCurve, RateSource};
+use crate::error::invalid_parameter;
 
-#[derive(Debug, Clone, Copy, Deserialize)] // Added Deserialize
-pub enum OptionType {
-    Call,
-    Put,
-}
-
-#[derive(Debug, Clone, Deserialize)] // Added Deserialize
+#[derive(Debug, Clone, Deserialize, JsonSchema)] // Added Deserialize
 pub struct EuropeanOption {
     pub underlying_price: f64, // S
     pub strike_price: f64,     // K
     pub time_to_maturity_years: f64, // T
-    pub risk_free_rate: f64,   // r
+    #[serde(default)]
+    pub risk_free_rate: Option<f64>, // r
     pub volatility: f64,       // sigma (annualized)
     pub option_type: OptionType,
+    // Term structure of rates, given instead of risk_free_rate.
+    #[serde(default)]
+    pub discount_curve: Option<DiscountCurve>,
+}
+
+impl EuropeanOption {
+    // Continuously compounded rate from today to maturity: the curve's zero rate, or the flat rate.
+    // Fails unless exactly one of risk_free_rate and discount_curve is set.
+    pub fn rate_to_maturity(&self) -> Result<f64, Error> {
+        let rates = RateSource::from_request(self.risk_free_rate, self.discount_curve.as_ref())?;
+        Ok(rates.zero_rate(self.time_to_maturity_years))
+    }
 }
 
 // Parameters of an option that are fixed, except for underlying price and time to maturity (for now T is fixed)
@@ -21,9 +32,10 @@ pub struct EuropeanOption {
 pub struct FixedOptionParams {
     pub strike_price: f64,     // K
     pub time_to_maturity_years: f64, // T
-    pub risk_free_rate: f64,   // r
+    pub risk_free_rate: Option<f64>, // r
     pub volatility: f64,       // sigma (annualized)
     pub option_type: OptionType,
+    pub discount_curve: Option<DiscountCurve>, // Given instead of risk_free_rate
 }
 
 pub fn price_series_for_black_scholes(
@@ -41,13 +53,14 @@ pub fn price_series_for_black_scholes(
                 risk_free_rate: fixed_params.risk_free_rate,
                 volatility: fixed_params.volatility,
                 option_type: fixed_params.option_type,
+                discount_curve: fixed_params.discount_curve.clone(),
             };
             black_scholes_price(&option_at_price) // This now returns Result<f64, Error>
         })
         .collect::<Result<Vec<f64>, Error>>() // Collect into a Result of a Vec
 }
 
-use crate::random_process::{TimeSeries, GeometricBrownianMotion, StochasticProcess};
+use crate::random_process::{TimeSeries, GeometricBrownianMotion, StochasticProcess, TRADING_DAYS_PER_YEAR};
 use statrs::distribution::ContinuousCDF; // Added for Normal.cdf()
 use anyhow::Error;
 
@@ -65,8 +78,9 @@ pub struct MonteCarloOptionPricer {
     // Parameters for the option itself
     pub strike_price: f64,
     pub time_to_maturity_years: f64,
-    pub risk_free_rate: f64,
+    pub risk_free_rate: Option<f64>,
     pub option_type: OptionType,
+    pub discount_curve: Option<DiscountCurve>, // Discounts the payoffs; given instead of risk_free_rate
     // Parameters for the underlying asset's stochastic process (GBM)
     pub underlying_initial_price: f64,
     pub underlying_drift: f64,       // Corresponds to risk_free_rate for risk-neutral pricing
@@ -77,51 +91,83 @@ pub struct MonteCarloOptionPricer {
 }
 
 impl MonteCarloOptionPricer {
+    // Pricer for a EuropeanOption under risk-neutral GBM with the option's own volatility.
+    // With a discount curve the drift is the zero rate to maturity, which gives the
+    // risk-neutral distribution of the terminal price.
+    pub fn for_european_option(option: &EuropeanOption, num_paths: usize, num_steps_per_path: usize) -> Result<Self, Error> {
+        let rate = option.rate_to_maturity()?;
+        Ok(MonteCarloOptionPricer {
+            strike_price: option.strike_price,
+            time_to_maturity_years: option.time_to_maturity_years,
+            risk_free_rate: option.risk_free_rate,
+            option_type: option.option_type,
+            discount_curve: option.discount_curve.clone(),
+            underlying_initial_price: option.underlying_price,
+            underlying_drift: rate,
+            underlying_volatility: option.volatility,
+            num_paths,
+            num_steps_per_path,
+        })
+    }
+
     // Helper to generate underlying paths
     fn simulate_underlying_paths(&self, seed: Option<u64>) -> Result<Vec<TimeSeries>, Error> {
-        let gbm = GeometricBrownianMotion {
-            drift: self.underlying_drift,
-            volatility: self.underlying_volatility,
-        };
+        let gbm = GeometricBrownianMotion::new(self.underlying_drift, self.underlying_volatility);
+        self.simulate_paths_with_process(&gbm, seed)
+    }
 
-        // dt for gbm.generate_path is expected in days.
+    // Simulates `num_paths` paths of `process` over the option's life, each with num_steps_per_path
+    // steps (num_steps_per_path + 1 points, the last one at maturity). Path-dependent payoffs can be
+    // evaluated on the result; `underlying_drift` and `underlying_volatility` are not used.
+    pub fn simulate_paths_with_process(&self, process: &dyn StochasticProcess, seed: Option<u64>) -> Result<Vec<TimeSeries>, Error> {
+        // dt for generate_path is expected in days.
         // self.time_to_maturity_years is in years.
         // self.num_steps_per_path is the number of steps for the option's life.
-        let total_simulation_days = self.time_to_maturity_years * 252.0; // Approx trading days in a year
-        let dt_for_gbm_step_in_days = total_simulation_days / self.num_steps_per_path as f64;
+        let total_simulation_days = self.time_to_maturity_years * TRADING_DAYS_PER_YEAR;
+        let dt_for_step_in_days = total_simulation_days / self.num_steps_per_path as f64;
 
         let mut all_paths = Vec::with_capacity(self.num_paths);
 
         // Seed handling for reproducibility:
         // If a seed is provided, we want each path to be different but the whole set deterministic.
         // So, we'll derive seeds for each path from the initial seed.
-        let mut path_seeds: Vec<Option<u64>> = vec![None; self.num_paths];
-        if let Some(initial_seed) = seed {
-            for i in 0..self.num_paths {
-                path_seeds[i] = Some(initial_seed + i as u64); // Simple seed derivation
-            }
-        }
-
         for i in 0..self.num_paths {
-            let path = gbm.generate_path(
+            let path_seed = seed.map(|initial_seed| initial_seed.wrapping_add(i as u64)); // Simple seed derivation
+            let path = process.generate_path(
                 self.underlying_initial_price,
-                dt_for_gbm_step_in_days, // dt is in days
+                dt_for_step_in_days, // dt is in days
                 self.num_steps_per_path + 1, // +1 to include S_T (num_steps_per_path intervals)
-                path_seeds[i],
+                path_seed,
             );
             all_paths.push(path);
         }
         Ok(all_paths)
     }
-}
 
-impl OptionPricer for MonteCarloOptionPricer {
-    fn price(&self, seed: Option<u64>) -> Result<f64, Error> {
-        if self.time_to_maturity_years <= 0.0 || self.num_paths == 0 || self.num_steps_per_path == 0 {
-             return Err(anyhow::anyhow!("Invalid parameters for Monte Carlo pricing. Ensure T > 0, num_paths > 0, num_steps > 0."));
-        }
+    // Prices the option with the underlying following `process` instead of GBM, e.g. a
+    // local_volatility::LocalVolatilityProcess for smile-consistent prices. The process should be
+    // risk-neutral (drift equal to risk_free_rate).
+    pub fn price_with_process(&self, process: &dyn StochasticProcess, seed: Option<u64>) -> Result<f64, Error> {
+        self.validate()?;
+        let underlying_paths = self.simulate_paths_with_process(process, seed)?;
+        self.discounted_mean_payoff(&underlying_paths)
+    }
 
-        let underlying_paths = self.simulate_underlying_paths(seed)?;
+    fn validate(&self) -> Result<(), Error> {
+        RateSource::from_request(self.risk_free_rate, self.discount_curve.as_ref())?;
+        let field = if self.time_to_maturity_years <= 0.0 {
+            "time_to_maturity_years"
+        } else if self.num_paths == 0 {
+            "num_paths"
+        } else if self.num_steps_per_path == 0 {
+            "num_steps_per_path"
+        } else {
+            return Ok(());
+        };
+        Err(invalid_parameter(field, "Invalid parameters for Monte Carlo pricing. Ensure T > 0, num_paths > 0, num_steps > 0."))
+    }
+
+    fn discounted_mean_payoff(&self, underlying_paths: &[TimeSeries]) -> Result<f64, Error> {
         let mut total_payoff = 0.0;
 
         for path in underlying_paths {
@@ -134,33 +180,44 @@ impl OptionPricer for MonteCarloOptionPricer {
         }
 
         let average_payoff = total_payoff / self.num_paths as f64;
-        let discounted_price = average_payoff * (-self.risk_free_rate * self.time_to_maturity_years).exp();
+        let rates = RateSource::from_request(self.risk_free_rate, self.discount_curve.as_ref())?;
+        let discount_factor = rates.discount_factor(self.time_to_maturity_years);
+        let discounted_price = average_payoff * discount_factor;
 
         Ok(discounted_price)
     }
 }
 
+impl OptionPricer for MonteCarloOptionPricer {
+    fn price(&self, seed: Option<u64>) -> Result<f64, Error> {
+        self.validate()?;
+        let underlying_paths = self.simulate_underlying_paths(seed)?;
+        self.discounted_mean_payoff(&underlying_paths)
+    }
+}
+
 
 pub fn black_scholes_price(option: &EuropeanOption) -> Result<f64, Error> {
     let s = option.underlying_price;
     let k = option.strike_price;
     let t = option.time_to_maturity_years;
-    let r = option.risk_free_rate;
+    let r = option.rate_to_maturity()?;
     let sigma = option.volatility;
 
     // Input Validation
-    if s <= 0.0 { return Err(anyhow::anyhow!("Underlying price (S) must be positive. Got {}", s)); }
-    if k <= 0.0 { return Err(anyhow::anyhow!("Strike price (K) must be positive. Got {}", k)); }
-    if t <= 0.0 { // If time to maturity is zero or negative, return intrinsic value.
+    if s <= 0.0 { return Err(invalid_parameter("underlying_price", format!("Underlying price (S) must be positive. Got {}", s))); }
+    if k <= 0.0 { return Err(invalid_parameter("strike_price", format!("Strike price (K) must be positive. Got {}", k))); }
+    if t < 0.0 { return Err(invalid_parameter("time_to_maturity_years", format!("Time to maturity (T) must be positive if not zero. Got {}", t))); }
+    if t == 0.0 { // At expiry the option is worth its intrinsic value.
         return Ok(match option.option_type {
             OptionType::Call => (s - k).max(0.0),
             OptionType::Put => (k - s).max(0.0),
         });
     }
     if r < 0.0 && (-r * t).exp().is_infinite() { // Guard against extreme negative rates if not desired
-        return Err(anyhow::anyhow!("Risk-free rate (r) is too negative, leading to instability. Got {}", r));
+        return Err(invalid_parameter("risk_free_rate", format!("Risk-free rate (r) is too negative, leading to instability. Got {}", r)));
     }
-    if sigma <= 0.0 { return Err(anyhow::anyhow!("Volatility (sigma) must be positive. Got {}", sigma)); }
+    if sigma <= 0.0 { return Err(invalid_parameter("volatility", format!("Volatility (sigma) must be positive. Got {}", sigma))); }
 
 
     let d1 = ( (s / k).ln() + (r + 0.5 * sigma.powi(2)) * t ) / (sigma * t.sqrt());
@@ -8,45 +8,192 @@ pub struct TimeSeries {
 
 use chrono::{NaiveDate, Duration}; // NaiveDateTime removed from here
 use rand::SeedableRng; // Rng removed
-use rand_distr::{Normal, Distribution};
+use rand_distr::{Normal, Poisson, StudentT, InverseGaussian, Distribution};
 use rand::rngs::StdRng;
+use rand::Rng;
+use statrs::function::gamma::ln_gamma;
+use crate::config::InnovationDistribution;
+
+// Trading days per year used to turn a step size in days into the year fraction the
+// annualized model parameters expect.
+pub const TRADING_DAYS_PER_YEAR: f64 = 252.0;
 
 pub trait StochasticProcess {
     fn generate_path(&self, initial_value: f64, dt: f64, steps: usize, seed: Option<u64>) -> TimeSeries;
+
+    // Rejects step sizes the process cannot simulate faithfully; checked before generate_path.
+    fn check_time_step(&self, _dt: f64) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+}
+
+// Relative slack when checking that a step is a whole number of model steps (0.1 / (1 / 30) is not
+// exactly 3 in floating point).
+const WHOLE_STEP_ACCURACY: f64 = 1e-9;
+
+// Number of model steps of `model_step_days` in a simulated step of dt days, for processes that
+// advance in discrete model steps (resampled returns, GARCH updates, regime transitions).
+pub(crate) fn model_steps_per_step(dt: f64, model_step_days: f64) -> usize {
+    ((dt / model_step_days).round() as usize).max(1)
 }
 
+// Such processes cannot take part of a model step: a quarter-day step would otherwise apply a full
+// day's return (or variance update, or transition).
+pub(crate) fn check_whole_model_steps(dt: f64, model_step_days: f64, model_step_name: &str) -> Result<(), anyhow::Error> {
+    let ratio = dt / model_step_days;
+    if ratio.round() < 1.0 || (ratio - ratio.round()).abs() > WHOLE_STEP_ACCURACY * ratio.max(1.0) {
+        return Err(crate::error::invalid_parameter(
+            "time_step_days",
+            format!("Time step of {} days must be a whole multiple of the {} of {} days.", dt, model_step_name, model_step_days),
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn rng_from_seed(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(s) => StdRng::seed_from_u64(s),
+        None => StdRng::from_entropy(),
+    }
+}
+
+pub(crate) fn path_start_time() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap()
+}
+
+// Shared driver for processes that only need the previous value, the step size in years and an RNG
+// to produce the next value. Timestamps start at path_start_time() and advance by dt days.
+pub(crate) fn generate_markov_path<F>(initial_value: f64, dt: f64, steps: usize, seed: Option<u64>, mut next_value: F) -> TimeSeries
+where
+    F: FnMut(f64, f64, &mut StdRng) -> f64,
+{
+    let mut rng = rng_from_seed(seed);
+    let dt_for_formula = dt / TRADING_DAYS_PER_YEAR;
+    let dt_duration = Duration::seconds((dt * 24.0 * 60.0 * 60.0) as i64);
+
+    let mut prices = Vec::with_capacity(steps);
+    let mut timestamps = Vec::with_capacity(steps);
+    let mut current_value = initial_value;
+    let mut current_time = path_start_time();
+
+    for _ in 0..steps {
+        prices.push(current_value);
+        timestamps.push(current_time);
+        current_value = next_value(current_value, dt_for_formula, &mut rng);
+        current_time += dt_duration;
+    }
+
+    TimeSeries { timestamps, prices }
+}
+
+// Draws standardised (mean 0, variance 1) shocks from an InnovationDistribution.
+// Construct from a validated distribution (InnovationDistribution::validate); invalid parameters panic.
+#[derive(Debug, Clone)]
+pub enum InnovationSampler {
+    StandardNormal(Normal<f64>),
+    StudentT { dist: StudentT<f64>, scale: f64 },
+    SkewedStudentT { dist: StudentT<f64>, scale: f64, skewness: f64, a: f64, b: f64 },
+    NormalInverseGaussian { mixing: InverseGaussian<f64>, beta: f64, location: f64 },
+}
+
+impl InnovationSampler {
+    pub fn new(distribution: &InnovationDistribution) -> Self {
+        match *distribution {
+            InnovationDistribution::StandardNormal => InnovationSampler::StandardNormal(Normal::new(0.0, 1.0).unwrap()),
+            InnovationDistribution::StudentT { degrees_of_freedom: nu } => InnovationSampler::StudentT {
+                dist: StudentT::new(nu).expect("Student-t degrees of freedom must be positive"),
+                scale: ((nu - 2.0) / nu).sqrt(),
+            },
+            InnovationDistribution::SkewedStudentT { degrees_of_freedom: nu, skewness: lambda } => {
+                // Hansen's constants: a and b standardise the two-piece density to mean 0, variance 1.
+                let c = (ln_gamma((nu + 1.0) / 2.0) - ln_gamma(nu / 2.0)).exp() / (std::f64::consts::PI * (nu - 2.0)).sqrt();
+                let a = 4.0 * lambda * c * (nu - 2.0) / (nu - 1.0);
+                let b = (1.0 + 3.0 * lambda * lambda - a * a).sqrt();
+                InnovationSampler::SkewedStudentT {
+                    dist: StudentT::new(nu).expect("Student-t degrees of freedom must be positive"),
+                    scale: ((nu - 2.0) / nu).sqrt(),
+                    skewness: lambda,
+                    a,
+                    b,
+                }
+            }
+            InnovationDistribution::NormalInverseGaussian { alpha, beta } => {
+                // X = location + beta * V + sqrt(V) * Z with V ~ IG(delta / gamma, delta^2);
+                // delta = gamma^3 / alpha^2 gives unit variance and location removes the mean.
+                let gamma = (alpha * alpha - beta * beta).sqrt();
+                let delta = gamma.powi(3) / (alpha * alpha);
+                InnovationSampler::NormalInverseGaussian {
+                    mixing: InverseGaussian::new(delta / gamma, delta * delta).expect("NIG requires alpha > |beta|"),
+                    beta,
+                    location: -delta * beta / gamma,
+                }
+            }
+        }
+    }
+
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        match self {
+            InnovationSampler::StandardNormal(normal) => normal.sample(rng),
+            InnovationSampler::StudentT { dist, scale } => scale * dist.sample(rng),
+            InnovationSampler::SkewedStudentT { dist, scale, skewness, a, b } => {
+                // Left branch (probability (1 - lambda) / 2) is the unit-variance t scaled by 1 - lambda,
+                // right branch scaled by 1 + lambda.
+                let magnitude = (scale * dist.sample(rng)).abs();
+                let y = if rng.gen::<f64>() < (1.0 - skewness) / 2.0 {
+                    -(1.0 - skewness) * magnitude
+                } else {
+                    (1.0 + skewness) * magnitude
+                };
+                (y - a) / b
+            }
+            InnovationSampler::NormalInverseGaussian { mixing, beta, location } => {
+                let v = mixing.sample(rng);
+                let z: f64 = rng.sample(rand_distr::StandardNormal);
+                location + beta * v + v.sqrt() * z
+            }
+        }
+    }
+}
+
+// With non-normal innovations the -sigma^2/2 correction keeps `drift` as the drift of log prices
+// rather than the exact expected return.
 pub struct GeometricBrownianMotion {
     pub drift: f64,
     pub volatility: f64,
+    pub innovations: InnovationDistribution,
+}
+
+impl GeometricBrownianMotion {
+    // GBM driven by standard normal shocks.
+    pub fn new(drift: f64, volatility: f64) -> Self {
+        GeometricBrownianMotion { drift, volatility, innovations: InnovationDistribution::StandardNormal }
+    }
 }
 
 impl StochasticProcess for GeometricBrownianMotion {
     fn generate_path(&self, initial_value: f64, dt: f64, steps: usize, seed: Option<u64>) -> TimeSeries {
-        let mut rng = match seed {
-            Some(s) => StdRng::seed_from_u64(s),
-            None => StdRng::from_entropy(),
-        };
-        let normal_dist = Normal::new(0.0, 1.0).unwrap();
+        let mut rng = rng_from_seed(seed);
+        let innovations = InnovationSampler::new(&self.innovations);
 
         let mut prices = Vec::with_capacity(steps);
         let mut timestamps = Vec::with_capacity(steps);
 
         let mut current_price = initial_value;
-        let mut current_time = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        let mut current_time = path_start_time();
 
         // Assuming dt is in days. Convert dt to seconds for Duration.
         // dt is also used in the GBM formula, typically as a fraction of a year.
         // If drift and volatility are annualized, dt should be scaled.
         // Let's assume dt for GBM formula is in years: dt_annual = dt / 252.0 (approx trading days)
         // For timestamp increment, dt is in days.
-        let dt_for_formula = dt / 252.0; // Assuming dt is in days, converting to year fraction
+        let dt_for_formula = dt / TRADING_DAYS_PER_YEAR; // Assuming dt is in days, converting to year fraction
         let dt_duration = Duration::seconds((dt * 24.0 * 60.0 * 60.0) as i64); // dt in days to seconds
 
         for _ in 0..steps {
             prices.push(current_price);
             timestamps.push(current_time);
 
-            let w_t = normal_dist.sample(&mut rng);
+            let w_t = innovations.sample(&mut rng);
             current_price *= ((self.drift - 0.5 * self.volatility.powi(2)) * dt_for_formula + self.volatility * dt_for_formula.sqrt() * w_t).exp();
             current_time += dt_duration;
         }
@@ -54,3 +201,115 @@ impl StochasticProcess for GeometricBrownianMotion {
         TimeSeries { timestamps, prices }
     }
 }
+
+// Mean-reverting Gaussian process, simulated with its exact transition density.
+pub struct OrnsteinUhlenbeck {
+    pub mean_reversion_speed: f64,
+    pub long_term_mean: f64,
+    pub volatility: f64,
+}
+
+impl StochasticProcess for OrnsteinUhlenbeck {
+    fn generate_path(&self, initial_value: f64, dt: f64, steps: usize, seed: Option<u64>) -> TimeSeries {
+        let normal_dist = Normal::new(0.0, 1.0).unwrap();
+        generate_markov_path(initial_value, dt, steps, seed, |x, dt_years, rng| {
+            let z = normal_dist.sample(rng);
+            if self.mean_reversion_speed.abs() < 1e-12 {
+                // Degenerates to arithmetic Brownian motion.
+                return x + self.volatility * dt_years.sqrt() * z;
+            }
+            let decay = (-self.mean_reversion_speed * dt_years).exp();
+            let std_dev = self.volatility * ((1.0 - decay * decay) / (2.0 * self.mean_reversion_speed)).sqrt();
+            self.long_term_mean + (x - self.long_term_mean) * decay + std_dev * z
+        })
+    }
+}
+
+// Square-root diffusion, Euler scheme with full truncation so the path never goes negative.
+pub struct CoxIngersollRoss {
+    pub mean_reversion_speed: f64,
+    pub long_term_mean: f64,
+    pub volatility: f64,
+}
+
+impl StochasticProcess for CoxIngersollRoss {
+    fn generate_path(&self, initial_value: f64, dt: f64, steps: usize, seed: Option<u64>) -> TimeSeries {
+        let normal_dist = Normal::new(0.0, 1.0).unwrap();
+        generate_markov_path(initial_value, dt, steps, seed, |x, dt_years, rng| {
+            let x_pos = x.max(0.0);
+            let z = normal_dist.sample(rng);
+            let next = x
+                + self.mean_reversion_speed * (self.long_term_mean - x_pos) * dt_years
+                + self.volatility * (x_pos * dt_years).sqrt() * z;
+            next.max(0.0)
+        })
+    }
+}
+
+// Merton jump-diffusion: GBM plus a compound Poisson process of log-normal jumps.
+pub struct MertonJumpDiffusion {
+    pub drift: f64,
+    pub volatility: f64,
+    pub jump_intensity: f64,
+    pub jump_mean: f64,
+    pub jump_volatility: f64,
+}
+
+impl MertonJumpDiffusion {
+    // Expected relative jump size E[e^J - 1]; subtracting intensity * this from the drift keeps
+    // `drift` equal to the total expected return.
+    pub fn jump_compensator(&self) -> f64 {
+        (self.jump_mean + 0.5 * self.jump_volatility.powi(2)).exp() - 1.0
+    }
+}
+
+impl StochasticProcess for MertonJumpDiffusion {
+    fn generate_path(&self, initial_value: f64, dt: f64, steps: usize, seed: Option<u64>) -> TimeSeries {
+        let normal_dist = Normal::new(0.0, 1.0).unwrap();
+        let poisson = if self.jump_intensity > 0.0 {
+            Some(Poisson::new(self.jump_intensity * dt / TRADING_DAYS_PER_YEAR).unwrap())
+        } else {
+            None
+        };
+        let compensated_drift = self.drift - self.jump_intensity * self.jump_compensator();
+
+        generate_markov_path(initial_value, dt, steps, seed, |s, dt_years, rng| {
+            let w_t = normal_dist.sample(rng);
+            let mut log_return = (compensated_drift - 0.5 * self.volatility.powi(2)) * dt_years
+                + self.volatility * dt_years.sqrt() * w_t;
+            if let Some(poisson) = &poisson {
+                let num_jumps = poisson.sample(rng) as u64;
+                for _ in 0..num_jumps {
+                    log_return += self.jump_mean + self.jump_volatility * normal_dist.sample(rng);
+                }
+            }
+            s * log_return.exp()
+        })
+    }
+}
+
+// Heston stochastic volatility, log-Euler for the price and full truncation Euler for the variance.
+pub struct Heston {
+    pub drift: f64,
+    pub initial_variance: f64,
+    pub mean_reversion_speed: f64,
+    pub long_term_variance: f64,
+    pub vol_of_vol: f64,
+    pub correlation: f64,
+}
+
+impl StochasticProcess for Heston {
+    fn generate_path(&self, initial_value: f64, dt: f64, steps: usize, seed: Option<u64>) -> TimeSeries {
+        let normal_dist = Normal::new(0.0, 1.0).unwrap();
+        let orthogonal_weight = (1.0 - self.correlation.powi(2)).max(0.0).sqrt();
+        let mut variance = self.initial_variance;
+        generate_markov_path(initial_value, dt, steps, seed, |s, dt_years, rng| {
+            let z_price = normal_dist.sample(rng);
+            let z_variance = self.correlation * z_price + orthogonal_weight * normal_dist.sample(rng);
+            let v = variance.max(0.0);
+            variance += self.mean_reversion_speed * (self.long_term_variance - v) * dt_years
+                + self.vol_of_vol * (v * dt_years).sqrt() * z_variance;
+            s * ((self.drift - 0.5 * v) * dt_years + (v * dt_years).sqrt() * z_price).exp()
+        })
+    }
+}
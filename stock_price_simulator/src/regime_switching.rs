@@ -0,0 +1,146 @@
+use crate::config::{InnovationDistribution, RegimeParams, RegimeSwitchingParams};
+use crate::error::invalid_parameter;
+use crate::random_process::{check_whole_model_steps, generate_markov_path, model_steps_per_step, rng_from_seed, InnovationSampler, StochasticProcess, TimeSeries};
+use anyhow::Error;
+use rand::rngs::StdRng;
+use rand::Rng;
+
+const ROW_SUM_ACCURACY: f64 = 1e-6;
+// Every transition step draws a regime, so steps shorter than a minute would multiply the work of a
+// simulated step beyond reason.
+pub const MIN_TRANSITION_TIME_STEP_DAYS: f64 = 1.0 / 1440.0;
+
+// Price path together with the regime index active at each of its points.
+#[derive(Debug, Clone)]
+pub struct RegimePath {
+    pub path: TimeSeries,
+    pub regimes: Vec<usize>,
+}
+
+// Markov regime-switching GBM: within a regime the log price moves with that regime's drift and
+// volatility; between transition steps the regime evolves by the transition matrix. A simulated step
+// of dt days, which must be a whole multiple of transition_time_step_days, is split into
+// dt / transition_time_step_days sub-steps, each followed by one transition.
+#[derive(Debug, Clone)]
+pub struct MarkovRegimeSwitching {
+    pub regimes: Vec<RegimeParams>,
+    pub transition_matrix: Vec<Vec<f64>>,
+    pub initial_regime: usize,
+    pub transition_time_step_days: f64,
+    pub innovations: InnovationDistribution,
+    // When set, the regime chain draws from its own RNG seeded with this value, so processes sharing
+    // a transition matrix and regime_seed switch regimes together.
+    pub regime_seed: Option<u64>,
+}
+
+impl MarkovRegimeSwitching {
+    pub fn from_params(params: &RegimeSwitchingParams) -> Result<Self, Error> {
+        let n = params.regimes.len();
+        if n == 0 {
+            return Err(invalid_parameter("regimes", "Regime-switching model requires at least one regime."));
+        }
+        if let Some((i, regime)) = params.regimes.iter().enumerate().find(|(_, r)| r.volatility < 0.0) {
+            return Err(invalid_parameter(&format!("regimes[{}].volatility", i), format!("Regime volatility cannot be negative. Got {}", regime.volatility)));
+        }
+        if params.transition_matrix.len() != n || params.transition_matrix.iter().any(|row| row.len() != n) {
+            return Err(invalid_parameter("transition_matrix", format!("Transition matrix must be {}x{} to match the number of regimes.", n, n)));
+        }
+        for (i, row) in params.transition_matrix.iter().enumerate() {
+            if row.iter().any(|p| !(0.0..=1.0).contains(p)) {
+                return Err(invalid_parameter(&format!("transition_matrix[{}]", i), format!("Transition probabilities in row {} must lie in [0, 1].", i)));
+            }
+            let row_sum: f64 = row.iter().sum();
+            if (row_sum - 1.0).abs() > ROW_SUM_ACCURACY {
+                return Err(invalid_parameter(&format!("transition_matrix[{}]", i), format!("Transition matrix row {} sums to {}, expected 1.", i, row_sum)));
+            }
+        }
+        if params.initial_regime >= n {
+            return Err(invalid_parameter("initial_regime", format!("Initial regime {} is out of range for {} regimes.", params.initial_regime, n)));
+        }
+        check_transition_time_step(params.transition_time_step_days)?;
+        Ok(MarkovRegimeSwitching {
+            regimes: params.regimes.clone(),
+            transition_matrix: params.transition_matrix.clone(),
+            initial_regime: params.initial_regime,
+            transition_time_step_days: params.transition_time_step_days,
+            innovations: InnovationDistribution::StandardNormal,
+            regime_seed: None,
+        })
+    }
+
+    // Long-run share of time spent in each regime (left eigenvector of the transition matrix),
+    // found by power iteration from the uniform distribution.
+    pub fn stationary_distribution(&self) -> Vec<f64> {
+        let n = self.regimes.len();
+        let mut pi = vec![1.0 / n as f64; n];
+        for _ in 0..10_000 {
+            let next: Vec<f64> = (0..n)
+                .map(|j| (0..n).map(|i| pi[i] * self.transition_matrix[i][j]).sum())
+                .collect();
+            let change: f64 = next.iter().zip(&pi).map(|(a, b)| (a - b).abs()).sum();
+            pi = next;
+            if change < 1e-14 {
+                break;
+            }
+        }
+        pi
+    }
+
+    fn next_regime(&self, current: usize, rng: &mut StdRng) -> usize {
+        let u: f64 = rng.gen();
+        let mut cumulative = 0.0;
+        for (j, p) in self.transition_matrix[current].iter().enumerate() {
+            cumulative += p;
+            if u < cumulative {
+                return j;
+            }
+        }
+        // Rounding left u above the row total; stay on the last reachable regime.
+        self.transition_matrix[current].iter().rposition(|p| *p > 0.0).unwrap_or(current)
+    }
+
+    pub fn generate_path_with_regimes(&self, initial_value: f64, dt: f64, steps: usize, seed: Option<u64>) -> RegimePath {
+        let innovations = InnovationSampler::new(&self.innovations);
+        let sub_steps = model_steps_per_step(dt, self.transition_time_step_days);
+        let mut chain_rng = self.regime_seed.map(|s| rng_from_seed(Some(s)));
+
+        let mut regime = self.initial_regime;
+        let mut regimes = Vec::with_capacity(steps);
+        let path = generate_markov_path(initial_value, dt, steps, seed, |s, dt_years, rng| {
+            regimes.push(regime);
+            let sub_dt = dt_years / sub_steps as f64;
+            let mut log_return = 0.0;
+            for _ in 0..sub_steps {
+                let RegimeParams { drift, volatility, .. } = self.regimes[regime];
+                log_return += (drift - 0.5 * volatility.powi(2)) * sub_dt + volatility * sub_dt.sqrt() * innovations.sample(rng);
+                regime = match chain_rng.as_mut() {
+                    Some(chain_rng) => self.next_regime(regime, chain_rng),
+                    None => self.next_regime(regime, rng),
+                };
+            }
+            s * log_return.exp()
+        });
+        RegimePath { path, regimes }
+    }
+}
+
+impl StochasticProcess for MarkovRegimeSwitching {
+    fn generate_path(&self, initial_value: f64, dt: f64, steps: usize, seed: Option<u64>) -> TimeSeries {
+        self.generate_path_with_regimes(initial_value, dt, steps, seed).path
+    }
+
+    fn check_time_step(&self, dt: f64) -> Result<(), Error> {
+        check_transition_time_step(self.transition_time_step_days)?;
+        check_whole_model_steps(dt, self.transition_time_step_days, "transition time step")
+    }
+}
+
+fn check_transition_time_step(transition_time_step_days: f64) -> Result<(), Error> {
+    if !transition_time_step_days.is_finite() || transition_time_step_days < MIN_TRANSITION_TIME_STEP_DAYS {
+        return Err(invalid_parameter("transition_time_step_days", format!(
+            "Transition time step must be at least one minute ({} days). Got {}",
+            MIN_TRANSITION_TIME_STEP_DAYS, transition_time_step_days,
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use crate::config::GlobalConfig;
+use crate::config_store::fnv1a64;
+use crate::error::{deserialize_request, ErrorCode, SimulationError};
+use crate::random_process::TRADING_DAYS_PER_YEAR;
+use anyhow::Error;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+const MINUTES_PER_DAY: f64 = 1440.0;
+
+// Simulation requests that take their period, step and seed from GlobalConfig when the client
+// leaves them out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RequestKind {
+    Stock,
+    Future,
+    Etf,
+    OptionMonteCarlo,
+}
+
+impl RequestKind {
+    // Part of the seed derivation, so the same body sent to two endpoints gets different seeds.
+    pub fn name(&self) -> &'static str {
+        match self {
+            RequestKind::Stock => "stock",
+            RequestKind::Future => "future",
+            RequestKind::Etf => "etf",
+            RequestKind::OptionMonteCarlo => "option_monte_carlo",
+        }
+    }
+
+    // Field holding the simulated period, and whether it counts steps (stock `days` and ETF
+    // `simulation_days` do) rather than calendar days.
+    fn period_field(&self) -> Option<(&'static str, bool)> {
+        match self {
+            RequestKind::Stock => Some(("days", true)),
+            RequestKind::Future => Some(("time_to_maturity_days", false)),
+            RequestKind::Etf => Some(("simulation_days", true)),
+            RequestKind::OptionMonteCarlo => None,
+        }
+    }
+}
+
+// A stock simulation request (query of GET /simulate/stock) with the defaults filled in.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct StockSimulationRequest {
+    pub asset_identifier: String,
+    pub initial_price: f64,
+    pub days: usize,
+    pub time_step_days: f64,
+    pub seed: Option<u64>,
+    pub drift: Option<f64>,
+    pub volatility: Option<f64>,
+}
+
+pub fn default_time_step_days(config: &GlobalConfig) -> f64 {
+    config.time_step_minutes as f64 / MINUTES_PER_DAY
+}
+
+// JSON with object keys sorted at every level, so the derived seed does not depend on key order.
+fn canonical_json(value: &Value) -> String {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            let fields: Vec<String> = entries.iter()
+                .map(|(key, value)| format!("{}:{}", Value::String((*key).clone()), canonical_json(value)))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        Value::Array(items) => format!("[{}]", items.iter().map(canonical_json).collect::<Vec<_>>().join(",")),
+        other => other.to_string(),
+    }
+}
+
+// Seed for a request that did not give one: FNV-1a (64 bit) of
+// `"<random_seed>:<kind>:<request>"`, where `<request>` is the request after defaults were filled
+// in, without its `seed` field, as JSON with sorted keys. A batch replayed with the same global
+// seed therefore reproduces every path, while different requests in it get unrelated seeds. The
+// result spans the whole u64 range; Monte Carlo paths and ETF constituents seed themselves with it
+// wrapping-added to their index.
+pub fn derive_seed(global_seed: u64, kind: RequestKind, request: &Value) -> u64 {
+    let mut request = request.clone();
+    if let Value::Object(map) = &mut request {
+        map.remove("seed");
+    }
+    fnv1a64(format!("{}:{}:{}", global_seed, kind.name(), canonical_json(&request)).as_bytes())
+}
+
+fn is_missing(map: &Map<String, Value>, field: &str) -> bool {
+    map.get(field).is_none_or(Value::is_null)
+}
+
+// Fills the fields a request body left out (or set to null) from GlobalConfig: time_step_days from
+// time_step_minutes, the period from simulation_period_days (as a number of steps of that size for
+// stock `days` and ETF `simulation_days`), num_steps_per_path of Monte Carlo options from the
+// maturity and the step (in the days of TRADING_DAYS_PER_YEAR the pricer simulates), and the seed
+// from random_seed (see derive_seed). Fields the request sets are kept. Without a global
+// random_seed, an unseeded request stays unseeded.
+pub fn apply_request_defaults(config: &GlobalConfig, kind: RequestKind, request: &mut Value) -> Result<(), Error> {
+    let map = request.as_object_mut()
+        .ok_or_else(|| SimulationError::new(ErrorCode::InvalidRequest, None, "The request body must be a JSON object."))?;
+    let time_step_days = match map.get("time_step_days").and_then(Value::as_f64) {
+        Some(step) => step,
+        None => default_time_step_days(config),
+    };
+    if kind != RequestKind::OptionMonteCarlo && is_missing(map, "time_step_days") {
+        map.insert("time_step_days".to_string(), Value::from(time_step_days));
+    }
+    if let Some((field, counts_steps)) = kind.period_field() {
+        if is_missing(map, field) {
+            let period_days = config.simulation_period_days as f64;
+            // Rounding error must not add a step when the period is a whole number of steps.
+            let value = if counts_steps { (period_days / time_step_days - 1e-9).ceil() } else { period_days };
+            if value.is_finite() && value >= 0.0 {
+                map.insert(field.to_string(), Value::from(value as u64));
+            }
+        }
+    }
+    if kind == RequestKind::OptionMonteCarlo && is_missing(map, "num_steps_per_path") {
+        if let Some(maturity_years) = map.get("time_to_maturity_years").and_then(Value::as_f64) {
+            let steps = (maturity_years * TRADING_DAYS_PER_YEAR / time_step_days).round().max(1.0);
+            if steps.is_finite() {
+                map.insert("num_steps_per_path".to_string(), Value::from(steps as u64));
+            }
+        }
+    }
+    if is_missing(map, "seed") {
+        if let Some(global_seed) = config.random_seed {
+            let seed = derive_seed(global_seed, kind, request);
+            request["seed"] = Value::from(seed);
+        }
+    }
+    Ok(())
+}
+
+// Applies the defaults to a request and reads it into its typed form.
+pub fn with_defaults<T: DeserializeOwned>(config: &GlobalConfig, kind: RequestKind, mut request: Value) -> Result<T, Error> {
+    apply_request_defaults(config, kind, &mut request)?;
+    deserialize_request(request, &format!("Invalid {} request", kind.name()))
+}
@@ -1,4 +1,6 @@
 use crate::random_process::{GeometricBrownianMotion, StochasticProcess, TimeSeries};
+use crate::regime_switching::{MarkovRegimeSwitching, RegimePath};
+use crate::error::invalid_parameter;
 use anyhow::Error;
 
 pub struct StockSimulator;
@@ -12,25 +14,56 @@ impl StockSimulator {
         time_step_days: f64,
         seed: Option<u64>,
     ) -> Result<TimeSeries, Error> {
-        if initial_price <= 0.0 {
-            return Err(anyhow::anyhow!("Initial price must be positive."));
-        }
         if volatility < 0.0 {
-            return Err(anyhow::anyhow!("Volatility cannot be negative."));
-        }
-        if time_step_days <= 0.0 {
-            return Err(anyhow::anyhow!("Time step must be positive."));
-        }
-        if days == 0 {
-            return Err(anyhow::anyhow!("Number of days (steps) must be positive."));
+            return Err(invalid_parameter("volatility", "Volatility cannot be negative."));
         }
 
-        let gbm = GeometricBrownianMotion { drift, volatility };
+        let gbm = GeometricBrownianMotion::new(drift, volatility);
+
+        Self::simulate_with_process(&gbm, initial_price, days, time_step_days, seed)
+    }
+
+    // Runs any StochasticProcess with the same input validation as the GBM entry point.
+    pub fn simulate_with_process(
+        process: &dyn StochasticProcess,
+        initial_price: f64,
+        days: usize, // Interpreted as number of steps
+        time_step_days: f64,
+        seed: Option<u64>,
+    ) -> Result<TimeSeries, Error> {
+        Self::validate_inputs(initial_price, days, time_step_days)?;
+        process.check_time_step(time_step_days)?;
 
         // 'days' is used as the number of steps directly.
         // 'time_step_days' is used as 'dt' for generate_path.
-        let path = gbm.generate_path(initial_price, time_step_days, days, seed);
+        let path = process.generate_path(initial_price, time_step_days, days, seed);
 
         Ok(path)
     }
+
+    // Regime-switching counterpart of simulate_with_process that also returns the regime sequence.
+    pub fn simulate_with_regimes(
+        process: &MarkovRegimeSwitching,
+        initial_price: f64,
+        days: usize, // Interpreted as number of steps
+        time_step_days: f64,
+        seed: Option<u64>,
+    ) -> Result<RegimePath, Error> {
+        Self::validate_inputs(initial_price, days, time_step_days)?;
+        process.check_time_step(time_step_days)?;
+        Ok(process.generate_path_with_regimes(initial_price, time_step_days, days, seed))
+    }
+
+    fn validate_inputs(initial_price: f64, days: usize, time_step_days: f64) -> Result<(), Error> {
+        if initial_price <= 0.0 {
+            return Err(invalid_parameter("initial_price", "Initial price must be positive."));
+        }
+        if time_step_days <= 0.0 {
+            return Err(invalid_parameter("time_step_days", "Time step must be positive."));
+        }
+        if days == 0 {
+            return Err(invalid_parameter("days", "Number of days (steps) must be positive."));
+        }
+        Ok(())
+    }
 }
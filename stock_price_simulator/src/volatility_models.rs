@@ -0,0 +1,259 @@
+use crate::config::{EgarchParams, GarchParams, InnovationDistribution};
+use crate::optimization::{nelder_mead, NelderMeadOptions};
+use crate::random_process::{check_whole_model_steps, generate_markov_path, model_steps_per_step, InnovationSampler, StochasticProcess, TimeSeries};
+use crate::error::{invalid_config, invalid_parameter, ErrorCode, SimulationError};
+use anyhow::Error;
+use rand_distr::{Distribution, Normal};
+
+// E|z| for a standard normal innovation, the centring term of the EGARCH size effect.
+const EXPECTED_ABS_NORMAL: f64 = 0.797_884_560_802_865_4; // sqrt(2 / pi)
+const MIN_FIT_OBSERVATIONS: usize = 30;
+
+// Conditional-heteroskedastic log-return generators. One model step corresponds to one observation of
+// history_time_step_days; a simulated step of dt days, which must be a whole multiple of it, aggregates
+// dt / history_time_step_days model steps, as the historical bootstrap does.
+
+#[derive(Debug, Clone)]
+pub struct Garch11 {
+    pub mean_return: f64,
+    pub omega: f64,
+    pub alpha: f64,
+    pub beta: f64,
+    pub initial_variance: f64,
+    pub history_time_step_days: f64,
+    pub innovations: InnovationDistribution, // e.g. Student-t for GARCH-t
+}
+
+#[derive(Debug, Clone)]
+pub struct Egarch11 {
+    pub mean_return: f64,
+    pub omega: f64,
+    pub alpha: f64,
+    pub gamma: f64,
+    pub beta: f64,
+    pub initial_variance: f64,
+    pub history_time_step_days: f64,
+}
+
+impl Garch11 {
+    pub fn from_params(params: &GarchParams) -> Result<Self, Error> {
+        if params.omega <= 0.0 || params.alpha < 0.0 || params.beta < 0.0 {
+            let field = if params.omega <= 0.0 { "omega" } else if params.alpha < 0.0 { "alpha" } else { "beta" };
+            return Err(invalid_config(Some(field), "GARCH parameters require omega > 0, alpha >= 0 and beta >= 0."));
+        }
+        let persistence = params.alpha + params.beta;
+        if persistence >= 1.0 {
+            return Err(invalid_config(Some("beta"), format!("GARCH process is not stationary: alpha + beta = {} must be below 1.", persistence)));
+        }
+        if params.history_time_step_days <= 0.0 {
+            return Err(invalid_config(Some("history_time_step_days"), "History time step must be positive."));
+        }
+        let initial_variance = params.initial_variance.unwrap_or(params.omega / (1.0 - persistence));
+        if initial_variance <= 0.0 {
+            return Err(invalid_config(Some("initial_variance"), format!("Initial variance must be positive. Got {}", initial_variance)));
+        }
+        Ok(Garch11 {
+            mean_return: params.mean_return,
+            omega: params.omega,
+            alpha: params.alpha,
+            beta: params.beta,
+            initial_variance,
+            history_time_step_days: params.history_time_step_days,
+            innovations: InnovationDistribution::StandardNormal,
+        })
+    }
+
+    pub fn unconditional_variance(&self) -> f64 {
+        self.omega / (1.0 - self.alpha - self.beta)
+    }
+}
+
+impl StochasticProcess for Garch11 {
+    fn generate_path(&self, initial_value: f64, dt: f64, steps: usize, seed: Option<u64>) -> TimeSeries {
+        let innovations = InnovationSampler::new(&self.innovations);
+        let model_steps = model_steps_per_step(dt, self.history_time_step_days);
+        let mut variance = self.initial_variance;
+        generate_markov_path(initial_value, dt, steps, seed, |s, _, rng| {
+            let mut log_return = 0.0;
+            for _ in 0..model_steps {
+                let shock = variance.sqrt() * innovations.sample(rng);
+                log_return += self.mean_return + shock;
+                variance = self.omega + self.alpha * shock * shock + self.beta * variance;
+            }
+            s * log_return.exp()
+        })
+    }
+
+    fn check_time_step(&self, dt: f64) -> Result<(), Error> {
+        check_whole_model_steps(dt, self.history_time_step_days, "history time step")
+    }
+}
+
+impl Egarch11 {
+    pub fn from_params(params: &EgarchParams) -> Result<Self, Error> {
+        if params.beta.abs() >= 1.0 {
+            return Err(invalid_config(Some("beta"), format!("EGARCH process is not stationary: |beta| = {} must be below 1.", params.beta.abs())));
+        }
+        if params.history_time_step_days <= 0.0 {
+            return Err(invalid_config(Some("history_time_step_days"), "History time step must be positive."));
+        }
+        let initial_variance = params.initial_variance.unwrap_or((params.omega / (1.0 - params.beta)).exp());
+        if initial_variance <= 0.0 {
+            return Err(invalid_config(Some("initial_variance"), format!("Initial variance must be positive. Got {}", initial_variance)));
+        }
+        Ok(Egarch11 {
+            mean_return: params.mean_return,
+            omega: params.omega,
+            alpha: params.alpha,
+            gamma: params.gamma,
+            beta: params.beta,
+            initial_variance,
+            history_time_step_days: params.history_time_step_days,
+        })
+    }
+
+    fn next_log_variance(&self, log_variance: f64, z: f64) -> f64 {
+        self.omega + self.alpha * (z.abs() - EXPECTED_ABS_NORMAL) + self.gamma * z + self.beta * log_variance
+    }
+}
+
+impl StochasticProcess for Egarch11 {
+    fn generate_path(&self, initial_value: f64, dt: f64, steps: usize, seed: Option<u64>) -> TimeSeries {
+        let normal_dist = Normal::new(0.0, 1.0).unwrap();
+        let model_steps = model_steps_per_step(dt, self.history_time_step_days);
+        let mut log_variance = self.initial_variance.ln();
+        generate_markov_path(initial_value, dt, steps, seed, |s, _, rng| {
+            let mut log_return = 0.0;
+            for _ in 0..model_steps {
+                let z = normal_dist.sample(rng);
+                log_return += self.mean_return + (0.5 * log_variance).exp() * z;
+                log_variance = self.next_log_variance(log_variance, z);
+            }
+            s * log_return.exp()
+        })
+    }
+
+    fn check_time_step(&self, dt: f64) -> Result<(), Error> {
+        check_whole_model_steps(dt, self.history_time_step_days, "history time step")
+    }
+}
+
+// --- Maximum likelihood fitting ---
+
+fn check_fit_input(returns: &[f64]) -> Result<(f64, f64), Error> {
+    if returns.len() < MIN_FIT_OBSERVATIONS {
+        return Err(invalid_parameter("returns", format!(
+            "At least {} returns are required to fit a volatility model, got {}.",
+            MIN_FIT_OBSERVATIONS,
+            returns.len()
+        )));
+    }
+    if returns.iter().any(|r| !r.is_finite()) {
+        return Err(invalid_parameter("returns", "Returns must be finite numbers."));
+    }
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / returns.len() as f64;
+    if variance <= 0.0 || returns.iter().all(|r| *r == returns[0]) {
+        return Err(invalid_parameter("returns", "Returns have zero variance; volatility model is not identifiable."));
+    }
+    Ok((mean, variance))
+}
+
+fn gaussian_negative_log_likelihood(residuals: &[f64], variances: &[f64]) -> f64 {
+    0.5 * residuals.iter().zip(variances).map(|(e, v)| {
+        (2.0 * std::f64::consts::PI).ln() + v.ln() + e * e / v
+    }).sum::<f64>()
+}
+
+fn fit_options(dimension: usize, scale: f64) -> NelderMeadOptions {
+    let mut initial_step = vec![0.5; dimension];
+    initial_step[0] = scale * 0.1;
+    NelderMeadOptions { max_iterations: 5000, tolerance: 1e-10, initial_step }
+}
+
+// Gaussian (quasi-)maximum-likelihood GARCH(1,1) fit on a series of per-observation log returns.
+// alpha and beta are optimised through a softmax-style transform that keeps alpha, beta > 0 and
+// alpha + beta < 1.
+pub fn fit_garch(returns: &[f64], history_time_step_days: f64) -> Result<GarchParams, Error> {
+    let (sample_mean, sample_variance) = check_fit_input(returns)?;
+
+    let unpack = |p: &[f64]| {
+        let (ea, eb) = (p[2].exp(), p[3].exp());
+        let denom = 1.0 + ea + eb;
+        (p[0], p[1].exp(), ea / denom, eb / denom)
+    };
+    // Start from alpha = 1/20 = 0.05, beta = 18/20 = 0.90 with the sample variance as the unconditional level.
+    let initial = [sample_mean, (sample_variance * 0.05).ln(), 0.0, 18f64.ln()];
+
+    let result = nelder_mead(
+        |p| {
+            let (mu, omega, alpha, beta) = unpack(p);
+            let residuals: Vec<f64> = returns.iter().map(|r| r - mu).collect();
+            let (variances, _) = garch_conditional_variances(&residuals, omega, alpha, beta);
+            gaussian_negative_log_likelihood(&residuals, &variances)
+        },
+        &initial,
+        &fit_options(initial.len(), sample_variance.sqrt()),
+    );
+    if !result.value.is_finite() {
+        return Err(SimulationError::new(ErrorCode::SimulationFailed, None, "GARCH likelihood optimisation failed.").into());
+    }
+
+    let (mean_return, omega, alpha, beta) = unpack(&result.point);
+    Ok(GarchParams { mean_return, omega, alpha, beta, initial_variance: None, history_time_step_days })
+}
+
+// Runs the GARCH(1,1) variance recursion over `residuals` (returns minus their mean), starting from
+// their sample variance. Returns the conditional variance of each observation and the one-step-ahead
+// forecast after the last one.
+pub fn garch_conditional_variances(residuals: &[f64], omega: f64, alpha: f64, beta: f64) -> (Vec<f64>, f64) {
+    let sample_variance = residuals.iter().map(|e| e * e).sum::<f64>() / residuals.len().max(1) as f64;
+    let mut variance = sample_variance.max(f64::MIN_POSITIVE);
+    let mut variances = Vec::with_capacity(residuals.len());
+    for e in residuals {
+        variances.push(variance);
+        variance = omega + alpha * e * e + beta * variance;
+    }
+    (variances, variance)
+}
+
+fn egarch_log_variances(residuals: &[f64], omega: f64, alpha: f64, gamma: f64, beta: f64, initial_log_variance: f64) -> Vec<f64> {
+    let mut log_variance = initial_log_variance;
+    residuals.iter().map(|e| {
+        let current = log_variance;
+        let z = e / (0.5 * current).exp();
+        log_variance = omega + alpha * (z.abs() - EXPECTED_ABS_NORMAL) + gamma * z + beta * current;
+        current
+    }).collect()
+}
+
+// Gaussian maximum-likelihood EGARCH(1,1) fit. beta is optimised as tanh(x) to stay inside (-1, 1).
+pub fn fit_egarch(returns: &[f64], history_time_step_days: f64) -> Result<EgarchParams, Error> {
+    let (sample_mean, sample_variance) = check_fit_input(returns)?;
+    let initial_log_variance = sample_variance.ln();
+
+    let unpack = |p: &[f64]| (p[0], p[1], p[2], p[3], p[4].tanh());
+    let initial_beta: f64 = 0.95;
+    let initial = [sample_mean, (1.0 - initial_beta) * initial_log_variance, 0.1, -0.05, initial_beta.atanh()];
+
+    let result = nelder_mead(
+        |p| {
+            let (mu, omega, alpha, gamma, beta) = unpack(p);
+            let residuals: Vec<f64> = returns.iter().map(|r| r - mu).collect();
+            let log_variances = egarch_log_variances(&residuals, omega, alpha, gamma, beta, initial_log_variance);
+            if log_variances.iter().any(|lv| !lv.is_finite() || lv.abs() > 700.0) {
+                return f64::INFINITY;
+            }
+            let variances: Vec<f64> = log_variances.iter().map(|lv| lv.exp()).collect();
+            gaussian_negative_log_likelihood(&residuals, &variances)
+        },
+        &initial,
+        &fit_options(initial.len(), sample_variance.sqrt()),
+    );
+    if !result.value.is_finite() {
+        return Err(SimulationError::new(ErrorCode::SimulationFailed, None, "EGARCH likelihood optimisation failed.").into());
+    }
+
+    let (mean_return, omega, alpha, gamma, beta) = unpack(&result.point);
+    Ok(EgarchParams { mean_return, omega, alpha, gamma, beta, initial_variance: None, history_time_step_days })
+}
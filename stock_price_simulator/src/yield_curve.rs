@@ -0,0 +1,417 @@
+use crate::error::{invalid_parameter, within, ErrorCode, SimulationError};
+use anyhow::Error;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// Swap bootstrapping solves for the new pillar's zero rate by bisection within these bounds.
+const MIN_ZERO_RATE: f64 = -1.0;
+const MAX_ZERO_RATE: f64 = 1.0;
+const BISECTION_ITERATIONS: usize = 200;
+// Monotone cubic pillars move when later pillars are added, so its bootstrap is repeated until stable.
+const MAX_BOOTSTRAP_SWEEPS: usize = 50;
+const BOOTSTRAP_TOLERANCE: f64 = 1e-12;
+// Step for the numerical derivative behind instantaneous forwards, in years.
+const FORWARD_BUMP_YEARS: f64 = 1e-5;
+// Bootstrapping reprices every swap's fixed leg many times over, so a curve may have at most this
+// many quotes, maturities and payments per year.
+pub const MAX_CURVE_QUOTES: usize = 60;
+pub const MAX_CURVE_MATURITY_YEARS: f64 = 100.0;
+pub const MAX_SWAP_PAYMENTS_PER_YEAR: u32 = 12;
+
+// Market quote a discount curve is built from. Maturities are in years from today.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(tag = "instrument", deny_unknown_fields)]
+pub enum CurveQuote {
+    // Continuously compounded zero rate.
+    Zero { maturity_years: f64, rate: f64 },
+    // Simple (money-market) rate: DF(T) = 1 / (1 + rate * T).
+    Deposit { maturity_years: f64, rate: f64 },
+    // Par swap rate with a fixed leg paying rate / payments_per_year every 1 / payments_per_year years.
+    // The maturity must be a whole number of payment periods.
+    Swap {
+        maturity_years: f64,
+        rate: f64,
+        #[serde(default = "default_swap_payments_per_year")]
+        payments_per_year: u32,
+    },
+}
+
+fn default_swap_payments_per_year() -> u32 {
+    1
+}
+
+// How the curve is filled in between pillars.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default, JsonSchema)]
+pub enum CurveInterpolation {
+    // Zero rates linear in time, flat beyond the first and last pillars.
+    LinearZero,
+    // ln DF linear in time, i.e. piecewise-constant forward rates; the last forward is kept beyond the curve.
+    #[default]
+    LogLinearDiscount,
+    // Monotone (Fritsch-Carlson) cubic Hermite spline on zero rates, flat beyond the pillars.
+    MonotoneCubic,
+}
+
+// In JSON: {"quotes": [{"instrument": "Deposit", "maturity_years": 0.5, "rate": 0.03}, ...],
+// "interpolation": "MonotoneCubic"}. Quotes may be given in any order but not two at one maturity.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DiscountCurveParams {
+    pub quotes: Vec<CurveQuote>,
+    #[serde(default)]
+    pub interpolation: CurveInterpolation,
+}
+
+// Discount factors, zero rates and forward rates for any maturity (in years from today), interpolated
+// between pillar zero rates. All rates are continuously compounded. Deserializes from
+// DiscountCurveParams, bootstrapping the quotes, and serializes as its pillars' zero rates.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "DiscountCurveParams", into = "DiscountCurveParams")]
+pub struct DiscountCurve {
+    pub times: Vec<f64>,
+    pub zero_rates: Vec<f64>,
+    pub interpolation: CurveInterpolation,
+    tangents: Vec<f64>, // dz/dt at each pillar, used by MonotoneCubic only
+}
+
+impl DiscountCurve {
+    pub fn new(times: Vec<f64>, zero_rates: Vec<f64>, interpolation: CurveInterpolation) -> Result<Self, Error> {
+        if times.is_empty() || times.len() != zero_rates.len() {
+            return Err(invalid_parameter("zero_rates", "A discount curve needs at least one pillar and one zero rate per pillar."));
+        }
+        if times.iter().any(|t| !t.is_finite() || *t <= 0.0) || times.windows(2).any(|w| w[1] <= w[0]) {
+            return Err(invalid_parameter("times", "Curve pillar times must be positive and strictly increasing."));
+        }
+        if zero_rates.iter().any(|z| !z.is_finite()) {
+            return Err(invalid_parameter("zero_rates", "Curve zero rates must be finite."));
+        }
+        let tangents = monotone_tangents(&times, &zero_rates);
+        Ok(DiscountCurve { times, zero_rates, interpolation, tangents })
+    }
+
+    // The same rate for every maturity.
+    pub fn flat(rate: f64) -> Result<Self, Error> {
+        Self::new(vec![1.0], vec![rate], CurveInterpolation::LinearZero)
+    }
+
+    // Bootstraps the curve from deposit, swap and zero quotes, shortest maturity first. Each swap is
+    // repriced to par off the curve built so far, including the interpolated discount factors of
+    // coupon dates that fall between pillars.
+    pub fn from_params(params: &DiscountCurveParams) -> Result<Self, Error> {
+        if params.quotes.is_empty() {
+            return Err(invalid_parameter("quotes", "A discount curve needs at least one quote."));
+        }
+        if params.quotes.len() > MAX_CURVE_QUOTES {
+            return Err(invalid_parameter("quotes", format!("A discount curve may have at most {} quotes. Got {}", MAX_CURVE_QUOTES, params.quotes.len())));
+        }
+        for (i, quote) in params.quotes.iter().enumerate() {
+            check_quote(quote).map_err(|e| within(&format!("quotes[{}]", i), e))?;
+        }
+        // Sorted by maturity, each with its position in the request for error paths.
+        let mut quotes: Vec<(usize, &CurveQuote)> = params.quotes.iter().enumerate().collect();
+        quotes.sort_by(|a, b| quote_maturity_and_rate(a.1).0.total_cmp(&quote_maturity_and_rate(b.1).0));
+        let times: Vec<f64> = quotes.iter().map(|(_, q)| quote_maturity_and_rate(q).0).collect();
+        if let Some(k) = (1..times.len()).find(|&k| times[k] - times[k - 1] < 1e-9) {
+            return Err(invalid_parameter(
+                &format!("quotes[{}].maturity_years", quotes[k].0),
+                format!("More than one curve quote at maturity {}.", times[k]),
+            ));
+        }
+
+        let mut zero_rates: Vec<f64> = Vec::with_capacity(quotes.len());
+        for (k, &(i, quote)) in quotes.iter().enumerate() {
+            let z = match quote {
+                CurveQuote::Swap { .. } => solve_swap_pillar(&times[..=k], &zero_rates, params.interpolation, quote)
+                    .map_err(|e| within(&format!("quotes[{}]", i), e))?,
+                _ => direct_zero_rate(quote),
+            };
+            zero_rates.push(z);
+        }
+
+        if params.interpolation == CurveInterpolation::MonotoneCubic && quotes.iter().any(|(_, q)| matches!(q, CurveQuote::Swap { .. })) {
+            for _ in 0..MAX_BOOTSTRAP_SWEEPS {
+                let mut largest_change: f64 = 0.0;
+                for (k, &(i, quote)) in quotes.iter().enumerate() {
+                    if matches!(quote, CurveQuote::Swap { .. }) {
+                        let z = solve_swap_pillar_in(&times, &zero_rates, k, params.interpolation, quote)
+                            .map_err(|e| within(&format!("quotes[{}]", i), e))?;
+                        largest_change = largest_change.max((z - zero_rates[k]).abs());
+                        zero_rates[k] = z;
+                    }
+                }
+                if largest_change < BOOTSTRAP_TOLERANCE {
+                    break;
+                }
+            }
+        }
+
+        Self::new(times, zero_rates, params.interpolation)
+    }
+
+    // Continuously compounded zero rate to maturity `t` (years).
+    pub fn zero_rate(&self, t: f64) -> f64 {
+        let last = self.times.len() - 1;
+        if t <= self.times[0] {
+            // Every interpolation keeps the first zero rate at the short end.
+            return self.zero_rates[0];
+        }
+        if t >= self.times[last] {
+            return match self.interpolation {
+                CurveInterpolation::LogLinearDiscount => {
+                    let forward = if last == 0 {
+                        self.zero_rates[0]
+                    } else {
+                        segment_forward(&self.times, &self.zero_rates, last - 1)
+                    };
+                    (self.zero_rates[last] * self.times[last] + forward * (t - self.times[last])) / t
+                }
+                _ => self.zero_rates[last],
+            };
+        }
+
+        let hi = self.times.partition_point(|p| *p <= t);
+        let lo = hi - 1;
+        let (t0, t1, z0, z1) = (self.times[lo], self.times[hi], self.zero_rates[lo], self.zero_rates[hi]);
+        let w = (t - t0) / (t1 - t0);
+        match self.interpolation {
+            CurveInterpolation::LinearZero => z0 + w * (z1 - z0),
+            CurveInterpolation::LogLinearDiscount => (z0 * t0 + w * (z1 * t1 - z0 * t0)) / t,
+            CurveInterpolation::MonotoneCubic => {
+                let h = t1 - t0;
+                let (w2, w3) = (w * w, w * w * w);
+                (2.0 * w3 - 3.0 * w2 + 1.0) * z0
+                    + (w3 - 2.0 * w2 + w) * h * self.tangents[lo]
+                    + (-2.0 * w3 + 3.0 * w2) * z1
+                    + (w3 - w2) * h * self.tangents[hi]
+            }
+        }
+    }
+
+    pub fn discount_factor(&self, t: f64) -> f64 {
+        if t <= 0.0 {
+            return 1.0;
+        }
+        (-self.zero_rate(t) * t).exp()
+    }
+
+    // Continuously compounded forward rate between t1 and t2 (years), ln(DF(t1) / DF(t2)) / (t2 - t1).
+    // Falls back to the instantaneous forward at t1 when the two times coincide.
+    pub fn forward_rate(&self, t1: f64, t2: f64) -> f64 {
+        if (t2 - t1).abs() < FORWARD_BUMP_YEARS {
+            return self.instantaneous_forward(t1);
+        }
+        (self.discount_factor(t1) / self.discount_factor(t2)).ln() / (t2 - t1)
+    }
+
+    // Short rate implied by the curve at time t (years), f(t) = -d ln DF(t) / dt.
+    pub fn instantaneous_forward(&self, t: f64) -> f64 {
+        let lo = (t - FORWARD_BUMP_YEARS).max(0.0);
+        let hi = lo + 2.0 * FORWARD_BUMP_YEARS;
+        (self.discount_factor(lo) / self.discount_factor(hi)).ln() / (hi - lo)
+    }
+}
+
+// Rates a request is priced or carried with: a flat continuously compounded rate, or a curve.
+#[derive(Debug, Clone, Copy)]
+pub enum RateSource<'a> {
+    Flat(f64),
+    Curve(&'a DiscountCurve),
+}
+
+impl<'a> RateSource<'a> {
+    // Requests carry an optional risk_free_rate and an optional discount_curve; exactly one of them
+    // must be given, so a curve never silently overrides a rate sent alongside it.
+    pub fn from_request(risk_free_rate: Option<f64>, discount_curve: Option<&'a DiscountCurve>) -> Result<Self, Error> {
+        Self::from_fields("risk_free_rate", risk_free_rate, "discount_curve", discount_curve)
+    }
+
+    // from_request for a request whose rate and curve fields have other names.
+    pub fn from_fields(rate_field: &str, rate: Option<f64>, curve_field: &str, curve: Option<&'a DiscountCurve>) -> Result<Self, Error> {
+        match (rate, curve) {
+            (Some(rate), None) => Ok(RateSource::Flat(rate)),
+            (None, Some(curve)) => Ok(RateSource::Curve(curve)),
+            (Some(_), Some(_)) => Err(invalid_parameter(curve_field, format!("Give either {} or {}, not both.", rate_field, curve_field))),
+            (None, None) => Err(SimulationError::new(
+                ErrorCode::MissingParameter,
+                Some(rate_field),
+                format!("Either {} or {} is required.", rate_field, curve_field),
+            ).into()),
+        }
+    }
+
+    // Continuously compounded rate from today to t years.
+    pub fn zero_rate(&self, t: f64) -> f64 {
+        match self {
+            RateSource::Flat(rate) => *rate,
+            RateSource::Curve(curve) => curve.zero_rate(t),
+        }
+    }
+
+    // Continuously compounded forward rate between t1 and t2 years.
+    pub fn forward_rate(&self, t1: f64, t2: f64) -> f64 {
+        match self {
+            RateSource::Flat(rate) => *rate,
+            RateSource::Curve(curve) => curve.forward_rate(t1, t2),
+        }
+    }
+
+    pub fn discount_factor(&self, t: f64) -> f64 {
+        match self {
+            RateSource::Flat(rate) => (-rate * t).exp(),
+            RateSource::Curve(curve) => curve.discount_factor(t),
+        }
+    }
+}
+
+impl TryFrom<DiscountCurveParams> for DiscountCurve {
+    type Error = Error;
+
+    fn try_from(params: DiscountCurveParams) -> Result<Self, Error> {
+        Self::from_params(&params)
+    }
+}
+
+// On the wire a curve is its quotes.
+impl schemars::JsonSchema for DiscountCurve {
+    fn schema_name() -> String {
+        DiscountCurveParams::schema_name()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        DiscountCurveParams::json_schema(gen)
+    }
+}
+
+// Zero quotes at the pillars bootstrap back to the same curve.
+impl From<DiscountCurve> for DiscountCurveParams {
+    fn from(curve: DiscountCurve) -> Self {
+        let quotes = curve.times.iter().zip(&curve.zero_rates)
+            .map(|(&maturity_years, &rate)| CurveQuote::Zero { maturity_years, rate })
+            .collect();
+        DiscountCurveParams { quotes, interpolation: curve.interpolation }
+    }
+}
+
+// Field errors are relative to the quote.
+fn check_quote(quote: &CurveQuote) -> Result<(), Error> {
+    let (maturity, rate) = quote_maturity_and_rate(quote);
+    if !maturity.is_finite() || maturity <= 0.0 || maturity > MAX_CURVE_MATURITY_YEARS {
+        return Err(invalid_parameter("maturity_years", format!(
+            "Curve quote maturities must be positive and at most {} years. Got {}", MAX_CURVE_MATURITY_YEARS, maturity,
+        )));
+    }
+    if !rate.is_finite() {
+        return Err(invalid_parameter("rate", format!("Curve quote rates must be finite. Got {}", rate)));
+    }
+    if let CurveQuote::Swap { payments_per_year, .. } = *quote {
+        if payments_per_year == 0 || payments_per_year > MAX_SWAP_PAYMENTS_PER_YEAR {
+            return Err(invalid_parameter("payments_per_year", format!(
+                "Swap payments per year must be between 1 and {}. Got {}", MAX_SWAP_PAYMENTS_PER_YEAR, payments_per_year,
+            )));
+        }
+        let periods = (maturity * payments_per_year as f64).round();
+        if periods < 1.0 || (periods / payments_per_year as f64 - maturity).abs() > 1e-6 {
+            return Err(invalid_parameter("maturity_years", format!(
+                "Swap maturity {} is not a whole number of {} payment periods per year.", maturity, payments_per_year,
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn quote_maturity_and_rate(quote: &CurveQuote) -> (f64, f64) {
+    match *quote {
+        CurveQuote::Zero { maturity_years, rate }
+        | CurveQuote::Deposit { maturity_years, rate }
+        | CurveQuote::Swap { maturity_years, rate, .. } => (maturity_years, rate),
+    }
+}
+
+fn direct_zero_rate(quote: &CurveQuote) -> f64 {
+    match *quote {
+        CurveQuote::Zero { rate, .. } => rate,
+        CurveQuote::Deposit { maturity_years, rate } => (1.0 + rate * maturity_years).ln() / maturity_years,
+        CurveQuote::Swap { .. } => unreachable!("swap pillars are solved for"),
+    }
+}
+
+// Forward rate over pillar segment [times[k], times[k + 1]], from the zero rates at both ends.
+fn segment_forward(times: &[f64], zero_rates: &[f64], k: usize) -> f64 {
+    (zero_rates[k + 1] * times[k + 1] - zero_rates[k] * times[k]) / (times[k + 1] - times[k])
+}
+
+// Fritsch-Carlson tangents: a cubic Hermite spline through the pillars with these slopes does not
+// overshoot, so it stays monotone wherever the pillar zero rates are.
+fn monotone_tangents(times: &[f64], zero_rates: &[f64]) -> Vec<f64> {
+    let n = times.len();
+    if n < 2 {
+        return vec![0.0; n];
+    }
+    let deltas: Vec<f64> = (0..n - 1).map(|k| (zero_rates[k + 1] - zero_rates[k]) / (times[k + 1] - times[k])).collect();
+    let mut tangents = vec![0.0; n];
+    tangents[0] = deltas[0];
+    tangents[n - 1] = deltas[n - 2];
+    for k in 1..n - 1 {
+        tangents[k] = if deltas[k - 1] * deltas[k] <= 0.0 { 0.0 } else { 0.5 * (deltas[k - 1] + deltas[k]) };
+    }
+    for k in 0..n - 1 {
+        if deltas[k] == 0.0 {
+            tangents[k] = 0.0;
+            tangents[k + 1] = 0.0;
+            continue;
+        }
+        let (a, b) = (tangents[k] / deltas[k], tangents[k + 1] / deltas[k]);
+        let norm = a * a + b * b;
+        if norm > 9.0 {
+            let scale = 3.0 / norm.sqrt();
+            tangents[k] = scale * a * deltas[k];
+            tangents[k + 1] = scale * b * deltas[k];
+        }
+    }
+    tangents
+}
+
+// Zero rate for the last of `times` that prices the swap at par, with the earlier pillars fixed at
+// `known_zero_rates`.
+fn solve_swap_pillar(times: &[f64], known_zero_rates: &[f64], interpolation: CurveInterpolation, quote: &CurveQuote) -> Result<f64, Error> {
+    let k = known_zero_rates.len();
+    let mut zero_rates = known_zero_rates.to_vec();
+    zero_rates.push(0.0);
+    solve_swap_pillar_in(times, &zero_rates, k, interpolation, quote)
+}
+
+// Zero rate for pillar k that prices the swap at par, all other pillars held at `zero_rates`.
+fn solve_swap_pillar_in(times: &[f64], zero_rates: &[f64], k: usize, interpolation: CurveInterpolation, quote: &CurveQuote) -> Result<f64, Error> {
+    let CurveQuote::Swap { maturity_years, rate, payments_per_year } = *quote else {
+        return Err(invalid_parameter("instrument", "Only swap quotes are solved for."));
+    };
+    // check_quote has bounded the number of periods.
+    let accrual = 1.0 / payments_per_year as f64;
+    let periods = (maturity_years * payments_per_year as f64).round();
+
+    let mut trial = zero_rates.to_vec();
+    let mut par_error = |z: f64| -> Result<f64, Error> {
+        trial[k] = z;
+        let curve = DiscountCurve::new(times.to_vec(), trial.clone(), interpolation)?;
+        let annuity: f64 = (1..=periods as usize).map(|j| accrual * curve.discount_factor(j as f64 * accrual)).sum();
+        Ok(rate * annuity + curve.discount_factor(maturity_years) - 1.0)
+    };
+
+    // The par error falls as the pillar zero rate rises.
+    let (mut lo, mut hi) = (MIN_ZERO_RATE, MAX_ZERO_RATE);
+    if par_error(lo)? < 0.0 || par_error(hi)? > 0.0 {
+        return Err(invalid_parameter("rate", format!("Could not bootstrap the {}-year swap quoted at {}.", maturity_years, rate)));
+    }
+    for _ in 0..BISECTION_ITERATIONS {
+        let mid = 0.5 * (lo + hi);
+        if par_error(mid)? > 0.0 {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+        if hi - lo < 1e-15 {
+            break;
+        }
+    }
+    Ok(0.5 * (lo + hi))
+}
@@ -6,6 +6,8 @@ use stock_price_simulator::{ // These are re-exported from lib.rs
 use stock_price_simulator::config::{
     GlobalConfig, AssetModelConfig, ModelType, ModelParameters, GeometricBrownianMotionParams
 };
+use stock_price_simulator::api_models::EtfData;
+use stock_price_simulator::etf_simulation::EtfBenchmark;
 
 const TEST_DAYS: usize = 5;
 const TEST_TIME_STEP: f64 = 1.0;
@@ -26,6 +28,7 @@ fn test_simulate_stock_direct_api() { // Renamed from test_simulate_stock_api
 // Helper to create a simple GlobalConfig for stock tests
 fn create_test_global_config() -> GlobalConfig {
     GlobalConfig {
+        include: Vec::new(),
         random_seed: Some(12345),
         simulation_period_days: 252,
         time_step_minutes: 1440,
@@ -36,15 +39,17 @@ fn create_test_global_config() -> GlobalConfig {
                 default_model: ModelType::GeometricBrownianMotion,
                 parameters: ModelParameters {
                     gbm: Some(GeometricBrownianMotionParams { drift: 0.05, volatility: 0.2 }),
+                    ..Default::default()
                 },
             },
             AssetModelConfig {
                 asset_type: "stock".to_string(),
                 asset_identifier_pattern: "TEST_STOCK_NO_GBM".to_string(),
                 default_model: ModelType::GeometricBrownianMotion,
-                parameters: ModelParameters { gbm: None }, // Missing GBM params
+                parameters: ModelParameters { gbm: None, ..Default::default() }, // Missing GBM params
             },
         ]),
+        instruments: Default::default(),
     }
 }
 
@@ -151,12 +156,13 @@ fn test_price_european_option_monte_carlo_api() {
         underlying_initial_price: 100.0,
         strike_price: 100.0,
         time_to_maturity_years: 0.1, // Shorter TTM for faster test
-        risk_free_rate: 0.05,
+        risk_free_rate: Some(0.05),
         underlying_volatility: 0.2,
         option_type: OptionType::Call,
         num_paths: 100, // Fewer paths for faster test
         num_steps_per_path: 10, // Corrected field name
         seed: Some(42),
+        discount_curve: None,
     };
     let result = price_european_option_monte_carlo(&input);
     assert!(result.is_ok(), "MC pricing failed: {:?}", result.err());
@@ -175,11 +181,12 @@ fn test_simulate_futures_api() {
     let contract = FuturesContract {
         underlying_symbol: "CRUDE".to_string(),
         initial_spot_price: 70.0,
-        risk_free_rate: 0.02,
+        risk_free_rate: Some(0.02),
         volatility: 0.3,
         time_to_maturity_days: 30,
         time_step_days: TEST_TIME_STEP,
         seed: Some(789),
+        discount_curve: None,
     };
     let result = simulate_futures(&contract);
     assert!(result.is_ok());
@@ -205,6 +212,8 @@ fn test_simulate_etf_api() {
                 drift: 0.1,
                 volatility: 0.2,
                 weight: 0.5,
+                regime_switching: None,
+                currency: None,
             },
             EtfConstituent {
                 symbol: "MSFT".to_string(),
@@ -212,11 +221,17 @@ fn test_simulate_etf_api() {
                 drift: 0.08,
                 volatility: 0.18,
                 weight: 0.5,
+                regime_switching: None,
+                currency: None,
             },
         ],
         simulation_days: TEST_DAYS,
         time_step_days: TEST_TIME_STEP,
         seed: Some(101),
+        fx: None,
+        expense_ratio: 0.0,
+        market: None,
+        benchmark: None,
     };
     let result = simulate_etf(&etf_def);
     assert!(result.is_ok());
@@ -230,3 +245,178 @@ fn test_simulate_etf_api() {
     let err_result = simulate_etf(&invalid_etf_def);
     assert!(err_result.is_err());
 }
+
+#[test]
+fn test_etf_simulation_converts_to_response_data() {
+    let stock = |symbol: &str, drift: f64, volatility: f64| EtfConstituent {
+        symbol: symbol.to_string(), initial_price: 100.0, drift, volatility, weight: 0.5,
+        regime_switching: None, currency: None,
+    };
+    let etf_def = EtfDefinition {
+        constituents: vec![stock("GROWTH", 0.12, 0.35), stock("VALUE", 0.06, 0.15)],
+        simulation_days: TEST_DAYS,
+        time_step_days: TEST_TIME_STEP,
+        seed: Some(17),
+        fx: None,
+        expense_ratio: 0.005,
+        market: None,
+        benchmark: Some(EtfBenchmark::default()),
+    };
+    let simulation = stock_price_simulator::etf_simulation::simulate_etf_with_market(&etf_def).unwrap();
+    let report = simulation.tracking.clone().unwrap();
+    let nav = simulation.nav.prices.clone();
+
+    let data = EtfData::from(simulation);
+    assert_eq!(data.etf_symbol, "SIMULATED_ETF");
+    assert_eq!(data.timestamps[0], "2024-01-01T00:00:00");
+    assert_eq!(data.nav_values, nav);
+    assert!(data.market_prices.is_none() && data.shares_outstanding.is_none());
+    let tracking = data.tracking.unwrap();
+    assert_eq!(tracking.tracking_differences, report.tracking_differences);
+    assert_eq!(tracking.attribution.len(), report.attribution.len());
+    assert_eq!(tracking.attribution[1].symbol, report.attribution[1].symbol);
+}
+
+#[test]
+fn test_simulate_stock_with_config_mean_reverting_model() {
+    use stock_price_simulator::config::OrnsteinUhlenbeckParams;
+
+    let mut config = create_test_global_config();
+    config.asset_models.as_mut().unwrap().push(AssetModelConfig {
+        asset_type: "stock".to_string(),
+        asset_identifier_pattern: "TEST_OU".to_string(),
+        default_model: ModelType::OrnsteinUhlenbeck,
+        parameters: ModelParameters {
+            ou: Some(OrnsteinUhlenbeckParams { mean_reversion_speed: 2.0, long_term_mean: 100.0, volatility: 5.0 }),
+            ..Default::default()
+        },
+    });
+
+    let ts = simulate_stock_with_config("TEST_OU", &config, 100.0, TEST_DAYS, TEST_TIME_STEP, Some(1), None, Some(6.0)).unwrap();
+    assert_eq!(ts.prices.len(), TEST_DAYS);
+
+    let drift_override = simulate_stock_with_config("TEST_OU", &config, 100.0, TEST_DAYS, TEST_TIME_STEP, Some(1), Some(0.1), None);
+    assert!(drift_override.unwrap_err().to_string().contains("Drift override is not supported"));
+}
+
+#[test]
+fn test_simulate_stock_with_config_historical_bootstrap() {
+    use stock_price_simulator::config::{BootstrapMethod, BootstrapParams};
+
+    let mut config = create_test_global_config();
+    config.asset_models.as_mut().unwrap().push(AssetModelConfig {
+        asset_type: "stock".to_string(),
+        asset_identifier_pattern: "TEST_BOOTSTRAP".to_string(),
+        default_model: ModelType::HistoricalBootstrap,
+        parameters: ModelParameters {
+            bootstrap: Some(BootstrapParams {
+                method: BootstrapMethod::StationaryBlock,
+                history_csv: None,
+                timestamp_column: None,
+                price_column: None,
+                log_returns: Some(vec![0.01, -0.01, 0.02, -0.015, 0.005]),
+                history_time_step_days: 1.0,
+                mean_block_length: Some(3.0),
+                garch: None,
+                history_returns: None,
+                fitted_garch: None,
+            }),
+            ..Default::default()
+        },
+    });
+
+    let ts = simulate_stock_with_config("TEST_BOOTSTRAP", &config, 100.0, TEST_DAYS, TEST_TIME_STEP, Some(4), None, None).unwrap();
+    assert_eq!(ts.prices.len(), TEST_DAYS);
+
+    let vol_override = simulate_stock_with_config("TEST_BOOTSTRAP", &config, 100.0, TEST_DAYS, TEST_TIME_STEP, Some(4), None, Some(0.3));
+    assert!(vol_override.is_err());
+}
+
+#[test]
+fn test_simulate_stock_with_config_garch() {
+    use stock_price_simulator::config::GarchParams;
+
+    let mut config = create_test_global_config();
+    config.asset_models.as_mut().unwrap().push(AssetModelConfig {
+        asset_type: "stock".to_string(),
+        asset_identifier_pattern: "TEST_GARCH".to_string(),
+        default_model: ModelType::Garch,
+        parameters: ModelParameters {
+            garch: Some(GarchParams {
+                mean_return: 0.0002, omega: 2e-6, alpha: 0.08, beta: 0.9,
+                initial_variance: None, history_time_step_days: 1.0,
+            }),
+            ..Default::default()
+        },
+    });
+
+    let ts = simulate_stock_with_config("TEST_GARCH", &config, 100.0, TEST_DAYS, TEST_TIME_STEP, Some(9), None, None).unwrap();
+    assert_eq!(ts.prices.len(), TEST_DAYS);
+    assert!(simulate_stock_with_config("TEST_GARCH", &config, 100.0, TEST_DAYS, TEST_TIME_STEP, Some(9), Some(0.1), None).is_err());
+}
+
+#[test]
+fn test_simulate_stock_with_config_fat_tailed_innovations() {
+    use stock_price_simulator::config::{InnovationDistribution, OrnsteinUhlenbeckParams};
+
+    let mut config = create_test_global_config();
+    config.asset_models.as_mut().unwrap().push(AssetModelConfig {
+        asset_type: "stock".to_string(),
+        asset_identifier_pattern: "TEST_FAT_TAILS".to_string(),
+        default_model: ModelType::GeometricBrownianMotion,
+        parameters: ModelParameters {
+            gbm: Some(GeometricBrownianMotionParams { drift: 0.05, volatility: 0.2 }),
+            innovations: Some(InnovationDistribution::StudentT { degrees_of_freedom: 4.0 }),
+            ..Default::default()
+        },
+    });
+    config.asset_models.as_mut().unwrap().push(AssetModelConfig {
+        asset_type: "stock".to_string(),
+        asset_identifier_pattern: "TEST_BAD_TAILS".to_string(),
+        default_model: ModelType::GeometricBrownianMotion,
+        parameters: ModelParameters {
+            gbm: Some(GeometricBrownianMotionParams { drift: 0.05, volatility: 0.2 }),
+            innovations: Some(InnovationDistribution::StudentT { degrees_of_freedom: 1.5 }),
+            ..Default::default()
+        },
+    });
+    config.asset_models.as_mut().unwrap().push(AssetModelConfig {
+        asset_type: "stock".to_string(),
+        asset_identifier_pattern: "TEST_OU_TAILS".to_string(),
+        default_model: ModelType::OrnsteinUhlenbeck,
+        parameters: ModelParameters {
+            ou: Some(OrnsteinUhlenbeckParams { mean_reversion_speed: 2.0, long_term_mean: 100.0, volatility: 5.0 }),
+            innovations: Some(InnovationDistribution::StudentT { degrees_of_freedom: 4.0 }),
+            ..Default::default()
+        },
+    });
+
+    let ts = simulate_stock_with_config("TEST_FAT_TAILS", &config, 100.0, TEST_DAYS, TEST_TIME_STEP, Some(5), None, None).unwrap();
+    assert_eq!(ts.prices.len(), TEST_DAYS);
+    assert!(simulate_stock_with_config("TEST_BAD_TAILS", &config, 100.0, TEST_DAYS, TEST_TIME_STEP, Some(5), None, None).is_err());
+    let unsupported = simulate_stock_with_config("TEST_OU_TAILS", &config, 100.0, TEST_DAYS, TEST_TIME_STEP, Some(5), None, None);
+    assert!(unsupported.unwrap_err().to_string().contains("not supported"));
+}
+
+#[test]
+fn test_simulate_stock_with_config_heston() {
+    use stock_price_simulator::config::HestonParams;
+
+    let mut config = create_test_global_config();
+    config.asset_models.as_mut().unwrap().push(AssetModelConfig {
+        asset_type: "stock".to_string(),
+        asset_identifier_pattern: "TEST_HESTON".to_string(),
+        default_model: ModelType::Heston,
+        parameters: ModelParameters {
+            heston: Some(HestonParams {
+                drift: 0.05, initial_variance: 0.04, mean_reversion_speed: 2.0,
+                long_term_variance: 0.04, vol_of_vol: 0.4, correlation: -0.6,
+            }),
+            ..Default::default()
+        },
+    });
+
+    let ts = simulate_stock_with_config("TEST_HESTON", &config, 100.0, TEST_DAYS, TEST_TIME_STEP, Some(2), Some(0.01), None).unwrap();
+    assert_eq!(ts.prices.len(), TEST_DAYS);
+    assert!(simulate_stock_with_config("TEST_HESTON", &config, 100.0, TEST_DAYS, TEST_TIME_STEP, Some(2), None, Some(0.2)).is_err());
+}
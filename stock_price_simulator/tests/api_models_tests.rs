@@ -1,6 +1,7 @@
 use stock_price_simulator::api_models::{
-    ApiResponse, ApiErrorResponse, StockData, OptionData, FutureData, EtfData,
+    ApiResponse, ApiErrorResponse, StockData, OptionData, FutureData, EtfData, EtfTrackingData, ConstituentAttributionData,
 };
+use stock_price_simulator::error::ErrorCode;
 // No need for local serde::Deserialize import if api_models derive it.
 
 // Helper macro for testing serde roundtrip
@@ -41,6 +42,19 @@ test_serde_roundtrip!(
     ApiErrorResponse {
         status: "error".to_string(),
         error: "Simulation failed".to_string(),
+        code: ErrorCode::SimulationFailed,
+        field: None,
+    }
+);
+
+test_serde_roundtrip!(
+    test_api_error_response_with_field,
+    ApiErrorResponse,
+    ApiErrorResponse {
+        status: "error".to_string(),
+        error: "Constituent 'B' weight cannot be negative.".to_string(),
+        code: ErrorCode::InvalidParameter,
+        field: Some("constituents[1].weight".to_string()),
     }
 );
 
@@ -101,5 +115,22 @@ test_serde_roundtrip!(
         etf_symbol: "SPY".to_string(),
         timestamps: vec!["2023-05-05T16:00:00Z".to_string()],
         nav_values: vec![450.55],
+        market_prices: Some(vec![450.80]),
+        premiums: Some(vec![0.00055]),
+        shares_outstanding: Some(vec![1_000_000]),
+        tracking: Some(EtfTrackingData {
+            benchmark_nav: vec![1.0],
+            tracking_differences: vec![0.0],
+            tracking_difference: -0.0012,
+            tracking_error: 0.004,
+            attribution: vec![ConstituentAttributionData {
+                symbol: "AAPL".to_string(),
+                returns: vec![0.0],
+                contributions: vec![0.0],
+                active_contributions: vec![0.0],
+                total_contribution: 0.0,
+            }],
+            expense_contributions: vec![0.0],
+        }),
     }
 );
@@ -0,0 +1,120 @@
+use stock_price_simulator::api_interface;
+use stock_price_simulator::asset_matching::{find_asset_model, AssetMatcher, pattern_kind, pattern_matches, resolve, validate_pattern, PatternKind};
+use stock_price_simulator::config::GlobalConfig;
+
+// One GBM stock model per pattern, in the given order; the drift records which entry was picked.
+fn config_with_patterns(patterns: &[&str]) -> GlobalConfig {
+    let mut toml_str = String::from("random_seed = 1\nsimulation_period_days = 10\ntime_step_minutes = 1440\n");
+    for (i, pattern) in patterns.iter().enumerate() {
+        toml_str.push_str(&format!(
+            "\n[[asset_models]]\nasset_type = \"stock\"\nasset_identifier_pattern = '{}'\ndefault_model = \"GeometricBrownianMotion\"\n[asset_models.parameters.gbm]\ndrift = {}\nvolatility = 0.2\n",
+            pattern, i as f64 / 100.0
+        ));
+    }
+    toml::from_str(&toml_str).unwrap()
+}
+
+fn resolved_pattern(config: &GlobalConfig, identifier: &str) -> Option<String> {
+    find_asset_model(config, "stock", identifier).ok().map(|m| m.asset_identifier_pattern.clone())
+}
+
+#[test]
+fn test_pattern_kinds_and_matching() {
+    assert_eq!(pattern_kind("AAPL", "stock"), PatternKind::Exact);
+    assert_eq!(pattern_kind("TECH_*", "stock"), PatternKind::Glob);
+    assert_eq!(pattern_kind("re:[A-Z]{4}", "stock"), PatternKind::Regex);
+    assert_eq!(pattern_kind("DEFAULT_STOCK", "stock"), PatternKind::Default);
+    assert_eq!(pattern_kind("DEFAULT_STOCK", "future"), PatternKind::Exact);
+
+    assert!(pattern_matches("TECH_*", "stock", "TECH_AAPL").unwrap());
+    assert!(pattern_matches("TECH_*", "stock", "TECH_").unwrap());
+    assert!(!pattern_matches("TECH_*", "stock", "FIN_JPM").unwrap());
+    assert!(pattern_matches("A?PL", "stock", "AAPL").unwrap());
+    assert!(!pattern_matches("A?PL", "stock", "APL").unwrap());
+    // Regexes are anchored: they must match the whole identifier.
+    assert!(pattern_matches("re:[A-Z]{4}", "stock", "MSFT").unwrap());
+    assert!(!pattern_matches("re:[A-Z]{4}", "stock", "MSFT2").unwrap());
+    assert!(pattern_matches("re:MSFT|AAPL", "stock", "AAPL").unwrap());
+    assert!(pattern_matches("DEFAULT_STOCK", "stock", "ANYTHING").unwrap());
+}
+
+#[test]
+fn test_precedence_exact_glob_regex_default() {
+    let config = config_with_patterns(&["DEFAULT_STOCK", "re:TECH_.*", "TECH_*", "TECH_AAPL"]);
+    assert_eq!(resolved_pattern(&config, "TECH_AAPL").as_deref(), Some("TECH_AAPL"));
+    assert_eq!(resolved_pattern(&config, "TECH_MSFT").as_deref(), Some("TECH_*"));
+    assert_eq!(resolved_pattern(&config, "XOM").as_deref(), Some("DEFAULT_STOCK"));
+
+    let config = config_with_patterns(&["re:TECH_.*", "DEFAULT_STOCK"]);
+    assert_eq!(resolved_pattern(&config, "TECH_MSFT").as_deref(), Some("re:TECH_.*"));
+
+    let resolution = resolve(&config, "stock", "TECH_MSFT").unwrap();
+    assert_eq!(resolution.resolved, Some(0));
+    assert_eq!(resolution.candidates.len(), 2);
+    assert!(resolution.candidates.iter().all(|c| c.matched));
+}
+
+#[test]
+fn test_more_specific_glob_wins_and_ties_go_to_file_order() {
+    let config = config_with_patterns(&["TECH_*", "TECH_SEMI_*", "*_SEMI_*"]);
+    assert_eq!(resolved_pattern(&config, "TECH_SEMI_NVDA").as_deref(), Some("TECH_SEMI_*"));
+    assert_eq!(resolved_pattern(&config, "TECH_AAPL").as_deref(), Some("TECH_*"));
+
+    let config = config_with_patterns(&["re:A.*", "re:.*L"]);
+    assert_eq!(resolved_pattern(&config, "AAPL").as_deref(), Some("re:A.*"));
+    let config = config_with_patterns(&["*PL", "AA*"]);
+    assert_eq!(resolved_pattern(&config, "AAPL").as_deref(), Some("*PL"));
+}
+
+#[test]
+fn test_unmatched_and_invalid_patterns() {
+    let config = config_with_patterns(&["TECH_*"]);
+    let err = find_asset_model(&config, "stock", "XOM").unwrap_err();
+    assert!(err.to_string().contains("No model config found for stock identifier: XOM"));
+    assert!(find_asset_model(&config, "future", "TECH_AAPL").is_err(), "Other asset types are not considered");
+    assert!(find_asset_model(&config, "STOCK", "TECH_AAPL").is_ok(), "Asset types compare case-insensitively");
+
+    assert!(validate_pattern("re:[A-Z").is_err());
+    let config = config_with_patterns(&["re:[A-Z", "FINE"]);
+    let problems = api_interface::check_asset_models(&config);
+    assert_eq!(problems.len(), 1);
+    assert!(problems[0].starts_with("asset_models[0] (stock 're:[A-Z')"), "{}", problems[0]);
+}
+
+#[test]
+fn test_simulation_uses_the_resolved_model() {
+    let config = config_with_patterns(&["DEFAULT_STOCK", "TECH_*"]);
+    let simulate = |identifier: &str| {
+        api_interface::simulate_stock_with_config(identifier, &config, 100.0, 20, 1.0, Some(5), None, None).unwrap().prices
+    };
+    let explicit = config_with_patterns(&["TECH_AAPL", "X"]);
+    let expected = api_interface::simulate_stock_with_config("X", &explicit, 100.0, 20, 1.0, Some(5), None, None).unwrap().prices;
+    // TECH_* is the second entry (drift 0.01), like X in the explicit config.
+    assert_eq!(simulate("TECH_AAPL"), expected);
+    assert_ne!(simulate("XOM"), expected);
+}
+
+#[test]
+fn test_glob_matching_is_not_exponential() {
+    // A backtracking matcher tries every split of the identifier between the stars.
+    let identifier = "A".repeat(400);
+    let start = std::time::Instant::now();
+    assert!(!pattern_matches("*A*A*A*A*B", "stock", &identifier).unwrap());
+    assert!(pattern_matches("*A*A*A*A*", "stock", &identifier).unwrap());
+    assert!(start.elapsed() < std::time::Duration::from_secs(1), "{:?}", start.elapsed());
+
+    assert!(pattern_matches("*_*X", "stock", "A_B_X").unwrap());
+    assert!(pattern_matches("A*B?C*", "stock", "AxxBxBzC").unwrap());
+    assert!(!pattern_matches("A*B?C", "stock", "AxxBxBzCD").unwrap());
+    assert!(pattern_matches("**", "stock", "").unwrap());
+    assert!(!pattern_matches("?", "stock", "").unwrap());
+}
+
+#[test]
+fn test_asset_matcher_reuses_compiled_patterns() {
+    let config = config_with_patterns(&["re:^TECH_[A-Z]+$", "TECH_*", "DEFAULT_STOCK"]);
+    let matcher = AssetMatcher::new(&config).unwrap();
+    assert_eq!(matcher.resolve(&config, "stock", "TECH_MSFT"), resolve(&config, "stock", "TECH_MSFT").unwrap());
+    assert_eq!(matcher.find(&config, "stock", "OTHER").unwrap().asset_identifier_pattern, "DEFAULT_STOCK");
+    assert!(AssetMatcher::new(&config_with_patterns(&["re:("])).is_err());
+}
@@ -0,0 +1,156 @@
+use stock_price_simulator::bond_simulation::{simulate_bond, BondContract, ShortRateModel, MAX_BOND_STEPS, MAX_BOND_STEP_COUPONS};
+use stock_price_simulator::error::classify;
+use stock_price_simulator::config::{CurveInterpolation, CurveQuote, DiscountCurveParams};
+use stock_price_simulator::yield_curve::DiscountCurve;
+
+const RATE: f64 = 0.04;
+
+// Vasicek without volatility starting at its long-term rate: a flat, deterministic 4% short rate.
+fn flat_vasicek() -> ShortRateModel {
+    ShortRateModel::Vasicek { initial_rate: RATE, mean_reversion_speed: 0.5, long_term_rate: RATE, volatility: 0.0 }
+}
+
+fn bond(coupon_rate: f64, maturity: f64, model: ShortRateModel) -> BondContract {
+    BondContract {
+        bond_symbol: "TEST_BOND".to_string(),
+        face_value: 100.0,
+        coupon_rate,
+        coupons_per_year: 2,
+        time_to_maturity_years: maturity,
+        short_rate_model: model,
+        time_step_days: 1.0,
+        seed: Some(42),
+    }
+}
+
+// Coupon rate that makes a semi-annual bond trade at par when continuously compounded rates are flat at RATE.
+fn par_coupon() -> f64 {
+    2.0 * ((RATE / 2.0).exp() - 1.0)
+}
+
+#[test]
+fn test_zero_coupon_bond_on_flat_rates() {
+    let path = simulate_bond(&bond(0.0, 5.0, flat_vasicek())).unwrap();
+    assert!((path.dirty_prices[0] - 100.0 * (-RATE * 5.0).exp()).abs() < 1e-9, "{}", path.dirty_prices[0]);
+    assert_eq!(path.accrued_interest[0], 0.0);
+    assert!((path.yields[0].unwrap() - par_coupon()).abs() < 1e-10);
+    assert!((path.macaulay_durations[0] - 5.0).abs() < 1e-9, "A zero-coupon bond's duration is its maturity");
+}
+
+#[test]
+fn test_par_bond_clean_and_dirty_prices() {
+    let contract = bond(par_coupon(), 3.0, flat_vasicek());
+    let path = simulate_bond(&contract).unwrap();
+    assert_eq!(path.timestamps.len(), 3 * 252 + 1);
+    assert!((path.dirty_prices[0] - 100.0).abs() < 1e-9, "Par bond priced at {}", path.dirty_prices[0]);
+
+    for (i, ((dirty, clean), accrued)) in path.dirty_prices.iter().zip(&path.clean_prices).zip(&path.accrued_interest).enumerate() {
+        assert!((dirty - clean - accrued).abs() < 1e-12);
+        assert!(*accrued >= 0.0 && *accrued <= 100.0 * par_coupon() / 2.0 + 1e-12, "accrued {} at {}", accrued, i);
+    }
+    // On a coupon date the coupon has just been paid: nothing accrued and the clean price is back at par.
+    assert!(path.accrued_interest[126].abs() < 1e-9);
+    assert!((path.clean_prices[126] - 100.0).abs() < 1e-6, "{}", path.clean_prices[126]);
+    // Mid-period the accrued coupon is half of it.
+    assert!((path.accrued_interest[63] - 100.0 * par_coupon() / 4.0).abs() < 1e-9);
+
+    let last = path.timestamps.len() - 1;
+    assert!((path.clean_prices[last] - 100.0).abs() < 1e-9);
+    assert!((path.dirty_prices[last] - 100.0 * (1.0 + par_coupon() / 2.0)).abs() < 1e-9);
+    assert_eq!(path.yields[last], None);
+    assert_eq!(path.modified_durations[last], 0.0);
+}
+
+#[test]
+fn test_duration_and_convexity_match_yield_bumps() {
+    let contract = bond(0.06, 10.0, flat_vasicek());
+    let path = simulate_bond(&contract).unwrap();
+    let y = path.yields[0].unwrap();
+    let price_at = |yield_rate: f64| -> f64 {
+        (1..=20).map(|k| {
+            let amount = if k == 20 { 103.0 } else { 3.0 };
+            amount * (1.0 + yield_rate / 2.0).powf(-(k as f64))
+        }).sum()
+    };
+    assert!((price_at(y) - path.dirty_prices[0]).abs() < 1e-8);
+
+    let h = 1e-5;
+    let price = price_at(y);
+    let duration = -(price_at(y + h) - price_at(y - h)) / (2.0 * h * price);
+    let convexity = (price_at(y + h) - 2.0 * price + price_at(y - h)) / (h * h * price);
+    assert!((path.modified_durations[0] - duration).abs() < 1e-6, "{} vs {}", path.modified_durations[0], duration);
+    assert!((path.convexities[0] - convexity).abs() < 1e-3, "{} vs {}", path.convexities[0], convexity);
+    assert!((path.macaulay_durations[0] - path.modified_durations[0] * (1.0 + y / 2.0)).abs() < 1e-12);
+}
+
+#[test]
+fn test_hull_white_reprices_the_initial_curve() {
+    let curve = DiscountCurve::from_params(&DiscountCurveParams {
+        quotes: vec![
+            CurveQuote::Deposit { maturity_years: 0.5, rate: 0.02 },
+            CurveQuote::Swap { maturity_years: 2.0, rate: 0.03, payments_per_year: 2 },
+            CurveQuote::Swap { maturity_years: 10.0, rate: 0.04, payments_per_year: 2 },
+        ],
+        interpolation: CurveInterpolation::MonotoneCubic,
+    }).unwrap();
+    let model = ShortRateModel::HullWhite { mean_reversion_speed: 0.1, volatility: 0.01, discount_curve: curve.clone() };
+    let path = simulate_bond(&bond(0.05, 7.0, model)).unwrap();
+
+    let expected: f64 = (1..=14).map(|k| {
+        let amount = if k == 14 { 102.5 } else { 2.5 };
+        amount * curve.discount_factor(k as f64 * 0.5)
+    }).sum();
+    assert!((path.dirty_prices[0] - expected).abs() < 1e-6, "{} vs {}", path.dirty_prices[0], expected);
+    assert!((path.short_rates[0] - curve.instantaneous_forward(0.0)).abs() < 1e-12);
+    assert!(path.short_rates.iter().any(|r| (r - path.short_rates[0]).abs() > 1e-4), "Rates should move");
+}
+
+#[test]
+fn test_cir_rates_stay_non_negative_and_match_vasicek_without_noise() {
+    let cir = ShortRateModel::CoxIngersollRoss { initial_rate: 0.01, mean_reversion_speed: 0.3, long_term_rate: 0.05, volatility: 0.15 };
+    let path = simulate_bond(&bond(0.0, 5.0, cir)).unwrap();
+    assert!(path.short_rates.iter().all(|r| *r >= 0.0));
+    assert!(path.dirty_prices.iter().all(|p| *p > 0.0 && *p <= 100.0 + 1e-9));
+
+    // With tiny volatility both models reduce to the same deterministic rate path.
+    let quiet_cir = ShortRateModel::CoxIngersollRoss { initial_rate: 0.01, mean_reversion_speed: 0.3, long_term_rate: 0.05, volatility: 1e-4 };
+    let quiet_vasicek = ShortRateModel::Vasicek { initial_rate: 0.01, mean_reversion_speed: 0.3, long_term_rate: 0.05, volatility: 0.0 };
+    let cir_price = quiet_cir.zero_coupon_price(0.0, 5.0, 0.01);
+    let vasicek_price = quiet_vasicek.zero_coupon_price(0.0, 5.0, 0.01);
+    assert!((cir_price - vasicek_price).abs() < 1e-6, "{} vs {}", cir_price, vasicek_price);
+}
+
+#[test]
+fn test_bond_simulation_is_reproducible_and_validated() {
+    let model = ShortRateModel::Vasicek { initial_rate: 0.03, mean_reversion_speed: 0.2, long_term_rate: 0.04, volatility: 0.01 };
+    let a = simulate_bond(&bond(0.05, 2.0, model.clone())).unwrap();
+    let b = simulate_bond(&bond(0.05, 2.0, model.clone())).unwrap();
+    assert_eq!(a, b);
+
+    assert!(simulate_bond(&BondContract { face_value: 0.0, ..bond(0.05, 2.0, model.clone()) }).is_err());
+    assert!(simulate_bond(&BondContract { coupons_per_year: 0, ..bond(0.05, 2.0, model.clone()) }).is_err());
+    assert!(simulate_bond(&BondContract { time_step_days: 0.0, ..bond(0.05, 2.0, model.clone()) }).is_err());
+    assert!(simulate_bond(&bond(0.05, 0.0, model)).is_err());
+    let negative_cir = ShortRateModel::CoxIngersollRoss { initial_rate: -0.01, mean_reversion_speed: 0.3, long_term_rate: 0.05, volatility: 0.1 };
+    assert!(simulate_bond(&bond(0.05, 2.0, negative_cir)).is_err());
+}
+
+#[test]
+fn test_bond_step_and_coupon_counts_are_capped() {
+    let field = |contract: BondContract| classify(&simulate_bond(&contract).unwrap_err()).field;
+    let tiny_steps = BondContract { time_step_days: 1e-6, ..bond(0.05, 30.0, flat_vasicek()) };
+    assert_eq!(field(tiny_steps).as_deref(), Some("time_step_days"));
+    assert_eq!(field(bond(0.05, 1e12, flat_vasicek())).as_deref(), Some("time_step_days"), "Very long maturity");
+    assert_eq!(field(bond(0.05, f64::INFINITY, flat_vasicek())).as_deref(), Some("time_step_days"));
+    let many_coupons = BondContract { coupons_per_year: u32::MAX, time_step_days: 30.0, ..bond(0.05, 1.0, flat_vasicek()) };
+    assert_eq!(field(many_coupons).as_deref(), Some("coupons_per_year"));
+
+    let err = simulate_bond(&BondContract { time_step_days: 1e-6, ..bond(0.05, 30.0, flat_vasicek()) }).unwrap_err();
+    assert!(err.to_string().contains(&format!("at most {}", MAX_BOND_STEPS)), "{}", err);
+
+    // Each count alone is allowed, but together they would price ~1e12 cash flows.
+    let both = BondContract { coupons_per_year: 1_000_000, time_step_days: 0.000252, ..bond(0.05, 1.0, flat_vasicek()) };
+    let err = simulate_bond(&both).unwrap_err();
+    assert_eq!(classify(&err).field.as_deref(), Some("time_step_days"));
+    assert!(err.to_string().contains(&format!("at most {}", MAX_BOND_STEP_COUPONS)), "{}", err);
+}
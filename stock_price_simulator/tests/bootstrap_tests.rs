@@ -0,0 +1,160 @@
+use stock_price_simulator::bootstrap::{load_bootstrap_inputs, HistoricalBootstrap, ResamplingScheme};
+use stock_price_simulator::config::{BootstrapMethod, BootstrapParams, GarchParams};
+use stock_price_simulator::random_process::StochasticProcess;
+use stock_price_simulator::stock_simulation::StockSimulator;
+use stock_price_simulator::volatility_models::garch_conditional_variances;
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+fn sample_returns() -> Vec<f64> {
+    vec![0.01, -0.02, 0.015, 0.003, -0.007, 0.02, -0.011, 0.004, 0.0, -0.005]
+}
+
+fn garch_params(omega: f64, alpha: f64, beta: f64) -> GarchParams {
+    GarchParams { mean_return: 0.0, omega, alpha, beta, initial_variance: None, history_time_step_days: 1.0 }
+}
+
+fn implied_returns(prices: &[f64]) -> Vec<f64> {
+    prices.windows(2).map(|w| (w[1] / w[0]).ln()).collect()
+}
+
+fn assert_is_historical(r: f64, history: &[f64]) {
+    assert!(history.iter().any(|h| (h - r).abs() < 1e-12), "Return {} was not drawn from history", r);
+}
+
+#[test]
+fn test_iid_bootstrap_draws_only_historical_returns() {
+    let history = sample_returns();
+    let bootstrap = HistoricalBootstrap::new(history.clone(), ResamplingScheme::Iid, 1.0).unwrap();
+    let path = bootstrap.generate_path(100.0, 1.0, 50, Some(3));
+
+    assert_eq!(path.prices.len(), 50);
+    assert_eq!(path.prices[0], 100.0);
+    for r in implied_returns(&path.prices) {
+        assert_is_historical(r, &history);
+    }
+    assert_eq!(path.prices, bootstrap.generate_path(100.0, 1.0, 50, Some(3)).prices, "Seeded paths must repeat");
+}
+
+#[test]
+fn test_stationary_block_bootstrap_keeps_consecutive_runs() {
+    // Strictly increasing returns make block continuations easy to spot.
+    let history: Vec<f64> = (0..100).map(|i| 0.0001 * (i + 1) as f64).collect();
+    let bootstrap = HistoricalBootstrap::new(
+        history.clone(), ResamplingScheme::StationaryBlock { mean_block_length: 20.0 }, 1.0,
+    ).unwrap();
+    let returns = implied_returns(&bootstrap.generate_path(1.0, 1.0, 500, Some(8)).prices);
+
+    let continuations = returns.windows(2)
+        .filter(|w| (w[1] - w[0] - 0.0001).abs() < 1e-9)
+        .count();
+    // With mean block length 20 about 95% of steps continue the current block.
+    assert!(continuations as f64 > 0.8 * returns.len() as f64, "Only {} continuations", continuations);
+}
+
+#[test]
+fn test_multi_day_steps_aggregate_draws() {
+    let history = vec![0.01; 5];
+    let bootstrap = HistoricalBootstrap::new(history, ResamplingScheme::Iid, 1.0).unwrap();
+    let path = bootstrap.generate_path(100.0, 5.0, 3, Some(1));
+    assert!((path.prices[1] - 100.0 * (0.05f64).exp()).abs() < 1e-9);
+}
+
+#[test]
+fn test_filtered_historical_simulation() {
+    let history = sample_returns();
+    let garch = garch_params(1e-6, 0.1, 0.85);
+    let residuals: Vec<f64> = history.iter().map(|r| r - garch.mean_return).collect();
+    let (variances, forecast) = garch_conditional_variances(&residuals, garch.omega, garch.alpha, garch.beta);
+    assert_eq!(variances.len(), history.len());
+    assert!(forecast > 0.0 && variances.iter().all(|v| *v > 0.0));
+
+    let bootstrap = HistoricalBootstrap::new(history, ResamplingScheme::FilteredHistorical { garch }, 1.0).unwrap();
+    let path = bootstrap.generate_path(100.0, 1.0, 200, Some(5));
+    assert!(path.prices.iter().all(|p| p.is_finite() && *p > 0.0));
+}
+
+#[test]
+fn test_bootstrap_invalid_inputs() {
+    assert!(HistoricalBootstrap::new(vec![0.01], ResamplingScheme::Iid, 1.0).is_err());
+    assert!(HistoricalBootstrap::new(sample_returns(), ResamplingScheme::StationaryBlock { mean_block_length: 0.5 }, 1.0).is_err());
+    let explosive = garch_params(1e-6, 0.5, 0.6);
+    assert!(HistoricalBootstrap::new(sample_returns(), ResamplingScheme::FilteredHistorical { garch: explosive }, 1.0).is_err());
+}
+
+#[test]
+fn test_bootstrap_from_params_with_history_csv() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    temp_file.write_all(b"Date,Close\n2024-01-01,100\n2024-01-02,101\n2024-01-03,99\n2024-01-04,100.5\n").unwrap();
+
+    let params = BootstrapParams {
+        method: BootstrapMethod::Iid,
+        history_csv: Some(temp_file.path().to_str().unwrap().to_string()),
+        timestamp_column: Some("Date".to_string()),
+        price_column: Some("Close".to_string()),
+        log_returns: None,
+        history_time_step_days: 1.0,
+        mean_block_length: None,
+        garch: None,
+        history_returns: None,
+        fitted_garch: None,
+    };
+    let bootstrap = HistoricalBootstrap::from_params(&params).unwrap();
+    assert_eq!(bootstrap.log_returns.len(), 3);
+
+    let missing_block_length = BootstrapParams {
+        method: BootstrapMethod::StationaryBlock,
+        history_csv: None,
+        log_returns: Some(sample_returns()),
+        ..params
+    };
+    assert!(HistoricalBootstrap::from_params(&missing_block_length).unwrap_err().to_string().contains("mean_block_length"));
+}
+
+#[test]
+fn test_filtered_historical_fits_garch_when_not_configured() {
+    let history: Vec<f64> = (0..200).map(|i| 0.01 * ((i * 7919) % 13) as f64 / 13.0 - 0.005).collect();
+    let params = BootstrapParams {
+        method: BootstrapMethod::FilteredHistorical,
+        history_csv: None,
+        timestamp_column: None,
+        price_column: None,
+        log_returns: Some(history),
+        history_time_step_days: 1.0,
+        mean_block_length: None,
+        garch: None,
+        history_returns: None,
+        fitted_garch: None,
+    };
+    let bootstrap = HistoricalBootstrap::from_params(&params).unwrap();
+    let fitted = match bootstrap.scheme {
+        ResamplingScheme::FilteredHistorical { garch } => garch,
+        other => panic!("Unexpected scheme {:?}", other),
+    };
+    assert!(fitted.alpha + fitted.beta < 1.0);
+
+    // Loading fits once; requests then reuse the fit.
+    let mut loaded = params.clone();
+    load_bootstrap_inputs(&mut loaded, std::path::Path::new(".")).unwrap();
+    assert_eq!(loaded.fitted_garch.as_ref(), Some(&fitted));
+    let cached = GarchParams { omega: 2e-6, ..fitted };
+    loaded.fitted_garch = Some(cached.clone());
+    match HistoricalBootstrap::from_params(&loaded).unwrap().scheme {
+        ResamplingScheme::FilteredHistorical { garch } => assert_eq!(garch, cached),
+        other => panic!("Unexpected scheme {:?}", other),
+    }
+}
+
+#[test]
+fn test_steps_must_be_whole_multiples_of_the_history_step() {
+    let bootstrap = HistoricalBootstrap::new(sample_returns(), ResamplingScheme::Iid, 1.0).unwrap();
+    for dt in [0.25, 1.4, 0.5] {
+        let err = StockSimulator::simulate_with_process(&bootstrap, 100.0, 10, dt, Some(1)).unwrap_err();
+        assert!(err.to_string().contains("whole multiple of the history time step"), "{}: {}", dt, err);
+    }
+    for dt in [1.0, 5.0] {
+        assert!(StockSimulator::simulate_with_process(&bootstrap, 100.0, 10, dt, Some(1)).is_ok(), "{}", dt);
+    }
+    let intraday = HistoricalBootstrap::new(sample_returns(), ResamplingScheme::Iid, 1.0 / 24.0).unwrap();
+    assert!(StockSimulator::simulate_with_process(&intraday, 100.0, 10, 0.25, Some(1)).is_ok());
+}
@@ -0,0 +1,86 @@
+use stock_price_simulator::calibration::{
+    calibrate_gbm, calibrate_ou, calibrate_cir, calibrate_jump_diffusion, calibrate_asset_model,
+};
+use stock_price_simulator::config::ModelType;
+use stock_price_simulator::random_process::{
+    GeometricBrownianMotion, OrnsteinUhlenbeck, CoxIngersollRoss, MertonJumpDiffusion, StochasticProcess,
+};
+
+const LONG_PATH_STEPS: usize = 20_000;
+
+#[test]
+fn test_calibrate_gbm_recovers_parameters() {
+    let gbm = GeometricBrownianMotion::new(0.08, 0.25);
+    let path = gbm.generate_path(100.0, 1.0, LONG_PATH_STEPS, Some(7));
+
+    let params = calibrate_gbm(&path.prices, 1.0).unwrap();
+    assert!((params.volatility - 0.25).abs() < 0.01, "Volatility estimate {} too far from 0.25", params.volatility);
+    // Drift standard error is sigma / sqrt(T) ~ 0.25 / sqrt(79) ~ 0.03
+    assert!((params.drift - 0.08).abs() < 0.1, "Drift estimate {} too far from 0.08", params.drift);
+}
+
+#[test]
+fn test_calibrate_gbm_scales_with_time_step() {
+    let gbm = GeometricBrownianMotion::new(0.05, 0.2);
+    let path = gbm.generate_path(100.0, 5.0, LONG_PATH_STEPS, Some(11));
+
+    let params = calibrate_gbm(&path.prices, 5.0).unwrap();
+    assert!((params.volatility - 0.2).abs() < 0.01);
+}
+
+#[test]
+fn test_calibrate_ou_recovers_parameters() {
+    let ou = OrnsteinUhlenbeck { mean_reversion_speed: 3.0, long_term_mean: 0.04, volatility: 0.01 };
+    let path = ou.generate_path(0.06, 1.0, LONG_PATH_STEPS, Some(21));
+
+    let params = calibrate_ou(&path.prices, 1.0).unwrap();
+    assert!((params.long_term_mean - 0.04).abs() < 0.002, "Long-term mean {}", params.long_term_mean);
+    assert!((params.volatility - 0.01).abs() < 0.0005, "Volatility {}", params.volatility);
+    assert!((params.mean_reversion_speed - 3.0).abs() < 1.0, "Speed {}", params.mean_reversion_speed);
+}
+
+#[test]
+fn test_calibrate_cir_recovers_parameters() {
+    let cir = CoxIngersollRoss { mean_reversion_speed: 2.0, long_term_mean: 0.05, volatility: 0.1 };
+    let path = cir.generate_path(0.03, 1.0, LONG_PATH_STEPS, Some(31));
+
+    let params = calibrate_cir(&path.prices, 1.0).unwrap();
+    assert!((params.long_term_mean - 0.05).abs() < 0.01, "Long-term mean {}", params.long_term_mean);
+    assert!((params.volatility - 0.1).abs() < 0.01, "Volatility {}", params.volatility);
+    assert!(params.mean_reversion_speed > 0.0);
+}
+
+#[test]
+fn test_calibrate_jump_diffusion_detects_jumps() {
+    let merton = MertonJumpDiffusion {
+        drift: 0.05, volatility: 0.15, jump_intensity: 10.0, jump_mean: -0.05, jump_volatility: 0.03,
+    };
+    let path = merton.generate_path(100.0, 1.0, LONG_PATH_STEPS, Some(41));
+
+    let params = calibrate_jump_diffusion(&path.prices, 1.0).unwrap();
+    assert!((params.volatility - 0.15).abs() < 0.02, "Diffusion volatility {}", params.volatility);
+    assert!((params.jump_intensity - 10.0).abs() < 4.0, "Jump intensity {}", params.jump_intensity);
+    assert!((params.jump_mean + 0.05).abs() < 0.02, "Jump mean {}", params.jump_mean);
+}
+
+#[test]
+fn test_calibration_invalid_inputs() {
+    assert!(calibrate_gbm(&[100.0, 101.0], 1.0).is_err(), "Too few observations");
+    assert!(calibrate_gbm(&[100.0, -1.0, 101.0], 1.0).is_err(), "Prices must be positive");
+    assert!(calibrate_gbm(&[100.0, 101.0, 102.0], 0.0).is_err(), "Time step must be positive");
+    assert!(calibrate_ou(&[1.0, 2.0, 3.0, 4.0, 5.0], 1.0).is_err(), "Trending series has no mean reversion");
+    assert!(calibrate_cir(&[0.05, 0.05, 0.05, 0.05], 1.0).is_err(), "Constant series is not identifiable");
+}
+
+#[test]
+fn test_calibrate_asset_model_builds_config_entry() {
+    let gbm = GeometricBrownianMotion::new(0.05, 0.2);
+    let history = gbm.generate_path(50.0, 1.0, 500, Some(5));
+
+    let model = calibrate_asset_model("stock", "FITTED", &history, ModelType::GeometricBrownianMotion, 1.0).unwrap();
+    assert_eq!(model.asset_type, "stock");
+    assert_eq!(model.asset_identifier_pattern, "FITTED");
+    assert_eq!(model.default_model, ModelType::GeometricBrownianMotion);
+    assert!(model.parameters.gbm.is_some());
+    assert!(model.parameters.ou.is_none());
+}
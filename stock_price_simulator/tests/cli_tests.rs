@@ -0,0 +1,165 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+use stock_price_simulator::api_interface;
+use stock_price_simulator::api_models::{EtfData, FutureData, OptionData, StockData};
+use stock_price_simulator::config::{load_config, AssetModelConfig, ModelType};
+use stock_price_simulator::export::{time_series_to_csv, CsvExportOptions};
+use stock_price_simulator::option_pricing::OptionType;
+use stock_price_simulator::request_defaults::{with_defaults, RequestKind, StockSimulationRequest};
+use stock_price_simulator::stock_simulation::StockSimulator;
+
+fn cli(args: &[&str], stdin: Option<&str>) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_stock_sim_cli"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to start stock_sim_cli");
+    child.stdin.take().unwrap().write_all(stdin.unwrap_or("").as_bytes()).unwrap();
+    child.wait_with_output().expect("stock_sim_cli did not finish")
+}
+
+fn stdout_of(args: &[&str], stdin: Option<&str>) -> String {
+    let output = cli(args, stdin);
+    assert!(output.status.success(), "stock_sim_cli {:?} failed: {}", args, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+// JSON output is not bit-exact (serde_json parses floats to within an ulp).
+fn assert_same_path(actual: &[f64], expected: &[f64]) {
+    assert_eq!(actual.len(), expected.len());
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() <= 1e-12 * e.abs(), "{} vs {}", a, e);
+    }
+}
+
+#[test]
+fn test_cli_stock_matches_api_interface() {
+    let out = stdout_of(&["stock", "--config", "config.test.toml", "--asset-identifier", "TEST_DEFAULT",
+        "--initial-price", "100", "--days", "10", "--time-step-days", "1", "--seed", "42"], None);
+    let data: StockData = serde_json::from_str(&out).unwrap();
+    let config = load_config("config.test.toml").unwrap();
+    let expected = api_interface::simulate_stock_with_config("TEST_DEFAULT", &config, 100.0, 10, 1.0, Some(42), None, None).unwrap();
+    assert_eq!(data.symbol, "TEST_DEFAULT");
+    assert_same_path(&data.prices, &expected.prices);
+
+    // Without a config the path is plain GBM and written as CSV on request.
+    let csv = stdout_of(&["--format", "csv", "stock", "--initial-price", "50", "--days", "5", "--drift", "-0.02",
+        "--volatility", "0.3", "--seed", "7"], None);
+    let series = StockSimulator::simulate_stock_price(50.0, -0.02, 0.3, 5, 1.0, Some(7)).unwrap();
+    assert_eq!(csv, time_series_to_csv(&series, &CsvExportOptions::default()).unwrap());
+
+    // With a config, days, step and seed default to it, as for GET /simulate/stock.
+    let out = stdout_of(&["stock", "--config", "config.test.toml", "--asset-identifier", "TEST_DEFAULT", "--initial-price", "100"], None);
+    let data: StockData = serde_json::from_str(&out).unwrap();
+    let request: StockSimulationRequest = with_defaults(&config, RequestKind::Stock, serde_json::json!({
+        "asset_identifier": "TEST_DEFAULT", "initial_price": 100.0, "days": null, "time_step_days": null,
+        "seed": null, "drift": null, "volatility": null })).unwrap();
+    let expected = api_interface::simulate_stock_with_config("TEST_DEFAULT", &config, 100.0, request.days, request.time_step_days,
+        request.seed, None, None).unwrap();
+    assert_eq!(data.prices.len(), 2400);
+    assert_same_path(&data.prices, &expected.prices);
+
+    let missing_volatility = cli(&["stock", "--initial-price", "50", "--days", "5", "--drift", "0.1"], None);
+    assert!(!missing_volatility.status.success());
+    assert!(String::from_utf8_lossy(&missing_volatility.stderr).contains("--volatility"));
+}
+
+#[test]
+fn test_cli_option_pricing_from_stdin() {
+    let bs_body = r#"{ "underlying_price": 100.0, "strike_price": 105.0, "time_to_maturity_years": 0.5,
+        "risk_free_rate": 0.02, "volatility": 0.22, "option_type": "Call" }"#;
+    let data: OptionData = serde_json::from_str(&stdout_of(&["option", "bs"], Some(bs_body))).unwrap();
+    let expected = api_interface::price_european_option_black_scholes(100.0, 105.0, 0.5, 0.02, 0.22, OptionType::Call).unwrap();
+    assert!((data.price.unwrap() - expected).abs() < 1e-12);
+    assert_eq!(data.option_type, "Call");
+
+    let mc_body = r#"{ "underlying_initial_price": 100.0, "strike_price": 102.0, "time_to_maturity_years": 0.75,
+        "risk_free_rate": 0.025, "underlying_volatility": 0.2, "option_type": "Put", "num_paths": 2000,
+        "num_steps_per_path": 20, "seed": 456 }"#;
+    let csv = stdout_of(&["option", "mc", "--format", "csv"], Some(mc_body));
+    let input = serde_json::from_str(mc_body).unwrap();
+    let expected = api_interface::price_european_option_monte_carlo(&input).unwrap();
+    assert_eq!(csv, format!("option_type,strike_price,price\nPut,102,{}\n", expected));
+
+    let invalid = cli(&["option", "bs"], None);
+    assert!(!invalid.status.success(), "Empty input is not a valid option");
+}
+
+#[test]
+fn test_cli_future_and_etf_from_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let future_path = dir.path().join("future.json");
+    std::fs::write(&future_path, r#"{ "underlying_symbol": "CRUDE_OIL", "initial_spot_price": 70.0, "risk_free_rate": 0.03,
+        "volatility": 0.25, "time_to_maturity_days": 30, "time_step_days": 1, "seed": 789 }"#).unwrap();
+    let output_path = dir.path().join("future_out.json");
+    let out = stdout_of(&["future", "--input", future_path.to_str().unwrap(), "--output", output_path.to_str().unwrap()], None);
+    assert!(out.is_empty(), "Output goes to the file");
+    let data: FutureData = serde_json::from_str(&std::fs::read_to_string(&output_path).unwrap()).unwrap();
+    assert_eq!(data.contract_symbol, "CRUDE_OIL");
+    assert_eq!(data.prices.len(), data.timestamps.len());
+    assert!(!data.prices.is_empty());
+
+    let etf_path = dir.path().join("etf.json");
+    std::fs::write(&etf_path, r#"{ "constituents": [
+            { "symbol": "A", "initial_price": 50.0, "drift": 0.1, "volatility": 0.3, "weight": 0.6 },
+            { "symbol": "B", "initial_price": 80.0, "drift": 0.05, "volatility": 0.2, "weight": 0.4 } ],
+        "simulation_days": 15, "time_step_days": 1, "seed": 101, "benchmark": { "weights": { "A": 0.5, "B": 0.5 } } }"#).unwrap();
+    let data: EtfData = serde_json::from_str(&stdout_of(&["etf", "-i", etf_path.to_str().unwrap()], None)).unwrap();
+    let expected = api_interface::simulate_etf(&serde_json::from_str(&std::fs::read_to_string(&etf_path).unwrap()).unwrap()).unwrap();
+    assert_same_path(&data.nav_values, &expected.prices);
+    assert_eq!(data.tracking.unwrap().attribution.len(), 2);
+}
+
+#[test]
+fn test_cli_calibrate_updates_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let history = StockSimulator::simulate_stock_price(100.0, 0.08, 0.25, 1000, 1.0, Some(3)).unwrap();
+    let history_path = dir.path().join("history.csv");
+    std::fs::write(&history_path, time_series_to_csv(&history, &CsvExportOptions::default()).unwrap()).unwrap();
+    let config_path = dir.path().join("config.toml");
+    std::fs::copy("config.test.toml", &config_path).unwrap();
+
+    let out = stdout_of(&["calibrate", "--history", history_path.to_str().unwrap(), "--model", "GeometricBrownianMotion",
+        "--asset-identifier-pattern", "CALIBRATED", "--update-config", config_path.to_str().unwrap()], None);
+    let model: AssetModelConfig = serde_json::from_str(&out).unwrap();
+    assert_eq!(model.default_model, ModelType::GeometricBrownianMotion);
+    assert!((model.parameters.gbm.as_ref().unwrap().volatility - 0.25).abs() < 0.03);
+
+    let updated = load_config(config_path.to_str().unwrap()).unwrap();
+    assert!(updated.asset_models.unwrap().contains(&model));
+
+    let unknown = cli(&["calibrate", "--history", history_path.to_str().unwrap(), "--model", "Unknown"], None);
+    assert!(!unknown.status.success());
+    assert!(String::from_utf8_lossy(&unknown.stderr).contains("Unknown model"));
+}
+
+#[test]
+fn test_cli_calibrate_replaces_existing_model_in_any_case() {
+    let dir = tempfile::tempdir().unwrap();
+    let history = StockSimulator::simulate_stock_price(100.0, 0.08, 0.25, 500, 1.0, Some(4)).unwrap();
+    let history_path = dir.path().join("history.csv");
+    std::fs::write(&history_path, time_series_to_csv(&history, &CsvExportOptions::default()).unwrap()).unwrap();
+    let config_path = dir.path().join("config.toml");
+    std::fs::copy("config.test.toml", &config_path).unwrap();
+    let model_count = || load_config(config_path.to_str().unwrap()).unwrap().asset_models.unwrap().len();
+    let before = model_count();
+
+    // config.test.toml spells the type "stock"; both the default and "Stock" replace its entry.
+    for asset_type in [None, Some("Stock")] {
+        let mut args = vec!["calibrate", "--history", history_path.to_str().unwrap(), "--model", "GeometricBrownianMotion",
+            "--asset-identifier-pattern", "TEST_DEFAULT", "--update-config", config_path.to_str().unwrap()];
+        args.extend(asset_type.iter().flat_map(|t| ["--asset-type", *t]));
+        stdout_of(&args, None);
+        assert_eq!(model_count(), before, "{:?}", asset_type);
+    }
+
+    // A command that is rejected leaves the config file alone.
+    let contents = std::fs::read_to_string(&config_path).unwrap();
+    let csv = cli(&["--format", "csv", "calibrate", "--history", history_path.to_str().unwrap(), "--model", "OrnsteinUhlenbeck",
+        "--asset-identifier-pattern", "NEW_ENTRY", "--update-config", config_path.to_str().unwrap()], None);
+    assert!(!csv.status.success());
+    assert_eq!(std::fs::read_to_string(&config_path).unwrap(), contents);
+}
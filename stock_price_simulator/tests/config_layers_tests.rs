@@ -0,0 +1,204 @@
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use stock_price_simulator::api_interface::simulate_stock_with_config;
+use stock_price_simulator::config::{load_config, save_config};
+use stock_price_simulator::config_layers::load_layered_config;
+use stock_price_simulator::config_store::{ConfigStore, ReloadOutcome};
+
+fn write(dir: &Path, name: &str, contents: &str) -> String {
+    let path = dir.join(name);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).unwrap();
+    }
+    std::fs::write(&path, contents).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+fn no_env() -> Vec<(String, String)> {
+    Vec::new()
+}
+
+const BASE: &str = r#"
+include = ["desks/rates.yaml"]
+random_seed = 1
+simulation_period_days = 252
+time_step_minutes = 1440
+
+[[asset_models]]
+asset_type = "stock"
+asset_identifier_pattern = "DEFAULT_STOCK"
+default_model = "GeometricBrownianMotion"
+[asset_models.parameters.gbm]
+drift = 0.05
+volatility = 0.2
+"#;
+
+const RATES_DESK: &str = r#"
+asset_models:
+  - asset_type: stock
+    asset_identifier_pattern: "RATES_*"
+    default_model: OrnsteinUhlenbeck
+    parameters:
+      ou: { mean_reversion_speed: 2.0, long_term_mean: 0.03, volatility: 0.01 }
+"#;
+
+const PROD_OVERLAY: &str = r#"{
+  "random_seed": 7,
+  "asset_models": [
+    { "asset_type": "stock", "asset_identifier_pattern": "DEFAULT_STOCK", "default_model": "GeometricBrownianMotion",
+      "parameters": { "gbm": { "drift": 0.04, "volatility": 0.3 } } },
+    { "asset_type": "stock", "asset_identifier_pattern": "PROD_ONLY", "default_model": "GeometricBrownianMotion",
+      "parameters": { "gbm": { "drift": 0.0, "volatility": 0.1 } } }
+  ]
+}"#;
+
+#[test]
+fn test_base_include_and_overlay_are_merged() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = write(dir.path(), "base.toml", BASE);
+    let rates = write(dir.path(), "desks/rates.yaml", RATES_DESK);
+    let prod = write(dir.path(), "prod.json", PROD_OVERLAY);
+
+    let layered = load_layered_config(&[base.as_str()], no_env()).unwrap();
+    let models = layered.config.asset_models.as_ref().unwrap();
+    assert_eq!(layered.files, vec![rates.clone(), base.clone()], "Includes are applied before the including file");
+    assert!(layered.config.include.is_empty());
+    let patterns: Vec<&str> = models.iter().map(|m| m.asset_identifier_pattern.as_str()).collect();
+    assert_eq!(patterns, ["RATES_*", "DEFAULT_STOCK"]);
+
+    let layered = load_layered_config(&[base.clone(), prod.clone()], no_env()).unwrap();
+    assert_eq!(layered.files, vec![rates, base, prod]);
+    let config = &layered.config;
+    assert_eq!(config.random_seed, Some(7));
+    assert_eq!(config.simulation_period_days, 252, "Fields the overlay leaves out keep the base value");
+    let models = config.asset_models.as_ref().unwrap();
+    let patterns: Vec<&str> = models.iter().map(|m| m.asset_identifier_pattern.as_str()).collect();
+    assert_eq!(patterns, ["RATES_*", "DEFAULT_STOCK", "PROD_ONLY"], "Same key replaces in place, new keys append");
+    assert_eq!(models[1].parameters.gbm.as_ref().unwrap().volatility, 0.3);
+}
+
+#[test]
+fn test_overlay_replaces_a_model_whose_asset_type_differs_in_case() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = write(dir.path(), "base.toml", BASE);
+    write(dir.path(), "desks/rates.yaml", RATES_DESK);
+    let overlay = write(dir.path(), "overlay.yaml", r#"
+asset_models:
+  - asset_type: Stock
+    asset_identifier_pattern: DEFAULT_STOCK
+    default_model: GeometricBrownianMotion
+    parameters:
+      gbm: { drift: 0.01, volatility: 0.15 }
+"#);
+    let config = load_layered_config(&[base, overlay], no_env()).unwrap().config;
+    let models = config.asset_models.as_ref().unwrap();
+    assert_eq!(models.len(), 2, "Stock and stock are the same asset type");
+    assert_eq!(models[1].parameters.gbm.as_ref().unwrap().volatility, 0.15);
+    assert!(stock_price_simulator::config_validation::validate_config(&config).is_empty());
+}
+
+#[test]
+fn test_environment_overrides_scalars() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = write(dir.path(), "base.toml", &BASE.replace("include = [\"desks/rates.yaml\"]\n", ""));
+    let env = vec![
+        ("STOCK_SIM_RANDOM_SEED".to_string(), "99".to_string()),
+        ("STOCK_SIM_TIME_STEP_MINUTES".to_string(), " 60 ".to_string()),
+        ("STOCK_SIM_PORT".to_string(), "not a config field".to_string()),
+    ];
+    let layered = load_layered_config(&[base.as_str()], env).unwrap();
+    assert_eq!(layered.config.random_seed, Some(99));
+    assert_eq!(layered.config.time_step_minutes, 60);
+    assert_eq!(layered.config.simulation_period_days, 252);
+    assert_eq!(layered.env_overrides, ["STOCK_SIM_RANDOM_SEED", "STOCK_SIM_TIME_STEP_MINUTES"]);
+
+    let err = load_layered_config(&[base], vec![("STOCK_SIM_RANDOM_SEED".to_string(), "-1".to_string())]).unwrap_err();
+    assert!(err.to_string().contains("STOCK_SIM_RANDOM_SEED must be a non-negative integer"), "{}", err);
+}
+
+#[test]
+fn test_layer_errors_name_the_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let a = write(dir.path(), "a.toml", "include = [\"b.toml\"]\nsimulation_period_days = 1\ntime_step_minutes = 1\n");
+    write(dir.path(), "b.toml", "include = [\"a.toml\"]\n");
+    let err = format!("{:#}", load_layered_config(&[a], no_env()).unwrap_err());
+    assert!(err.contains("Include cycle"), "{}", err);
+
+    let partial = write(dir.path(), "partial.yaml", "random_seed: 3\n");
+    let err = load_layered_config(&[partial], no_env()).unwrap_err();
+    assert!(err.to_string().contains("simulation_period_days is not set by any of"), "{}", err);
+
+    let typo = write(dir.path(), "typo.json", r#"{ "random_sed": 3 }"#);
+    let err = format!("{:#}", load_layered_config(&[typo.as_str()], no_env()).unwrap_err());
+    assert!(err.contains(&typo) && err.contains("random_sed"), "{}", err);
+
+    let missing_include = write(dir.path(), "missing.toml", "include = [\"nowhere.toml\"]\n");
+    let err = format!("{:#}", load_layered_config(&[missing_include], no_env()).unwrap_err());
+    assert!(err.contains("nowhere.toml"), "{}", err);
+}
+
+const HISTORY_DESK: &str = r#"
+asset_models:
+  - asset_type: stock
+    asset_identifier_pattern: HISTORY
+    default_model: HistoricalBootstrap
+    parameters:
+      bootstrap: { method: Iid, history_csv: data/prices.csv }
+"#;
+
+#[test]
+fn test_history_csv_is_read_once_relative_to_its_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = write(dir.path(), "base.toml", &BASE.replace("desks/rates.yaml", "desks/history.yaml"));
+    write(dir.path(), "desks/history.yaml", HISTORY_DESK);
+    let csv = write(dir.path(), "desks/data/prices.csv", "timestamp,price
+2024-01-01,100
+2024-01-02,101
+2024-01-03,99.5
+");
+
+    let config = load_layered_config(&[base.as_str()], no_env()).unwrap().config;
+    let bootstrap = config.asset_models.as_ref().unwrap().iter()
+        .find_map(|m| m.parameters.bootstrap.as_ref()).unwrap();
+    assert_eq!(bootstrap.history_returns.as_ref().map(Vec::len), Some(2));
+
+    // Requests use the returns read at load time, not the file.
+    std::fs::remove_file(&csv).unwrap();
+    assert!(simulate_stock_with_config("HISTORY", &config, 100.0, 5, 1.0, Some(1), None, None).is_ok());
+
+    let err = format!("{:#}", load_layered_config(&[base.as_str()], no_env()).unwrap_err());
+    assert!(err.contains("history.yaml") && err.contains("data/prices.csv"), "{}", err);
+}
+
+#[test]
+fn test_yaml_and_json_files_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = load_config("config.example.toml").unwrap();
+    for name in ["config.yaml", "config.yml", "config.json"] {
+        let path = dir.path().join(name);
+        let path = path.to_str().unwrap();
+        save_config(&config, path).unwrap();
+        assert_eq!(load_config(path).unwrap(), config, "{}", name);
+    }
+    let json = std::fs::read_to_string(dir.path().join("config.json")).unwrap();
+    assert!(json.trim_start().starts_with('{'));
+}
+
+#[test]
+fn test_store_reloads_when_an_included_file_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = write(dir.path(), "base.toml", BASE);
+    let rates = write(dir.path(), "desks/rates.yaml", RATES_DESK);
+    let prod = write(dir.path(), "prod.json", PROD_OVERLAY);
+    let store = ConfigStore::from_files(&[base, prod]).unwrap();
+    assert_eq!(store.files().len(), 3);
+    assert!(store.reload_if_modified().is_none());
+
+    std::fs::write(&rates, RATES_DESK.replace("volatility: 0.01", "volatility: 0.02")).unwrap();
+    let file = std::fs::File::options().write(true).open(&rates).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(5)).unwrap();
+    assert_eq!(store.reload_if_modified().unwrap().unwrap(), ReloadOutcome::Reloaded { version: 2 });
+    let config = store.config();
+    assert_eq!(config.asset_models.as_ref().unwrap()[0].parameters.ou.as_ref().unwrap().volatility, 0.02);
+}
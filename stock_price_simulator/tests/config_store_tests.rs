@@ -0,0 +1,119 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use stock_price_simulator::config::load_config;
+use stock_price_simulator::config_store::{config_hash, ConfigStore, ReloadOutcome};
+
+fn temp_config() -> (tempfile::TempDir, String) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    std::fs::copy("config.test.toml", &path).unwrap();
+    (dir, path.to_str().unwrap().to_string())
+}
+
+fn edit(path: &str, from: &str, to: &str) {
+    let contents = std::fs::read_to_string(path).unwrap();
+    assert!(contents.contains(from));
+    std::fs::write(path, contents.replacen(from, to, 1)).unwrap();
+}
+
+#[test]
+fn test_reload_swaps_in_a_new_version() {
+    let (_dir, path) = temp_config();
+    let store = ConfigStore::from_file(&path).unwrap();
+    let first = store.current();
+    assert_eq!(first.version, 1);
+    assert_eq!(first.hash, config_hash(&load_config(&path).unwrap()).unwrap());
+
+    // Comments and formatting do not change the parsed config.
+    edit(&path, "# Test-specific configuration", "# Edited comment");
+    assert_eq!(store.reload().unwrap(), ReloadOutcome::Unchanged { version: 1 });
+
+    edit(&path, "drift = 0.03", "drift = 0.04");
+    assert_eq!(store.reload().unwrap(), ReloadOutcome::Reloaded { version: 2 });
+    let second = store.current();
+    assert_ne!(second.hash, first.hash);
+    assert_eq!(second.config.asset_models.as_ref().unwrap()[0].parameters.gbm.as_ref().unwrap().drift, 0.04);
+    // Holders of the old snapshot still see the old config.
+    assert_eq!(first.config.asset_models.as_ref().unwrap()[0].parameters.gbm.as_ref().unwrap().drift, 0.03);
+}
+
+#[test]
+fn test_invalid_reload_keeps_the_current_config() {
+    let (_dir, path) = temp_config();
+    let store = ConfigStore::from_file(&path).unwrap();
+    let before = store.current();
+
+    std::fs::write(&path, "random_seed = [").unwrap();
+    assert!(store.reload().is_err());
+    assert!(store.last_error().unwrap().contains("Failed to load"));
+
+    // Parses, but the model cannot be built.
+    std::fs::copy("config.test.toml", &path).unwrap();
+    edit(&path, "[asset_models.parameters.gbm]\ndrift = 0.03\nvolatility = 0.15", "[asset_models.parameters]");
+    let error = store.reload().unwrap_err().to_string();
+    assert!(error.contains("Invalid config") && error.contains("TEST_DEFAULT"), "{}", error);
+
+    let after = store.current();
+    assert_eq!(after.version, before.version);
+    assert_eq!(after.hash, before.hash);
+
+    std::fs::copy("config.test.toml", &path).unwrap();
+    assert_eq!(store.reload().unwrap(), ReloadOutcome::Unchanged { version: 1 });
+    assert_eq!(store.last_error(), None, "A successful reload clears the error");
+}
+
+#[test]
+fn test_reload_if_modified_follows_the_file_time() {
+    let (_dir, path) = temp_config();
+    let store = ConfigStore::from_file(&path).unwrap();
+    assert!(store.reload_if_modified().is_none(), "Untouched file");
+
+    edit(&path, "volatility = 0.15", "volatility = 0.2");
+    // Make sure the new mtime differs even on filesystems with coarse timestamps.
+    let file = std::fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(5)).unwrap();
+    assert_eq!(store.reload_if_modified().unwrap().unwrap(), ReloadOutcome::Reloaded { version: 2 });
+    assert!(store.reload_if_modified().is_none(), "Each change is picked up once");
+}
+
+#[test]
+fn test_reload_picks_up_an_edited_history_csv() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv = dir.path().join("prices.csv");
+    std::fs::write(&csv, "timestamp,price\n2024-01-01,100\n2024-01-02,101\n2024-01-03,99.5\n").unwrap();
+    let path = dir.path().join("config.toml");
+    std::fs::write(&path, r#"
+simulation_period_days = 5
+time_step_minutes = 1440
+
+[[asset_models]]
+asset_type = "stock"
+asset_identifier_pattern = "HISTORY"
+default_model = "HistoricalBootstrap"
+
+[asset_models.parameters.bootstrap]
+method = "Iid"
+history_csv = "prices.csv"
+"#).unwrap();
+    let path = path.to_str().unwrap().to_string();
+    let store = ConfigStore::from_file(&path).unwrap();
+    assert_eq!(store.files(), vec![path.clone(), csv.to_str().unwrap().to_string()]);
+    let first_return = |store: &ConfigStore| store.config().asset_models.as_ref().unwrap()[0]
+        .parameters.bootstrap.as_ref().unwrap().history_returns.as_ref().unwrap()[0];
+    assert!((first_return(&store) - (101.0f64 / 100.0).ln()).abs() < 1e-12);
+
+    std::fs::write(&csv, "timestamp,price\n2024-01-01,100\n2024-01-02,102\n2024-01-03,99.5\n").unwrap();
+    let file = std::fs::File::options().write(true).open(&csv).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(5)).unwrap();
+    assert_eq!(store.reload_if_modified().unwrap().unwrap(), ReloadOutcome::Reloaded { version: 2 });
+    assert!((first_return(&store) - (102.0f64 / 100.0).ln()).abs() < 1e-12);
+}
+
+#[test]
+fn test_store_without_file_cannot_reload() {
+    let store = Arc::new(ConfigStore::new(load_config("config.test.toml").unwrap(), None).unwrap());
+    assert!(store.reload().is_err());
+    assert!(store.reload_if_modified().is_none());
+    assert!(ConfigStore::from_file("does_not_exist.toml").is_err());
+}
@@ -81,3 +81,30 @@ time_step_minutes = 5
     assert_eq!(loaded_config.time_step_minutes, 5);
     assert!(loaded_config.asset_models.is_none());
 }
+
+#[test]
+fn test_save_config_round_trip_with_calibrated_model() {
+    use stock_price_simulator::config::{save_config, upsert_asset_model, AssetModelConfig, ModelParameters, OrnsteinUhlenbeckParams};
+
+    let mut temp_file = NamedTempFile::new().unwrap();
+    temp_file.write_all(b"random_seed = 1\nsimulation_period_days = 10\ntime_step_minutes = 1440\n").unwrap();
+    let path = temp_file.path().to_str().unwrap();
+    let mut config = load_config(path).unwrap();
+
+    let fitted = AssetModelConfig {
+        asset_type: "stock".to_string(),
+        asset_identifier_pattern: "SPREAD".to_string(),
+        default_model: ModelType::OrnsteinUhlenbeck,
+        parameters: ModelParameters {
+            ou: Some(OrnsteinUhlenbeckParams { mean_reversion_speed: 2.0, long_term_mean: 1.5, volatility: 0.3 }),
+            ..Default::default()
+        },
+    };
+    upsert_asset_model(&mut config, fitted.clone());
+    upsert_asset_model(&mut config, fitted.clone()); // Same key replaces, does not duplicate
+    save_config(&config, path).unwrap();
+
+    let reloaded = load_config(path).unwrap();
+    assert_eq!(reloaded, config);
+    assert_eq!(reloaded.asset_models.unwrap(), vec![fitted]);
+}
@@ -0,0 +1,164 @@
+use stock_price_simulator::api_interface;
+use stock_price_simulator::config::{load_config, parse_config};
+use stock_price_simulator::config_validation::validate_config;
+
+const HEADER: &str = "random_seed = 1\nsimulation_period_days = 10\ntime_step_minutes = 1440\n";
+
+fn parse(entries: &str) -> stock_price_simulator::config::GlobalConfig {
+    parse_config(&format!("{}{}", HEADER, entries)).unwrap()
+}
+
+#[test]
+fn test_bundled_configs_are_clean() {
+    for path in ["config.example.toml", "config.test.toml"] {
+        let diagnostics = validate_config(&load_config(path).unwrap());
+        assert!(diagnostics.is_empty(), "{}: {:?}", path, diagnostics);
+    }
+}
+
+#[test]
+fn test_missing_parameters_and_negative_volatility() {
+    let config = parse(r#"
+[[asset_models]]
+asset_type = "stock"
+asset_identifier_pattern = "NO_GBM"
+default_model = "GeometricBrownianMotion"
+[asset_models.parameters.ou]
+mean_reversion_speed = 1.0
+long_term_mean = 100.0
+volatility = 5.0
+
+[[asset_models]]
+asset_type = "stock"
+asset_identifier_pattern = "NEGATIVE"
+default_model = "GeometricBrownianMotion"
+[asset_models.parameters.gbm]
+drift = 0.05
+volatility = -0.2
+
+[[asset_models]]
+asset_type = "stock"
+asset_identifier_pattern = "REGIMES"
+default_model = "RegimeSwitching"
+[asset_models.parameters.regime_switching]
+transition_matrix = [[0.9, 0.1], [0.2, 0.8]]
+[[asset_models.parameters.regime_switching.regimes]]
+drift = 0.1
+volatility = 0.15
+[[asset_models.parameters.regime_switching.regimes]]
+drift = -0.2
+volatility = nan
+"#);
+    let diagnostics = validate_config(&config);
+    assert_eq!(diagnostics.len(), 3, "{:?}", diagnostics);
+
+    assert_eq!(diagnostics[0].index, Some(0));
+    assert_eq!(diagnostics[0].field, "parameters.gbm");
+    assert_eq!(
+        diagnostics[0].to_string(),
+        "asset_models[0] (stock 'NO_GBM'): parameters.gbm: missing, but default_model GeometricBrownianMotion needs it"
+    );
+    assert_eq!(diagnostics[1].index, Some(1));
+    assert_eq!(diagnostics[1].field, "parameters.gbm.volatility");
+    assert_eq!(diagnostics[1].asset_identifier_pattern.as_deref(), Some("NEGATIVE"));
+    assert_eq!(diagnostics[2].field, "parameters.regime_switching.regimes[1].volatility");
+
+    // The server's startup check reports the same problems and does not build the broken entries.
+    let problems = api_interface::check_asset_models(&config);
+    assert_eq!(problems, diagnostics.iter().map(|d| d.to_string()).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_duplicates_and_top_level_settings() {
+    let config = parse_config(r#"
+random_seed = 1
+simulation_period_days = 0
+time_step_minutes = 1440
+
+[[asset_models]]
+asset_type = "stock"
+asset_identifier_pattern = "AAPL"
+default_model = "GeometricBrownianMotion"
+[asset_models.parameters.gbm]
+drift = 0.05
+volatility = 0.2
+
+[[asset_models]]
+asset_type = "Stock"
+asset_identifier_pattern = "AAPL"
+default_model = "GeometricBrownianMotion"
+[asset_models.parameters.gbm]
+drift = 0.07
+volatility = 0.3
+
+[[asset_models]]
+asset_type = "future"
+asset_identifier_pattern = "AAPL"
+default_model = "GeometricBrownianMotion"
+[asset_models.parameters.gbm]
+drift = 0.07
+volatility = 0.3
+"#).unwrap();
+    let diagnostics = validate_config(&config);
+    assert_eq!(diagnostics.len(), 2, "{:?}", diagnostics);
+    assert_eq!(diagnostics[0].index, None);
+    assert_eq!(diagnostics[0].to_string(), "simulation_period_days: must be positive");
+    assert_eq!(diagnostics[1].index, Some(1));
+    assert!(diagnostics[1].message.contains("duplicates asset_models[0]"), "{}", diagnostics[1]);
+}
+
+#[test]
+fn test_unknown_keys_are_rejected_with_the_entry_index() {
+    let err = parse_config(&format!("{}extra_setting = 1\n", HEADER)).unwrap_err();
+    assert!(err.to_string().contains("unknown field `extra_setting`"), "{}", err);
+
+    let err = parse_config(&format!("{}{}", HEADER, r#"
+[[asset_models]]
+asset_type = "stock"
+asset_identifier_pattern = "FINE"
+default_model = "GeometricBrownianMotion"
+[asset_models.parameters.gbm]
+drift = 0.05
+volatility = 0.2
+
+[[asset_models]]
+asset_type = "stock"
+asset_identifier_pattern = "TYPO"
+default_model = "GeometricBrownianMotion"
+[asset_models.parameters.gbm]
+drift = 0.05
+volatilty = 0.2
+"#)).unwrap_err();
+    let message = err.to_string();
+    assert!(message.starts_with("In asset_models[1]:"), "{}", message);
+    assert!(message.contains("volatilty"), "{}", message);
+
+    let err = parse_config(&format!("{}{}", HEADER, r#"
+[[asset_models]]
+asset_type = "stock"
+asset_identifier_pattern = "TAILS"
+default_model = "GeometricBrownianMotion"
+[asset_models.parameters.gbm]
+drift = 0.05
+volatility = 0.2
+[asset_models.parameters.innovations]
+distribution = "StudentT"
+degrees_of_freedom = 5.0
+skew = 0.1
+"#)).unwrap_err();
+    assert!(err.to_string().contains("skew"), "{}", err);
+
+    // The same entry without the stray key parses.
+    parse(r#"
+[[asset_models]]
+asset_type = "stock"
+asset_identifier_pattern = "TAILS"
+default_model = "GeometricBrownianMotion"
+[asset_models.parameters.gbm]
+drift = 0.05
+volatility = 0.2
+[asset_models.parameters.innovations]
+distribution = "StudentT"
+degrees_of_freedom = 5.0
+"#);
+}
@@ -0,0 +1,182 @@
+use serde_json::json;
+use stock_price_simulator::api_interface::{price_european_option_monte_carlo, MonteCarloEuropeanOptionInput};
+use stock_price_simulator::asset_matching::find_asset_model;
+use stock_price_simulator::config::{parse_config, GlobalConfig};
+use stock_price_simulator::error::{classify, deserialize_request, within, ErrorCode, SimulationError};
+use stock_price_simulator::etf_simulation::{simulate_etf_nav, EtfConstituent, EtfDefinition};
+use stock_price_simulator::futures_simulation::{simulate_futures_price, FuturesContract};
+use stock_price_simulator::instruments::{resolve_instrument, InstrumentKind};
+use stock_price_simulator::option_pricing::{black_scholes_price, EuropeanOption, OptionType};
+use stock_price_simulator::stock_simulation::StockSimulator;
+
+fn code_and_field(error: &anyhow::Error) -> (ErrorCode, Option<String>) {
+    let error = classify(error);
+    (error.code, error.field)
+}
+
+fn constituent(symbol: &str, weight: f64) -> EtfConstituent {
+    EtfConstituent {
+        symbol: symbol.to_string(),
+        initial_price: 100.0,
+        drift: 0.05,
+        volatility: 0.2,
+        weight,
+        regime_switching: None,
+        currency: None,
+    }
+}
+
+#[test]
+fn test_validation_errors_name_the_offending_field() {
+    let err = StockSimulator::simulate_stock_price(100.0, 0.05, -0.2, 10, 1.0, Some(1)).unwrap_err();
+    assert_eq!(code_and_field(&err), (ErrorCode::InvalidParameter, Some("volatility".to_string())));
+    assert_eq!(err.to_string(), "Volatility cannot be negative.", "Messages are unchanged");
+
+    let option = EuropeanOption {
+        underlying_price: 100.0,
+        strike_price: 100.0,
+        time_to_maturity_years: -1.0,
+        risk_free_rate: Some(0.02),
+        volatility: 0.2,
+        option_type: OptionType::Call,
+        discount_curve: None,
+    };
+    let err = black_scholes_price(&option).unwrap_err();
+    assert_eq!(code_and_field(&err), (ErrorCode::InvalidParameter, Some("time_to_maturity_years".to_string())));
+
+    let monte_carlo = MonteCarloEuropeanOptionInput {
+        underlying_initial_price: 100.0,
+        strike_price: 100.0,
+        time_to_maturity_years: 1.0,
+        risk_free_rate: Some(0.02),
+        underlying_volatility: 0.2,
+        option_type: OptionType::Put,
+        num_paths: 0,
+        num_steps_per_path: 10,
+        seed: Some(1),
+        discount_curve: None,
+    };
+    let err = price_european_option_monte_carlo(&monte_carlo).unwrap_err();
+    assert_eq!(code_and_field(&err), (ErrorCode::InvalidParameter, Some("num_paths".to_string())));
+
+    let future = FuturesContract {
+        underlying_symbol: "ES".to_string(),
+        initial_spot_price: 4500.0,
+        risk_free_rate: Some(0.05),
+        volatility: 0.2,
+        time_to_maturity_days: 30,
+        time_step_days: 0.0,
+        seed: Some(1),
+        discount_curve: None,
+    };
+    let err = simulate_futures_price(&future).unwrap_err();
+    assert_eq!(code_and_field(&err), (ErrorCode::InvalidParameter, Some("time_step_days".to_string())));
+
+    let etf = EtfDefinition {
+        constituents: vec![constituent("A", 1.2), constituent("B", -0.2)],
+        simulation_days: 5,
+        time_step_days: 1.0,
+        seed: Some(1),
+        fx: None,
+        expense_ratio: 0.0,
+        market: None,
+        benchmark: None,
+    };
+    let err = simulate_etf_nav(&etf).unwrap_err();
+    assert_eq!(code_and_field(&err), (ErrorCode::InvalidParameter, Some("constituents[1].weight".to_string())));
+    assert_eq!(err.to_string(), "Constituent 'B' weight cannot be negative.");
+}
+
+#[test]
+fn test_unreadable_requests_report_the_path() {
+    let err = deserialize_request::<EtfDefinition, _>(json!({
+        "simulation_days": 5,
+        "time_step_days": 1.0,
+        "seed": null,
+        "constituents": [{ "symbol": "A", "initial_price": 10.0, "drift": 0.0, "volatility": 0.1 }],
+    }), "Invalid ETF request").unwrap_err();
+    assert_eq!(code_and_field(&err), (ErrorCode::MissingParameter, Some("constituents[0].weight".to_string())));
+    assert_eq!(err.to_string(), "Invalid ETF request: missing field `weight`");
+
+    let err = deserialize_request::<FuturesContract, _>(json!({
+        "underlying_symbol": "ES",
+        "initial_spot_price": "a lot",
+    }), "Invalid future request").unwrap_err();
+    assert_eq!(code_and_field(&err), (ErrorCode::InvalidRequest, Some("initial_spot_price".to_string())));
+}
+
+#[test]
+fn test_classify_looks_through_context() {
+    let err = StockSimulator::simulate_stock_price(-1.0, 0.05, 0.2, 10, 1.0, None).unwrap_err()
+        .context("Simulating ACME");
+    let classified = classify(&err);
+    assert_eq!(classified.code, ErrorCode::InvalidParameter);
+    assert_eq!(classified.field.as_deref(), Some("initial_price"));
+    assert_eq!(classified.message, "Simulating ACME", "The message is that of the whole error");
+
+    let untyped = classify(&anyhow::anyhow!("Something else"));
+    assert_eq!(untyped, SimulationError::new(ErrorCode::SimulationFailed, None, "Something else"));
+
+    let nested = within("pair", stock_price_simulator::error::invalid_parameter("spot_rate", "FX spot rate must be positive."));
+    assert_eq!(classify(&nested).field.as_deref(), Some("pair.spot_rate"));
+}
+
+#[test]
+fn test_unknown_assets_and_bad_configs() {
+    let config: GlobalConfig = parse_config("simulation_period_days = 10\ntime_step_minutes = 1440\n").unwrap();
+    let err = find_asset_model(&config, "stock", "ACME").unwrap_err();
+    assert_eq!(code_and_field(&err), (ErrorCode::UnknownAsset, Some("asset_identifier".to_string())));
+
+    let err = resolve_instrument(&config, InstrumentKind::Future, json!({ "asset_identifier": "ESZ24" })).unwrap_err();
+    assert_eq!(code_and_field(&err), (ErrorCode::UnknownAsset, Some("asset_identifier".to_string())));
+
+    let err = parse_config(r#"
+simulation_period_days = 10
+time_step_minutes = 1440
+
+[[asset_models]]
+asset_identifier_pattern = "ACME"
+asset_type = "stock"
+default_model = "NoSuchModel"
+"#).unwrap_err();
+    assert_eq!(code_and_field(&err), (ErrorCode::InvalidConfig, Some("asset_models[0]".to_string())));
+}
+
+#[test]
+fn test_model_and_pricer_errors_are_typed() {
+    use stock_price_simulator::bootstrap::{HistoricalBootstrap, ResamplingScheme};
+    use stock_price_simulator::calibration::calibrate_gbm;
+    use stock_price_simulator::config::{GarchParams, LocalVolGridParams, LocalVolatilityParams};
+    use stock_price_simulator::finite_difference::FiniteDifferenceOptionPricer;
+    use stock_price_simulator::local_volatility::LocalVolatilityProcess;
+    use stock_price_simulator::volatility_models::{fit_garch, Garch11};
+
+    let field = |error: anyhow::Error| code_and_field(&error);
+    let config_field = |name: &str| (ErrorCode::InvalidConfig, Some(name.to_string()));
+    let parameter_field = |name: &str| (ErrorCode::InvalidParameter, Some(name.to_string()));
+
+    let garch = GarchParams { mean_return: 0.0, omega: 1e-6, alpha: 0.6, beta: 0.5, initial_variance: None, history_time_step_days: 1.0 };
+    assert_eq!(field(Garch11::from_params(&garch).unwrap_err()), config_field("beta"));
+    let filtered = ResamplingScheme::FilteredHistorical { garch };
+    assert_eq!(field(HistoricalBootstrap::new(vec![0.01, -0.01], filtered, 1.0).unwrap_err()), config_field("garch.beta"));
+    assert_eq!(field(HistoricalBootstrap::new(vec![0.01], ResamplingScheme::Iid, 1.0).unwrap_err()), config_field("log_returns"));
+    assert_eq!(field(fit_garch(&[0.01; 10], 1.0).unwrap_err()), parameter_field("returns"));
+
+    let grid = LocalVolGridParams { spots: vec![120.0, 80.0], times_years: vec![0.0], volatilities: vec![vec![0.2, 0.2]] };
+    let local_vol = LocalVolatilityParams { drift: 0.0, grid: Some(grid), implied_surface: None };
+    assert_eq!(field(LocalVolatilityProcess::from_params(&local_vol).unwrap_err()), config_field("grid.spots"));
+
+    let option = EuropeanOption {
+        underlying_price: 100.0,
+        strike_price: 100.0,
+        time_to_maturity_years: 1.0,
+        risk_free_rate: Some(0.02),
+        volatility: 0.2,
+        option_type: OptionType::Call,
+        discount_curve: None,
+    };
+    let pricer = FiniteDifferenceOptionPricer::for_european_option(&option, 2, 10).unwrap();
+    assert_eq!(field(pricer.solve().unwrap_err()), parameter_field("spot_steps"));
+
+    assert_eq!(field(calibrate_gbm(&[100.0, 101.0, 102.0], 0.0).unwrap_err()), parameter_field("time_step_days"));
+}
@@ -1,4 +1,5 @@
-use stock_price_simulator::etf_simulation::{EtfConstituent, EtfDefinition, simulate_etf_nav};
+use stock_price_simulator::etf_simulation::{EtfConstituent, EtfDefinition, EtfBenchmark, EtfFxSettings, EtfMarketParams, simulate_etf_nav, simulate_etf_with_market};
+use stock_price_simulator::fx_simulation::FxPair;
 
 const NAV_PRICE_ACCURACY: f64 = 1e-9;
 
@@ -10,12 +11,18 @@ fn test_simulate_etf_nav_single_constituent() {
         drift: 0.1,
         volatility: 0.2,
         weight: 1.0,
+        regime_switching: None,
+        currency: None,
     };
     let etf_def = EtfDefinition {
         constituents: vec![constituent1.clone()],
         simulation_days: 10,
         time_step_days: 1.0,
         seed: Some(123),
+        fx: None,
+        expense_ratio: 0.0,
+        market: None,
+        benchmark: None,
     };
 
     let etf_nav_result = simulate_etf_nav(&etf_def).unwrap();
@@ -36,7 +43,7 @@ fn test_simulate_etf_nav_single_constituent() {
         constituent1.volatility,
         etf_def.simulation_days,
         etf_def.time_step_days,
-        etf_def.seed.map(|s| s + 0), // Same seed derivation as in simulate_etf_nav
+        etf_def.seed, // Same seed derivation as in simulate_etf_nav (constituent 0 uses seed + 0)
     ).unwrap();
 
     assert_eq!(etf_nav_result.prices.len(), stock_a_path.prices.len());
@@ -51,20 +58,28 @@ fn test_simulate_etf_nav_single_constituent() {
 #[test]
 fn test_simulate_etf_nav_deterministic() {
     let constituents = vec![
-        EtfConstituent { symbol: "A".to_string(), initial_price: 100.0, drift: 0.1, volatility: 0.2, weight: 0.5 },
-        EtfConstituent { symbol: "B".to_string(), initial_price: 50.0, drift: 0.05, volatility: 0.15, weight: 0.5 },
+        EtfConstituent { symbol: "A".to_string(), initial_price: 100.0, drift: 0.1, volatility: 0.2, weight: 0.5, regime_switching: None, currency: None },
+        EtfConstituent { symbol: "B".to_string(), initial_price: 50.0, drift: 0.05, volatility: 0.15, weight: 0.5, regime_switching: None, currency: None },
     ];
     let etf_def1 = EtfDefinition {
         constituents: constituents.clone(),
         simulation_days: 5,
         time_step_days: 1.0,
         seed: Some(42),
+        fx: None,
+        expense_ratio: 0.0,
+        market: None,
+        benchmark: None,
     };
     let etf_def2 = EtfDefinition { // Same params and seed
         constituents: constituents.clone(),
         simulation_days: 5,
         time_step_days: 1.0,
         seed: Some(42),
+        fx: None,
+        expense_ratio: 0.0,
+        market: None,
+        benchmark: None,
     };
 
     let result1 = simulate_etf_nav(&etf_def1).unwrap();
@@ -78,11 +93,11 @@ fn test_simulate_etf_nav_deterministic() {
 fn test_simulate_etf_nav_output_length() {
     let etf_def = EtfDefinition {
         constituents: vec![
-            EtfConstituent { symbol: "C".to_string(), initial_price: 100.0, drift: 0.1, volatility: 0.2, weight: 1.0 }
+            EtfConstituent { symbol: "C".to_string(), initial_price: 100.0, drift: 0.1, volatility: 0.2, weight: 1.0, regime_switching: None, currency: None }
         ],
         simulation_days: 20,
         time_step_days: 0.5,
-        seed: None,
+        seed: None, fx: None, expense_ratio: 0.0, market: None, benchmark: None
     };
     // simulate_stock_price with 'days' = 20 produces 20 data points.
     let expected_data_points = etf_def.simulation_days;
@@ -96,8 +111,8 @@ fn test_simulate_etf_nav_basic_sanity_check() {
     // If all stocks go up, NAV should go up (assuming positive weights)
     // This is hard to guarantee with GBM, but let's test initial NAV calculation.
     let constituents = vec![
-        EtfConstituent { symbol: "UP1".to_string(), initial_price: 10.0, drift: 0.1, volatility: 0.001, weight: 0.7 },
-        EtfConstituent { symbol: "UP2".to_string(), initial_price: 20.0, drift: 0.1, volatility: 0.001, weight: 0.3 },
+        EtfConstituent { symbol: "UP1".to_string(), initial_price: 10.0, drift: 0.1, volatility: 0.001, weight: 0.7, regime_switching: None, currency: None },
+        EtfConstituent { symbol: "UP2".to_string(), initial_price: 20.0, drift: 0.1, volatility: 0.001, weight: 0.3, regime_switching: None, currency: None },
     ];
      // Low volatility, positive drift means prices are very likely to go up for a few steps.
     let etf_def = EtfDefinition {
@@ -105,6 +120,10 @@ fn test_simulate_etf_nav_basic_sanity_check() {
         simulation_days: 3, // Few steps
         time_step_days: 1.0,
         seed: Some(777),
+        fx: None,
+        expense_ratio: 0.0,
+        market: None,
+        benchmark: None,
     };
     let result = simulate_etf_nav(&etf_def).unwrap();
 
@@ -130,36 +149,323 @@ fn test_simulate_etf_nav_basic_sanity_check() {
 fn test_etf_invalid_inputs() {
     assert!(simulate_etf_nav(&EtfDefinition {
         constituents: vec![], // Empty constituents
-        simulation_days: 10, time_step_days: 1.0, seed: None
+        simulation_days: 10, time_step_days: 1.0, seed: None, fx: None, expense_ratio: 0.0, market: None, benchmark: None
     }).is_err(), "Empty constituents list should be an error.");
 
     assert!(simulate_etf_nav(&EtfDefinition {
-        constituents: vec![ EtfConstituent { symbol: "A".into(), initial_price: 100.0, drift: 0.1, volatility: 0.2, weight: 0.5 } ],
-        simulation_days: 10, time_step_days: 1.0, seed: None
+        constituents: vec![ EtfConstituent { symbol: "A".into(), initial_price: 100.0, drift: 0.1, volatility: 0.2, weight: 0.5, regime_switching: None, currency: None } ],
+        simulation_days: 10, time_step_days: 1.0, seed: None, fx: None, expense_ratio: 0.0, market: None, benchmark: None
     }).is_err(), "Sum of weights not close to 1.0 should be an error.");
 
     assert!(simulate_etf_nav(&EtfDefinition {
-        constituents: vec![ EtfConstituent { symbol: "A".into(), initial_price: 100.0, drift: 0.1, volatility: 0.2, weight: 1.0 } ],
-        simulation_days: 0, time_step_days: 1.0, seed: None // simulation_days = 0
+        constituents: vec![ EtfConstituent { symbol: "A".into(), initial_price: 100.0, drift: 0.1, volatility: 0.2, weight: 1.0, regime_switching: None, currency: None } ],
+        simulation_days: 0, time_step_days: 1.0, seed: None, fx: None, expense_ratio: 0.0, market: None, benchmark: None // simulation_days = 0
     }).is_err(), "Simulation days = 0 should be an error.");
 
     assert!(simulate_etf_nav(&EtfDefinition {
-        constituents: vec![ EtfConstituent { symbol: "A".into(), initial_price: 100.0, drift: 0.1, volatility: 0.2, weight: 1.0 } ],
-        simulation_days: 10, time_step_days: 0.0, seed: None // time_step_days = 0
+        constituents: vec![ EtfConstituent { symbol: "A".into(), initial_price: 100.0, drift: 0.1, volatility: 0.2, weight: 1.0, regime_switching: None, currency: None } ],
+        simulation_days: 10, time_step_days: 0.0, seed: None, fx: None, expense_ratio: 0.0, market: None, benchmark: None // time_step_days = 0
     }).is_err(), "Time step days = 0 should be an error.");
 
     assert!(simulate_etf_nav(&EtfDefinition {
-        constituents: vec![ EtfConstituent { symbol: "A".into(), initial_price: -10.0, drift: 0.1, volatility: 0.2, weight: 1.0 } ],
-        simulation_days: 10, time_step_days: 1.0, seed: None
+        constituents: vec![ EtfConstituent { symbol: "A".into(), initial_price: -10.0, drift: 0.1, volatility: 0.2, weight: 1.0, regime_switching: None, currency: None } ],
+        simulation_days: 10, time_step_days: 1.0, seed: None, fx: None, expense_ratio: 0.0, market: None, benchmark: None
     }).is_err(), "Negative initial price for constituent should be an error.");
 
     assert!(simulate_etf_nav(&EtfDefinition {
-        constituents: vec![ EtfConstituent { symbol: "A".into(), initial_price: 10.0, drift: 0.1, volatility: -0.2, weight: 1.0 } ],
-        simulation_days: 10, time_step_days: 1.0, seed: None
+        constituents: vec![ EtfConstituent { symbol: "A".into(), initial_price: 10.0, drift: 0.1, volatility: -0.2, weight: 1.0, regime_switching: None, currency: None } ],
+        simulation_days: 10, time_step_days: 1.0, seed: None, fx: None, expense_ratio: 0.0, market: None, benchmark: None
     }).is_err(), "Negative volatility for constituent should be an error.");
 
     assert!(simulate_etf_nav(&EtfDefinition {
-        constituents: vec![ EtfConstituent { symbol: "A".into(), initial_price: 10.0, drift: 0.1, volatility: 0.2, weight: -0.1 } ],
-        simulation_days: 10, time_step_days: 1.0, seed: None
+        constituents: vec![ EtfConstituent { symbol: "A".into(), initial_price: 10.0, drift: 0.1, volatility: 0.2, weight: -0.1, regime_switching: None, currency: None } ],
+        simulation_days: 10, time_step_days: 1.0, seed: None, fx: None, expense_ratio: 0.0, market: None, benchmark: None
     }).is_err(), "Negative weight for constituent should be an error.");
 }
+
+fn eur_usd(volatility: f64) -> FxPair {
+    FxPair {
+        base_currency: "EUR".to_string(),
+        quote_currency: "USD".to_string(),
+        spot_rate: 1.10,
+        domestic_rate: Some(0.05),
+        foreign_rate: Some(0.02),
+        volatility,
+        domestic_curve: None,
+        foreign_curve: None,
+        pip_size: 0.0001,
+    }
+}
+
+// A USD fund holding a flat-priced European stock and a flat-priced US stock.
+fn international_etf(currency_hedged: bool) -> EtfDefinition {
+    let flat = |symbol: &str, currency: Option<&str>, weight: f64| EtfConstituent {
+        symbol: symbol.to_string(), initial_price: 40.0, drift: 0.0, volatility: 0.0, weight,
+        regime_switching: None, currency: currency.map(str::to_string),
+    };
+    EtfDefinition {
+        constituents: vec![flat("SAP", Some("EUR"), 0.6), flat("AAPL", Some("USD"), 0.4)],
+        simulation_days: 252,
+        time_step_days: 1.0,
+        seed: Some(8),
+        fx: Some(EtfFxSettings { base_currency: "USD".to_string(), fx_pairs: vec![eur_usd(0.1)], currency_hedged }),
+        expense_ratio: 0.0,
+        market: None,
+        benchmark: None,
+    }
+}
+
+#[test]
+fn test_multi_currency_etf_nav_converts_at_simulated_fx() {
+    let simulation = simulate_etf_with_market(&international_etf(false)).unwrap();
+    let eur_usd_path = &simulation.fx_rates["EUR"];
+    assert_eq!(simulation.fx_rates.len(), 1, "USD constituents need no FX path");
+    assert_eq!(eur_usd_path.len(), 252);
+    assert_eq!(eur_usd_path[0], 1.10);
+
+    // Local prices are flat, so the NAV only moves with EUR/USD: currency drag or boost.
+    for (nav, fx) in simulation.nav.prices.iter().zip(eur_usd_path) {
+        let expected = 0.6 * fx / 1.10 + 0.4;
+        assert!((nav - expected).abs() < NAV_PRICE_ACCURACY, "{} vs {}", nav, expected);
+    }
+    assert!(eur_usd_path.iter().any(|fx| (fx - 1.10).abs() > 0.01));
+}
+
+#[test]
+fn test_currency_hedged_share_class_earns_carry_only() {
+    let hedged = simulate_etf_with_market(&international_etf(true)).unwrap();
+    let unhedged = simulate_etf_with_market(&international_etf(false)).unwrap();
+    assert_eq!(hedged.fx_rates, unhedged.fx_rates, "Both share classes see the same FX market");
+
+    for (i, nav) in hedged.nav.prices.iter().enumerate() {
+        let t = i as f64 / 252.0;
+        let expected = 0.6 * ((0.05f64 - 0.02) * t).exp() + 0.4;
+        assert!((nav - expected).abs() < NAV_PRICE_ACCURACY, "step {}: {} vs {}", i, nav, expected);
+    }
+}
+
+#[test]
+fn test_multi_currency_etf_validation() {
+    let mut missing_pair = international_etf(false);
+    missing_pair.fx.as_mut().unwrap().fx_pairs.clear();
+    assert!(simulate_etf_nav(&missing_pair).is_err(), "EUR constituent without an EUR/USD pair");
+
+    let mut wrong_quote = international_etf(false);
+    wrong_quote.fx.as_mut().unwrap().base_currency = "GBP".to_string();
+    assert!(simulate_etf_nav(&wrong_quote).is_err(), "Pairs must be quoted in the fund currency");
+
+    let no_settings = EtfDefinition { fx: None, ..international_etf(false) };
+    assert!(simulate_etf_nav(&no_settings).is_err(), "Constituent currencies need fx settings");
+
+    let mut duplicate = international_etf(false);
+    duplicate.fx.as_mut().unwrap().fx_pairs.push(eur_usd(0.2));
+    assert!(simulate_etf_nav(&duplicate).is_err());
+
+    let json = r#"{
+        "constituents": [{ "symbol": "SAP", "initial_price": 40.0, "drift": 0.0, "volatility": 0.0, "weight": 1.0, "currency": "EUR" }],
+        "simulation_days": 5, "time_step_days": 1.0, "seed": 1,
+        "fx": { "base_currency": "USD", "currency_hedged": true, "fx_pairs": [
+            { "base_currency": "EUR", "quote_currency": "USD", "spot_rate": 1.1, "domestic_rate": 0.05, "foreign_rate": 0.02, "volatility": 0.1 }
+        ] }
+    }"#;
+    let etf_def: EtfDefinition = serde_json::from_str(json).unwrap();
+    assert_eq!(simulate_etf_nav(&etf_def).unwrap().prices.len(), 5);
+}
+
+fn flat_etf(expense_ratio: f64, market: Option<EtfMarketParams>) -> EtfDefinition {
+    EtfDefinition {
+        constituents: vec![EtfConstituent {
+            symbol: "FLAT".to_string(), initial_price: 100.0, drift: 0.0, volatility: 0.0, weight: 1.0,
+            regime_switching: None, currency: None,
+        }],
+        simulation_days: 252,
+        time_step_days: 1.0,
+        seed: Some(5),
+        fx: None,
+        expense_ratio,
+        market,
+        benchmark: None,
+    }
+}
+
+fn market_params(premium_volatility: f64) -> EtfMarketParams {
+    EtfMarketParams {
+        initial_premium: 0.0,
+        premium_mean_reversion: 5.0,
+        premium_volatility,
+        creation_unit_size: 50_000,
+        creation_cost_bps: 10.0,
+        redemption_cost_bps: 15.0,
+        initial_shares_outstanding: 20_000_000,
+        premium_impact_bps_per_unit: 1.0,
+    }
+}
+
+#[test]
+fn test_expense_ratio_accrues_against_nav() {
+    let simulation = simulate_etf_with_market(&flat_etf(0.01, None)).unwrap();
+    let nav = &simulation.nav.prices;
+    assert_eq!(nav[0], 1.0);
+    for (i, (net, gross)) in nav.iter().zip(&simulation.gross_nav).enumerate() {
+        assert!((gross - 1.0).abs() < NAV_PRICE_ACCURACY);
+        assert!((net - (-0.01 * i as f64 / 252.0f64).exp()).abs() < NAV_PRICE_ACCURACY, "step {}", i);
+    }
+    // About one year of fees: close to, and slightly less than, the 1% expense ratio.
+    let drag = 1.0 - nav[nav.len() - 1];
+    assert!(drag > 0.0098 && drag < 0.01, "{}", drag);
+
+    // A step longer than a year at a high ratio still leaves positive, falling NAV.
+    let long_steps = simulate_etf_with_market(&EtfDefinition { time_step_days: 500.0, ..flat_etf(0.9, None) }).unwrap();
+    assert!(long_steps.nav.prices.windows(2).all(|w| w[1] > 0.0 && w[1] < w[0]), "{:?}", long_steps.nav.prices);
+
+    let no_fees = simulate_etf_with_market(&flat_etf(0.0, None)).unwrap();
+    assert_eq!(no_fees.nav.prices, no_fees.gross_nav);
+    assert!(no_fees.market.is_none());
+}
+
+#[test]
+fn test_ap_arbitrage_keeps_premium_within_cost_band() {
+    let simulation = simulate_etf_with_market(&flat_etf(0.0, Some(market_params(0.01)))).unwrap();
+    let market = simulation.market.as_ref().unwrap();
+    assert_eq!(market.market_prices.len(), simulation.nav.prices.len());
+
+    let mut shares = 20_000_000i64;
+    for i in 0..market.premiums.len() {
+        let premium = market.premiums[i];
+        assert!((market.market_prices[i] - simulation.nav.prices[i] * (1.0 + premium)).abs() < NAV_PRICE_ACCURACY);
+        assert!((-15e-4 - 1e-12..=10e-4 + 1e-12).contains(&premium), "premium {} at step {}", premium, i);
+        shares += market.net_creation_units[i] * 50_000;
+        assert_eq!(market.shares_outstanding[i] as i64, shares, "step {}", i);
+    }
+    assert!(market.net_creation_units.iter().any(|u| *u > 0), "Premiums above the creation cost trigger creations");
+    assert!(market.net_creation_units.iter().any(|u| *u < 0), "Discounts below the redemption cost trigger redemptions");
+
+    // Without noise or arbitrage pressure the premium just decays towards zero.
+    let quiet = simulate_etf_with_market(&flat_etf(0.0, Some(EtfMarketParams { initial_premium: 0.0008, ..market_params(0.0) }))).unwrap();
+    let premiums = &quiet.market.unwrap().premiums;
+    assert!((premiums[1] - 0.0008 * (-5.0f64 / 252.0).exp()).abs() < 1e-15);
+    assert!(premiums.windows(2).all(|w| w[1] < w[0]));
+
+    assert_eq!(simulation.market, simulate_etf_with_market(&flat_etf(0.0, Some(market_params(0.01)))).unwrap().market);
+}
+
+#[test]
+fn test_redemptions_are_capped_by_shares_outstanding() {
+    let params = EtfMarketParams { initial_premium: -0.05, initial_shares_outstanding: 100_000, premium_volatility: 0.0, ..market_params(0.0) };
+    let market = simulate_etf_with_market(&flat_etf(0.0, Some(params))).unwrap().market.unwrap();
+    assert_eq!(market.net_creation_units[1], -2);
+    assert_eq!(market.shares_outstanding[1], 0);
+    assert!(market.premiums[1] < -15e-4, "Nothing left to redeem, the discount persists");
+}
+
+#[test]
+fn test_invalid_expense_ratio_and_market_params() {
+    assert!(simulate_etf_nav(&flat_etf(-0.01, None)).is_err());
+    assert!(simulate_etf_nav(&flat_etf(1.0, None)).is_err());
+    assert!(simulate_etf_nav(&flat_etf(0.0, Some(EtfMarketParams { creation_unit_size: 0, ..market_params(0.01) }))).is_err());
+    assert!(simulate_etf_nav(&flat_etf(0.0, Some(EtfMarketParams { creation_cost_bps: -1.0, ..market_params(0.01) }))).is_err());
+    assert!(simulate_etf_nav(&flat_etf(0.0, Some(EtfMarketParams { premium_impact_bps_per_unit: 0.0, ..market_params(0.01) }))).is_err());
+    assert!(simulate_etf_nav(&flat_etf(0.0, Some(EtfMarketParams { premium_volatility: -0.1, ..market_params(0.01) }))).is_err());
+    assert!(simulate_etf_nav(&flat_etf(0.0, Some(EtfMarketParams { premium_volatility: f64::NAN, ..market_params(0.01) }))).is_err());
+    assert!(simulate_etf_nav(&flat_etf(0.0, Some(EtfMarketParams { initial_premium: f64::INFINITY, ..market_params(0.01) }))).is_err());
+    assert!(simulate_etf_nav(&flat_etf(0.0, Some(EtfMarketParams { premium_impact_bps_per_unit: 1e-310, ..market_params(0.01) }))).is_err());
+}
+
+#[test]
+fn test_tiny_premium_impact_does_not_overflow_share_count() {
+    let tiny_impact = EtfMarketParams { premium_impact_bps_per_unit: 1e-300, ..market_params(0.05) };
+    let market = simulate_etf_with_market(&flat_etf(0.0, Some(tiny_impact))).unwrap().market.unwrap();
+    assert!(market.net_creation_units.iter().all(|u| u.unsigned_abs() <= 1_000_000), "Units per step are capped");
+
+    let huge_units = EtfMarketParams { premium_impact_bps_per_unit: 1e-300, creation_unit_size: u64::MAX / 2, ..market_params(0.05) };
+    let err = simulate_etf_with_market(&flat_etf(0.0, Some(huge_units))).unwrap_err();
+    assert!(err.to_string().contains("Shares outstanding overflowed"), "{}", err);
+}
+
+fn two_stock_etf(fund_weights: (f64, f64), expense_ratio: f64, benchmark: EtfBenchmark) -> EtfDefinition {
+    let stock = |symbol: &str, drift: f64, volatility: f64, weight: f64| EtfConstituent {
+        symbol: symbol.to_string(), initial_price: 100.0, drift, volatility, weight,
+        regime_switching: None, currency: None,
+    };
+    EtfDefinition {
+        constituents: vec![stock("GROWTH", 0.12, 0.35, fund_weights.0), stock("VALUE", 0.06, 0.15, fund_weights.1)],
+        simulation_days: 252,
+        time_step_days: 1.0,
+        seed: Some(17),
+        fx: None,
+        expense_ratio,
+        market: None,
+        benchmark: Some(benchmark),
+    }
+}
+
+#[test]
+fn test_tracking_report_for_fee_only_difference() {
+    // Same weights as the fund: the only difference is the fund's expense ratio.
+    let simulation = simulate_etf_with_market(&two_stock_etf((0.5, 0.5), 0.005, EtfBenchmark::default())).unwrap();
+    let report = simulation.tracking.as_ref().unwrap();
+    assert_eq!(report.benchmark_nav, simulation.gross_nav);
+
+    let fee = 0.005 / 252.0;
+    for (i, (difference, expenses)) in report.tracking_differences.iter().zip(&report.expense_contributions).enumerate().skip(1) {
+        assert!((difference - expenses).abs() < 1e-12, "step {}", i);
+        assert!(*expenses < 0.0 && *expenses > -2.0 * fee, "step {}: {}", i, expenses);
+        for attribution in &report.attribution {
+            assert_eq!(attribution.active_contributions[i], 0.0);
+        }
+    }
+    let last = simulation.nav.prices.len() - 1;
+    assert!((report.tracking_difference - (simulation.nav.prices[last] - report.benchmark_nav[last])).abs() < 1e-12);
+    assert!(report.tracking_difference < 0.0);
+    // Fee drag is almost constant, so tracking error is tiny next to the tracking difference.
+    assert!(report.tracking_error < 0.001, "{}", report.tracking_error);
+}
+
+#[test]
+fn test_attribution_sums_to_nav_returns() {
+    let benchmark = EtfBenchmark { weights: [("GROWTH".to_string(), 0.3), ("VALUE".to_string(), 0.7)].into_iter().collect(), expense_ratio: 0.0 };
+    let simulation = simulate_etf_with_market(&two_stock_etf((0.6, 0.4), 0.002, benchmark)).unwrap();
+    let report = simulation.tracking.as_ref().unwrap();
+    let nav = &simulation.nav.prices;
+    let index = &report.benchmark_nav;
+    assert_eq!(report.attribution.len(), 2);
+    assert_eq!(report.attribution[0].symbol, "GROWTH");
+
+    for i in 1..nav.len() {
+        let fund_return = nav[i] / nav[i - 1] - 1.0;
+        let index_return = index[i] / index[i - 1] - 1.0;
+        let contributions: f64 = report.attribution.iter().map(|a| a.contributions[i]).sum();
+        assert!((contributions + report.expense_contributions[i] - fund_return).abs() < 1e-12, "step {}", i);
+        let active: f64 = report.attribution.iter().map(|a| a.active_contributions[i]).sum();
+        let gross_return = simulation.gross_nav[i] / simulation.gross_nav[i - 1] - 1.0;
+        assert!((active - (gross_return - index_return)).abs() < 1e-12, "step {}", i);
+    }
+    // Overweighting the more volatile stock leaves a clearly visible tracking error.
+    assert!(report.tracking_error > 0.02, "{}", report.tracking_error);
+    let total: f64 = report.attribution[0].contributions.iter().sum();
+    assert_eq!(report.attribution[0].total_contribution, total);
+
+    assert!(simulate_etf_with_market(&two_stock_etf((0.5, 0.5), 0.0, EtfBenchmark::default())).unwrap().tracking.is_some());
+    assert!(simulate_etf_with_market(&flat_etf(0.0, None)).unwrap().tracking.is_none());
+}
+
+#[test]
+fn test_invalid_benchmarks() {
+    let weights = |pairs: &[(&str, f64)]| pairs.iter().map(|(s, w)| (s.to_string(), *w)).collect();
+    let unknown = EtfBenchmark { weights: weights(&[("GROWTH", 0.5), ("OTHER", 0.5)]), expense_ratio: 0.0 };
+    assert!(simulate_etf_nav(&two_stock_etf((0.5, 0.5), 0.0, unknown)).is_err());
+    let not_normalized = EtfBenchmark { weights: weights(&[("GROWTH", 0.5), ("VALUE", 0.4)]), expense_ratio: 0.0 };
+    assert!(simulate_etf_nav(&two_stock_etf((0.5, 0.5), 0.0, not_normalized)).is_err());
+    let negative = EtfBenchmark { weights: weights(&[("GROWTH", 1.5), ("VALUE", -0.5)]), expense_ratio: 0.0 };
+    assert!(simulate_etf_nav(&two_stock_etf((0.5, 0.5), 0.0, negative)).is_err());
+    assert!(simulate_etf_nav(&two_stock_etf((0.5, 0.5), 0.0, EtfBenchmark { expense_ratio: -0.01, ..Default::default() })).is_err());
+    // A single-stock index is fine: the other constituent is simply not in it.
+    let single = EtfBenchmark { weights: weights(&[("VALUE", 1.0)]), expense_ratio: 0.0 };
+    assert!(simulate_etf_nav(&two_stock_etf((0.5, 0.5), 0.0, single)).is_ok());
+}
+
+#[test]
+fn test_seed_near_u64_max_wraps() {
+    let etf_def = EtfDefinition { seed: Some(u64::MAX), ..two_stock_etf((0.5, 0.5), 0.0, EtfBenchmark::default()) };
+    assert!(simulate_etf_nav(&etf_def).is_ok());
+}
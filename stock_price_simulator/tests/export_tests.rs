@@ -0,0 +1,110 @@
+use stock_price_simulator::export::{
+    time_series_to_csv, paths_to_csv, time_series_to_arrow_ipc, paths_to_arrow_ipc,
+    time_series_to_parquet, paths_to_parquet, CsvExportOptions,
+};
+use stock_price_simulator::stock_simulation::StockSimulator;
+use stock_price_simulator::TimeSeries;
+use arrow_array::{Array, Float64Array, UInt32Array};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use std::io::Cursor;
+
+fn sample_series(seed: u64) -> TimeSeries {
+    StockSimulator::simulate_stock_price(100.0, 0.05, 0.2, 4, 1.0, Some(seed)).unwrap()
+}
+
+#[test]
+fn test_time_series_to_csv_default_options() {
+    let series = sample_series(1);
+    let csv = time_series_to_csv(&series, &CsvExportOptions::default()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(lines.len(), series.prices.len() + 1, "Header plus one row per point");
+    assert_eq!(lines[0], "timestamp,price");
+    assert_eq!(lines[1], "2024-01-01T00:00:00,100");
+    let last_price: f64 = lines[4].split(',').nth(1).unwrap().parse().unwrap();
+    assert_eq!(last_price, series.prices[3], "Prices should round-trip exactly");
+}
+
+#[test]
+fn test_time_series_to_csv_custom_delimiter_and_format() {
+    let series = sample_series(2);
+    let options = CsvExportOptions {
+        delimiter: b';',
+        timestamp_format: "%Y/%m/%d".to_string(),
+        include_header: false,
+    };
+    let csv = time_series_to_csv(&series, &options).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(lines.len(), series.prices.len());
+    assert_eq!(lines[0], "2024/01/01;100");
+    assert!(lines[1].starts_with("2024/01/02;"));
+}
+
+#[test]
+fn test_paths_to_csv_long_format() {
+    let paths = vec![sample_series(1), sample_series(2)];
+    let csv = paths_to_csv(&paths, &CsvExportOptions::default()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(lines[0], "path,timestamp,price");
+    assert_eq!(lines.len(), 1 + 2 * 4);
+    assert!(lines[1].starts_with("0,2024-01-01T00:00:00,"));
+    assert!(lines[5].starts_with("1,2024-01-01T00:00:00,"));
+}
+
+#[test]
+fn test_csv_export_rejects_mismatched_lengths() {
+    let mut series = sample_series(3);
+    series.prices.pop();
+    assert!(time_series_to_csv(&series, &CsvExportOptions::default()).is_err());
+    assert!(time_series_to_parquet(&series).is_err());
+}
+
+#[test]
+fn test_time_series_arrow_ipc_round_trip() {
+    let series = sample_series(4);
+    let bytes = time_series_to_arrow_ipc(&series).unwrap();
+
+    let reader = arrow_ipc::reader::StreamReader::try_new(Cursor::new(bytes), None).unwrap();
+    let batches: Vec<_> = reader.collect::<Result<_, _>>().unwrap();
+    assert_eq!(batches.len(), 1);
+    let batch = &batches[0];
+    assert_eq!(batch.num_columns(), 2);
+    assert_eq!(batch.schema().field(0).name(), "timestamp");
+    let prices = batch.column(1).as_any().downcast_ref::<Float64Array>().unwrap();
+    assert_eq!(prices.values().to_vec(), series.prices);
+}
+
+#[test]
+fn test_paths_arrow_ipc_has_path_column() {
+    let paths = vec![sample_series(1), sample_series(2), sample_series(3)];
+    let bytes = paths_to_arrow_ipc(&paths).unwrap();
+
+    let reader = arrow_ipc::reader::StreamReader::try_new(Cursor::new(bytes), None).unwrap();
+    let batch = reader.into_iter().next().unwrap().unwrap();
+    assert_eq!(batch.num_rows(), 12);
+    let path_ids = batch.column(0).as_any().downcast_ref::<UInt32Array>().unwrap();
+    assert_eq!(path_ids.value(0), 0);
+    assert_eq!(path_ids.value(11), 2);
+}
+
+#[test]
+fn test_parquet_round_trip() {
+    let series = sample_series(5);
+    let bytes = time_series_to_parquet(&series).unwrap();
+
+    let reader = ParquetRecordBatchReaderBuilder::try_new(bytes::Bytes::from(bytes)).unwrap().build().unwrap();
+    let batches: Vec<_> = reader.collect::<Result<_, _>>().unwrap();
+    let prices: Vec<f64> = batches.iter()
+        .flat_map(|b| b.column(1).as_any().downcast_ref::<Float64Array>().unwrap().values().to_vec())
+        .collect();
+    assert_eq!(prices, series.prices);
+
+    let paths = vec![sample_series(1), sample_series(2)];
+    let multi = paths_to_parquet(&paths).unwrap();
+    let reader = ParquetRecordBatchReaderBuilder::try_new(bytes::Bytes::from(multi)).unwrap().build().unwrap();
+    let total_rows: usize = reader.map(|b| b.unwrap().num_rows()).sum();
+    assert_eq!(total_rows, 8);
+    assert!(!batches[0].column(0).is_empty());
+}
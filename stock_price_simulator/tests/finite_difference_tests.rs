@@ -0,0 +1,123 @@
+use stock_price_simulator::finite_difference::{Barrier, BarrierType, ExerciseStyle, FiniteDifferenceOptionPricer};
+use stock_price_simulator::option_pricing::{black_scholes_price, EuropeanOption, MonteCarloOptionPricer, OptionPricer, OptionType};
+use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+
+const SPOT_STEPS: usize = 400;
+const TIME_STEPS: usize = 400;
+
+fn option(strike: f64, option_type: OptionType) -> EuropeanOption {
+    EuropeanOption {
+        underlying_price: 100.0,
+        strike_price: strike,
+        time_to_maturity_years: 1.0,
+        risk_free_rate: Some(0.05),
+        volatility: 0.2,
+        option_type,
+        discount_curve: None,
+    }
+}
+
+fn pricer(option: &EuropeanOption) -> FiniteDifferenceOptionPricer {
+    FiniteDifferenceOptionPricer::for_european_option(option, SPOT_STEPS, TIME_STEPS).unwrap()
+}
+
+#[test]
+fn test_european_matches_black_scholes_and_greeks() {
+    let n = Normal::new(0.0, 1.0).unwrap();
+    for &strike in &[80.0, 100.0, 120.0] {
+        for option_type in [OptionType::Call, OptionType::Put] {
+            let opt = option(strike, option_type);
+            let fd = pricer(&opt).solve().unwrap();
+            let bs = black_scholes_price(&opt).unwrap();
+            assert!((fd.price - bs).abs() < 0.01, "K={} {:?}: FD {} vs BS {}", strike, option_type, fd.price, bs);
+
+            let d1 = ((100.0f64 / strike).ln() + (0.05 + 0.02)) / 0.2;
+            let bs_delta = match option_type { OptionType::Call => n.cdf(d1), OptionType::Put => n.cdf(d1) - 1.0 };
+            let bs_gamma = n.pdf(d1) / (100.0 * 0.2);
+            assert!((fd.delta - bs_delta).abs() < 1e-3, "delta {} vs {}", fd.delta, bs_delta);
+            assert!((fd.gamma - bs_gamma).abs() < 1e-3, "gamma {} vs {}", fd.gamma, bs_gamma);
+        }
+    }
+}
+
+#[test]
+fn test_theta_matches_black_scholes() {
+    let opt = option(100.0, OptionType::Call);
+    let fd = pricer(&opt).solve().unwrap();
+    let n = Normal::new(0.0, 1.0).unwrap();
+    let d1: f64 = (0.05 + 0.02) / 0.2;
+    let d2 = d1 - 0.2;
+    let bs_theta = -100.0 * n.pdf(d1) * 0.2 / 2.0 - 0.05 * 100.0 * (-0.05f64).exp() * n.cdf(d2);
+    assert!((fd.theta - bs_theta).abs() < 0.05, "theta {} vs {}", fd.theta, bs_theta);
+}
+
+#[test]
+fn test_american_exercise() {
+    let put = option(100.0, OptionType::Put);
+    let american_put = FiniteDifferenceOptionPricer { exercise: ExerciseStyle::American, ..pricer(&put) };
+    let price = american_put.price(None).unwrap();
+    // Reference value for S = K = 100, T = 1, r = 5%, sigma = 20%.
+    assert!((price - 6.090).abs() < 0.01, "American put {}", price);
+    assert!(price > black_scholes_price(&put).unwrap());
+
+    // Without dividends an American call is never exercised early.
+    let call = option(100.0, OptionType::Call);
+    let american_call = FiniteDifferenceOptionPricer { exercise: ExerciseStyle::American, ..pricer(&call) };
+    assert!((american_call.price(None).unwrap() - black_scholes_price(&call).unwrap()).abs() < 0.01);
+}
+
+// Reiner-Rubinstein down-and-in call for barrier H below the strike.
+fn down_and_in_call(s: f64, k: f64, h: f64, t: f64, r: f64, sigma: f64) -> f64 {
+    let n = Normal::new(0.0, 1.0).unwrap();
+    let lambda = (r + 0.5 * sigma * sigma) / (sigma * sigma);
+    let y = (h * h / (s * k)).ln() / (sigma * t.sqrt()) + lambda * sigma * t.sqrt();
+    s * (h / s).powf(2.0 * lambda) * n.cdf(y) - k * (-r * t).exp() * (h / s).powf(2.0 * lambda - 2.0) * n.cdf(y - sigma * t.sqrt())
+}
+
+#[test]
+fn test_barrier_options() {
+    let call = option(100.0, OptionType::Call);
+    let vanilla = black_scholes_price(&call).unwrap();
+    let expected_in = down_and_in_call(100.0, 100.0, 90.0, 1.0, 0.05, 0.2);
+
+    let with_barrier = |barrier_type| FiniteDifferenceOptionPricer {
+        barrier: Some(Barrier { barrier_type, level: 90.0 }),
+        ..pricer(&call)
+    };
+    let knock_in = with_barrier(BarrierType::DownAndIn).price(None).unwrap();
+    let knock_out = with_barrier(BarrierType::DownAndOut).price(None).unwrap();
+    assert!((knock_in - expected_in).abs() < 0.02, "down-and-in {} vs {}", knock_in, expected_in);
+    assert!((knock_out - (vanilla - expected_in)).abs() < 0.02, "down-and-out {}", knock_out);
+
+    // Up-and-out call is cheaper than vanilla and worthless once the barrier is breached.
+    let up_and_out = FiniteDifferenceOptionPricer { barrier: Some(Barrier { barrier_type: BarrierType::UpAndOut, level: 130.0 }), ..pricer(&call) };
+    let up_price = up_and_out.price(None).unwrap();
+    assert!(up_price > 0.0 && up_price < vanilla);
+    let breached = FiniteDifferenceOptionPricer { underlying_price: 131.0, ..up_and_out.clone() };
+    assert_eq!(breached.price(None).unwrap(), 0.0);
+
+    let american_knock_in = FiniteDifferenceOptionPricer { exercise: ExerciseStyle::American, ..with_barrier(BarrierType::UpAndIn) };
+    assert!(american_knock_in.price(None).is_err());
+}
+
+#[test]
+fn test_time_dependent_rates_match_average_rate() {
+    // A European price depends on the rate path only through its integral.
+    let call = option(100.0, OptionType::Call);
+    let curve = FiniteDifferenceOptionPricer { short_rates: Some(vec![(0.0, 0.02), (1.0, 0.08)]), ..pricer(&call) };
+    let bs_average = black_scholes_price(&EuropeanOption { risk_free_rate: Some(0.05), ..call.clone() }).unwrap();
+    assert!((curve.price(None).unwrap() - bs_average).abs() < 0.01);
+
+    let flat = FiniteDifferenceOptionPricer { short_rates: Some(vec![(0.5, 0.05)]), ..pricer(&call) };
+    assert!((flat.price(None).unwrap() - pricer(&call).price(None).unwrap()).abs() < 1e-12);
+    let bad = FiniteDifferenceOptionPricer { short_rates: Some(vec![(1.0, 0.05), (0.5, 0.05)]), ..pricer(&call) };
+    assert!(bad.price(None).is_err());
+}
+
+#[test]
+fn test_finite_difference_agrees_with_monte_carlo() {
+    let put = option(110.0, OptionType::Put);
+    let fd = pricer(&put).price(None).unwrap();
+    let mc = MonteCarloOptionPricer::for_european_option(&put, 20_000, 10).unwrap().price(Some(3)).unwrap();
+    assert!((fd - mc).abs() < 0.3, "FD {} vs MC {}", fd, mc);
+}
@@ -0,0 +1,182 @@
+use stock_price_simulator::calibration::{calibrate_heston, OptionQuote};
+use stock_price_simulator::config::{HestonParams, JumpDiffusionParams};
+use stock_price_simulator::fourier_pricing::{CharacteristicModel, FourierOptionPricer};
+use stock_price_simulator::option_pricing::{black_scholes_price, EuropeanOption, MonteCarloOptionPricer, OptionPricer, OptionType};
+use stock_price_simulator::random_process::{Heston, MertonJumpDiffusion};
+use statrs::distribution::{Discrete, Poisson};
+
+const SPOT: f64 = 100.0;
+const RATE: f64 = 0.03;
+
+fn heston_params() -> HestonParams {
+    HestonParams {
+        drift: RATE,
+        initial_variance: 0.04,
+        mean_reversion_speed: 2.0,
+        long_term_variance: 0.05,
+        vol_of_vol: 0.5,
+        correlation: -0.7,
+    }
+}
+
+fn merton_params() -> JumpDiffusionParams {
+    JumpDiffusionParams { drift: RATE, volatility: 0.2, jump_intensity: 0.8, jump_mean: -0.1, jump_volatility: 0.15 }
+}
+
+fn fourier(strike: f64, maturity: f64, option_type: OptionType, model: CharacteristicModel) -> FourierOptionPricer {
+    FourierOptionPricer {
+        underlying_price: SPOT,
+        strike_price: strike,
+        time_to_maturity_years: maturity,
+        risk_free_rate: RATE,
+        option_type,
+        model,
+    }
+}
+
+fn bs(strike: f64, maturity: f64, rate: f64, volatility: f64, option_type: OptionType) -> f64 {
+    black_scholes_price(&EuropeanOption {
+        underlying_price: SPOT,
+        strike_price: strike,
+        time_to_maturity_years: maturity,
+        risk_free_rate: Some(rate),
+        volatility,
+        option_type,
+        discount_curve: None,
+    }).unwrap()
+}
+
+#[test]
+fn test_degenerate_models_match_black_scholes() {
+    // Without jumps Merton is Black-Scholes.
+    let no_jumps = JumpDiffusionParams { jump_intensity: 0.0, ..merton_params() };
+    // With negligible vol of vol and v0 = theta Heston variance stays at theta.
+    let constant_variance = HestonParams {
+        initial_variance: 0.04, long_term_variance: 0.04, vol_of_vol: 1e-3, correlation: 0.0, ..heston_params()
+    };
+
+    for &(strike, maturity) in &[(80.0, 0.25), (100.0, 1.0), (130.0, 2.0)] {
+        for option_type in [OptionType::Call, OptionType::Put] {
+            let expected = bs(strike, maturity, RATE, 0.2, option_type);
+            let merton = fourier(strike, maturity, option_type, CharacteristicModel::MertonJumpDiffusion(no_jumps.clone())).price(None).unwrap();
+            let heston = fourier(strike, maturity, option_type, CharacteristicModel::Heston(constant_variance.clone())).price(None).unwrap();
+            assert!((merton - expected).abs() < 1e-6, "Merton K={} T={}: {} vs {}", strike, maturity, merton, expected);
+            assert!((heston - expected).abs() < 1e-4, "Heston K={} T={}: {} vs {}", strike, maturity, heston, expected);
+        }
+    }
+}
+
+#[test]
+fn test_merton_fourier_matches_poisson_series() {
+    // Merton (1976): a Poisson-weighted sum of Black-Scholes prices.
+    let p = merton_params();
+    let (strike, maturity) = (95.0, 1.0);
+    let k = (p.jump_mean + 0.5 * p.jump_volatility.powi(2)).exp() - 1.0;
+    let lambda_prime = p.jump_intensity * (1.0 + k);
+    let poisson = Poisson::new(lambda_prime * maturity).unwrap();
+    let series: f64 = (0..40u64).map(|n| {
+        let volatility = (p.volatility.powi(2) + n as f64 * p.jump_volatility.powi(2) / maturity).sqrt();
+        let rate = RATE - p.jump_intensity * k + n as f64 * (1.0 + k).ln() / maturity;
+        poisson.pmf(n) * bs(strike, maturity, rate, volatility, OptionType::Call)
+    }).sum();
+
+    let price = fourier(strike, maturity, OptionType::Call, CharacteristicModel::MertonJumpDiffusion(p)).price(None).unwrap();
+    assert!((price - series).abs() < 1e-6, "Fourier {} vs series {}", price, series);
+}
+
+#[test]
+fn test_heston_put_call_parity_and_skew() {
+    let model = CharacteristicModel::Heston(heston_params());
+    let call = fourier(100.0, 1.0, OptionType::Call, model.clone()).fourier_price().unwrap();
+    let put = fourier(100.0, 1.0, OptionType::Put, model.clone()).fourier_price().unwrap();
+    assert!((call - put - (SPOT - 100.0 * (-RATE).exp())).abs() < 1e-8);
+
+    // Negative correlation makes low strikes dearer than a flat-volatility model would: compare the
+    // price ratio to Black-Scholes at the at-the-money implied level.
+    let atm_vol = bisect_implied_vol(call, 100.0, 1.0);
+    let otm_put = fourier(80.0, 1.0, OptionType::Put, model).fourier_price().unwrap();
+    assert!(otm_put > bs(80.0, 1.0, RATE, atm_vol, OptionType::Put));
+}
+
+fn bisect_implied_vol(call_price: f64, strike: f64, maturity: f64) -> f64 {
+    let (mut lo, mut hi) = (1e-4, 3.0);
+    for _ in 0..100 {
+        let mid = 0.5 * (lo + hi);
+        if bs(strike, maturity, RATE, mid, OptionType::Call) > call_price { hi = mid } else { lo = mid }
+    }
+    0.5 * (lo + hi)
+}
+
+#[test]
+fn test_heston_fourier_cross_checks_monte_carlo() {
+    let p = heston_params();
+    let process = Heston {
+        drift: RATE,
+        initial_variance: p.initial_variance,
+        mean_reversion_speed: p.mean_reversion_speed,
+        long_term_variance: p.long_term_variance,
+        vol_of_vol: p.vol_of_vol,
+        correlation: p.correlation,
+    };
+    let option = EuropeanOption {
+        underlying_price: SPOT,
+        strike_price: 100.0,
+        time_to_maturity_years: 1.0,
+        risk_free_rate: Some(RATE),
+        volatility: 0.2, // Unused by price_with_process
+        option_type: OptionType::Call,
+        discount_curve: None,
+    };
+    let mc = MonteCarloOptionPricer::for_european_option(&option, 20_000, 100).unwrap().price_with_process(&process, Some(5)).unwrap();
+    let analytic = fourier(100.0, 1.0, OptionType::Call, CharacteristicModel::Heston(p)).price(None).unwrap();
+    assert!((mc - analytic).abs() < 0.3, "MC {} vs Fourier {}", mc, analytic);
+
+    let merton = merton_params();
+    let merton_process = MertonJumpDiffusion {
+        drift: RATE,
+        volatility: merton.volatility,
+        jump_intensity: merton.jump_intensity,
+        jump_mean: merton.jump_mean,
+        jump_volatility: merton.jump_volatility,
+    };
+    let mc = MonteCarloOptionPricer::for_european_option(&option, 20_000, 50).unwrap().price_with_process(&merton_process, Some(6)).unwrap();
+    let analytic = fourier(100.0, 1.0, OptionType::Call, CharacteristicModel::MertonJumpDiffusion(merton)).price(None).unwrap();
+    assert!((mc - analytic).abs() < 0.3, "MC {} vs Fourier {}", mc, analytic);
+}
+
+#[test]
+fn test_calibrate_heston_recovers_quoted_prices() {
+    let truth = heston_params();
+    let mut quotes = Vec::new();
+    for &maturity in &[0.5, 1.0, 2.0] {
+        for &strike in &[80.0, 90.0, 100.0, 110.0, 120.0] {
+            let option_type = if strike < SPOT { OptionType::Put } else { OptionType::Call };
+            let price = fourier(strike, maturity, option_type, CharacteristicModel::Heston(truth.clone())).fourier_price().unwrap();
+            quotes.push(OptionQuote { strike_price: strike, time_to_maturity_years: maturity, option_type, price });
+        }
+    }
+
+    let guess = HestonParams {
+        drift: RATE,
+        initial_variance: 0.06,
+        mean_reversion_speed: 1.0,
+        long_term_variance: 0.06,
+        vol_of_vol: 0.3,
+        correlation: -0.3,
+    };
+    let calibration = calibrate_heston(SPOT, RATE, &quotes, &guess).unwrap();
+    assert!(calibration.rmse < 0.01, "RMSE {}", calibration.rmse);
+    assert!((calibration.params.initial_variance - truth.initial_variance).abs() < 0.005);
+    assert!((calibration.params.correlation - truth.correlation).abs() < 0.15);
+
+    assert!(calibrate_heston(SPOT, RATE, &[], &guess).is_err());
+}
+
+#[test]
+fn test_fourier_pricer_validation() {
+    let bad = HestonParams { correlation: -1.5, ..heston_params() };
+    assert!(fourier(100.0, 1.0, OptionType::Call, CharacteristicModel::Heston(bad)).price(None).is_err());
+    assert!(fourier(-1.0, 1.0, OptionType::Call, CharacteristicModel::Heston(heston_params())).price(None).is_err());
+    let expired = fourier(90.0, 0.0, OptionType::Call, CharacteristicModel::Heston(heston_params())).price(None).unwrap();
+    assert!((expired - 10.0).abs() < 1e-12);
+}
@@ -8,20 +8,22 @@ fn test_futures_simulation_deterministic() {
     let contract1 = FuturesContract {
         underlying_symbol: "TEST".to_string(),
         initial_spot_price: 100.0,
-        risk_free_rate: 0.05,
+        risk_free_rate: Some(0.05),
         volatility: 0.2,
         time_to_maturity_days: 30,
         time_step_days: 1.0,
         seed: Some(12345),
+        discount_curve: None,
     };
     let contract2 = FuturesContract { // Same params, same seed
         underlying_symbol: "TEST".to_string(),
         initial_spot_price: 100.0,
-        risk_free_rate: 0.05,
+        risk_free_rate: Some(0.05),
         volatility: 0.2,
         time_to_maturity_days: 30,
         time_step_days: 1.0,
         seed: Some(12345),
+        discount_curve: None,
     };
 
     let result1 = simulate_futures_price(&contract1).unwrap();
@@ -38,11 +40,12 @@ fn test_futures_simulation_output_length() {
     let contract = FuturesContract {
         underlying_symbol: "TEST_LEN".to_string(),
         initial_spot_price: 100.0,
-        risk_free_rate: 0.05,
+        risk_free_rate: Some(0.05),
         volatility: 0.2,
         time_to_maturity_days: ttm_days,
         time_step_days: time_step,
         seed: None,
+        discount_curve: None,
     };
 
     let expected_steps = (ttm_days as f64 / time_step).ceil() as usize;
@@ -67,11 +70,12 @@ fn test_futures_price_convergence_to_spot() {
     let contract = FuturesContract {
         underlying_symbol: "CONVERGE".to_string(),
         initial_spot_price: 120.0,
-        risk_free_rate: 0.03,
+        risk_free_rate: Some(0.03),
         volatility: 0.15,
         time_to_maturity_days: 5, // Short maturity
         time_step_days: 1.0,      // Daily steps
         seed: Some(99),
+        discount_curve: None,
     };
 
     let result = simulate_futures_price(&contract).unwrap();
@@ -79,10 +83,10 @@ fn test_futures_price_convergence_to_spot() {
 
     // To get the last spot price, we need to simulate it separately or extract from an intermediate step if possible
     // For simplicity, let's re-simulate the spot path with the same parameters
-    let gbm = stock_price_simulator::random_process::GeometricBrownianMotion {
-        drift: contract.risk_free_rate,
-        volatility: contract.volatility,
-    };
+    let gbm = stock_price_simulator::random_process::GeometricBrownianMotion::new(
+        contract.risk_free_rate.unwrap(),
+        contract.volatility,
+    );
     let num_steps = (contract.time_to_maturity_days as f64 / contract.time_step_days).ceil() as usize;
     let spot_path = gbm.generate_path(
         contract.initial_spot_price,
@@ -112,20 +116,21 @@ fn test_futures_price_with_zero_risk_free_rate() {
     let contract = FuturesContract {
         underlying_symbol: "ZERO_RFR".to_string(),
         initial_spot_price: 75.0,
-        risk_free_rate: 0.0, // Zero risk-free rate
+        risk_free_rate: Some(0.0), // Zero risk-free rate
         volatility: 0.22,
         time_to_maturity_days: 10,
         time_step_days: 1.0,
         seed: Some(101),
+        discount_curve: None,
     };
 
     let result = simulate_futures_price(&contract).unwrap();
 
     // Need the spot prices to compare
-    let gbm = stock_price_simulator::random_process::GeometricBrownianMotion {
-        drift: contract.risk_free_rate, // which is 0.0
-        volatility: contract.volatility,
-    };
+    let gbm = stock_price_simulator::random_process::GeometricBrownianMotion::new(
+        contract.risk_free_rate.unwrap(), // which is 0.0
+        contract.volatility,
+    );
     let num_steps = (contract.time_to_maturity_days as f64 / contract.time_step_days).ceil() as usize;
     let spot_path = gbm.generate_path(
         contract.initial_spot_price,
@@ -149,19 +154,20 @@ fn test_futures_price_relationship_spot_futures() {
     let contract_contango = FuturesContract {
         underlying_symbol: "CONTANGO".to_string(),
         initial_spot_price: 100.0,
-        risk_free_rate: 0.05, // Positive r
+        risk_free_rate: Some(0.05), // Positive r
         volatility: 0.2,
         time_to_maturity_days: 30,
         time_step_days: 1.0,
         seed: Some(111),
+        discount_curve: None,
     };
 
     let result_contango = simulate_futures_price(&contract_contango).unwrap();
 
-    let gbm_spot = stock_price_simulator::random_process::GeometricBrownianMotion {
-        drift: contract_contango.risk_free_rate,
-        volatility: contract_contango.volatility,
-    };
+    let gbm_spot = stock_price_simulator::random_process::GeometricBrownianMotion::new(
+        contract_contango.risk_free_rate.unwrap(),
+        contract_contango.volatility,
+    );
     let num_steps_contango = (contract_contango.time_to_maturity_days as f64 / contract_contango.time_step_days).ceil() as usize;
     let spot_path_contango = gbm_spot.generate_path(
         contract_contango.initial_spot_price,
@@ -179,7 +185,7 @@ fn test_futures_price_relationship_spot_futures() {
                     "In contango (r>0), F_t ({}) should generally be >= S_t ({}) (step {})",
                     result_contango.prices[i], spot_path_contango.prices[i], i);
 
-            let expected_f_t = spot_path_contango.prices[i] * (contract_contango.risk_free_rate * (remaining_days.max(0.0) / 365.0)).exp();
+            let expected_f_t = spot_path_contango.prices[i] * (contract_contango.risk_free_rate.unwrap() * (remaining_days.max(0.0) / 365.0)).exp();
             assert!((result_contango.prices[i] - expected_f_t).abs() < PRICE_ACCURACY,
                     "Futures price at step {} ({}) does not match formula S_t * exp(r(T-t)) ({})",
                     i, result_contango.prices[i], expected_f_t);
@@ -195,24 +201,24 @@ fn test_futures_price_relationship_spot_futures() {
 #[test]
 fn test_invalid_inputs_for_futures() {
     assert!(simulate_futures_price(&FuturesContract {
-        initial_spot_price: -100.0, risk_free_rate: 0.05, volatility: 0.2, time_to_maturity_days: 30, time_step_days: 1.0, seed: None, underlying_symbol: "T".into()
+        initial_spot_price: -100.0, risk_free_rate: Some(0.05), volatility: 0.2, time_to_maturity_days: 30, time_step_days: 1.0, seed: None, underlying_symbol: "T".into(), discount_curve: None
     }).is_err(), "Initial spot price must be positive");
 
     assert!(simulate_futures_price(&FuturesContract {
-        initial_spot_price: 100.0, risk_free_rate: 0.05, volatility: -0.2, time_to_maturity_days: 30, time_step_days: 1.0, seed: None, underlying_symbol: "T".into()
+        initial_spot_price: 100.0, risk_free_rate: Some(0.05), volatility: -0.2, time_to_maturity_days: 30, time_step_days: 1.0, seed: None, underlying_symbol: "T".into(), discount_curve: None
     }).is_err(), "Volatility cannot be negative");
 
     assert!(simulate_futures_price(&FuturesContract {
-        initial_spot_price: 100.0, risk_free_rate: 0.05, volatility: 0.2, time_to_maturity_days: 30, time_step_days: 0.0, seed: None, underlying_symbol: "T".into()
+        initial_spot_price: 100.0, risk_free_rate: Some(0.05), volatility: 0.2, time_to_maturity_days: 30, time_step_days: 0.0, seed: None, underlying_symbol: "T".into(), discount_curve: None
     }).is_err(), "Time step must be positive");
 
     assert!(simulate_futures_price(&FuturesContract {
-        initial_spot_price: 100.0, risk_free_rate: 0.05, volatility: 0.2, time_to_maturity_days: 30, time_step_days: -1.0, seed: None, underlying_symbol: "T".into()
+        initial_spot_price: 100.0, risk_free_rate: Some(0.05), volatility: 0.2, time_to_maturity_days: 30, time_step_days: -1.0, seed: None, underlying_symbol: "T".into(), discount_curve: None
     }).is_err(), "Time step must be positive");
 
     // TTM = 0 is allowed, should produce one price point
     let res_ttm_zero = simulate_futures_price(&FuturesContract {
-        initial_spot_price: 100.0, risk_free_rate: 0.05, volatility: 0.2, time_to_maturity_days: 0, time_step_days: 1.0, seed: None, underlying_symbol: "T".into()
+        initial_spot_price: 100.0, risk_free_rate: Some(0.05), volatility: 0.2, time_to_maturity_days: 0, time_step_days: 1.0, seed: None, underlying_symbol: "T".into(), discount_curve: None
     });
     assert!(res_ttm_zero.is_ok(), "TTM=0 should be a valid scenario, got: {:?}", res_ttm_zero.err());
     if let Ok(ts) = res_ttm_zero {
@@ -0,0 +1,121 @@
+use stock_price_simulator::config::{CurveInterpolation, CurveQuote, DiscountCurveParams};
+use stock_price_simulator::error::{classify, ErrorCode};
+use stock_price_simulator::fx_simulation::{garman_kohlhagen_price, simulate_fx_forward, FxForwardContract, FxOption, FxPair, MAX_FX_STEPS};
+use stock_price_simulator::option_pricing::OptionType;
+use stock_price_simulator::random_process::TRADING_DAYS_PER_YEAR;
+use stock_price_simulator::yield_curve::DiscountCurve;
+use statrs::distribution::{ContinuousCDF, Normal};
+
+fn eur_usd() -> FxPair {
+    FxPair {
+        base_currency: "EUR".to_string(),
+        quote_currency: "USD".to_string(),
+        spot_rate: 1.10,
+        domestic_rate: Some(0.05),
+        foreign_rate: Some(0.03),
+        volatility: 0.08,
+        domestic_curve: None,
+        foreign_curve: None,
+        pip_size: 0.0001,
+    }
+}
+
+fn fx_option(strike: f64, option_type: OptionType) -> FxOption {
+    FxOption { pair: eur_usd(), strike_price: strike, time_to_maturity_years: 0.75, option_type }
+}
+
+#[test]
+fn test_garman_kohlhagen_closed_form() {
+    let (s, k, t, rd, rf, sigma) = (1.10f64, 1.12f64, 0.75f64, 0.05f64, 0.03f64, 0.08f64);
+    let n = Normal::new(0.0, 1.0).unwrap();
+    let d1 = ((s / k).ln() + (rd - rf + 0.5 * sigma * sigma) * t) / (sigma * t.sqrt());
+    let d2 = d1 - sigma * t.sqrt();
+    let call = s * (-rf * t).exp() * n.cdf(d1) - k * (-rd * t).exp() * n.cdf(d2);
+    let put = k * (-rd * t).exp() * n.cdf(-d2) - s * (-rf * t).exp() * n.cdf(-d1);
+
+    let gk_call = garman_kohlhagen_price(&fx_option(k, OptionType::Call)).unwrap();
+    let gk_put = garman_kohlhagen_price(&fx_option(k, OptionType::Put)).unwrap();
+    assert!((gk_call - call).abs() < 1e-12, "{} vs {}", gk_call, call);
+    assert!((gk_put - put).abs() < 1e-12, "{} vs {}", gk_put, put);
+    // Put-call parity against the forward.
+    assert!((gk_call - gk_put - (s * (-rf * t).exp() - k * (-rd * t).exp())).abs() < 1e-12);
+}
+
+#[test]
+fn test_forward_from_covered_interest_parity() {
+    let pair = eur_usd();
+    let forward = pair.forward_rate(1.0);
+    assert!((forward - 1.10 * (0.02f64).exp()).abs() < 1e-12);
+    assert!((pair.forward_points(1.0) - (forward - 1.10) / 0.0001).abs() < 1e-9);
+    assert!(pair.forward_points(1.0) > 200.0, "USD rates above EUR rates put EUR/USD at a premium");
+
+    // With curves the forward uses the discount factors of both currencies.
+    let curve = |quotes: Vec<CurveQuote>| DiscountCurve::from_params(&DiscountCurveParams { quotes, interpolation: CurveInterpolation::LinearZero }).unwrap();
+    let usd = curve(vec![CurveQuote::Zero { maturity_years: 0.5, rate: 0.05 }, CurveQuote::Zero { maturity_years: 5.0, rate: 0.04 }]);
+    let eur = curve(vec![CurveQuote::Zero { maturity_years: 0.5, rate: 0.03 }, CurveQuote::Zero { maturity_years: 5.0, rate: 0.025 }]);
+    let curved = FxPair { domestic_rate: None, foreign_rate: None, domestic_curve: Some(usd.clone()), foreign_curve: Some(eur.clone()), ..eur_usd() };
+    assert!((curved.forward_rate(3.0) - 1.10 * eur.discount_factor(3.0) / usd.discount_factor(3.0)).abs() < 1e-12);
+
+    // An at-the-money-forward call and put are worth the same.
+    let atmf = FxOption { pair: curved.clone(), strike_price: curved.forward_rate(3.0), time_to_maturity_years: 3.0, option_type: OptionType::Call };
+    let call = garman_kohlhagen_price(&atmf).unwrap();
+    let put = garman_kohlhagen_price(&FxOption { option_type: OptionType::Put, ..atmf }).unwrap();
+    assert!((call - put).abs() < 1e-12, "{} vs {}", call, put);
+}
+
+#[test]
+fn test_fx_forward_simulation() {
+    let contract = FxForwardContract { pair: eur_usd(), time_to_maturity_days: 90, time_step_days: 1.0, seed: Some(21) };
+    let path = simulate_fx_forward(&contract).unwrap();
+    assert_eq!(path.spot_rates.len(), 91);
+    assert_eq!(path.forward_rates.len(), 91);
+    assert_eq!(path.spot_rates[0], 1.10);
+    assert!((path.forward_rates[0] - eur_usd().forward_rate(90.0 / TRADING_DAYS_PER_YEAR)).abs() < 1e-12);
+
+    for (i, (spot, forward)) in path.spot_rates.iter().zip(&path.forward_rates).enumerate() {
+        let remaining = (90.0 - i as f64) / TRADING_DAYS_PER_YEAR;
+        assert!((forward / spot - (0.02 * remaining).exp()).abs() < 1e-12, "step {}", i);
+    }
+    assert!((path.forward_rates[90] - path.spot_rates[90]).abs() < 1e-12, "The forward converges to spot at delivery");
+    assert!(path.forward_points[90].abs() < 1e-8);
+
+    assert_eq!(path, simulate_fx_forward(&contract).unwrap());
+}
+
+#[test]
+fn test_invalid_fx_inputs() {
+    assert!(garman_kohlhagen_price(&FxOption { pair: FxPair { spot_rate: 0.0, ..eur_usd() }, ..fx_option(1.1, OptionType::Call) }).is_err());
+    assert!(garman_kohlhagen_price(&FxOption { pair: FxPair { volatility: -0.1, ..eur_usd() }, ..fx_option(1.1, OptionType::Call) }).is_err());
+    assert!(garman_kohlhagen_price(&fx_option(-1.0, OptionType::Put)).is_err());
+    assert!(simulate_fx_forward(&FxForwardContract { pair: eur_usd(), time_to_maturity_days: 30, time_step_days: 0.0, seed: None }).is_err());
+    assert!(simulate_fx_forward(&FxForwardContract { pair: FxPair { pip_size: 0.0, ..eur_usd() }, time_to_maturity_days: 30, time_step_days: 1.0, seed: None }).is_err());
+
+    for time_step_days in [1e-6, f64::NAN, f64::INFINITY] {
+        let contract = FxForwardContract { pair: eur_usd(), time_to_maturity_days: 30, time_step_days, seed: None };
+        let error = classify(&simulate_fx_forward(&contract).unwrap_err());
+        assert_eq!(error.field.as_deref(), Some("time_step_days"), "{}", time_step_days);
+    }
+    let at_cap = FxForwardContract { pair: eur_usd(), time_to_maturity_days: 1000, time_step_days: 1000.0 / MAX_FX_STEPS as f64, seed: Some(1) };
+    assert_eq!(simulate_fx_forward(&at_cap).unwrap().spot_rates.len(), MAX_FX_STEPS + 1);
+}
+
+#[test]
+fn test_each_currency_takes_a_rate_or_a_curve() {
+    let usd = DiscountCurve::flat(0.05).unwrap();
+    let code_and_field = |pair: FxPair| {
+        let contract = FxForwardContract { pair, time_to_maturity_days: 30, time_step_days: 1.0, seed: None };
+        let error = classify(&simulate_fx_forward(&contract).unwrap_err());
+        (error.code, error.field.unwrap())
+    };
+    assert_eq!(
+        code_and_field(FxPair { domestic_curve: Some(usd.clone()), ..eur_usd() }),
+        (ErrorCode::InvalidParameter, "pair.domestic_curve".to_string()),
+    );
+    assert_eq!(
+        code_and_field(FxPair { foreign_rate: None, ..eur_usd() }),
+        (ErrorCode::MissingParameter, "pair.foreign_rate".to_string()),
+    );
+
+    let curved = FxPair { domestic_rate: None, domestic_curve: Some(usd), ..eur_usd() };
+    assert!((curved.forward_rate(1.0) - eur_usd().forward_rate(1.0)).abs() < 1e-12);
+}
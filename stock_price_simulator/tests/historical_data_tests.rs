@@ -0,0 +1,68 @@
+use stock_price_simulator::historical_data::{read_price_history_csv, load_price_history_csv, log_returns, PriceCsvOptions};
+use stock_price_simulator::export::{time_series_to_csv, CsvExportOptions};
+use stock_price_simulator::stock_simulation::StockSimulator;
+use chrono::NaiveDate;
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+#[test]
+fn test_read_price_history_round_trips_exported_csv() {
+    let series = StockSimulator::simulate_stock_price(100.0, 0.05, 0.2, 10, 1.0, Some(3)).unwrap();
+    let csv = time_series_to_csv(&series, &CsvExportOptions::default()).unwrap();
+
+    let loaded = read_price_history_csv(csv.as_bytes(), &PriceCsvOptions::default()).unwrap();
+    assert_eq!(loaded.timestamps, series.timestamps);
+    assert_eq!(loaded.prices, series.prices);
+}
+
+#[test]
+fn test_read_price_history_custom_columns_and_sorting() {
+    let csv = "Date;Open;Close\n2024-03-04;10;11.5\n2024-03-01;9;10.5\n2024-03-05;11;12\n";
+    let options = PriceCsvOptions {
+        delimiter: b';',
+        timestamp_column: "date".to_string(),
+        price_column: "CLOSE".to_string(),
+        timestamp_format: Some("%Y-%m-%d".to_string()),
+    };
+
+    let loaded = read_price_history_csv(csv.as_bytes(), &options).unwrap();
+    assert_eq!(loaded.prices, vec![10.5, 11.5, 12.0]);
+    assert_eq!(loaded.timestamps[0], NaiveDate::from_ymd_opt(2024, 3, 1).unwrap().and_hms_opt(0, 0, 0).unwrap());
+}
+
+#[test]
+fn test_read_price_history_errors() {
+    let options = PriceCsvOptions::default();
+    let missing_column = read_price_history_csv("timestamp,close\n2024-01-01,1\n".as_bytes(), &options);
+    assert!(missing_column.unwrap_err().to_string().contains("Column 'price' not found"));
+
+    let bad_price = read_price_history_csv("timestamp,price\n2024-01-01,1\n2024-01-02,abc\n".as_bytes(), &options);
+    assert!(bad_price.unwrap_err().to_string().contains("Line 3"));
+
+    let negative = read_price_history_csv("timestamp,price\n2024-01-01,-1\n".as_bytes(), &options);
+    assert!(negative.is_err());
+
+    let duplicate = read_price_history_csv("timestamp,price\n2024-01-01,1\n2024-01-01,2\n".as_bytes(), &options);
+    assert!(duplicate.unwrap_err().to_string().contains("Duplicate timestamp"));
+
+    let empty = read_price_history_csv("timestamp,price\n".as_bytes(), &options);
+    assert!(empty.is_err());
+}
+
+#[test]
+fn test_load_price_history_from_file() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    temp_file.write_all(b"timestamp,price\n2024-01-01 00:00:00,100\n2024-01-02 00:00:00,101\n").unwrap();
+
+    let loaded = load_price_history_csv(temp_file.path().to_str().unwrap(), &PriceCsvOptions::default()).unwrap();
+    assert_eq!(loaded.prices, vec![100.0, 101.0]);
+    assert!(load_price_history_csv("does_not_exist.csv", &PriceCsvOptions::default()).is_err());
+}
+
+#[test]
+fn test_log_returns() {
+    let returns = log_returns(&[100.0, 110.0, 99.0]);
+    assert_eq!(returns.len(), 2);
+    assert!((returns[0] - (1.1f64).ln()).abs() < 1e-12);
+    assert!((returns[1] - (0.9f64).ln()).abs() < 1e-12);
+}
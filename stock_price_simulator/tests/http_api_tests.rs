@@ -1,13 +1,19 @@
 use stock_price_simulator::config::GlobalConfig;
+use stock_price_simulator::config_store::ConfigStore;
 // run_server is not directly used here anymore, http_server module's handlers are referenced.
 use actix_web::{dev::ServerHandle, web, App, HttpServer, middleware::Logger, rt as actix_rt};
 use std::net::TcpListener;
+use std::sync::Arc;
 use std::time::Duration;
 
 
 // Helper function to spawn the server for testing
 // Returns the base URL and the ServerHandle to stop it
 async fn spawn_test_app_server(config: GlobalConfig) -> (String, ServerHandle) {
+    spawn_test_app_server_with_store(Arc::new(ConfigStore::new(config, None).expect("Failed to hash test config"))).await
+}
+
+async fn spawn_test_app_server_with_store(config_store: Arc<ConfigStore>) -> (String, ServerHandle) {
     let port = portpicker::pick_unused_port().expect("No free ports found");
     let address = format!("127.0.0.1:{}", port);
 
@@ -15,7 +21,7 @@ async fn spawn_test_app_server(config: GlobalConfig) -> (String, ServerHandle) {
     drop(listener);
 
     let server_address = address.clone();
-    let app_config_data = web::Data::new(config.clone());
+    let app_config_data = web::Data::from(config_store);
 
     let (tx, rx) = std::sync::mpsc::channel();
 
@@ -26,11 +32,7 @@ async fn spawn_test_app_server(config: GlobalConfig) -> (String, ServerHandle) {
             App::new()
                 .app_data(app_config_data.clone())
                 .wrap(Logger::default())
-                .route("/simulate/stock", web::get().to(stock_price_simulator::http_server::simulate_stock_handler))
-                .route("/simulate/option/black_scholes", web::post().to(stock_price_simulator::http_server::simulate_option_bs_handler))
-                .route("/simulate/option/monte_carlo", web::post().to(stock_price_simulator::http_server::simulate_option_mc_handler))
-                .route("/simulate/future", web::post().to(stock_price_simulator::http_server::simulate_future_handler))
-                .route("/simulate/etf", web::post().to(stock_price_simulator::http_server::simulate_etf_handler))
+                .configure(stock_price_simulator::http_server::configure_routes) // Same routes as run_server
         })
         .bind(&server_address)
         .unwrap_or_else(|e| panic!("Failed to bind test server: {}", e))
@@ -54,8 +56,9 @@ async fn spawn_test_app_server(config: GlobalConfig) -> (String, ServerHandle) {
 mod tests {
     use super::*;
     use reqwest::Client;
-    use stock_price_simulator::api_models::{ApiResponse, StockData, ApiErrorResponse, OptionData};
+    use stock_price_simulator::api_models::{ApiResponse, StockData, ApiErrorResponse, OptionData, EtfData, BondData, FxData, ConfigStatusData, ModelResolutionData, FutureData, HealthData, VersionData};
     use serde_json::json;
+    use stock_price_simulator::error::ErrorCode;
 
     #[actix_web::test]
     async fn initial_server_spawn_test() {
@@ -224,4 +227,505 @@ mod tests {
         assert!(err_resp.error.contains("Time to maturity (T) must be positive if not zero"));
         server_handle.stop(true).await;
     }
+
+    #[actix_web::test]
+    async fn test_simulate_stock_csv_response() {
+        let test_config = stock_price_simulator::config::load_config("config.test.toml")
+            .expect("Failed to load test config for stock CSV");
+        let (base_url, server_handle) = spawn_test_app_server(test_config).await;
+
+        let client = Client::new();
+        let url = format!(
+            "{}/simulate/stock?asset_identifier=TEST_DEFAULT&initial_price=100.0&days=3&time_step_days=1.0&seed=123",
+            base_url
+        );
+        let resp = client.get(&url).header("Accept", "text/csv").send().await.expect("Request failed");
+
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+        let content_type = resp.headers().get("content-type").unwrap().to_str().unwrap().to_string();
+        assert!(content_type.starts_with("text/csv"), "Unexpected content type: {}", content_type);
+        let body = resp.text().await.expect("Failed to read CSV body");
+        let lines: Vec<&str> = body.lines().collect();
+        assert_eq!(lines[0], "timestamp,price");
+        assert_eq!(lines.len(), 4);
+        server_handle.stop(true).await;
+    }
+
+    #[actix_web::test]
+    async fn test_simulate_etf_defaults_to_json_for_unsupported_accept() {
+        let test_config = stock_price_simulator::config::load_config("config.test.toml")
+            .expect("Failed to load test config for ETF accept fallback");
+        let (base_url, server_handle) = spawn_test_app_server(test_config).await;
+        let client = Client::new();
+        let url = format!("{}/simulate/etf", base_url);
+
+        let etf_input = json!({
+            "constituents": [
+                { "symbol": "A", "initial_price": 50.0, "drift": 0.1, "volatility": 0.3, "weight": 1.0 }
+            ],
+            "simulation_days": 5,
+            "time_step_days": 1.0,
+            "seed": 7
+        });
+        let resp = client.post(&url).header("Accept", "application/xml").json(&etf_input)
+            .send().await.expect("Request failed");
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+        let api_resp = resp.json::<ApiResponse<EtfData>>().await.expect("Failed to parse JSON fallback");
+        assert_eq!(api_resp.data.nav_values.len(), 5);
+        server_handle.stop(true).await;
+    }
+
+    #[actix_web::test]
+    async fn test_simulate_etf_with_market_price() {
+        let test_config = stock_price_simulator::config::load_config("config.test.toml")
+            .expect("Failed to load test config for ETF market simulation");
+        let (base_url, server_handle) = spawn_test_app_server(test_config).await;
+        let client = Client::new();
+        let url = format!("{}/simulate/etf", base_url);
+
+        let etf_input = json!({
+            "constituents": [
+                { "symbol": "A", "initial_price": 50.0, "drift": 0.1, "volatility": 0.3, "weight": 1.0 }
+            ],
+            "simulation_days": 10,
+            "time_step_days": 1.0,
+            "seed": 7,
+            "expense_ratio": 0.002,
+            "market": {
+                "premium_mean_reversion": 5.0, "premium_volatility": 0.01, "creation_unit_size": 50000,
+                "creation_cost_bps": 10.0, "redemption_cost_bps": 15.0, "initial_shares_outstanding": 20000000
+            }
+        });
+        let resp = client.post(&url).json(&etf_input).send().await.expect("Request failed");
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+        let data = resp.json::<ApiResponse<EtfData>>().await.expect("Failed to parse ETF response").data;
+        let market_prices = data.market_prices.expect("market_prices missing");
+        let premiums = data.premiums.expect("premiums missing");
+        assert_eq!(market_prices.len(), data.nav_values.len());
+        for ((price, nav), premium) in market_prices.iter().zip(&data.nav_values).zip(&premiums) {
+            assert!((price - nav * (1.0 + premium)).abs() < 1e-9);
+        }
+        assert_eq!(data.shares_outstanding.expect("shares_outstanding missing")[0], 20_000_000);
+        server_handle.stop(true).await;
+    }
+
+    #[actix_web::test]
+    async fn test_simulate_etf_with_benchmark_returns_tracking() {
+        let test_config = stock_price_simulator::config::load_config("config.test.toml")
+            .expect("Failed to load test config for ETF tracking");
+        let (base_url, server_handle) = spawn_test_app_server(test_config).await;
+        let client = Client::new();
+        let url = format!("{}/simulate/etf", base_url);
+
+        let etf_input = json!({
+            "constituents": [
+                { "symbol": "A", "initial_price": 50.0, "drift": 0.1, "volatility": 0.3, "weight": 0.6 },
+                { "symbol": "B", "initial_price": 80.0, "drift": 0.05, "volatility": 0.2, "weight": 0.4 }
+            ],
+            "simulation_days": 20,
+            "time_step_days": 1.0,
+            "seed": 11,
+            "expense_ratio": 0.003,
+            "benchmark": { "weights": { "A": 0.5, "B": 0.5 } }
+        });
+        let resp = client.post(&url).json(&etf_input).send().await.expect("Request failed");
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+        let data = resp.json::<ApiResponse<EtfData>>().await.expect("Failed to parse ETF response").data;
+        assert!(data.market_prices.is_none());
+        let tracking = data.tracking.expect("tracking missing");
+        assert_eq!(tracking.benchmark_nav.len(), data.nav_values.len());
+        assert_eq!(tracking.attribution.len(), 2);
+        assert_eq!(tracking.attribution[1].symbol, "B");
+        assert!(tracking.tracking_error > 0.0);
+
+        let bad_input = json!({
+            "constituents": [{ "symbol": "A", "initial_price": 50.0, "drift": 0.1, "volatility": 0.3, "weight": 1.0 }],
+            "simulation_days": 20, "time_step_days": 1.0, "seed": 11,
+            "benchmark": { "weights": { "C": 1.0 } }
+        });
+        let resp = client.post(&url).json(&bad_input).send().await.expect("Request failed");
+        assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+        server_handle.stop(true).await;
+    }
+
+    #[actix_web::test]
+    async fn test_simulate_bond_success() {
+        let test_config = stock_price_simulator::config::load_config("config.test.toml")
+            .expect("Failed to load test config for bond simulation");
+        let (base_url, server_handle) = spawn_test_app_server(test_config).await;
+        let client = Client::new();
+        let url = format!("{}/simulate/bond", base_url);
+
+        let bond_input = json!({
+            "bond_symbol": "UST_2Y",
+            "face_value": 100.0,
+            "coupon_rate": 0.04,
+            "time_to_maturity_years": 2.0,
+            "short_rate_model": {
+                "model": "Vasicek", "initial_rate": 0.03, "mean_reversion_speed": 0.2,
+                "long_term_rate": 0.04, "volatility": 0.01
+            },
+            "time_step_days": 21.0,
+            "seed": 5
+        });
+        let resp = client.post(&url).json(&bond_input).send().await.expect("Request failed");
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+        let api_resp = resp.json::<ApiResponse<BondData>>().await.expect("Failed to parse bond response");
+        assert_eq!(api_resp.data.bond_symbol, "UST_2Y");
+        assert_eq!(api_resp.data.clean_prices.len(), 25);
+        assert_eq!(api_resp.data.yields.last(), Some(&None));
+        assert!(api_resp.data.modified_durations[0] > 1.8 && api_resp.data.modified_durations[0] < 2.0);
+
+        let invalid = json!({
+            "bond_symbol": "BAD", "face_value": -1.0, "time_to_maturity_years": 2.0,
+            "short_rate_model": { "model": "Vasicek", "initial_rate": 0.03, "mean_reversion_speed": 0.2, "long_term_rate": 0.04, "volatility": 0.01 },
+            "time_step_days": 21.0, "seed": null
+        });
+        let resp = client.post(&url).json(&invalid).send().await.expect("Request failed");
+        assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+        server_handle.stop(true).await;
+    }
+
+    #[actix_web::test]
+    async fn test_simulate_fx_and_garman_kohlhagen() {
+        let test_config = stock_price_simulator::config::load_config("config.test.toml")
+            .expect("Failed to load test config for FX simulation");
+        let (base_url, server_handle) = spawn_test_app_server(test_config).await;
+        let client = Client::new();
+        let pair = json!({
+            "base_currency": "EUR", "quote_currency": "USD", "spot_rate": 1.1,
+            "domestic_rate": 0.05, "foreign_rate": 0.03, "volatility": 0.08
+        });
+
+        let fx_input = json!({ "pair": pair, "time_to_maturity_days": 30, "time_step_days": 1.0, "seed": 3 });
+        let resp = client.post(format!("{}/simulate/fx", base_url)).json(&fx_input).send().await.expect("Request failed");
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+        let api_resp = resp.json::<ApiResponse<FxData>>().await.expect("Failed to parse FX response");
+        assert_eq!(api_resp.data.pair, "EUR/USD");
+        assert_eq!(api_resp.data.spot_rates.len(), 31);
+        assert!(api_resp.data.forward_points[0] > 0.0);
+
+        let option_input = json!({ "pair": pair, "strike_price": 1.1, "time_to_maturity_years": 0.5, "option_type": "Call" });
+        let resp = client.post(format!("{}/simulate/option/garman_kohlhagen", base_url)).json(&option_input).send().await.expect("Request failed");
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+        let api_resp = resp.json::<ApiResponse<OptionData>>().await.expect("Failed to parse option response");
+        assert_eq!(api_resp.data.underlying_symbol, "EUR/USD");
+        assert!(api_resp.data.price.unwrap() > 0.0);
+        server_handle.stop(true).await;
+    }
+
+    #[actix_web::test]
+    async fn test_admin_config_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+        std::fs::copy("config.test.toml", &config_path).unwrap();
+        let store = Arc::new(ConfigStore::from_file(config_path.to_str().unwrap()).unwrap());
+        let (base_url, server_handle) = spawn_test_app_server_with_store(store).await;
+        let client = Client::new();
+        let stock_url = format!("{}/simulate/stock?asset_identifier=TEST_DEFAULT&initial_price=100&days=5&time_step_days=1&seed=3", base_url);
+        let simulate = || async {
+            client.get(&stock_url).send().await.unwrap().json::<ApiResponse<StockData>>().await.unwrap().data.prices
+        };
+
+        let status = client.get(format!("{}/admin/config", base_url)).send().await.unwrap()
+            .json::<ApiResponse<ConfigStatusData>>().await.unwrap().data;
+        assert_eq!(status.version, 1);
+        assert_eq!(status.asset_model_count, 2);
+        assert_eq!(status.source.as_deref(), config_path.to_str());
+        let before = simulate().await;
+
+        let contents = std::fs::read_to_string(&config_path).unwrap();
+        std::fs::write(&config_path, contents.replacen("volatility = 0.15", "volatility = 0.45", 1)).unwrap();
+        let resp = client.post(format!("{}/admin/config/reload", base_url)).send().await.unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+        let reloaded = resp.json::<ApiResponse<ConfigStatusData>>().await.unwrap().data;
+        assert_eq!(reloaded.version, 2);
+        assert_ne!(reloaded.hash, status.hash);
+        let after = simulate().await;
+        assert_ne!(before, after, "Requests after the reload use the new volatility");
+
+        std::fs::write(&config_path, "not = [valid").unwrap();
+        let resp = client.post(format!("{}/admin/config/reload", base_url)).send().await.unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::UNPROCESSABLE_ENTITY);
+        let status = client.get(format!("{}/admin/config", base_url)).send().await.unwrap()
+            .json::<ApiResponse<ConfigStatusData>>().await.unwrap().data;
+        assert_eq!(status.version, 2);
+        assert!(status.last_reload_error.is_some());
+        assert_eq!(simulate().await, after, "The last valid config keeps serving");
+        server_handle.stop(true).await;
+    }
+
+    #[actix_web::test]
+    async fn test_admin_config_resolve() {
+        let mut config = stock_price_simulator::config::load_config("config.test.toml").unwrap();
+        let mut glob = config.asset_models.as_ref().unwrap()[1].clone();
+        glob.asset_identifier_pattern = "TEST_*".to_string();
+        config.asset_models.as_mut().unwrap().push(glob);
+        let (base_url, server_handle) = spawn_test_app_server(config).await;
+        let client = Client::new();
+        let resolve = |identifier: &'static str| {
+            let url = format!("{}/admin/config/resolve?asset_identifier={}", base_url, identifier);
+            let client = client.clone();
+            async move { client.get(url).send().await.unwrap().json::<ApiResponse<ModelResolutionData>>().await.unwrap().data }
+        };
+
+        let exact = resolve("TEST_DEFAULT").await;
+        assert_eq!(exact.asset_type, "stock");
+        let resolved = exact.resolved.unwrap();
+        assert_eq!((resolved.index, resolved.match_kind.as_str()), (0, "exact"));
+        assert_eq!(resolved.model.parameters.gbm.unwrap().drift, 0.03);
+        assert_eq!(exact.candidates.len(), 3);
+        assert_eq!(exact.candidates.iter().filter(|c| c.matched).count(), 2, "TEST_DEFAULT and TEST_*");
+
+        let glob = resolve("TEST_OTHER").await.resolved.unwrap();
+        assert_eq!((glob.index, glob.pattern.as_str(), glob.match_kind.as_str()), (2, "TEST_*", "glob"));
+
+        let none = resolve("UNKNOWN").await;
+        assert!(none.resolved.is_none());
+        assert!(none.candidates.iter().all(|c| !c.matched));
+
+        let resp = client.get(format!("{}/simulate/stock?asset_identifier=TEST_OTHER&initial_price=100&days=3&time_step_days=1&seed=1", base_url))
+            .send().await.unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::OK, "Glob entries serve simulations too");
+        server_handle.stop(true).await;
+    }
+
+    #[actix_web::test]
+    async fn test_requests_default_to_the_global_config() {
+        let test_config = stock_price_simulator::config::load_config("config.test.toml").unwrap();
+        let (base_url, server_handle) = spawn_test_app_server(test_config).await;
+        let client = Client::new();
+        let stock_url = format!("{}/simulate/stock?asset_identifier=TEST_DEFAULT&initial_price=100", base_url);
+        let stock = || async {
+            let resp = client.get(&stock_url).send().await.unwrap();
+            assert_eq!(resp.status(), reqwest::StatusCode::OK);
+            resp.json::<ApiResponse<StockData>>().await.unwrap().data
+        };
+        let first = stock().await;
+        assert_eq!(first.prices.len(), 2400, "simulation_period_days in steps of time_step_minutes");
+        assert_eq!(first.prices, stock().await.prices, "The seed is derived from random_seed");
+        let explicit = client.get(format!("{}&seed=1", stock_url)).send().await.unwrap()
+            .json::<ApiResponse<StockData>>().await.unwrap().data;
+        assert_ne!(first.prices, explicit.prices);
+
+        let future_body = json!({ "underlying_symbol": "CL", "initial_spot_price": 70.0, "risk_free_rate": 0.03,
+            "volatility": 0.25, "time_step_days": 1.0 });
+        let future = || async {
+            client.post(format!("{}/simulate/future", base_url)).json(&future_body).send().await.unwrap()
+                .json::<ApiResponse<FutureData>>().await.unwrap().data.prices
+        };
+        let prices = future().await;
+        assert_eq!(prices.len(), 101);
+        assert_eq!(prices, future().await);
+
+        let resp = client.post(format!("{}/simulate/future", base_url)).json(&json!({ "underlying_symbol": "CL" })).send().await.unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+        server_handle.stop(true).await;
+    }
+
+    #[actix_web::test]
+    async fn test_post_endpoints_accept_configured_instruments() {
+        let config = stock_price_simulator::config::parse_config(r#"
+            simulation_period_days = 10
+            time_step_minutes = 1440
+            [[instruments.options]]
+            identifier = "ACME_C100"
+            underlying_symbol = "ACME"
+            option_type = "Call"
+            strike_price = 100.0
+            time_to_maturity_years = 1.0
+            underlying_price = 100.0
+            volatility = 0.2
+            risk_free_rate = 0.03
+            [[instruments.futures]]
+            identifier = "ESZ24"
+            underlying_symbol = "ES"
+            initial_spot_price = 4500.0
+            risk_free_rate = 0.05
+            volatility = 0.18
+            [[instruments.etfs]]
+            identifier = "BASKET"
+            [[instruments.etfs.constituents]]
+            symbol = "A"
+            initial_price = 10.0
+            drift = 0.05
+            volatility = 0.2
+            weight = 1.0
+        "#).unwrap();
+        let (base_url, server_handle) = spawn_test_app_server(config).await;
+        let client = Client::new();
+
+        let option = client.post(format!("{}/simulate/option/black_scholes", base_url))
+            .json(&json!({ "asset_identifier": "ACME_C100" })).send().await.unwrap()
+            .json::<ApiResponse<OptionData>>().await.unwrap().data;
+        assert_eq!(option.underlying_symbol, "ACME");
+        let deeper = client.post(format!("{}/simulate/option/black_scholes", base_url))
+            .json(&json!({ "asset_identifier": "ACME_C100", "strike_price": 90.0 })).send().await.unwrap()
+            .json::<ApiResponse<OptionData>>().await.unwrap().data;
+        assert!(deeper.price.unwrap() > option.price.unwrap());
+
+        let future = client.post(format!("{}/simulate/future", base_url))
+            .json(&json!({ "asset_identifier": "ESZ24", "time_to_maturity_days": 5 })).send().await.unwrap()
+            .json::<ApiResponse<FutureData>>().await.unwrap().data;
+        assert_eq!(future.contract_symbol, "ESZ24");
+        assert_eq!(future.prices.len(), 6);
+        assert!((future.prices[0] - 4500.0 * (0.05f64 * 5.0 / 365.0).exp()).abs() < 1e-9);
+
+        let etf = client.post(format!("{}/simulate/etf", base_url))
+            .json(&json!({ "asset_identifier": "BASKET", "seed": 3 })).send().await.unwrap()
+            .json::<ApiResponse<EtfData>>().await.unwrap().data;
+        assert_eq!(etf.etf_symbol, "BASKET");
+        assert_eq!(etf.nav_values.len(), 10, "simulation_days from the GlobalConfig");
+
+        let resp = client.post(format!("{}/simulate/etf", base_url))
+            .json(&json!({ "asset_identifier": "UNKNOWN" })).send().await.unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+        let err = resp.json::<ApiErrorResponse>().await.unwrap();
+        assert!(err.error.contains("No instrument 'UNKNOWN'"), "{}", err.error);
+        server_handle.stop(true).await;
+    }
+
+    #[actix_web::test]
+    async fn test_versioned_routes_match_the_openapi_document() {
+        let test_config = stock_price_simulator::config::load_config("config.test.toml").unwrap();
+        let (base_url, server_handle) = spawn_test_app_server(test_config).await;
+        let client = Client::new();
+
+        let document: serde_json::Value = client.get(format!("{}/openapi.json", base_url)).send().await.unwrap()
+            .json().await.unwrap();
+        assert!(document["openapi"].as_str().unwrap().starts_with("3."));
+        let health = client.get(format!("{}/healthz", base_url)).send().await.unwrap()
+            .json::<ApiResponse<HealthData>>().await.unwrap().data;
+        assert_eq!(health, HealthData { config_version: 1, config_reload_failing: false });
+        let paths = document["paths"].as_object().unwrap();
+        assert!(paths.contains_key("/v1/simulate/stock"));
+        assert!(!paths.contains_key("/simulate/stock"), "Unversioned aliases are not documented");
+        // Every documented operation is routed (an empty body is a 400, not a 404 or 405). This also
+        // fails a reload, as the test config has no file.
+        for (path, item) in paths {
+            for method in item.as_object().unwrap().keys() {
+                let url = format!("{}{}", base_url, path);
+                let resp = match method.as_str() {
+                    "get" => client.get(&url).send().await.unwrap(),
+                    "post" => client.post(&url).json(&json!({})).send().await.unwrap(),
+                    other => panic!("Unexpected method {}", other),
+                };
+                assert!(![404, 405].contains(&resp.status().as_u16()), "{} {} -> {}", method, path, resp.status());
+            }
+        }
+        let schemas = document["components"]["schemas"].as_object().unwrap();
+        for name in ["ApiResponse_for_StockData", "ApiErrorResponse", "EtfConstituent", "DiscountCurveParams"] {
+            assert!(schemas.contains_key(name), "{} missing", name);
+        }
+        let stock_days = paths["/v1/simulate/stock"]["get"]["parameters"].as_array().unwrap().iter()
+            .find(|p| p["name"] == "days").unwrap().clone();
+        assert_eq!(stock_days["required"], false, "days defaults to the config");
+        let body_description = |path: &str| {
+            paths[path]["post"]["requestBody"]["content"]["application/json"]["schema"]["description"].as_str().unwrap().to_string()
+        };
+        assert!(!body_description("/v1/simulate/option/black_scholes").contains("GlobalConfig"), "Black-Scholes takes no config defaults");
+        assert!(body_description("/v1/simulate/option/monte_carlo").contains("GlobalConfig"));
+
+        let versioned = client.get(format!("{}/v1/simulate/stock?asset_identifier=TEST_DEFAULT&initial_price=100&days=5&seed=1", base_url))
+            .send().await.unwrap().json::<ApiResponse<StockData>>().await.unwrap().data;
+        let legacy = client.get(format!("{}/simulate/stock?asset_identifier=TEST_DEFAULT&initial_price=100&days=5&seed=1", base_url))
+            .send().await.unwrap().json::<ApiResponse<StockData>>().await.unwrap().data;
+        assert_eq!(versioned, legacy);
+
+        let version = client.get(format!("{}/version", base_url)).send().await.unwrap()
+            .json::<ApiResponse<VersionData>>().await.unwrap().data;
+        assert_eq!(version.api_version, "v1");
+        assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(document["info"]["version"], version.version);
+        server_handle.stop(true).await;
+    }
+
+    #[actix_web::test]
+    async fn test_errors_carry_a_code_and_field() {
+        let test_config = stock_price_simulator::config::load_config("config.test.toml").unwrap();
+        let (base_url, server_handle) = spawn_test_app_server(test_config).await;
+        let client = Client::new();
+        let etf_url = format!("{}/v1/simulate/etf", base_url);
+
+        let resp = client.post(&etf_url).header("content-type", "application/json").body("{ not json").send().await.unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+        let err = resp.json::<ApiErrorResponse>().await.expect("Malformed JSON gets the error envelope");
+        assert_eq!(err.code, ErrorCode::InvalidRequest);
+
+        let resp = client.post(&etf_url).header("content-type", "text/plain").body("{}").send().await.unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(resp.json::<ApiErrorResponse>().await.unwrap().code, ErrorCode::UnsupportedMediaType);
+
+        let resp = client.post(&etf_url).json(&json!({
+            "constituents": [{ "symbol": "A", "initial_price": 10.0, "drift": 0.0, "volatility": 0.1 }],
+        })).send().await.unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+        let err = resp.json::<ApiErrorResponse>().await.unwrap();
+        assert_eq!((err.code, err.field.as_deref()), (ErrorCode::MissingParameter, Some("constituents[0].weight")));
+
+        let resp = client.post(&etf_url).json(&json!({
+            "constituents": [
+                { "symbol": "A", "initial_price": 10.0, "drift": 0.0, "volatility": 0.1, "weight": 1.2 },
+                { "symbol": "B", "initial_price": 10.0, "drift": 0.0, "volatility": -0.1, "weight": -0.2 },
+            ],
+        })).send().await.unwrap();
+        let err = resp.json::<ApiErrorResponse>().await.unwrap();
+        assert_eq!((err.code, err.field.as_deref()), (ErrorCode::InvalidParameter, Some("constituents[1].volatility")));
+
+        let resp = client.get(format!("{}/v1/simulate/stock?asset_identifier=TEST_DEFAULT", base_url)).send().await.unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+        let err = resp.json::<ApiErrorResponse>().await.unwrap();
+        assert_eq!((err.code, err.field.as_deref()), (ErrorCode::MissingParameter, Some("initial_price")));
+
+        for (query, field) in [("initial_price=abc", "initial_price"), ("initial_price=100&days=-3", "days")] {
+            let resp = client.get(format!("{}/v1/simulate/stock?asset_identifier=TEST_DEFAULT&{}", base_url, query)).send().await.unwrap();
+            assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+            let err = resp.json::<ApiErrorResponse>().await.unwrap();
+            assert_eq!((err.code, err.field.as_deref()), (ErrorCode::InvalidRequest, Some(field)), "{}: {}", query, err.error);
+        }
+        let resp = client.get(format!("{}/v1/admin/config/resolve", base_url)).send().await.unwrap();
+        let err = resp.json::<ApiErrorResponse>().await.unwrap();
+        assert_eq!((err.code, err.field.as_deref()), (ErrorCode::MissingParameter, Some("asset_identifier")));
+
+        let resp = client.get(format!("{}/v1/simulate/stock?asset_identifier=NON_EXISTENT&initial_price=100", base_url)).send().await.unwrap();
+        let err = resp.json::<ApiErrorResponse>().await.unwrap();
+        assert_eq!((err.code, err.field.as_deref()), (ErrorCode::UnknownAsset, Some("asset_identifier")));
+
+        let resp = client.get(format!("{}/v1/no/such/route", base_url)).send().await.unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::NOT_FOUND);
+        assert_eq!(resp.json::<ApiErrorResponse>().await.unwrap().code, ErrorCode::NotFound);
+        server_handle.stop(true).await;
+    }
+
+    #[actix_web::test]
+    async fn test_admin_routes_need_loopback_or_the_token() {
+        use actix_web::test::{call_service, init_service, TestRequest};
+        use stock_price_simulator::http_server::{configure_routes, AdminAccess};
+
+        let config = stock_price_simulator::config::load_config("config.test.toml").unwrap();
+        let store = web::Data::new(ConfigStore::new(config, None).unwrap());
+        let status_of = |request: TestRequest| request.uri("/v1/admin/config").to_request();
+        let loopback = "127.0.0.1:40000".parse().unwrap();
+        let remote = "203.0.113.7:40000".parse().unwrap();
+
+        let open = init_service(App::new().app_data(store.clone()).configure(configure_routes)).await;
+        let resp = call_service(&open, status_of(TestRequest::get().peer_addr(loopback))).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::OK);
+        let resp = call_service(&open, status_of(TestRequest::get().peer_addr(remote))).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::FORBIDDEN);
+        let err: ApiErrorResponse = actix_web::test::read_body_json(resp).await;
+        assert_eq!(err.code, ErrorCode::Forbidden);
+        let resp = call_service(&open, TestRequest::post().uri("/admin/config/reload").peer_addr(remote).to_request()).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::FORBIDDEN, "Unprefixed routes are guarded too");
+
+        let access = web::Data::new(AdminAccess { token: Some("s3cret".to_string()) });
+        let guarded = init_service(App::new().app_data(store.clone()).app_data(access).configure(configure_routes)).await;
+        let with_token = |token: &str| TestRequest::get().peer_addr(remote).insert_header(("Authorization", format!("Bearer {}", token)));
+        let resp = call_service(&guarded, status_of(with_token("s3cret"))).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::OK);
+        for request in [with_token("wrong"), with_token("s3cre"), with_token("s3cretx"), TestRequest::get().peer_addr(loopback)] {
+            let resp = call_service(&guarded, status_of(request)).await;
+            assert_eq!(resp.status(), actix_web::http::StatusCode::FORBIDDEN, "A token is required even over loopback");
+        }
+    }
 }
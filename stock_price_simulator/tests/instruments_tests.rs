@@ -0,0 +1,203 @@
+use serde_json::json;
+use stock_price_simulator::config::{parse_config, save_config, load_config, GlobalConfig};
+use stock_price_simulator::config_layers::load_layered_config;
+use stock_price_simulator::config_validation::validate_config;
+use stock_price_simulator::instruments::{resolve_instrument, InstrumentKind};
+
+const INSTRUMENTS: &str = r#"
+simulation_period_days = 100
+time_step_minutes = 1440
+
+[[instruments.options]]
+identifier = "ACME_C100"
+underlying_symbol = "ACME"
+option_type = "Call"
+strike_price = 100.0
+time_to_maturity_years = 1.0
+underlying_price = 95.0
+volatility = 0.2
+risk_free_rate = 0.03
+[instruments.options.discount_curve]
+interpolation = "MonotoneCubic"
+quotes = [
+    { instrument = "Deposit", maturity_years = 0.5, rate = 0.03 },
+    { instrument = "Swap", maturity_years = 2.0, rate = 0.035 },
+]
+
+[[instruments.futures]]
+identifier = "ESZ24"
+underlying_symbol = "ES"
+initial_spot_price = 4500.0
+risk_free_rate = 0.05
+volatility = 0.18
+time_to_maturity_days = 90
+
+[[instruments.etfs]]
+identifier = "BASKET"
+expense_ratio = 0.002
+[instruments.etfs.market]
+premium_mean_reversion = 50.0
+premium_volatility = 0.02
+creation_unit_size = 50000
+creation_cost_bps = 5.0
+redemption_cost_bps = 5.0
+initial_shares_outstanding = 1000000
+[[instruments.etfs.constituents]]
+symbol = "A"
+initial_price = 10.0
+drift = 0.05
+volatility = 0.2
+weight = 0.5
+[[instruments.etfs.constituents]]
+symbol = "B"
+initial_price = 20.0
+drift = 0.05
+volatility = 0.3
+weight = 0.5
+"#;
+
+fn config() -> GlobalConfig {
+    parse_config(INSTRUMENTS).unwrap()
+}
+
+#[test]
+fn test_body_overrides_the_configured_instrument() {
+    let config = config();
+    let etf = resolve_instrument(&config, InstrumentKind::Etf, json!({
+        "asset_identifier": "BASKET",
+        "simulation_days": 5,
+        "expense_ratio": null,
+        "market": { "premium_volatility": 0.05 },
+    })).unwrap();
+    assert_eq!(etf["asset_identifier"], "BASKET");
+    assert_eq!(etf["simulation_days"], 5);
+    assert!(etf.get("expense_ratio").is_none(), "null removes a configured field");
+    assert_eq!(etf["market"]["premium_volatility"], 0.05);
+    assert_eq!(etf["market"]["creation_unit_size"], 50000, "Nested tables merge key by key");
+    assert_eq!(etf["constituents"].as_array().unwrap().len(), 2);
+
+    let replaced = resolve_instrument(&config, InstrumentKind::Etf, json!({
+        "asset_identifier": "BASKET",
+        "constituents": [{ "symbol": "C", "initial_price": 5.0, "drift": 0.0, "volatility": 0.1, "weight": 1.0 }],
+    })).unwrap();
+    assert_eq!(replaced["constituents"].as_array().unwrap().len(), 1, "Arrays are replaced");
+
+    let future = resolve_instrument(&config, InstrumentKind::Future, json!({ "asset_identifier": "ESZ24", "volatility": 0.3 })).unwrap();
+    assert_eq!(future["underlying_symbol"], "ES");
+    assert_eq!(future["volatility"], 0.3);
+    assert_eq!(future["time_to_maturity_days"], 90);
+}
+
+#[test]
+fn test_option_fields_follow_the_pricing_endpoint() {
+    let config = config();
+    let black_scholes = resolve_instrument(&config, InstrumentKind::OptionBlackScholes, json!({ "asset_identifier": "ACME_C100" })).unwrap();
+    assert_eq!(black_scholes["underlying_price"], 95.0);
+    assert_eq!(black_scholes["volatility"], 0.2);
+    let option: stock_price_simulator::EuropeanOption = serde_json::from_value(black_scholes).unwrap();
+    assert!(option.discount_curve.is_some());
+
+    let monte_carlo = resolve_instrument(&config, InstrumentKind::OptionMonteCarlo, json!({
+        "asset_identifier": "ACME_C100", "num_paths": 100, "num_steps_per_path": 10,
+    })).unwrap();
+    assert_eq!(monte_carlo["underlying_initial_price"], 95.0);
+    assert_eq!(monte_carlo["underlying_volatility"], 0.2);
+    assert!(monte_carlo.get("underlying_price").is_none());
+    serde_json::from_value::<stock_price_simulator::MonteCarloEuropeanOptionInput>(monte_carlo).unwrap();
+}
+
+#[test]
+fn test_unknown_and_missing_identifiers() {
+    let config = config();
+    let body = json!({ "underlying_symbol": "CL", "volatility": 0.2 });
+    assert_eq!(resolve_instrument(&config, InstrumentKind::Future, body.clone()).unwrap(), body);
+
+    let err = resolve_instrument(&config, InstrumentKind::Future, json!({ "asset_identifier": "BASKET" })).unwrap_err();
+    assert!(err.to_string().contains("No instrument 'BASKET' in the [[instruments.futures]]"), "{}", err);
+    assert!(resolve_instrument(&config, InstrumentKind::Etf, json!({ "asset_identifier": 7 })).is_err());
+}
+
+#[test]
+fn test_instruments_round_trip_and_layer() {
+    let dir = std::env::temp_dir().join(format!("instruments_tests_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let config = config();
+    for name in ["saved.toml", "saved.yaml", "saved.json"] {
+        let path = dir.join(name).to_str().unwrap().to_string();
+        save_config(&config, &path).unwrap();
+        let reloaded = load_config(&path).unwrap();
+        assert_eq!(reloaded.instruments.etfs, config.instruments.etfs, "{}", name);
+        let (curve, reloaded_curve) = (
+            config.instruments.options[0].discount_curve.as_ref().unwrap(),
+            reloaded.instruments.options[0].discount_curve.as_ref().unwrap(),
+        );
+        for t in [0.25, 1.0, 3.0] {
+            assert!((curve.zero_rate(t) - reloaded_curve.zero_rate(t)).abs() < 1e-12, "{}", name);
+        }
+    }
+
+    let base = dir.join("base.toml").to_str().unwrap().to_string();
+    std::fs::write(&base, INSTRUMENTS).unwrap();
+    let overlay = dir.join("overlay.yaml").to_str().unwrap().to_string();
+    std::fs::write(&overlay, r#"
+instruments:
+  futures:
+    - identifier: ESZ24
+      underlying_symbol: ES
+      volatility: 0.25
+    - identifier: CLF25
+      underlying_symbol: CL
+"#).unwrap();
+    let layered = load_layered_config(&[base, overlay], Vec::new()).unwrap().config;
+    let futures = &layered.instruments.futures;
+    assert_eq!(futures.len(), 2);
+    assert_eq!(futures[0].volatility, Some(0.25));
+    assert_eq!(futures[0].time_to_maturity_days, None, "An overlay entry replaces the whole instrument");
+    assert_eq!(layered.instruments.etfs.len(), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_instrument_problems_are_reported() {
+    let contents = INSTRUMENTS.to_string() + r#"
+[[instruments.futures]]
+identifier = "ESZ24"
+underlying_symbol = "ES"
+volatility = -0.1
+
+[[instruments.etfs]]
+identifier = "EMPTY"
+constituents = []
+"#;
+    let diagnostics: Vec<String> = validate_config(&parse_config(&contents).unwrap()).iter().map(|d| d.to_string()).collect();
+    assert_eq!(diagnostics, vec![
+        "instruments.futures[1].identifier: duplicates instruments.futures[0], so this entry is never used",
+        "instruments.futures[1].volatility: must be a finite, non-negative number. Got -0.1",
+        "instruments.etfs[1].constituents: must not be empty",
+    ]);
+    assert!(validate_config(&config()).is_empty());
+
+    let err = parse_config(&(INSTRUMENTS.to_string() + "[[instruments.futures]]\nidentifier = \"X\"\nunderlying_symbol = \"X\"\nspot = 1.0\n")).unwrap_err();
+    assert!(!err.to_string().contains("asset_models"), "{}", err);
+    assert!(err.to_string().contains("spot"), "{}", err);
+}
+
+#[test]
+fn test_unknown_keys_in_nested_instrument_tables_are_rejected() {
+    let pair = "base_currency = \"EUR\"\nquote_currency = \"USD\"\nspot_rate = 1.1\ndomestic_rate = 0.05\nforeign_rate = 0.03\nvolatility = 0.1";
+    let constituent = "symbol = \"C\"\ninitial_price = 1.0\ndrift = 0.0\nvolatility = 0.1\nweight = 0.0";
+    for (contents, unknown) in [
+        (INSTRUMENTS.replace("premium_volatility = 0.02", "premium_volatility = 0.02\npremium_volatilty = 0.02"), "premium_volatilty"),
+        (format!("{}[[instruments.etfs.constituents]]\n{}\nwieght = 0.1\n", INSTRUMENTS, constituent), "wieght"),
+        (format!("{}[instruments.etfs.benchmark]\nexpense_ration = 0.001\n", INSTRUMENTS), "expense_ration"),
+        (format!("{}[instruments.etfs.fx]\nbase_currency = \"USD\"\nhedged = true\n", INSTRUMENTS), "hedged"),
+        (format!("{}[instruments.etfs.fx]\nbase_currency = \"USD\"\n[[instruments.etfs.fx.fx_pairs]]\n{}\nforeign_rates = 0.03\n", INSTRUMENTS, pair), "foreign_rates"),
+        (INSTRUMENTS.replace("interpolation = \"MonotoneCubic\"", "interpolation = \"MonotoneCubic\"\nshape = \"flat\""), "shape"),
+        (INSTRUMENTS.replace("maturity_years = 0.5, rate = 0.03 }", "maturity_years = 0.5, rate = 0.03, payments_per_year = 2 }"), "payments_per_year"),
+        (INSTRUMENTS.replace("maturity_years = 2.0, rate = 0.035 }", "maturity_years = 2.0, rate = 0.035, frequency = 2 }"), "frequency"),
+    ] {
+        assert_ne!(contents, INSTRUMENTS);
+        let err = parse_config(&contents).unwrap_err();
+        assert!(err.to_string().contains(unknown), "{}: {}", unknown, err);
+    }
+}
@@ -0,0 +1,146 @@
+use stock_price_simulator::config::{LocalVolGridParams, LocalVolatilityParams};
+use stock_price_simulator::local_volatility::{ImpliedVolSurface, LocalVolSurface, LocalVolatilityProcess};
+use stock_price_simulator::option_pricing::{black_scholes_price, EuropeanOption, MonteCarloOptionPricer, OptionType};
+use stock_price_simulator::random_process::StochasticProcess;
+
+const SPOT: f64 = 100.0;
+const RATE: f64 = 0.03;
+
+fn grid(start: f64, end: f64, step: f64) -> Vec<f64> {
+    let n = ((end - start) / step).round() as usize;
+    (0..=n).map(|i| start + i as f64 * step).collect()
+}
+
+// Equity-style skew: implied volatility falls with log-moneyness and the skew flattens with maturity.
+fn skewed_surface() -> ImpliedVolSurface {
+    let strikes = grid(40.0, 250.0, 2.5);
+    let maturities = vec![0.25, 0.5, 0.75, 1.0, 1.5, 2.0];
+    let volatilities = maturities.iter().map(|t: &f64| {
+        strikes.iter().map(|k| {
+            let y = (k / SPOT).ln();
+            (0.22 - 0.1 * y / t.sqrt().max(0.5) + 0.05 * y * y).max(0.08)
+        }).collect()
+    }).collect();
+    ImpliedVolSurface::new(SPOT, RATE, strikes, maturities, volatilities).unwrap()
+}
+
+fn european(strike: f64, maturity: f64, volatility: f64) -> EuropeanOption {
+    EuropeanOption {
+        underlying_price: SPOT,
+        strike_price: strike,
+        time_to_maturity_years: maturity,
+        risk_free_rate: Some(RATE),
+        volatility,
+        option_type: OptionType::Call,
+        discount_curve: None,
+    }
+}
+
+#[test]
+fn test_flat_implied_surface_gives_flat_local_vol() {
+    let strikes = grid(50.0, 150.0, 10.0);
+    let maturities = vec![0.5, 1.0, 2.0];
+    let volatilities = vec![vec![0.25; strikes.len()]; maturities.len()];
+    let surface = ImpliedVolSurface::new(SPOT, RATE, strikes, maturities, volatilities).unwrap();
+    let local = surface.to_local_vol().unwrap();
+    for row in &local.volatilities {
+        for v in row {
+            assert!((v - 0.25).abs() < 1e-9, "local vol {}", v);
+        }
+    }
+    assert!((surface.implied_vol(123.0, 0.7) - 0.25).abs() < 1e-12);
+}
+
+#[test]
+fn test_local_vol_surface_interpolation() {
+    let surface = LocalVolSurface::new(vec![80.0, 120.0], vec![0.0, 1.0], vec![vec![0.3, 0.1], vec![0.5, 0.3]]).unwrap();
+    assert!((surface.local_vol(100.0, 0.0) - 0.2).abs() < 1e-12);
+    assert!((surface.local_vol(100.0, 0.5) - 0.3).abs() < 1e-12);
+    assert!((surface.local_vol(10.0, 5.0) - 0.5).abs() < 1e-12, "flat extrapolation");
+
+    assert!(LocalVolSurface::new(vec![120.0, 80.0], vec![0.0], vec![vec![0.2, 0.2]]).is_err());
+    assert!(LocalVolSurface::new(vec![80.0, 120.0], vec![0.0], vec![vec![0.2]]).is_err());
+    assert!(LocalVolSurface::new(vec![80.0], vec![0.0], vec![vec![-0.2]]).is_err());
+}
+
+#[test]
+fn test_skew_produces_steeper_local_vol() {
+    let implied = skewed_surface();
+    let local = implied.to_local_vol().unwrap();
+    // Dupire local skew is roughly twice the implied skew near the money.
+    let implied_slope = implied.implied_vol(90.0, 0.5) - implied.implied_vol(110.0, 0.5);
+    let local_slope = local.local_vol(90.0, 0.5) - local.local_vol(110.0, 0.5);
+    assert!(implied_slope > 0.0);
+    assert!(local_slope > 1.5 * implied_slope, "local slope {} vs implied slope {}", local_slope, implied_slope);
+}
+
+#[test]
+fn test_calendar_arbitrage_is_rejected() {
+    let strikes = vec![90.0, 100.0, 110.0];
+    let maturities = vec![0.5, 1.0];
+    // Total variance falls from 0.5y to 1y.
+    let volatilities = vec![vec![0.4; 3], vec![0.2; 3]];
+    let surface = ImpliedVolSurface::new(SPOT, RATE, strikes, maturities, volatilities).unwrap();
+    assert!(surface.to_local_vol().unwrap_err().to_string().contains("Calendar arbitrage"));
+}
+
+#[test]
+fn test_monte_carlo_under_local_vol_reprices_the_smile() {
+    let implied = skewed_surface();
+    let process = LocalVolatilityProcess { drift: RATE, surface: implied.to_local_vol().unwrap() };
+
+    for &(strike, maturity) in &[(85.0, 1.0), (100.0, 1.0), (115.0, 1.0)] {
+        let option = european(strike, maturity, implied.implied_vol(strike, maturity));
+        let target = black_scholes_price(&option).unwrap();
+        let mc = MonteCarloOptionPricer::for_european_option(&option, 20_000, 100).unwrap()
+            .price_with_process(&process, Some(42))
+            .unwrap();
+        assert!((mc - target).abs() < 0.35, "K={} T={}: local vol MC {} vs smile BS {}", strike, maturity, mc, target);
+    }
+}
+
+#[test]
+fn test_constant_local_vol_matches_black_scholes() {
+    let params = LocalVolatilityParams {
+        drift: RATE,
+        grid: Some(LocalVolGridParams { spots: vec![SPOT], times_years: vec![0.0], volatilities: vec![vec![0.2]] }),
+        implied_surface: None,
+    };
+    let process = LocalVolatilityProcess::from_params(&params).unwrap();
+    let option = european(100.0, 1.0, 0.2);
+    let bs = black_scholes_price(&option).unwrap();
+    let mc = MonteCarloOptionPricer::for_european_option(&option, 20_000, 50).unwrap().price_with_process(&process, Some(7)).unwrap();
+    assert!((mc - bs).abs() < 0.3, "MC {} vs BS {}", mc, bs);
+
+    let path = process.generate_path(SPOT, 1.0, 10, Some(1));
+    assert_eq!(path.prices.len(), 10);
+    assert!(LocalVolatilityProcess::from_params(&LocalVolatilityParams { drift: RATE, grid: None, implied_surface: None }).is_err());
+}
+
+#[test]
+fn test_local_volatility_from_toml_config() {
+    use stock_price_simulator::api_interface::simulate_stock_with_config;
+    use stock_price_simulator::config::GlobalConfig;
+
+    let toml_str = r#"
+        simulation_period_days = 252
+        time_step_minutes = 1440
+
+        [[asset_models]]
+        asset_type = "stock"
+        asset_identifier_pattern = "SMILE_STOCK"
+        default_model = "LocalVolatility"
+        [asset_models.parameters.local_volatility]
+        drift = 0.03
+        [asset_models.parameters.local_volatility.implied_surface]
+        spot = 100.0
+        risk_free_rate = 0.03
+        strikes = [80.0, 90.0, 100.0, 110.0, 120.0]
+        maturities_years = [0.5, 1.0]
+        volatilities = [[0.28, 0.24, 0.2, 0.18, 0.17], [0.26, 0.23, 0.2, 0.185, 0.175]]
+    "#;
+    let config: GlobalConfig = toml::from_str(toml_str).unwrap();
+    let path = simulate_stock_with_config("SMILE_STOCK", &config, 100.0, 20, 1.0, Some(3), Some(0.0), None).unwrap();
+    assert_eq!(path.prices.len(), 20);
+    assert!(simulate_stock_with_config("SMILE_STOCK", &config, 100.0, 20, 1.0, Some(3), None, Some(0.2)).is_err());
+}
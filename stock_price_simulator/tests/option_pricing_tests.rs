@@ -15,9 +15,10 @@ fn test_black_scholes_call_price() {
         underlying_price: 100.0,
         strike_price: 100.0,
         time_to_maturity_years: 1.0,
-        risk_free_rate: 0.05,
+        risk_free_rate: Some(0.05),
         volatility: 0.2,
         option_type: OptionType::Call,
+        discount_curve: None,
     };
     let expected_price = 10.450583572185565;
     let calculated_price_result = black_scholes_price(&option);
@@ -30,9 +31,10 @@ fn test_black_scholes_call_price() {
         underlying_price: 100.0,
         strike_price: 95.0,
         time_to_maturity_years: 0.5,
-        risk_free_rate: 0.03,
+        risk_free_rate: Some(0.03),
         volatility: 0.25,
         option_type: OptionType::Call,
+        discount_curve: None,
     };
     let expected_price2 = 10.49687533872639; // Updated from 9.52
     let calculated_price2_result = black_scholes_price(&option2);
@@ -48,9 +50,10 @@ fn test_black_scholes_put_price() {
         underlying_price: 100.0,
         strike_price: 100.0,
         time_to_maturity_years: 1.0,
-        risk_free_rate: 0.05,
+        risk_free_rate: Some(0.05),
         volatility: 0.2,
         option_type: OptionType::Put,
+        discount_curve: None,
     };
     let expected_price = 5.573526022256971;
     let calculated_price_result = black_scholes_price(&option);
@@ -63,9 +66,10 @@ fn test_black_scholes_put_price() {
         underlying_price: 60.0,
         strike_price: 65.0,
         time_to_maturity_years: 0.25, // 3 months
-        risk_free_rate: 0.04,
+        risk_free_rate: Some(0.04),
         volatility: 0.30,
         option_type: OptionType::Put,
+        discount_curve: None,
     };
     let expected_price2 = 6.290973155899039; // Updated from 6.24
     let calculated_price2_result = black_scholes_price(&option2);
@@ -79,9 +83,10 @@ fn test_price_series_for_black_scholes_func() {
     let fixed_params = FixedOptionParams {
         strike_price: 100.0,
         time_to_maturity_years: 0.5,
-        risk_free_rate: 0.05,
+        risk_free_rate: Some(0.05),
         volatility: 0.2,
         option_type: OptionType::Call,
+        discount_curve: None,
     };
     let underlying_prices = [90.0, 100.0, 110.0];
 
@@ -117,9 +122,10 @@ fn test_monte_carlo_vs_black_scholes_call() {
         underlying_price: s,
         strike_price: k,
         time_to_maturity_years: t,
-        risk_free_rate: r,
+        risk_free_rate: Some(r),
         volatility: sigma,
         option_type: OptionType::Call,
+        discount_curve: None,
     };
     let bs_price_result = black_scholes_price(&bs_option);
     assert!(bs_price_result.is_ok());
@@ -128,13 +134,14 @@ fn test_monte_carlo_vs_black_scholes_call() {
     let mc_pricer = MonteCarloOptionPricer {
         strike_price: k,
         time_to_maturity_years: t,
-        risk_free_rate: r, // Used for discounting
+        risk_free_rate: Some(r), // Used for discounting
         option_type: OptionType::Call,
         underlying_initial_price: s,
         underlying_drift: r, // For risk-neutral simulation
         underlying_volatility: sigma,
         num_paths: 20000, // Increased for better accuracy
         num_steps_per_path: 100, // More steps for better path accuracy
+        discount_curve: None,
     };
 
     let mc_price = mc_pricer.price(seed).unwrap();
@@ -158,9 +165,10 @@ fn test_monte_carlo_vs_black_scholes_put() {
         underlying_price: s,
         strike_price: k,
         time_to_maturity_years: t,
-        risk_free_rate: r,
+        risk_free_rate: Some(r),
         volatility: sigma,
         option_type: OptionType::Put,
+        discount_curve: None,
     };
     let bs_price_result = black_scholes_price(&bs_option);
     assert!(bs_price_result.is_ok());
@@ -169,13 +177,14 @@ fn test_monte_carlo_vs_black_scholes_put() {
     let mc_pricer = MonteCarloOptionPricer {
         strike_price: k,
         time_to_maturity_years: t,
-        risk_free_rate: r,
+        risk_free_rate: Some(r),
         option_type: OptionType::Put,
         underlying_initial_price: s,
         underlying_drift: r,
         underlying_volatility: sigma,
         num_paths: 20000,
         num_steps_per_path: 100,
+        discount_curve: None,
     };
 
     let mc_price = mc_pricer.price(seed).unwrap();
@@ -189,23 +198,40 @@ fn test_monte_carlo_vs_black_scholes_put() {
 #[test]
 fn test_mc_pricer_invalid_inputs() {
      let mc_pricer_invalid_t = MonteCarloOptionPricer {
-        strike_price: 100.0, time_to_maturity_years: 0.0, risk_free_rate: 0.05, option_type: OptionType::Call,
+        strike_price: 100.0, time_to_maturity_years: 0.0, risk_free_rate: Some(0.05), option_type: OptionType::Call,
         underlying_initial_price: 100.0, underlying_drift: 0.05, underlying_volatility: 0.2,
         num_paths: 100, num_steps_per_path: 10,
+        discount_curve: None,
     };
     assert!(mc_pricer_invalid_t.price(None).is_err());
 
     let mc_pricer_invalid_paths = MonteCarloOptionPricer {
-        strike_price: 100.0, time_to_maturity_years: 1.0, risk_free_rate: 0.05, option_type: OptionType::Call,
+        strike_price: 100.0, time_to_maturity_years: 1.0, risk_free_rate: Some(0.05), option_type: OptionType::Call,
         underlying_initial_price: 100.0, underlying_drift: 0.05, underlying_volatility: 0.2,
         num_paths: 0, num_steps_per_path: 10,
+        discount_curve: None,
     };
     assert!(mc_pricer_invalid_paths.price(None).is_err());
 
      let mc_pricer_invalid_steps = MonteCarloOptionPricer {
-        strike_price: 100.0, time_to_maturity_years: 1.0, risk_free_rate: 0.05, option_type: OptionType::Call,
+        strike_price: 100.0, time_to_maturity_years: 1.0, risk_free_rate: Some(0.05), option_type: OptionType::Call,
         underlying_initial_price: 100.0, underlying_drift: 0.05, underlying_volatility: 0.2,
         num_paths: 100, num_steps_per_path: 0,
+        discount_curve: None,
     };
     assert!(mc_pricer_invalid_steps.price(None).is_err());
+
+    // Neither a flat rate nor a curve to discount with.
+    let mc_pricer_no_rate = MonteCarloOptionPricer { risk_free_rate: None, num_steps_per_path: 10, ..mc_pricer_invalid_steps };
+    assert!(mc_pricer_no_rate.price(None).is_err());
+}
+
+#[test]
+fn test_monte_carlo_seed_near_u64_max_wraps() {
+    let mc_pricer = MonteCarloOptionPricer {
+        strike_price: 100.0, time_to_maturity_years: 1.0, risk_free_rate: Some(0.05), option_type: OptionType::Call,
+        underlying_initial_price: 100.0, underlying_drift: 0.05, underlying_volatility: 0.2,
+        num_paths: 10, num_steps_per_path: 5, discount_curve: None,
+    };
+    assert!(mc_pricer.price(Some(u64::MAX - 2)).is_ok());
 }
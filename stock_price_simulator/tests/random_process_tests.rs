@@ -3,7 +3,7 @@ use chrono::{NaiveDate, Duration};
 
 #[test]
 fn test_gbm_generate_path_deterministic() {
-    let gbm = GeometricBrownianMotion { drift: 0.1, volatility: 0.2 };
+    let gbm = GeometricBrownianMotion::new(0.1, 0.2);
     let initial_value = 100.0;
     let dt = 1.0; // 1 day
     let steps = 5;
@@ -35,7 +35,7 @@ fn test_gbm_generate_path_deterministic() {
 
 #[test]
 fn test_gbm_generate_path_lengths() {
-    let gbm = GeometricBrownianMotion { drift: 0.05, volatility: 0.15 };
+    let gbm = GeometricBrownianMotion::new(0.05, 0.15);
     let initial_value = 50.0;
     let dt = 1.0; // 1 day
     let steps = 10;
@@ -48,7 +48,7 @@ fn test_gbm_generate_path_lengths() {
 
 #[test]
 fn test_gbm_generate_path_timestamps() {
-    let gbm = GeometricBrownianMotion { drift: 0.0, volatility: 0.1 };
+    let gbm = GeometricBrownianMotion::new(0.0, 0.1);
     let initial_value = 1000.0;
     let dt_days = 1.0; // 1 day
     let steps = 3;
@@ -64,3 +64,111 @@ fn test_gbm_generate_path_timestamps() {
     let expected_third_time = expected_second_time + Duration::days(dt_days as i64);
     assert_eq!(path.timestamps[2], expected_third_time, "Third timestamp should be incremented by dt_days");
 }
+
+#[test]
+fn test_mean_reverting_processes_deterministic_and_bounded() {
+    use stock_price_simulator::random_process::{OrnsteinUhlenbeck, CoxIngersollRoss};
+
+    let ou = OrnsteinUhlenbeck { mean_reversion_speed: 5.0, long_term_mean: 10.0, volatility: 1.0 };
+    let ou_path1 = ou.generate_path(20.0, 1.0, 500, Some(1));
+    let ou_path2 = ou.generate_path(20.0, 1.0, 500, Some(1));
+    assert_eq!(ou_path1.prices, ou_path2.prices);
+    assert_eq!(ou_path1.prices[0], 20.0);
+    let late_mean: f64 = ou_path1.prices[250..].iter().sum::<f64>() / 250.0;
+    assert!((late_mean - 10.0).abs() < 1.0, "OU path should revert towards its long-term mean, got {}", late_mean);
+
+    let cir = CoxIngersollRoss { mean_reversion_speed: 0.5, long_term_mean: 0.01, volatility: 0.5 };
+    let cir_path = cir.generate_path(0.01, 1.0, 1000, Some(2));
+    assert!(cir_path.prices.iter().all(|&x| x >= 0.0), "CIR path must stay non-negative");
+}
+
+#[test]
+fn test_merton_without_jumps_matches_gbm() {
+    use stock_price_simulator::random_process::MertonJumpDiffusion;
+
+    let merton = MertonJumpDiffusion { drift: 0.1, volatility: 0.2, jump_intensity: 0.0, jump_mean: -0.1, jump_volatility: 0.1 };
+    let gbm = GeometricBrownianMotion::new(0.1, 0.2);
+    let merton_path = merton.generate_path(100.0, 1.0, 20, Some(99));
+    let gbm_path = gbm.generate_path(100.0, 1.0, 20, Some(99));
+    for (m, g) in merton_path.prices.iter().zip(&gbm_path.prices) {
+        assert!((m - g).abs() < 1e-9);
+    }
+    assert_eq!(merton_path.timestamps, gbm_path.timestamps);
+}
+
+fn sample_moments(distribution: &stock_price_simulator::config::InnovationDistribution, n: usize) -> (f64, f64, f64, f64) {
+    use rand::SeedableRng;
+    use stock_price_simulator::random_process::InnovationSampler;
+
+    let sampler = InnovationSampler::new(distribution);
+    let mut rng = rand::rngs::StdRng::seed_from_u64(2024);
+    let draws: Vec<f64> = (0..n).map(|_| sampler.sample(&mut rng)).collect();
+    let mean = draws.iter().sum::<f64>() / n as f64;
+    let variance = draws.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n as f64;
+    let skewness = draws.iter().map(|x| (x - mean).powi(3)).sum::<f64>() / n as f64 / variance.powf(1.5);
+    let kurtosis = draws.iter().map(|x| (x - mean).powi(4)).sum::<f64>() / n as f64 / (variance * variance);
+    (mean, variance, skewness, kurtosis)
+}
+
+#[test]
+fn test_innovations_are_standardised() {
+    use stock_price_simulator::config::InnovationDistribution;
+
+    let cases = [
+        InnovationDistribution::StandardNormal,
+        InnovationDistribution::StudentT { degrees_of_freedom: 6.0 },
+        InnovationDistribution::SkewedStudentT { degrees_of_freedom: 6.0, skewness: -0.3 },
+        InnovationDistribution::NormalInverseGaussian { alpha: 1.5, beta: -0.5 },
+    ];
+    for distribution in &cases {
+        let (mean, variance, _, _) = sample_moments(distribution, 200_000);
+        assert!(mean.abs() < 0.02, "{:?}: mean {}", distribution, mean);
+        assert!((variance - 1.0).abs() < 0.05, "{:?}: variance {}", distribution, variance);
+    }
+}
+
+#[test]
+fn test_innovations_tails_and_skew() {
+    use stock_price_simulator::config::InnovationDistribution;
+
+    let (_, _, _, normal_kurtosis) = sample_moments(&InnovationDistribution::StandardNormal, 200_000);
+    let (_, _, _, t_kurtosis) = sample_moments(&InnovationDistribution::StudentT { degrees_of_freedom: 6.0 }, 200_000);
+    assert!((normal_kurtosis - 3.0).abs() < 0.1);
+    assert!(t_kurtosis > 4.0, "Student-t(6) kurtosis should be about 6, got {}", t_kurtosis);
+
+    let (_, _, skewed_t_skew, _) =
+        sample_moments(&InnovationDistribution::SkewedStudentT { degrees_of_freedom: 6.0, skewness: -0.3 }, 200_000);
+    let (_, _, nig_skew, nig_kurtosis) =
+        sample_moments(&InnovationDistribution::NormalInverseGaussian { alpha: 1.5, beta: -0.5 }, 200_000);
+    assert!(skewed_t_skew < -0.2, "skewed t should have a heavier left tail, got skew {}", skewed_t_skew);
+    assert!(nig_skew < -0.2, "NIG with beta < 0 should be left-skewed, got skew {}", nig_skew);
+    assert!(nig_kurtosis > 3.5);
+}
+
+#[test]
+fn test_innovation_distribution_validation() {
+    use stock_price_simulator::config::InnovationDistribution;
+
+    assert!(InnovationDistribution::StudentT { degrees_of_freedom: 5.0 }.validate().is_ok());
+    assert!(InnovationDistribution::StudentT { degrees_of_freedom: 2.0 }.validate().is_err());
+    assert!(InnovationDistribution::StudentT { degrees_of_freedom: f64::NAN }.validate().is_err());
+    assert!(InnovationDistribution::SkewedStudentT { degrees_of_freedom: 5.0, skewness: 1.0 }.validate().is_err());
+    assert!(InnovationDistribution::NormalInverseGaussian { alpha: 1.0, beta: 1.0 }.validate().is_err());
+    assert!(InnovationDistribution::NormalInverseGaussian { alpha: 1.0, beta: 0.5 }.validate().is_ok());
+}
+
+#[test]
+fn test_gbm_with_student_t_innovations() {
+    use stock_price_simulator::config::InnovationDistribution;
+
+    let gbm = GeometricBrownianMotion {
+        drift: 0.05,
+        volatility: 0.2,
+        innovations: InnovationDistribution::StudentT { degrees_of_freedom: 4.0 },
+    };
+    let path1 = gbm.generate_path(100.0, 1.0, 100, Some(3));
+    let path2 = gbm.generate_path(100.0, 1.0, 100, Some(3));
+    assert_eq!(path1.prices, path2.prices);
+    assert!(path1.prices.iter().all(|p| *p > 0.0));
+    assert_ne!(path1.prices, GeometricBrownianMotion::new(0.05, 0.2).generate_path(100.0, 1.0, 100, Some(3)).prices);
+}
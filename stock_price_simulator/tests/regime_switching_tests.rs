@@ -0,0 +1,209 @@
+use stock_price_simulator::api_interface::{simulate_stock_regimes_with_config, simulate_stock_with_config};
+use stock_price_simulator::config::{GlobalConfig, ModelType, RegimeParams, RegimeSwitchingParams};
+use stock_price_simulator::etf_simulation::{simulate_etf_with_market, EtfConstituent, EtfDefinition};
+use stock_price_simulator::error::{classify, ErrorCode};
+use stock_price_simulator::random_process::StochasticProcess;
+use stock_price_simulator::regime_switching::MarkovRegimeSwitching;
+use stock_price_simulator::stock_simulation::StockSimulator;
+
+// Calm bull market and a persistent high-volatility crisis.
+fn bull_crisis_params() -> RegimeSwitchingParams {
+    RegimeSwitchingParams {
+        regimes: vec![
+            RegimeParams { name: Some("bull".to_string()), drift: 0.1, volatility: 0.1 },
+            RegimeParams { name: Some("crisis".to_string()), drift: -0.3, volatility: 0.6 },
+        ],
+        transition_matrix: vec![vec![0.98, 0.02], vec![0.05, 0.95]],
+        initial_regime: 0,
+        transition_time_step_days: 1.0,
+    }
+}
+
+// Lengths of consecutive runs spent in `regime`.
+fn run_lengths(regimes: &[usize], regime: usize) -> Vec<usize> {
+    let mut runs = Vec::new();
+    let mut current = 0;
+    for r in regimes {
+        if *r == regime {
+            current += 1;
+        } else if current > 0 {
+            runs.push(current);
+            current = 0;
+        }
+    }
+    runs
+}
+
+#[test]
+fn test_regime_path_is_deterministic_and_aligned() {
+    let process = MarkovRegimeSwitching::from_params(&bull_crisis_params()).unwrap();
+    let first = process.generate_path_with_regimes(100.0, 1.0, 500, Some(7));
+    let second = process.generate_path_with_regimes(100.0, 1.0, 500, Some(7));
+
+    assert_eq!(first.path.prices, second.path.prices);
+    assert_eq!(first.regimes, second.regimes);
+    assert_eq!(first.regimes.len(), first.path.prices.len());
+    assert_eq!(first.regimes[0], 0);
+    assert_eq!(first.path.prices[0], 100.0);
+    assert_eq!(process.generate_path(100.0, 1.0, 500, Some(7)).prices, first.path.prices);
+}
+
+#[test]
+fn test_regimes_persist_and_match_stationary_distribution() {
+    let process = MarkovRegimeSwitching::from_params(&bull_crisis_params()).unwrap();
+    let stationary = process.stationary_distribution();
+    assert!((stationary[1] - 0.02 / 0.07).abs() < 1e-9);
+
+    let regime_path = process.generate_path_with_regimes(100.0, 1.0, 200_000, Some(11));
+    let crisis_share = regime_path.regimes.iter().filter(|r| **r == 1).count() as f64 / regime_path.regimes.len() as f64;
+    assert!((crisis_share - stationary[1]).abs() < 0.02, "crisis share {}", crisis_share);
+
+    // Expected crisis duration is 1 / (1 - 0.95) = 20 steps, far from i.i.d. switching.
+    let runs = run_lengths(&regime_path.regimes, 1);
+    let mean_run = runs.iter().sum::<usize>() as f64 / runs.len() as f64;
+    assert!((mean_run - 20.0).abs() < 2.0, "mean crisis run length {}", mean_run);
+}
+
+#[test]
+fn test_crisis_regime_has_higher_realized_volatility() {
+    let process = MarkovRegimeSwitching::from_params(&bull_crisis_params()).unwrap();
+    let regime_path = process.generate_path_with_regimes(100.0, 1.0, 50_000, Some(3));
+
+    let mut squared = [Vec::new(), Vec::new()];
+    for (i, w) in regime_path.path.prices.windows(2).enumerate() {
+        squared[regime_path.regimes[i]].push((w[1] / w[0]).ln().powi(2));
+    }
+    let annualized_vol = |sq: &Vec<f64>| (sq.iter().sum::<f64>() / sq.len() as f64 * 252.0).sqrt();
+    assert!((annualized_vol(&squared[0]) - 0.1).abs() < 0.01);
+    assert!((annualized_vol(&squared[1]) - 0.6).abs() < 0.05);
+}
+
+#[test]
+fn test_regime_switching_validation() {
+    let field_of = |params: &RegimeSwitchingParams| {
+        let error = classify(&MarkovRegimeSwitching::from_params(params).unwrap_err());
+        assert_eq!(error.code, ErrorCode::InvalidParameter);
+        error.field.unwrap()
+    };
+
+    let mut bad_rows = bull_crisis_params();
+    bad_rows.transition_matrix[0] = vec![0.9, 0.2];
+    assert_eq!(field_of(&bad_rows), "transition_matrix[0]");
+
+    let mut bad_shape = bull_crisis_params();
+    bad_shape.transition_matrix = vec![vec![1.0]];
+    assert_eq!(field_of(&bad_shape), "transition_matrix");
+
+    let mut bad_initial = bull_crisis_params();
+    bad_initial.initial_regime = 2;
+    assert_eq!(field_of(&bad_initial), "initial_regime");
+
+    let mut bad_volatility = bull_crisis_params();
+    bad_volatility.regimes[1].volatility = -0.1;
+    assert_eq!(field_of(&bad_volatility), "regimes[1].volatility");
+
+    for step in [0.0, 1e-12, f64::NAN] {
+        let mut bad_step = bull_crisis_params();
+        bad_step.transition_time_step_days = step;
+        let error = classify(&MarkovRegimeSwitching::from_params(&bad_step).unwrap_err());
+        assert_eq!((error.code, error.field.as_deref()), (ErrorCode::InvalidParameter, Some("transition_time_step_days")));
+    }
+    let tiny_step = MarkovRegimeSwitching { transition_time_step_days: 1e-12, ..MarkovRegimeSwitching::from_params(&bull_crisis_params()).unwrap() };
+    assert!(StockSimulator::simulate_with_regimes(&tiny_step, 100.0, 10, 1.0, Some(1)).is_err());
+}
+
+#[test]
+fn test_steps_must_be_whole_multiples_of_the_transition_step() {
+    let process = MarkovRegimeSwitching::from_params(&bull_crisis_params()).unwrap();
+    let err = StockSimulator::simulate_with_regimes(&process, 100.0, 10, 0.25, Some(1)).unwrap_err();
+    assert!(err.to_string().contains("whole multiple of the transition time step"), "{}", err);
+    assert!(StockSimulator::simulate_with_process(&process, 100.0, 10, 1.5, Some(1)).is_err());
+    assert!(StockSimulator::simulate_with_regimes(&process, 100.0, 10, 3.0, Some(1)).is_ok());
+}
+
+#[test]
+fn test_regime_switching_from_toml_config() {
+    let toml_str = r#"
+        simulation_period_days = 252
+        time_step_minutes = 1440
+
+        [[asset_models]]
+        asset_type = "stock"
+        asset_identifier_pattern = "REGIME_STOCK"
+        default_model = "RegimeSwitching"
+        [asset_models.parameters.regime_switching]
+        transition_matrix = [[0.98, 0.02], [0.05, 0.95]]
+        [[asset_models.parameters.regime_switching.regimes]]
+        name = "bull"
+        drift = 0.1
+        volatility = 0.1
+        [[asset_models.parameters.regime_switching.regimes]]
+        name = "crisis"
+        drift = -0.3
+        volatility = 0.6
+    "#;
+    let config: GlobalConfig = toml::from_str(toml_str).unwrap();
+    let params = config.asset_models.as_ref().unwrap()[0].parameters.regime_switching.clone().unwrap();
+    assert_eq!(params, bull_crisis_params());
+
+    let regime_path = simulate_stock_regimes_with_config("REGIME_STOCK", &config, 50.0, 30, 1.0, Some(5)).unwrap();
+    assert_eq!(regime_path.regimes.len(), 30);
+    let path = simulate_stock_with_config("REGIME_STOCK", &config, 50.0, 30, 1.0, Some(5), None, None).unwrap();
+    assert_eq!(path.prices, regime_path.path.prices);
+
+    assert!(simulate_stock_with_config("REGIME_STOCK", &config, 50.0, 30, 1.0, Some(5), None, Some(0.2)).is_err());
+    assert!(simulate_stock_regimes_with_config("REGIME_STOCK", &config, 0.0, 30, 1.0, Some(5)).is_err());
+
+    let mut gbm_config = config.clone();
+    gbm_config.asset_models.as_mut().unwrap()[0].default_model = ModelType::GeometricBrownianMotion;
+    let error = classify(&simulate_stock_regimes_with_config("REGIME_STOCK", &gbm_config, 50.0, 30, 1.0, Some(5)).unwrap_err());
+    assert_eq!((error.code, error.field.as_deref()), (ErrorCode::InvalidParameter, Some("asset_identifier")));
+}
+
+#[test]
+fn test_etf_constituents_share_regime_chain() {
+    let regime_constituent = |symbol: &str, weight: f64| EtfConstituent {
+        symbol: symbol.to_string(),
+        initial_price: 100.0,
+        drift: 0.0,
+        volatility: 0.0,
+        weight,
+        regime_switching: Some(bull_crisis_params()),
+        currency: None,
+    };
+    let etf_def = EtfDefinition {
+        constituents: vec![
+            regime_constituent("A", 0.4),
+            regime_constituent("B", 0.4),
+            EtfConstituent {
+                symbol: "C".to_string(), initial_price: 50.0, drift: 0.05, volatility: 0.2, weight: 0.2, regime_switching: None, currency: None,
+            },
+        ],
+        simulation_days: 300,
+        time_step_days: 1.0,
+        seed: Some(21),
+        fx: None,
+        expense_ratio: 0.0,
+        market: None,
+        benchmark: None,
+    };
+
+    let simulation = simulate_etf_with_market(&etf_def).unwrap();
+    assert_eq!(simulation.nav.prices.len(), 300);
+    let regimes_a = simulation.constituent_regimes[0].as_ref().unwrap();
+    let regimes_b = simulation.constituent_regimes[1].as_ref().unwrap();
+    assert_eq!(regimes_a, regimes_b, "constituents with the same transition matrix should switch together");
+    assert!(simulation.constituent_regimes[2].is_none());
+
+    let rerun = simulate_etf_with_market(&etf_def).unwrap();
+    assert_eq!(rerun.nav.prices, simulation.nav.prices);
+
+    // Errors from a constituent's model name the constituent.
+    let mut bad_regimes = etf_def.clone();
+    bad_regimes.constituents[1].regime_switching.as_mut().unwrap().initial_regime = 5;
+    let error = classify(&simulate_etf_with_market(&bad_regimes).unwrap_err());
+    assert_eq!((error.code, error.field.as_deref()), (ErrorCode::InvalidParameter, Some("constituents[1].regime_switching.initial_regime")));
+    let quarter_days = EtfDefinition { time_step_days: 0.25, ..etf_def };
+    let error = classify(&simulate_etf_with_market(&quarter_days).unwrap_err());
+    assert_eq!((error.code, error.field.as_deref()), (ErrorCode::InvalidParameter, Some("constituents[0].time_step_days")));
+}
@@ -0,0 +1,96 @@
+use serde_json::json;
+
+use stock_price_simulator::api_interface::{self, MonteCarloEuropeanOptionInput};
+use stock_price_simulator::config::load_config;
+use stock_price_simulator::etf_simulation::EtfDefinition;
+use stock_price_simulator::futures_simulation::FuturesContract;
+use stock_price_simulator::request_defaults::{
+    apply_request_defaults, default_time_step_days, derive_seed, with_defaults, RequestKind, StockSimulationRequest,
+};
+
+// config.test.toml: random_seed = 9999, simulation_period_days = 100, time_step_minutes = 60.
+
+#[test]
+fn test_missing_fields_come_from_the_config() {
+    let config = load_config("config.test.toml").unwrap();
+    assert_eq!(default_time_step_days(&config), 1.0 / 24.0);
+
+    let request: StockSimulationRequest = with_defaults(&config, RequestKind::Stock,
+        json!({ "asset_identifier": "TEST_DEFAULT", "initial_price": 100.0 })).unwrap();
+    assert_eq!(request.time_step_days, 1.0 / 24.0);
+    assert_eq!(request.days, 2400, "100 days of hourly steps");
+    assert!(request.seed.is_some());
+
+    let request: StockSimulationRequest = with_defaults(&config, RequestKind::Stock,
+        json!({ "asset_identifier": "TEST_DEFAULT", "initial_price": 100.0, "days": 5, "time_step_days": 1.0, "seed": 3 })).unwrap();
+    assert_eq!((request.days, request.time_step_days, request.seed), (5, 1.0, Some(3)), "Request values win");
+
+    let request: StockSimulationRequest = with_defaults(&config, RequestKind::Stock,
+        json!({ "asset_identifier": "TEST_DEFAULT", "initial_price": 100.0, "time_step_days": 2.0 })).unwrap();
+    assert_eq!(request.days, 50, "The period is split into steps of the requested size");
+
+    let contract: FuturesContract = with_defaults(&config, RequestKind::Future, json!({ "underlying_symbol": "CL",
+        "initial_spot_price": 70.0, "risk_free_rate": 0.03, "volatility": 0.25 })).unwrap();
+    assert_eq!(contract.time_to_maturity_days, 100, "A maturity is in calendar days, not steps");
+    assert_eq!(contract.time_step_days, 1.0 / 24.0);
+
+    let etf: EtfDefinition = with_defaults(&config, RequestKind::Etf, json!({ "constituents": [
+        { "symbol": "A", "initial_price": 50.0, "drift": 0.1, "volatility": 0.3, "weight": 1.0 } ],
+        "time_step_days": 1.0 })).unwrap();
+    assert_eq!(etf.simulation_days, 100);
+
+    let option: MonteCarloEuropeanOptionInput = with_defaults(&config, RequestKind::OptionMonteCarlo, json!({
+        "underlying_initial_price": 100.0, "strike_price": 100.0, "time_to_maturity_years": 0.5, "risk_free_rate": 0.02,
+        "underlying_volatility": 0.2, "option_type": "Call", "num_paths": 100 })).unwrap();
+    assert_eq!(option.num_steps_per_path, 3024, "Half a year of hourly steps, 252 days to the year");
+    assert!(option.seed.is_some());
+
+    let err = with_defaults::<FuturesContract>(&config, RequestKind::Future, json!({ "underlying_symbol": "CL" })).unwrap_err();
+    assert!(err.to_string().contains("Invalid future request"), "{}", err);
+}
+
+#[test]
+fn test_derived_seeds_are_reproducible() {
+    let config = load_config("config.test.toml").unwrap();
+    let seed_of = |kind: RequestKind, request: serde_json::Value| {
+        let mut request = request;
+        apply_request_defaults(&config, kind, &mut request).unwrap();
+        request["seed"].as_u64()
+    };
+    let a = seed_of(RequestKind::Stock, json!({ "asset_identifier": "A", "initial_price": 100.0 })).unwrap();
+    assert_eq!(seed_of(RequestKind::Stock, json!({ "initial_price": 100.0, "asset_identifier": "A" })), Some(a),
+        "Key order does not matter");
+    assert_eq!(seed_of(RequestKind::Stock, json!({ "asset_identifier": "A", "initial_price": 100.0, "seed": null })), Some(a));
+    assert_ne!(seed_of(RequestKind::Stock, json!({ "asset_identifier": "B", "initial_price": 100.0 })), Some(a));
+    assert_ne!(seed_of(RequestKind::Etf, json!({ "asset_identifier": "A", "initial_price": 100.0 })), Some(a));
+
+    // The seed is derived from the request with its defaults, as documented.
+    let filled = json!({ "asset_identifier": "A", "initial_price": 100.0, "days": 2400, "time_step_days": 1.0 / 24.0 });
+    assert_eq!(derive_seed(9999, RequestKind::Stock, &filled), a);
+    assert_ne!(derive_seed(10000, RequestKind::Stock, &filled), a);
+
+    let mut unseeded = load_config("config.test.toml").unwrap();
+    unseeded.random_seed = None;
+    let mut request = json!({ "asset_identifier": "A", "initial_price": 100.0 });
+    apply_request_defaults(&unseeded, RequestKind::Stock, &mut request).unwrap();
+    assert!(request.get("seed").is_none(), "No global seed, no derived seed");
+
+    let mut not_an_object = json!([1, 2]);
+    assert!(apply_request_defaults(&config, RequestKind::Stock, &mut not_an_object).is_err());
+}
+
+#[test]
+fn test_global_seed_reproduces_a_batch() {
+    let config = load_config("config.test.toml").unwrap();
+    let run = || -> Vec<Vec<f64>> {
+        ["TEST_DEFAULT", "TEST_OVERRIDE"].iter().map(|identifier| {
+            let request: StockSimulationRequest = with_defaults(&config, RequestKind::Stock,
+                json!({ "asset_identifier": identifier, "initial_price": 100.0, "days": 30, "time_step_days": 1.0 })).unwrap();
+            api_interface::simulate_stock_with_config(&request.asset_identifier, &config, request.initial_price,
+                request.days, request.time_step_days, request.seed, None, None).unwrap().prices
+        }).collect()
+    };
+    let first = run();
+    assert_eq!(first, run());
+    assert_ne!(first[0], first[1]);
+}
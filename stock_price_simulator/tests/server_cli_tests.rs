@@ -0,0 +1,204 @@
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, Instant};
+
+fn server() -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_stock_sim_server"));
+    // Keep the tests independent of the environment they run in.
+    for var in ["STOCK_SIM_HOST", "STOCK_SIM_PORT", "STOCK_SIM_CONFIG", "STOCK_SIM_WORKERS", "STOCK_SIM_LOG_LEVEL", "STOCK_SIM_CONFIG_POLL_SECS"] {
+        command.env_remove(var);
+    }
+    for (var, _) in stock_price_simulator::config_layers::ENV_OVERRIDES {
+        command.env_remove(var);
+    }
+    command
+}
+
+// Sends a GET and returns the raw HTTP response, retrying until the server accepts connections.
+fn http_get(port: u16, path: &str) -> String {
+    let deadline = Instant::now() + Duration::from_secs(20);
+    loop {
+        if let Ok(mut stream) = TcpStream::connect(("127.0.0.1", port)) {
+            let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path);
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            return response;
+        }
+        assert!(Instant::now() < deadline, "Server did not start on port {}", port);
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
+fn run(command: &mut Command) -> Output {
+    command.output().expect("Failed to run stock_sim_server")
+}
+
+#[test]
+fn test_check_config_accepts_valid_files() {
+    let output = run(server().args(["--check-config", "--config", "config.test.toml"]));
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("is valid: 2 asset model(s)"));
+
+    let output = run(server().arg("--check-config").env("STOCK_SIM_CONFIG", "config.example.toml"));
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("config.example.toml is valid"));
+}
+
+#[test]
+fn test_check_config_reports_every_problem() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("broken.toml");
+    std::fs::write(&config_path, r#"
+random_seed = 1
+simulation_period_days = 252
+time_step_minutes = 1440
+
+[[asset_models]]
+asset_type = "stock"
+asset_identifier_pattern = "NO_PARAMS"
+default_model = "GeometricBrownianMotion"
+[asset_models.parameters]
+
+[[asset_models]]
+asset_type = "stock"
+asset_identifier_pattern = "FINE"
+default_model = "GeometricBrownianMotion"
+[asset_models.parameters.gbm]
+drift = 0.05
+volatility = 0.2
+
+[[asset_models]]
+asset_type = "stock"
+asset_identifier_pattern = "BAD_TAILS"
+default_model = "GeometricBrownianMotion"
+[asset_models.parameters.gbm]
+drift = 0.05
+volatility = 0.2
+[asset_models.parameters.innovations]
+distribution = "StudentT"
+degrees_of_freedom = 1.5
+"#).unwrap();
+
+    let output = run(server().args(["--check-config", "--config", config_path.to_str().unwrap()]));
+    assert_eq!(output.status.code(), Some(1));
+    let report = String::from_utf8_lossy(&output.stderr);
+    assert!(report.contains("2 problem(s)"), "{}", report);
+    assert!(report.contains("asset_models[0] (stock 'NO_PARAMS')"), "{}", report);
+    assert!(report.contains("asset_models[2] (stock 'BAD_TAILS')"), "{}", report);
+    assert!(!report.contains("FINE"), "{}", report);
+
+    std::fs::write(&config_path, "random_seed = \"not a number\"").unwrap();
+    let output = run(server().args(["--check-config", "--config", config_path.to_str().unwrap()]));
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Failed to parse"));
+
+    let output = run(server().args(["--check-config", "--config", "does_not_exist.toml"]));
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not found"));
+
+    std::fs::write(&config_path, r#"
+simulation_period_days = 252
+time_step_minutes = 1440
+
+[[asset_models]]
+asset_type = "stock"
+asset_identifier_pattern = "HISTORY"
+default_model = "HistoricalBootstrap"
+[asset_models.parameters.bootstrap]
+method = "Iid"
+history_csv = "missing_prices.csv"
+"#).unwrap();
+    let output = run(server().args(["--check-config", "--config", config_path.to_str().unwrap()]));
+    assert_eq!(output.status.code(), Some(1));
+    let report = String::from_utf8_lossy(&output.stderr);
+    assert!(report.contains("missing_prices.csv"), "{}", report);
+}
+
+#[test]
+fn test_check_config_with_layers_and_environment() {
+    let dir = tempfile::tempdir().unwrap();
+    let overlay_path = dir.path().join("overlay.yaml");
+    std::fs::write(&overlay_path, "random_seed: 5\nasset_models:\n  - asset_type: stock\n    asset_identifier_pattern: EXTRA\n    default_model: GeometricBrownianMotion\n    parameters:\n      gbm: { drift: 0.01, volatility: 0.1 }\n").unwrap();
+    let layers = format!("config.test.toml,{}", overlay_path.to_str().unwrap());
+    let output = run(server().arg("--check-config").env("STOCK_SIM_CONFIG", &layers).env("STOCK_SIM_RANDOM_SEED", "11"));
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let report = String::from_utf8_lossy(&output.stdout);
+    assert!(report.contains("is valid: 3 asset model(s)"), "{}", report);
+    assert!(report.contains("Overridden from the environment: STOCK_SIM_RANDOM_SEED"), "{}", report);
+
+    let output = run(server().args(["--check-config", "--config", "config.test.toml"]).env("STOCK_SIM_TIME_STEP_MINUTES", "often"));
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("STOCK_SIM_TIME_STEP_MINUTES"));
+}
+
+#[test]
+fn test_invalid_flags_are_rejected() {
+    let output = run(server().args(["--check-config", "--config", "config.test.toml", "--log-level", "loud"]));
+    assert!(!output.status.success());
+    let output = run(server().args(["--check-config", "--config", "config.test.toml"]).env("STOCK_SIM_WORKERS", "0"));
+    assert!(!output.status.success(), "Zero workers must be rejected");
+}
+
+#[test]
+fn test_server_binds_to_port_from_environment() {
+    let port = portpicker::pick_unused_port().expect("No free port");
+    let mut child = server()
+        .args(["--config", "config.test.toml", "--workers", "1", "--log-level", "warn"])
+        .env("STOCK_SIM_PORT", port.to_string())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to start stock_sim_server");
+
+    let response = http_get(port, "/simulate/stock?asset_identifier=TEST_DEFAULT&initial_price=100&days=3&time_step_days=1&seed=1");
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    assert!(response.contains("\"status\":\"success\""), "{}", response);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_sighup_and_file_change_reload_the_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    std::fs::copy("config.test.toml", &config_path).unwrap();
+    let port = portpicker::pick_unused_port().expect("No free port");
+    let mut child = server()
+        .args(["--config", config_path.to_str().unwrap(), "--port", &port.to_string(), "--workers", "1", "--config-poll-secs", "1"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to start stock_sim_server");
+    let version = |port: u16| -> u64 {
+        let response = http_get(port, "/admin/config");
+        let body = &response[response.find("\r\n\r\n").unwrap() + 4..];
+        let body = body.lines().find(|line| line.starts_with('{')).unwrap_or(body);
+        serde_json::from_str::<serde_json::Value>(body).unwrap()["data"]["version"].as_u64().unwrap()
+    };
+    let wait_for_version = |expected: u64| {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while version(port) != expected {
+            assert!(Instant::now() < deadline, "Config version {} never reached", expected);
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    };
+    assert_eq!(version(port), 1);
+
+    let contents = std::fs::read_to_string(&config_path).unwrap();
+    std::fs::write(&config_path, contents.replacen("drift = 0.03", "drift = 0.05", 1)).unwrap();
+    let status = Command::new("kill").args(["-HUP", &child.id().to_string()]).status().unwrap();
+    assert!(status.success());
+    wait_for_version(2);
+
+    // Picked up by the file poller, without a signal.
+    std::thread::sleep(Duration::from_millis(1100)); // Let the modification time move on
+    std::fs::write(&config_path, contents.replacen("drift = 0.03", "drift = 0.07", 1)).unwrap();
+    wait_for_version(3);
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
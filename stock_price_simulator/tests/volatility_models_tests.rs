@@ -0,0 +1,103 @@
+use stock_price_simulator::config::{EgarchParams, GarchParams};
+use stock_price_simulator::historical_data::log_returns;
+use stock_price_simulator::random_process::StochasticProcess;
+use stock_price_simulator::stock_simulation::StockSimulator;
+use stock_price_simulator::volatility_models::{fit_egarch, fit_garch, Egarch11, Garch11};
+
+const FIT_PATH_STEPS: usize = 8000;
+
+fn garch_params() -> GarchParams {
+    GarchParams {
+        mean_return: 0.0003,
+        omega: 2e-6,
+        alpha: 0.08,
+        beta: 0.9,
+        initial_variance: None,
+        history_time_step_days: 1.0,
+    }
+}
+
+fn egarch_params() -> EgarchParams {
+    EgarchParams {
+        mean_return: 0.0002,
+        omega: -0.2,
+        alpha: 0.15,
+        gamma: -0.08,
+        beta: 0.98,
+        initial_variance: None,
+        history_time_step_days: 1.0,
+    }
+}
+
+// First-order autocorrelation of squared returns; positive under volatility clustering.
+fn squared_return_autocorrelation(returns: &[f64]) -> f64 {
+    let sq: Vec<f64> = returns.iter().map(|r| r * r).collect();
+    let mean = sq.iter().sum::<f64>() / sq.len() as f64;
+    let var: f64 = sq.iter().map(|x| (x - mean).powi(2)).sum();
+    let cov: f64 = sq.windows(2).map(|w| (w[0] - mean) * (w[1] - mean)).sum();
+    cov / var
+}
+
+#[test]
+fn test_garch_path_shows_volatility_clustering() {
+    let garch = Garch11::from_params(&garch_params()).unwrap();
+    assert!((garch.initial_variance - 1e-4).abs() < 1e-12, "Initial variance defaults to omega / (1 - alpha - beta)");
+
+    let path = garch.generate_path(100.0, 1.0, FIT_PATH_STEPS, Some(17));
+    assert_eq!(path.prices.len(), FIT_PATH_STEPS);
+    assert_eq!(path.prices, garch.generate_path(100.0, 1.0, FIT_PATH_STEPS, Some(17)).prices);
+    assert!(squared_return_autocorrelation(&log_returns(&path.prices)) > 0.05);
+}
+
+#[test]
+fn test_fit_garch_recovers_parameters() {
+    let garch = Garch11::from_params(&garch_params()).unwrap();
+    let returns = log_returns(&garch.generate_path(100.0, 1.0, FIT_PATH_STEPS, Some(23)).prices);
+
+    let fitted = fit_garch(&returns, 1.0).unwrap();
+    assert!((fitted.alpha - 0.08).abs() < 0.04, "alpha {}", fitted.alpha);
+    assert!((fitted.beta - 0.9).abs() < 0.05, "beta {}", fitted.beta);
+    assert!(fitted.alpha + fitted.beta < 1.0);
+    let unconditional = fitted.omega / (1.0 - fitted.alpha - fitted.beta);
+    assert!((unconditional - 1e-4).abs() < 3e-5, "Unconditional variance {}", unconditional);
+}
+
+#[test]
+fn test_fit_egarch_recovers_leverage_sign() {
+    let egarch = Egarch11::from_params(&egarch_params()).unwrap();
+    let returns = log_returns(&egarch.generate_path(100.0, 1.0, FIT_PATH_STEPS, Some(29)).prices);
+
+    let fitted = fit_egarch(&returns, 1.0).unwrap();
+    assert!(fitted.gamma < 0.0, "Leverage effect sign not recovered: gamma {}", fitted.gamma);
+    assert!((fitted.beta - 0.98).abs() < 0.05, "beta {}", fitted.beta);
+    assert!(fitted.beta.abs() < 1.0);
+}
+
+#[test]
+fn test_multi_day_steps_aggregate_model_steps() {
+    let garch = Garch11::from_params(&GarchParams { mean_return: 0.001, omega: 1e-12, alpha: 0.0, beta: 0.0, initial_variance: Some(1e-12), ..garch_params() }).unwrap();
+    let path = garch.generate_path(100.0, 5.0, 2, Some(1));
+    assert!((path.prices[1] / 100.0).ln() - 0.005 < 1e-4);
+}
+
+#[test]
+fn test_sub_step_time_steps_are_rejected() {
+    let garch = Garch11::from_params(&garch_params()).unwrap();
+    let egarch = Egarch11::from_params(&egarch_params()).unwrap();
+    for dt in [0.25, 2.5] {
+        let err = StockSimulator::simulate_with_process(&garch, 100.0, 10, dt, Some(1)).unwrap_err();
+        assert!(err.to_string().contains("whole multiple of the history time step"), "{}: {}", dt, err);
+        assert!(StockSimulator::simulate_with_process(&egarch, 100.0, 10, dt, Some(1)).is_err(), "{}", dt);
+    }
+    assert!(StockSimulator::simulate_with_process(&garch, 100.0, 10, 2.0, Some(1)).is_ok());
+    assert!(StockSimulator::simulate_with_process(&egarch, 100.0, 10, 2.0, Some(1)).is_ok());
+}
+
+#[test]
+fn test_volatility_model_invalid_inputs() {
+    assert!(Garch11::from_params(&GarchParams { alpha: 0.2, beta: 0.85, ..garch_params() }).is_err(), "alpha + beta >= 1");
+    assert!(Garch11::from_params(&GarchParams { omega: 0.0, ..garch_params() }).is_err(), "omega must be positive");
+    assert!(Egarch11::from_params(&EgarchParams { beta: 1.0, ..egarch_params() }).is_err(), "|beta| >= 1");
+    assert!(fit_garch(&[0.01; 10], 1.0).is_err(), "Too few observations");
+    assert!(fit_garch(&[0.01; 100], 1.0).is_err(), "Zero variance");
+}
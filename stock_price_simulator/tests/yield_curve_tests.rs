@@ -0,0 +1,271 @@
+use stock_price_simulator::api_interface::{price_european_option_monte_carlo, MonteCarloEuropeanOptionInput};
+use stock_price_simulator::config::{CurveInterpolation, CurveQuote, DiscountCurveParams};
+use stock_price_simulator::error::{classify, ErrorCode};
+use stock_price_simulator::finite_difference::FiniteDifferenceOptionPricer;
+use stock_price_simulator::futures_simulation::{simulate_futures_price, FuturesContract};
+use stock_price_simulator::option_pricing::{black_scholes_price, EuropeanOption, MonteCarloOptionPricer, OptionPricer, OptionType};
+use stock_price_simulator::yield_curve::{DiscountCurve, MAX_CURVE_QUOTES, MAX_SWAP_PAYMENTS_PER_YEAR};
+
+const INTERPOLATIONS: [CurveInterpolation; 3] = [
+    CurveInterpolation::LinearZero,
+    CurveInterpolation::LogLinearDiscount,
+    CurveInterpolation::MonotoneCubic,
+];
+
+// Upward-sloping market: money-market deposits at the short end, semi-annual par swaps beyond.
+fn market_quotes() -> Vec<CurveQuote> {
+    vec![
+        CurveQuote::Deposit { maturity_years: 0.25, rate: 0.020 },
+        CurveQuote::Deposit { maturity_years: 0.5, rate: 0.022 },
+        CurveQuote::Zero { maturity_years: 1.0, rate: 0.025 },
+        CurveQuote::Swap { maturity_years: 2.0, rate: 0.030, payments_per_year: 2 },
+        CurveQuote::Swap { maturity_years: 5.0, rate: 0.036, payments_per_year: 2 },
+        CurveQuote::Swap { maturity_years: 10.0, rate: 0.040, payments_per_year: 2 },
+    ]
+}
+
+fn market_curve(interpolation: CurveInterpolation) -> DiscountCurve {
+    DiscountCurve::from_params(&DiscountCurveParams { quotes: market_quotes(), interpolation }).unwrap()
+}
+
+fn swap_par_rate(curve: &DiscountCurve, maturity: f64, payments_per_year: u32) -> f64 {
+    let accrual = 1.0 / payments_per_year as f64;
+    let periods = (maturity * payments_per_year as f64).round() as usize;
+    let annuity: f64 = (1..=periods).map(|j| accrual * curve.discount_factor(j as f64 * accrual)).sum();
+    (1.0 - curve.discount_factor(maturity)) / annuity
+}
+
+#[test]
+fn test_bootstrap_reprices_every_quote() {
+    for interpolation in INTERPOLATIONS {
+        let curve = market_curve(interpolation);
+        assert!((curve.discount_factor(0.25) - 1.0 / (1.0 + 0.020 * 0.25)).abs() < 1e-12, "{:?}", interpolation);
+        assert!((curve.discount_factor(0.5) - 1.0 / (1.0 + 0.022 * 0.5)).abs() < 1e-12, "{:?}", interpolation);
+        assert!((curve.zero_rate(1.0) - 0.025).abs() < 1e-12, "{:?}", interpolation);
+        for (maturity, rate) in [(2.0, 0.030), (5.0, 0.036), (10.0, 0.040)] {
+            let par = swap_par_rate(&curve, maturity, 2);
+            assert!((par - rate).abs() < 1e-9, "{:?} {}y swap reprices at {}", interpolation, maturity, par);
+        }
+        assert_eq!(curve.discount_factor(0.0), 1.0);
+    }
+}
+
+#[test]
+fn test_interpolation_schemes() {
+    let times = vec![1.0, 2.0, 5.0];
+    let zeros = vec![0.02, 0.03, 0.035];
+
+    let linear = DiscountCurve::new(times.clone(), zeros.clone(), CurveInterpolation::LinearZero).unwrap();
+    assert!((linear.zero_rate(1.5) - 0.025).abs() < 1e-12);
+    assert!((linear.zero_rate(0.5) - 0.02).abs() < 1e-12 && (linear.zero_rate(8.0) - 0.035).abs() < 1e-12);
+
+    // Log-linear discount factors give piecewise-constant forwards, continued past the last pillar.
+    let log_linear = DiscountCurve::new(times.clone(), zeros.clone(), CurveInterpolation::LogLinearDiscount).unwrap();
+    let segment_forward = (0.035 * 5.0 - 0.03 * 2.0) / 3.0;
+    assert!((log_linear.forward_rate(2.2, 2.7) - segment_forward).abs() < 1e-12);
+    assert!((log_linear.instantaneous_forward(4.0) - segment_forward).abs() < 1e-8);
+    assert!((log_linear.forward_rate(6.0, 9.0) - segment_forward).abs() < 1e-12);
+
+    // The monotone cubic passes through the pillars and, for increasing pillars, never leaves their range.
+    let cubic = DiscountCurve::new(times.clone(), zeros.clone(), CurveInterpolation::MonotoneCubic).unwrap();
+    for (t, z) in times.iter().zip(&zeros) {
+        assert!((cubic.zero_rate(*t) - z).abs() < 1e-12);
+    }
+    let mut previous = cubic.zero_rate(1.0);
+    for i in 1..=400 {
+        let z = cubic.zero_rate(1.0 + i as f64 * 0.01);
+        assert!(z >= previous - 1e-15 && z <= 0.035 + 1e-15, "cubic zero rate {} at step {}", z, i);
+        previous = z;
+    }
+}
+
+#[test]
+fn test_forward_rates_are_consistent_with_discount_factors() {
+    let curve = market_curve(CurveInterpolation::MonotoneCubic);
+    for (t1, t2) in [(0.0, 0.5), (0.7, 1.3), (2.0, 7.5), (9.0, 15.0)] {
+        let forward = curve.forward_rate(t1, t2);
+        let implied = curve.discount_factor(t1) * (-forward * (t2 - t1)).exp();
+        assert!((implied - curve.discount_factor(t2)).abs() < 1e-12);
+    }
+    // Averaging instantaneous forwards over an interval recovers the term forward rate.
+    let steps = 2000;
+    let average: f64 = (0..steps).map(|i| curve.instantaneous_forward(3.0 + (i as f64 + 0.5) * 2.0 / steps as f64)).sum::<f64>() / steps as f64;
+    assert!((average - curve.forward_rate(3.0, 5.0)).abs() < 1e-6, "{} vs {}", average, curve.forward_rate(3.0, 5.0));
+
+    let flat = DiscountCurve::flat(0.04).unwrap();
+    assert!((flat.instantaneous_forward(0.0) - 0.04).abs() < 1e-9);
+    assert!((flat.forward_rate(2.0, 3.0) - 0.04).abs() < 1e-12);
+}
+
+#[test]
+fn test_invalid_curves() {
+    let params = |quotes: Vec<CurveQuote>| DiscountCurveParams { quotes, interpolation: CurveInterpolation::LinearZero };
+    assert!(DiscountCurve::from_params(&params(vec![])).is_err());
+    assert!(DiscountCurve::from_params(&params(vec![CurveQuote::Zero { maturity_years: -1.0, rate: 0.02 }])).is_err());
+    assert!(DiscountCurve::from_params(&params(vec![
+        CurveQuote::Zero { maturity_years: 1.0, rate: 0.02 },
+        CurveQuote::Deposit { maturity_years: 1.0, rate: 0.02 },
+    ])).is_err(), "Two quotes at one maturity");
+    assert!(DiscountCurve::from_params(&params(vec![
+        CurveQuote::Swap { maturity_years: 1.3, rate: 0.02, payments_per_year: 2 },
+    ])).is_err(), "Swap maturity must be a whole number of periods");
+    assert!(DiscountCurve::new(vec![2.0, 1.0], vec![0.01, 0.02], CurveInterpolation::LinearZero).is_err());
+}
+
+#[test]
+fn test_curve_quote_bounds_name_the_quote() {
+    let field_of = |quotes: Vec<CurveQuote>| {
+        let error = classify(&DiscountCurve::from_params(&DiscountCurveParams { quotes, interpolation: CurveInterpolation::LinearZero }).unwrap_err());
+        assert_eq!(error.code, ErrorCode::InvalidParameter);
+        error.field.unwrap()
+    };
+    let one_year = CurveQuote::Deposit { maturity_years: 1.0, rate: 0.02 };
+
+    assert_eq!(field_of(vec![]), "quotes");
+    assert_eq!(field_of(vec![one_year.clone(); MAX_CURVE_QUOTES + 1]), "quotes");
+    assert_eq!(field_of(vec![
+        one_year.clone(),
+        CurveQuote::Swap { maturity_years: 1e6, rate: 0.02, payments_per_year: 2 },
+    ]), "quotes[1].maturity_years");
+    assert_eq!(field_of(vec![
+        one_year.clone(),
+        CurveQuote::Swap { maturity_years: 10.0, rate: 0.02, payments_per_year: MAX_SWAP_PAYMENTS_PER_YEAR + 1 },
+    ]), "quotes[1].payments_per_year");
+    assert_eq!(field_of(vec![CurveQuote::Swap { maturity_years: 2.0, rate: 0.02, payments_per_year: 0 }]), "quotes[0].payments_per_year");
+    assert_eq!(field_of(vec![CurveQuote::Zero { maturity_years: 2.0, rate: f64::NAN }]), "quotes[0].rate");
+    assert_eq!(field_of(vec![
+        CurveQuote::Zero { maturity_years: 2.0, rate: 0.02 },
+        CurveQuote::Swap { maturity_years: 1.3, rate: 0.02, payments_per_year: 2 },
+    ]), "quotes[1].maturity_years");
+    assert_eq!(field_of(vec![CurveQuote::Zero { maturity_years: 2.0, rate: 0.02 }, one_year.clone(), one_year]), "quotes[2].maturity_years");
+    assert_eq!(field_of(vec![CurveQuote::Swap { maturity_years: 5.0, rate: 50.0, payments_per_year: 1 }]), "quotes[0].rate");
+}
+
+fn option_with_curve(curve: Option<DiscountCurve>) -> EuropeanOption {
+    EuropeanOption {
+        underlying_price: 100.0,
+        strike_price: 100.0,
+        time_to_maturity_years: 5.0,
+        risk_free_rate: None,
+        volatility: 0.2,
+        option_type: OptionType::Call,
+        discount_curve: curve,
+    }
+}
+
+#[test]
+fn test_option_pricers_use_the_curve() {
+    let curve = market_curve(CurveInterpolation::LogLinearDiscount);
+    let rate = curve.zero_rate(5.0);
+    let with_curve = option_with_curve(Some(curve));
+    let flat = EuropeanOption { risk_free_rate: Some(rate), ..option_with_curve(None) };
+
+    let bs = black_scholes_price(&with_curve).unwrap();
+    assert!((bs - black_scholes_price(&flat).unwrap()).abs() < 1e-12);
+    let zero_rate = EuropeanOption { risk_free_rate: Some(0.0), ..option_with_curve(None) };
+    assert!(bs > black_scholes_price(&zero_rate).unwrap() + 1.0, "Positive rates raise the call price");
+
+    // Same paths and discounting as the flat rate equal to the zero rate to maturity.
+    let mc = MonteCarloOptionPricer::for_european_option(&with_curve, 20_000, 10).unwrap().price(Some(7)).unwrap();
+    let mc_flat = MonteCarloOptionPricer::for_european_option(&flat, 20_000, 10).unwrap().price(Some(7)).unwrap();
+    assert!((mc - mc_flat).abs() < 1e-9, "MC {} vs flat MC {}", mc, mc_flat);
+    assert!((mc - bs).abs() < 1.0, "MC {} vs BS {}", mc, bs); // About three standard errors
+
+    let fd = FiniteDifferenceOptionPricer::for_european_option(&with_curve, 400, 400).unwrap().solve().unwrap();
+    assert!((fd.price - bs).abs() < 0.02, "FD {} vs BS {}", fd.price, bs);
+}
+
+#[test]
+fn test_option_json_with_discount_curve() {
+    let json = r#"{
+        "underlying_price": 100.0, "strike_price": 100.0, "time_to_maturity_years": 5.0,
+        "volatility": 0.2, "option_type": "Call",
+        "discount_curve": {
+            "quotes": [
+                {"instrument": "Deposit", "maturity_years": 0.5, "rate": 0.02},
+                {"instrument": "Swap", "maturity_years": 5.0, "rate": 0.035}
+            ],
+            "interpolation": "MonotoneCubic"
+        }
+    }"#;
+    let option: EuropeanOption = serde_json::from_str(json).unwrap();
+    let curve = option.discount_curve.as_ref().unwrap();
+    assert_eq!(curve.interpolation, CurveInterpolation::MonotoneCubic);
+    assert!((swap_par_rate(curve, 5.0, 1) - 0.035).abs() < 1e-9);
+
+    let bad = json.replace("\"maturity_years\": 5.0", "\"maturity_years\": 0.5");
+    assert!(serde_json::from_str::<EuropeanOption>(&bad).is_err(), "Duplicate maturities must fail to deserialize");
+}
+
+#[test]
+fn test_futures_use_curve_forwards() {
+    let curve = market_curve(CurveInterpolation::LogLinearDiscount);
+    let contract = FuturesContract {
+        underlying_symbol: "LONG_DATED".to_string(),
+        initial_spot_price: 100.0,
+        risk_free_rate: None,
+        volatility: 0.2,
+        time_to_maturity_days: 3650,
+        time_step_days: 5.0,
+        seed: Some(3),
+        discount_curve: Some(curve.clone()),
+    };
+    let futures = simulate_futures_price(&contract).unwrap();
+    let maturity = 3650.0 / 365.0;
+    assert!((futures.prices[0] - 100.0 / curve.discount_factor(maturity)).abs() < 1e-9, "F0 = S0 / DF(T), got {}", futures.prices[0]);
+
+    // A flat curve reproduces the scalar rate.
+    let flat = FuturesContract { discount_curve: Some(DiscountCurve::flat(0.03).unwrap()), ..contract.clone() };
+    let scalar = FuturesContract { risk_free_rate: Some(0.03), discount_curve: None, ..contract };
+    let (a, b) = (simulate_futures_price(&flat).unwrap(), simulate_futures_price(&scalar).unwrap());
+    for (x, y) in a.prices.iter().zip(&b.prices) {
+        assert!((x - y).abs() < 1e-6 * y, "{} vs {}", x, y);
+    }
+}
+
+#[test]
+fn test_exactly_one_of_rate_and_curve() {
+    let curve = market_curve(CurveInterpolation::LogLinearDiscount);
+    let code_and_field = |error: anyhow::Error| {
+        let error = classify(&error);
+        (error.code, error.field)
+    };
+    let both = (ErrorCode::InvalidParameter, Some("discount_curve".to_string()));
+    let neither = (ErrorCode::MissingParameter, Some("risk_free_rate".to_string()));
+
+    let option = EuropeanOption { risk_free_rate: Some(0.03), ..option_with_curve(Some(curve.clone())) };
+    assert_eq!(code_and_field(black_scholes_price(&option).unwrap_err()), both);
+    assert_eq!(code_and_field(black_scholes_price(&option_with_curve(None)).unwrap_err()), neither);
+    assert!(MonteCarloOptionPricer::for_european_option(&option, 100, 10).is_err());
+    assert!(FiniteDifferenceOptionPricer::for_european_option(&option_with_curve(None), 100, 100).is_err());
+
+    let monte_carlo = MonteCarloEuropeanOptionInput {
+        underlying_initial_price: 100.0,
+        strike_price: 100.0,
+        time_to_maturity_years: 1.0,
+        risk_free_rate: Some(0.03),
+        underlying_volatility: 0.2,
+        option_type: OptionType::Put,
+        num_paths: 100,
+        num_steps_per_path: 10,
+        seed: Some(1),
+        discount_curve: Some(curve.clone()),
+    };
+    assert_eq!(code_and_field(price_european_option_monte_carlo(&monte_carlo).unwrap_err()), both);
+    let monte_carlo = MonteCarloEuropeanOptionInput { risk_free_rate: None, discount_curve: None, ..monte_carlo };
+    assert_eq!(code_and_field(price_european_option_monte_carlo(&monte_carlo).unwrap_err()), neither);
+
+    let contract = FuturesContract {
+        underlying_symbol: "ES".to_string(),
+        initial_spot_price: 100.0,
+        risk_free_rate: Some(0.03),
+        volatility: 0.2,
+        time_to_maturity_days: 30,
+        time_step_days: 1.0,
+        seed: Some(1),
+        discount_curve: Some(curve),
+    };
+    assert_eq!(code_and_field(simulate_futures_price(&contract).unwrap_err()), both);
+    let contract = FuturesContract { risk_free_rate: None, discount_curve: None, ..contract };
+    assert_eq!(code_and_field(simulate_futures_price(&contract).unwrap_err()), neither);
+}